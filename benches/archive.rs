@@ -0,0 +1,52 @@
+//! Size and speed of the archive column encodings.
+//!
+//! Run with `cargo bench --bench archive`.
+use std::time::Instant;
+
+use oscal_types::{archive, DatatypeKind, UUIDDatatype};
+
+const ROWS: usize = 100_000;
+
+fn bench(kind: DatatypeKind, values: &[String]) {
+    let text: usize = values.iter().map(|v| v.len() + 1).sum();
+
+    let start = Instant::now();
+    let bytes = archive::encode_column(kind, values).unwrap();
+    let encode = start.elapsed();
+
+    let start = Instant::now();
+    let decoded = archive::decode_column(kind, &bytes).unwrap();
+    let decode = start.elapsed();
+    assert_eq!(decoded, values);
+
+    println!(
+        "{:<28} {:>9} bytes as text, {:>9} encoded ({:>5.1}%), encode {:>8.2?}, decode {:>8.2?}",
+        kind.name(),
+        text,
+        bytes.len(),
+        100.0 * bytes.len() as f64 / text as f64,
+        encode,
+        decode,
+    );
+}
+
+fn main() {
+    let uuids: Vec<String> = (0..ROWS).map(|_| UUIDDatatype::new().to_string()).collect();
+    bench(DatatypeKind::Uuid, &uuids);
+
+    let dates: Vec<String> = (0..ROWS as u64)
+        .map(|i| {
+            let date = chrono::NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+            (date + chrono::Days::new(i / 50)).to_string()
+        })
+        .collect();
+    bench(DatatypeKind::Date, &dates);
+
+    let counts: Vec<String> = (0..ROWS).map(|i| (i * 3 % 1000).to_string()).collect();
+    bench(DatatypeKind::NonNegativeInteger, &counts);
+
+    let tokens: Vec<String> = (0..ROWS)
+        .map(|i| format!("ac-{}.{}", i % 25, i % 7))
+        .collect();
+    bench(DatatypeKind::Token, &tokens);
+}
@@ -0,0 +1,51 @@
+//! Lookup speed of SmallIndexMap against HashMap at the sizes of props and
+//! links collections.
+//!
+//! Run with `cargo bench --bench small_map`.
+use std::collections::HashMap;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use oscal_types::small_map::SmallIndexMap;
+use oscal_types::{TokenDatatype, UUIDDatatype};
+
+const LOOKUPS: usize = 1_000_000;
+
+fn time(mut lookup: impl FnMut(usize) -> bool) -> Duration {
+    let start = Instant::now();
+    let mut found = 0;
+    for i in 0..LOOKUPS {
+        found += lookup(i) as usize;
+    }
+    black_box(found);
+    start.elapsed()
+}
+
+fn bench<K: oscal_types::OscalDatatype + Eq + std::hash::Hash>(label: &str, keys: &[K]) {
+    let small: SmallIndexMap<K, usize> = keys.iter().cloned().zip(0..).collect();
+    let hashed: HashMap<K, usize> = keys.iter().cloned().zip(0..).collect();
+
+    let small_time = time(|i| small.get(black_box(&keys[i % keys.len()])).is_some());
+    let hashed_time = time(|i| hashed.contains_key(black_box(&keys[i % keys.len()])));
+
+    println!(
+        "{:<6} {:>2} entries: SmallIndexMap {:>8.2?}, HashMap {:>8.2?} per {} lookups",
+        label,
+        keys.len(),
+        small_time,
+        hashed_time,
+        LOOKUPS,
+    );
+}
+
+fn main() {
+    for size in [2, 5, 10, 20] {
+        let tokens: Vec<TokenDatatype> = (0..size)
+            .map(|i| TokenDatatype::try_from(format!("prop-name-{i}").as_str()).unwrap())
+            .collect();
+        bench("token", &tokens);
+
+        let uuids: Vec<UUIDDatatype> = (0..size).map(|_| UUIDDatatype::new()).collect();
+        bench("uuid", &uuids);
+    }
+}
@@ -0,0 +1,68 @@
+//! Generate OSCAL metadata from typed values.
+//!
+//! The metadata is built from the datatypes' constructors, serialized, and
+//! checked against the same field bindings a consumer would validate it
+//! with.  The JSON Schema definitions of the datatypes it uses are printed
+//! after the document, ready to be referenced from a model schema.
+//!
+//! ```text
+//! cargo run --example generate_metadata
+//! ```
+use std::collections::BTreeSet;
+
+use oscal_types::{
+    bindings::FieldBindings, registry::TypeRegistry, schema, walk, DatatypeKind,
+    DateTimeWithTimezoneDatatype, EmailAddressDatatype, MarkupLineDatatype, StringDatatype,
+    TokenDatatype, UUIDDatatype,
+};
+use serde_json::{json, Map, Value};
+
+fn main() {
+    let namespace = UUIDDatatype::try_from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").unwrap();
+    let metadata = json!({
+        "title": MarkupLineDatatype::try_from("Agency **Moderate** Baseline").unwrap(),
+        "last-modified": DateTimeWithTimezoneDatatype::new(),
+        "version": StringDatatype::try_from("1.0").unwrap(),
+        "oscal-version": StringDatatype::try_from("1.1.2").unwrap(),
+        "roles": [{"id": TokenDatatype::try_from("maintainer").unwrap()}],
+        "parties": [{
+            "uuid": UUIDDatatype::new_v5(&namespace, "security-office"),
+            "type": "organization",
+            "email-addresses": [EmailAddressDatatype::try_from("security@agency.gov").unwrap()],
+        }],
+    });
+    let doc = json!({"catalog": {"uuid": UUIDDatatype::new(), "metadata": metadata}});
+
+    let bindings = FieldBindings::new()
+        .bind("/**/uuid", DatatypeKind::Uuid)
+        .bind("/catalog/metadata/title", DatatypeKind::MarkupLine)
+        .bind(
+            "/catalog/metadata/last-modified",
+            DatatypeKind::DateTimeWithTimezone,
+        )
+        .bind("/catalog/metadata/version", DatatypeKind::String)
+        .bind("/catalog/metadata/oscal-version", DatatypeKind::String)
+        .bind("/**/roles/*/id", DatatypeKind::Token)
+        .bind("/**/email-addresses/*", DatatypeKind::EmailAddress);
+
+    let mut used = BTreeSet::new();
+    walk::walk(&doc, &bindings, |pointer, kind, value| {
+        let lexical = walk::lexical_form(value).unwrap_or_default();
+        if let Err(e) = kind.validate(&lexical) {
+            panic!("generated an invalid {kind} at {pointer}: {e}");
+        }
+        used.insert(kind.name());
+    });
+    println!("{}", serde_json::to_string_pretty(&doc).unwrap());
+
+    let registry = TypeRegistry::builtin();
+    let defs: Map<String, Value> = used
+        .into_iter()
+        .map(|name| {
+            let info = registry.get(name).expect("a registered datatype");
+            (name.to_string(), schema::definition(info))
+        })
+        .collect();
+    let schema = json!({"$schema": schema::SCHEMA_DIALECT, "$defs": defs});
+    println!("{}", serde_json::to_string_pretty(&schema).unwrap());
+}
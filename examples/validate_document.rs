@@ -0,0 +1,102 @@
+//! Validate and canonicalize an OSCAL JSON document.
+//!
+//! The datatype of each field is looked up by name in the type registry,
+//! as a tool driven by a Metaschema definition would.  Every invalid value
+//! is reported with its JSON Pointer and the constraints it violates, then
+//! the document is canonicalized and the rewritten values are listed.
+//!
+//! ```text
+//! cargo run --example validate_document [document.json]
+//! ```
+//!
+//! Without an argument a small built-in document is used.  Exits with a
+//! failure status if any value is invalid.
+use std::process::ExitCode;
+
+use oscal_types::{
+    bindings::FieldBindings, normalize, registry::TypeRegistry, report, walk, DatatypeKind,
+};
+use serde_json::{json, Value};
+
+/// Field paths and the names of their datatypes.
+const FIELDS: &[(&str, &str)] = &[
+    ("/**/uuid", "UUIDDatatype"),
+    ("/**/metadata/last-modified", "DateTimeWithTimezoneDatatype"),
+    ("/**/metadata/published", "DateTimeWithTimezoneDatatype"),
+    ("/**/metadata/oscal-version", "StringDatatype"),
+    ("/**/props/*/name", "TokenDatatype"),
+    ("/**/links/*/href", "URIReferenceDatatype"),
+    ("/**/parties/*/email-addresses/*", "EmailAddressDatatype"),
+    ("/**/role-ids/*", "TokenDatatype"),
+    ("/**/controls/*/id", "TokenDatatype"),
+];
+
+fn sample() -> Value {
+    json!({"catalog": {
+        "uuid": "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301",
+        "metadata": {
+            "title": "Sample Catalog",
+            "last-modified": "2024-02-10T23:30:00-05:00",
+            "oscal-version": "1.1.2",
+            "parties": [{
+                "uuid": "cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53",
+                "type": "organization",
+                "email-addresses": ["Security@Agency.GOV", "not an address"],
+            }],
+            "props": [{"name": "marking", "value": "cui"}],
+        },
+        "controls": [
+            {"id": "ac-1", "links": [{"href": "#ac-2"}]},
+            {"id": "1ac", "props": [{"name": "label", "value": "AC-1"}]},
+        ],
+    }})
+}
+
+/// Resolve each datatype name through the registry.
+fn bindings(registry: &TypeRegistry) -> FieldBindings {
+    FIELDS
+        .iter()
+        .fold(FieldBindings::new(), |bindings, (path, name)| {
+            let info = registry.get(name).expect("a registered datatype");
+            let kind: DatatypeKind = info.name().parse().expect("a builtin datatype");
+            bindings.bind(path, kind)
+        })
+}
+
+fn main() -> ExitCode {
+    let mut doc = match std::env::args().nth(1) {
+        Some(path) => {
+            let text = std::fs::read_to_string(&path).expect("readable document");
+            serde_json::from_str(&text).expect("a JSON document")
+        }
+        None => sample(),
+    };
+    let bindings = bindings(&TypeRegistry::builtin());
+
+    let mut invalid = 0;
+    walk::walk(&doc, &bindings, |pointer, kind, value| {
+        let lexical = walk::lexical_form(value).unwrap_or_default();
+        let report = report::validate_detailed(kind, &lexical);
+        if !report.is_valid() {
+            invalid += 1;
+            println!("{pointer} = {lexical:?}");
+            for violation in report.violations() {
+                println!("    {violation}");
+            }
+        }
+    });
+
+    let before = doc.clone();
+    normalize::normalize_document(&mut doc, &bindings);
+    walk::walk(&doc, &bindings, |pointer, _, value| {
+        if let Some(original) = before.pointer(pointer).filter(|v| *v != value) {
+            println!("canonicalized {pointer}: {original} -> {value}");
+        }
+    });
+
+    println!("{invalid} invalid value(s)");
+    match invalid {
+        0 => ExitCode::SUCCESS,
+        _ => ExitCode::FAILURE,
+    }
+}
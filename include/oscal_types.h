@@ -0,0 +1,60 @@
+/*
+ * C interface to the oscal_types validators.  Built with the `capi`
+ * feature; see the `capi` module documentation.
+ *
+ * Strings are NUL-terminated UTF-8.  Messages written to `out_err` are
+ * owned by the caller and freed with oscal_string_free.  Handles returned
+ * by oscal_value_new are freed with oscal_value_free.
+ */
+#ifndef OSCAL_TYPES_H
+#define OSCAL_TYPES_H
+
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* The value is valid. */
+#define OSCAL_VALID 0
+/* The value is not valid for its datatype. */
+#define OSCAL_INVALID 1
+/*
+ * An argument was null or not UTF-8, the datatype name is unknown, or the
+ * library failed internally.
+ */
+#define OSCAL_ERROR -1
+
+/* A value constructed by oscal_value_new. */
+typedef struct OscalHandle OscalHandle;
+
+/*
+ * Validate `value` as the datatype named `type_name`, e.g. "DateDatatype".
+ * Returns OSCAL_VALID, OSCAL_INVALID or OSCAL_ERROR, and on failure writes
+ * a message to `out_err` unless it is NULL.
+ */
+int32_t oscal_validate(const char *type_name, const char *value, char **out_err);
+
+/*
+ * Construct a value of the datatype named `type_name`, or return NULL and
+ * write a message to `out_err` unless it is NULL.
+ */
+OscalHandle *oscal_value_new(const char *type_name, const char *value, char **out_err);
+
+/* The datatype name of `handle`, borrowed from it. */
+const char *oscal_value_type_name(const OscalHandle *handle);
+
+/* The lexical form of `handle`, borrowed from it. */
+const char *oscal_value_lexical(const OscalHandle *handle);
+
+/* Free a handle returned by oscal_value_new.  NULL is ignored. */
+void oscal_value_free(OscalHandle *handle);
+
+/* Free a message written to an `out_err`.  NULL is ignored. */
+void oscal_string_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* OSCAL_TYPES_H */
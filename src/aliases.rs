@@ -0,0 +1,73 @@
+//! Historical datatype names.
+//!
+//! Metaschema has renamed some datatypes, e.g. `ncname` became `token` and
+//! `base64Binary` became `base64`.  Models generated from older definitions
+//! use the old names, so they remain available as deprecated aliases of the
+//! current types, and [DatatypeKind] parses them with a
+//! [Warning::DeprecatedName].
+//!
+//! ```
+//! use oscal_types::warnings::{self, Warning};
+//! use oscal_types::DatatypeKind;
+//!
+//! let kind: DatatypeKind = "NCNameDatatype".parse().unwrap();
+//! assert_eq!(kind, DatatypeKind::Token);
+//! assert_eq!(
+//!     warnings::take(),
+//!     vec![Warning::DeprecatedName {
+//!         name: "NCNameDatatype".to_string(),
+//!         current: "TokenDatatype"
+//!     }]
+//! );
+//! ```
+#![allow(deprecated)]
+
+use crate::warnings::{self, Warning};
+use crate::*;
+
+#[deprecated(note = "renamed to TokenDatatype")]
+pub type NCNameDatatype = TokenDatatype;
+
+#[deprecated(note = "renamed to Base64Datatype")]
+pub type Base64BinaryDatatype = Base64Datatype;
+
+#[deprecated(note = "renamed to EmailAddressDatatype")]
+pub type EmailDatatype = EmailAddressDatatype;
+
+/// Historical type names and the datatypes they now name.
+pub const DEPRECATED_NAMES: &[(&str, DatatypeKind)] = &[
+    ("NCNameDatatype", DatatypeKind::Token),
+    ("Base64BinaryDatatype", DatatypeKind::Base64),
+    ("EmailDatatype", DatatypeKind::EmailAddress),
+];
+
+/// The datatype a historical name now refers to, recording a
+/// [Warning::DeprecatedName].
+pub(crate) fn resolve(name: &str) -> Option<DatatypeKind> {
+    let (_, kind) = DEPRECATED_NAMES.iter().find(|(old, _)| *old == name)?;
+    warnings::emit(Warning::DeprecatedName {
+        name: name.to_string(),
+        current: kind.name(),
+    });
+    Some(*kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases() {
+        let token: NCNameDatatype = NCNameDatatype::try_from("ac-1").unwrap();
+        assert_eq!(&*token, "ac-1");
+
+        warnings::take();
+        for (name, kind) in DEPRECATED_NAMES {
+            assert_eq!(name.parse::<DatatypeKind>().unwrap(), *kind);
+        }
+        assert_eq!(warnings::take().len(), DEPRECATED_NAMES.len());
+        assert!(validate_by_name("EmailDatatype", "ops@agency.gov").is_ok());
+        assert!("TokenDatatype".parse::<DatatypeKind>().is_ok());
+        assert_eq!(warnings::take().len(), 1);
+    }
+}
@@ -0,0 +1,220 @@
+//! Keyed, deterministic pseudonymization.
+//!
+//! An [Anonymizer] replaces identifying values with pseudonyms derived from
+//! an HMAC of the original value.  The same key and input always produce the
+//! same pseudonym, so references between scrubbed documents stay intact, and
+//! every pseudonym is a valid value of the original datatype.
+//!
+//! Pseudonyms use reserved ranges so they can never collide with real
+//! infrastructure: `*.example` names (RFC 2606), `10.0.0.0/8` IPv4 and
+//! `fd00::/8` IPv6 addresses.  Pseudonyms are shorter than the values they
+//! replace, so distinct values can collide, which is likely for IPv4
+//! addresses (see [Anonymizer::ipv4]).
+//!
+//! ```
+//! use oscal_types::{anonymize::Anonymizer, IPV4AddressDatatype};
+//!
+//! let anonymizer = Anonymizer::new(b"secret");
+//! let ip = IPV4AddressDatatype::try_from("192.0.2.7").unwrap();
+//! assert_eq!(anonymizer.ipv4(&ip), anonymizer.ipv4(&ip));
+//! assert!(anonymizer.ipv4(&ip).starts_with("10."));
+//! ```
+use sha1_smol::Sha1;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use uuid::{Builder, Uuid};
+
+use crate::{
+    DatatypeKind, EmailAddressDatatype, Error, HostnameDatatype, IPV4AddressDatatype,
+    IPV6AddressDatatype, UUIDDatatype,
+};
+
+const BLOCK_SIZE: usize = 64;
+
+pub struct Anonymizer {
+    key: [u8; BLOCK_SIZE],
+}
+
+impl Anonymizer {
+    pub fn new(key: &[u8]) -> Self {
+        let mut block = [0u8; BLOCK_SIZE];
+        if key.len() > BLOCK_SIZE {
+            block[..20].copy_from_slice(&Sha1::from(key).digest().bytes());
+        } else {
+            block[..key.len()].copy_from_slice(key);
+        }
+        Self { key: block }
+    }
+
+    /// HMAC-SHA1 (RFC 2104) of the concatenated `message` parts.
+    fn hmac(&self, message: &[&[u8]]) -> [u8; 20] {
+        let pad = |byte: u8| self.key.map(|k| k ^ byte);
+
+        let mut inner = Sha1::from(pad(0x36));
+        for part in message {
+            inner.update(part);
+        }
+
+        let mut outer = Sha1::from(pad(0x5c));
+        outer.update(&inner.digest().bytes());
+        outer.digest().bytes()
+    }
+
+    /// HMAC of `value`, domain separated by `label`.
+    fn mac(&self, label: &str, value: &str) -> [u8; 20] {
+        self.hmac(&[label.as_bytes(), &[0], value.as_bytes()])
+    }
+
+    fn hex(&self, label: &str, value: &str, len: usize) -> String {
+        self.mac(label, value)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()[..len]
+            .to_string()
+    }
+
+    /// A random-looking version 4 UUID.
+    pub fn uuid(&self, value: &UUIDDatatype) -> UUIDDatatype {
+        let mac = self.mac("uuid", &value.to_lowercase());
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&mac[..16]);
+        let uuid: Uuid = Builder::from_random_bytes(bytes).into_uuid();
+        UUIDDatatype::try_from(uuid.to_string().as_str()).expect("generated UUID")
+    }
+
+    /// `user-<hash>@<hash>.example`.  The local part and domain are
+    /// pseudonymized separately, so addresses sharing a domain still do.
+    pub fn email(&self, value: &EmailAddressDatatype) -> EmailAddressDatatype {
+        let (local, domain) = value.rsplit_once('@').unwrap_or((value, ""));
+        let pseudonym = format!(
+            "user-{}@{}.example",
+            self.hex(
+                "email-local",
+                &format!("{}@{}", local, domain.to_lowercase()),
+                10
+            ),
+            self.hex("hostname", &domain.to_lowercase(), 10)
+        );
+        EmailAddressDatatype::try_from(pseudonym.as_str()).expect("generated email")
+    }
+
+    /// `host-<hash>.example`
+    pub fn hostname(&self, value: &HostnameDatatype) -> HostnameDatatype {
+        let pseudonym = format!(
+            "host-{}.example",
+            self.hex("hostname", &value.to_lowercase(), 10)
+        );
+        HostnameDatatype::try_from(pseudonym.as_str()).expect("generated hostname")
+    }
+
+    /// An address in `10.0.0.0/8`.
+    ///
+    /// The 2^32 IPv4 addresses map into the 2^24 of `10.0.0.0/8`, so
+    /// distinct addresses can share a pseudonym; among a few thousand
+    /// addresses that is likely.  Pseudonyms group references to one
+    /// address, but equal pseudonyms do not prove the originals were equal.
+    pub fn ipv4(&self, value: &IPV4AddressDatatype) -> IPV4AddressDatatype {
+        let mac = self.mac("ipv4", value);
+        let ip = Ipv4Addr::new(10, mac[0], mac[1], mac[2]);
+        IPV4AddressDatatype::try_from(ip.to_string().as_str()).expect("generated address")
+    }
+
+    /// An address in `fd00::/8`.
+    pub fn ipv6(&self, value: &IPV6AddressDatatype) -> IPV6AddressDatatype {
+        // Canonicalize so that equivalent spellings share a pseudonym
+        let canonical = value
+            .parse::<Ipv6Addr>()
+            .map(|ip| ip.to_string())
+            .unwrap_or_else(|_| value.to_string());
+        let mac = self.mac("ipv6", &canonical);
+        let mut octets = [0u8; 16];
+        octets[0] = 0xfd;
+        octets[1..].copy_from_slice(&mac[..15]);
+        let ip = Ipv6Addr::from(octets);
+        IPV6AddressDatatype::try_from(ip.to_string().as_str()).expect("generated address")
+    }
+
+    /// Pseudonymize the lexical `value` of `kind`.  Returns `Ok(None)` for
+    /// datatypes that are not identifying.
+    pub fn anonymize(&self, kind: DatatypeKind, value: &str) -> Result<Option<String>, Error> {
+        let pseudonym = match kind {
+            DatatypeKind::Uuid => self.uuid(&value.try_into()?).to_string(),
+            DatatypeKind::EmailAddress => self.email(&value.try_into()?).to_string(),
+            DatatypeKind::Hostname => self.hostname(&value.try_into()?).to_string(),
+            DatatypeKind::Ipv4Address => self.ipv4(&value.try_into()?).to_string(),
+            DatatypeKind::Ipv6Address => self.ipv6(&value.try_into()?).to_string(),
+            _ => return Ok(None),
+        };
+        Ok(Some(pseudonym))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Validate;
+
+    #[test]
+    fn test_hmac_sha1() {
+        // RFC 2202 test case 2
+        let mac = Anonymizer::new(b"Jefe").hmac(&[b"what do ya want for nothing?"]);
+        let hex: String = mac.iter().map(|b| format!("{b:02x}")).collect();
+        assert_eq!(hex, "effcdf6ae5eb2fa2d27416d5f184df9c259a7c79");
+    }
+
+    #[test]
+    fn test_deterministic_and_valid() {
+        let a = Anonymizer::new(b"key one");
+        let b = Anonymizer::new(b"key two");
+
+        let uuid = UUIDDatatype::try_from("A78F7E4C-A27A-4B1E-901B-EBFECF2B0301").unwrap();
+        assert_eq!(a.uuid(&uuid), a.uuid(&uuid));
+        assert_ne!(a.uuid(&uuid), b.uuid(&uuid));
+        assert!(UUIDDatatype::validate(&a.uuid(&uuid)).is_ok());
+
+        let v6 = IPV6AddressDatatype::try_from("fe80:0::1").unwrap();
+        let same = IPV6AddressDatatype::try_from("fe80::1").unwrap();
+        assert_eq!(a.ipv6(&v6), a.ipv6(&same));
+        assert!(a.ipv6(&v6).starts_with("fd"));
+    }
+
+    #[test]
+    fn test_ipv4_collides() {
+        use std::collections::HashMap;
+
+        let a = Anonymizer::new(b"key");
+        let mut seen = HashMap::new();
+        let collision = (0..20_000u32).find_map(|n| {
+            let ip = Ipv4Addr::from(0xc000_0200 + n).to_string();
+            let pseudonym = a.ipv4(&IPV4AddressDatatype::try_from(ip.as_str()).unwrap());
+            seen.insert(pseudonym, ip.clone()).map(|other| (other, ip))
+        });
+        let (first, second) = collision.expect("a collision among 20000 addresses");
+        assert_ne!(first, second);
+        assert_eq!(
+            a.anonymize(DatatypeKind::Ipv4Address, &first).unwrap(),
+            a.anonymize(DatatypeKind::Ipv4Address, &second).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_email_keeps_domain_grouping() {
+        let a = Anonymizer::new(b"key");
+        let alice = a
+            .anonymize(DatatypeKind::EmailAddress, "alice@agency.gov")
+            .unwrap()
+            .unwrap();
+        let bob = a
+            .anonymize(DatatypeKind::EmailAddress, "bob@AGENCY.gov")
+            .unwrap()
+            .unwrap();
+        assert_ne!(alice, bob);
+        assert_eq!(
+            alice.split_once('@').unwrap().1,
+            bob.split_once('@').unwrap().1
+        );
+        assert!(!alice.contains("agency"));
+
+        assert_eq!(a.anonymize(DatatypeKind::Date, "2024-02-10").unwrap(), None);
+        assert!(a.anonymize(DatatypeKind::Uuid, "not-a-uuid").is_err());
+    }
+}
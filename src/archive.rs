@@ -0,0 +1,374 @@
+//! Compact column encoding for archiving validated values.
+//!
+//! [encode_column] packs a column of values of one datatype into bytes that
+//! are small and compress well, and [decode_column] restores the exact
+//! lexical forms:
+//!
+//! - UUIDs are stored as their 16 raw bytes.
+//! - Dates are stored as the difference in days from the previous date.
+//! - Integers are stored as the difference from the previous integer.
+//! - Everything else, and values not in their canonical form, e.g. an
+//!   uppercase UUID, is dictionary coded.
+//!
+//! ```
+//! use oscal_types::{archive, DatatypeKind};
+//!
+//! let dates = ["2024-02-10", "2024-02-11", "2024-02-13"];
+//! let bytes = archive::encode_column(DatatypeKind::Date, &dates).unwrap();
+//! assert!(bytes.len() < 12);
+//! assert_eq!(archive::decode_column(DatatypeKind::Date, &bytes).unwrap(), dates);
+//! ```
+use chrono::{Datelike, NaiveDate};
+use std::collections::HashMap;
+
+use crate::*;
+
+const FORMAT_VERSION: u8 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[repr(u8)]
+enum Encoding {
+    Dictionary = 0,
+    Uuid = 1,
+    DayDelta = 2,
+    IntegerDelta = 3,
+}
+
+impl Encoding {
+    fn for_kind(kind: DatatypeKind) -> Self {
+        match kind {
+            DatatypeKind::Uuid => Encoding::Uuid,
+            DatatypeKind::Date => Encoding::DayDelta,
+            DatatypeKind::Integer
+            | DatatypeKind::NonNegativeInteger
+            | DatatypeKind::PositiveInteger => Encoding::IntegerDelta,
+            _ => Encoding::Dictionary,
+        }
+    }
+
+    fn from_byte(byte: u8, kind: DatatypeKind) -> Result<Self, Error> {
+        let encoding = match byte {
+            0 => Encoding::Dictionary,
+            1 => Encoding::Uuid,
+            2 => Encoding::DayDelta,
+            3 => Encoding::IntegerDelta,
+            _ => return Err(corrupt(format!("unknown encoding {byte}"))),
+        };
+        match encoding == Encoding::Dictionary || encoding == Self::for_kind(kind) {
+            true => Ok(encoding),
+            false => Err(corrupt(format!("{encoding:?} encoding used for {kind}"))),
+        }
+    }
+}
+
+fn corrupt(reason: impl Into<String>) -> Error {
+    Error::Archive(reason.into())
+}
+
+/// Encode a column of `values` of the datatype `kind`.  Every value is
+/// validated first.
+pub fn encode_column<S: AsRef<str>>(kind: DatatypeKind, values: &[S]) -> Result<Vec<u8>, Error> {
+    let values: Vec<&str> = values.iter().map(AsRef::as_ref).collect();
+    for value in &values {
+        kind.validate(value)?;
+    }
+    let mut out = vec![FORMAT_VERSION];
+    let encoding = Encoding::for_kind(kind);
+    let encoded = match encoding {
+        Encoding::Uuid => encode_uuids(&values),
+        Encoding::DayDelta => encode_dates(&values),
+        Encoding::IntegerDelta => encode_integers(&values),
+        Encoding::Dictionary => None,
+    };
+    match encoded {
+        Some(body) => {
+            out.push(encoding as u8);
+            write_varint(&mut out, values.len() as u128);
+            out.extend(body);
+        }
+        None => {
+            out.push(Encoding::Dictionary as u8);
+            write_varint(&mut out, values.len() as u128);
+            encode_dictionary(&mut out, &values);
+        }
+    }
+    Ok(out)
+}
+
+/// Decode a column written by [encode_column] for the same `kind`.
+/// Dictionary entries are validated; the other encodings can only produce
+/// well formed values.
+pub fn decode_column(kind: DatatypeKind, bytes: &[u8]) -> Result<Vec<String>, Error> {
+    let mut reader = Reader { bytes, position: 0 };
+    let version = reader.byte()?;
+    if version != FORMAT_VERSION {
+        return Err(corrupt(format!("unsupported format version {version}")));
+    }
+    let encoding = Encoding::from_byte(reader.byte()?, kind)?;
+    let count = reader.length()?;
+    let values = match encoding {
+        Encoding::Dictionary => decode_dictionary(&mut reader, kind, count)?,
+        Encoding::Uuid => decode_uuids(&mut reader, count)?,
+        Encoding::DayDelta => decode_dates(&mut reader, count)?,
+        Encoding::IntegerDelta => decode_integers(&mut reader, count)?,
+    };
+    match reader.position == bytes.len() {
+        true => Ok(values),
+        false => Err(corrupt("trailing bytes")),
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u128) {
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+fn zigzag(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+
+fn unzigzag(value: u128) -> i128 {
+    (value >> 1) as i128 ^ -((value & 1) as i128)
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl Reader<'_> {
+    fn take(&mut self, len: usize) -> Result<&[u8], Error> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or_else(|| corrupt("unexpected end of data"))?;
+        let taken = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(taken)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn varint(&mut self) -> Result<u128, Error> {
+        let mut value = 0u128;
+        for shift in (0..128).step_by(7) {
+            let byte = self.byte()?;
+            value |= u128::from(byte & 0x7F) << shift;
+            if byte < 0x80 {
+                return Ok(value);
+            }
+        }
+        Err(corrupt("varint too long"))
+    }
+
+    /// A count or length, which can be no larger than the remaining data.
+    fn length(&mut self) -> Result<usize, Error> {
+        let remaining = self.bytes.len() - self.position;
+        match usize::try_from(self.varint()?) {
+            Ok(len) if len <= remaining => Ok(len),
+            _ => Err(corrupt("length exceeds the data")),
+        }
+    }
+}
+
+fn encode_uuids(values: &[&str]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(values.len() * 16);
+    for value in values {
+        let uuid = ::uuid::Uuid::parse_str(value).ok()?;
+        if uuid.hyphenated().to_string() != *value {
+            return None;
+        }
+        out.extend(uuid.as_bytes());
+    }
+    Some(out)
+}
+
+fn decode_uuids(reader: &mut Reader, count: usize) -> Result<Vec<String>, Error> {
+    (0..count)
+        .map(|_| {
+            let bytes = reader.take(16)?.try_into().expect("16 bytes");
+            Ok(::uuid::Uuid::from_bytes(bytes).hyphenated().to_string())
+        })
+        .collect()
+}
+
+fn encode_dates(values: &[&str]) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    let mut previous = 0;
+    for value in values {
+        let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+        if date.format("%Y-%m-%d").to_string() != *value {
+            return None;
+        }
+        let days = i128::from(date.num_days_from_ce());
+        write_varint(&mut out, zigzag(days - previous));
+        previous = days;
+    }
+    Some(out)
+}
+
+fn decode_dates(reader: &mut Reader, count: usize) -> Result<Vec<String>, Error> {
+    let mut previous = 0i128;
+    (0..count)
+        .map(|_| {
+            previous = previous
+                .checked_add(unzigzag(reader.varint()?))
+                .ok_or_else(|| corrupt("date out of range"))?;
+            i32::try_from(previous)
+                .ok()
+                .and_then(NaiveDate::from_num_days_from_ce_opt)
+                .map(|date| date.format("%Y-%m-%d").to_string())
+                .ok_or_else(|| corrupt("date out of range"))
+        })
+        .collect()
+}
+
+fn encode_integers(values: &[&str]) -> Option<Vec<u8>> {
+    let mut out = vec![];
+    let mut previous = 0;
+    for value in values {
+        let number = value.parse::<i128>().ok()?;
+        if number.to_string() != *value {
+            return None;
+        }
+        write_varint(&mut out, zigzag(number - previous));
+        previous = number;
+    }
+    Some(out)
+}
+
+fn decode_integers(reader: &mut Reader, count: usize) -> Result<Vec<String>, Error> {
+    let mut previous = 0i128;
+    (0..count)
+        .map(|_| {
+            previous = previous
+                .checked_add(unzigzag(reader.varint()?))
+                .ok_or_else(|| corrupt("integer out of range"))?;
+            Ok(previous.to_string())
+        })
+        .collect()
+}
+
+/// Distinct values in order of first appearance, then one index per value.
+fn encode_dictionary(out: &mut Vec<u8>, values: &[&str]) {
+    let mut entries: Vec<&str> = vec![];
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let indices: Vec<usize> = values
+        .iter()
+        .map(|&value| {
+            *index.entry(value).or_insert_with(|| {
+                entries.push(value);
+                entries.len() - 1
+            })
+        })
+        .collect();
+    write_varint(out, entries.len() as u128);
+    for entry in entries {
+        write_varint(out, entry.len() as u128);
+        out.extend(entry.as_bytes());
+    }
+    for i in indices {
+        write_varint(out, i as u128);
+    }
+}
+
+fn decode_dictionary(
+    reader: &mut Reader,
+    kind: DatatypeKind,
+    count: usize,
+) -> Result<Vec<String>, Error> {
+    let entries = (0..reader.length()?)
+        .map(|_| {
+            let len = reader.length()?;
+            let entry = std::str::from_utf8(reader.take(len)?)
+                .map_err(|e| corrupt(e.to_string()))?
+                .to_string();
+            kind.validate(&entry)?;
+            Ok(entry)
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    (0..count)
+        .map(|_| {
+            let i = reader.varint()?;
+            usize::try_from(i)
+                .ok()
+                .and_then(|i| entries.get(i))
+                .cloned()
+                .ok_or_else(|| corrupt(format!("dictionary index {i} out of range")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip(kind: DatatypeKind, values: &[&str]) -> usize {
+        let bytes = encode_column(kind, values).unwrap();
+        assert_eq!(decode_column(kind, &bytes).unwrap(), values, "{kind}");
+        bytes.len()
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let uuids = [
+            "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+            "cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53",
+        ];
+        assert_eq!(round_trip(DatatypeKind::Uuid, &uuids), 3 + 32);
+        // Not canonical, so dictionary coded
+        round_trip(
+            DatatypeKind::Uuid,
+            &["A78F7E4C-A27A-4B1E-901B-EBFECF2B0301"],
+        );
+        round_trip(
+            DatatypeKind::Date,
+            &["2024-02-10", "1999-12-31", "2024-02-10"],
+        );
+        round_trip(
+            DatatypeKind::Integer,
+            &["-9223372036854775808", "0", "9223372036854775807"],
+        );
+        round_trip(
+            DatatypeKind::NonNegativeInteger,
+            &["18446744073709551615", "0"],
+        );
+        round_trip(DatatypeKind::Token, &["ac-1", "ac-2", "ac-1", "ac-1"]);
+        round_trip(DatatypeKind::String, &[] as &[&str]);
+    }
+
+    #[test]
+    fn test_size_reduction() {
+        let dates: Vec<String> = (0..1000)
+            .map(|i| {
+                (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap() + chrono::Days::new(i)).to_string()
+            })
+            .collect();
+        let tokens: Vec<String> = (0..1000).map(|i| format!("ac-{}", i % 20)).collect();
+        for (kind, values) in [(DatatypeKind::Date, dates), (DatatypeKind::Token, tokens)] {
+            let text: usize = values.iter().map(|v| v.len() + 1).sum();
+            let bytes = encode_column(kind, &values).unwrap();
+            assert!(bytes.len() * 4 < text, "{kind}: {} of {text}", bytes.len());
+        }
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert!(encode_column(DatatypeKind::Uuid, &["not-a-uuid"]).is_err());
+        let bytes = encode_column(DatatypeKind::Date, &["2024-02-10"]).unwrap();
+        assert!(matches!(
+            decode_column(DatatypeKind::Uuid, &bytes),
+            Err(Error::Archive(_))
+        ));
+        assert!(decode_column(DatatypeKind::Date, &bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_column(DatatypeKind::Date, &[1, 0, 0xFF]).is_err());
+        // A dictionary entry that is not a valid token
+        assert!(decode_column(DatatypeKind::Token, &[1, 0, 1, 1, 3, b'a', b' ', b'b', 0]).is_err());
+    }
+}
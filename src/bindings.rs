@@ -0,0 +1,199 @@
+//! Runtime datatype bindings for JSON documents.
+//!
+//! A [FieldBindings] maps path patterns to a [DatatypeKind], so models that
+//! are only known at runtime, e.g. generated from a metaschema, can still
+//! have their values validated by this crate.
+//!
+//! Patterns are JSON Pointers in which a `*` segment matches any single
+//! member name or array index, and a `**` segment matches any number of
+//! segments, including none.
+//!
+//! ```
+//! use oscal_types::{bindings::FieldBindings, DatatypeKind};
+//! use serde_json::json;
+//!
+//! let bindings = FieldBindings::new()
+//!     .bind("/**/last-modified", DatatypeKind::DateTimeWithTimezone)
+//!     .bind("/**/uuid", DatatypeKind::Uuid);
+//!
+//! let doc = json!({"catalog": {
+//!     "uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+//!     "metadata": {"last-modified": "yesterday"}
+//! }});
+//! let errors = bindings.validate(&doc);
+//! assert_eq!(errors.len(), 1);
+//! assert_eq!(errors[0].path(), "/catalog/metadata/last-modified");
+//! ```
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::de::PathError;
+use crate::walk::{lexical_form, walk_at};
+use crate::DatatypeKind;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldBinding {
+    pub path: String,
+    pub kind: DatatypeKind,
+}
+
+/// An ordered list of bindings.  The first matching pattern wins.
+///
+/// Serializes as a JSON array of `{"path": ..., "kind": ...}` objects, with
+/// kinds named by their type name.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FieldBindings {
+    bindings: Vec<FieldBinding>,
+}
+
+impl FieldBindings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bind values at paths matching `pattern` to `kind`.
+    pub fn bind(mut self, pattern: &str, kind: DatatypeKind) -> Self {
+        self.bindings.push(FieldBinding {
+            path: pattern.to_string(),
+            kind,
+        });
+        self
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &FieldBinding> {
+        self.bindings.iter()
+    }
+
+    /// The kind bound to the value at the JSON Pointer `pointer`.
+    pub fn kind_for(&self, pointer: &str) -> Option<DatatypeKind> {
+        let segments = split(pointer);
+        self.bindings
+            .iter()
+            .find(|binding| matches(&split(&binding.path), &segments))
+            .map(|binding| binding.kind)
+    }
+
+    /// Validate every scalar in `value` whose path is bound, returning one
+    /// error per invalid value.  Numbers and booleans are validated by their
+    /// JSON text.
+    pub fn validate(&self, value: &Value) -> Vec<PathError> {
+        self.validate_at(value, "")
+    }
+
+    /// Like [FieldBindings::validate], for a `value` found at the JSON
+    /// Pointer `pointer` of a larger document.
+    pub fn validate_at(&self, value: &Value, pointer: &str) -> Vec<PathError> {
+        let mut errors = vec![];
+        walk_at(value, pointer, self, &mut |pointer, kind, value| {
+            let text = lexical_form(value).unwrap_or_default();
+            if let Err(e) = kind.validate(&text) {
+                errors.push(PathError::new(pointer, format!("{kind}: {e}")));
+            }
+        });
+        errors
+    }
+}
+
+/// Split a JSON Pointer into its unescaped segments.
+fn split(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Whether `segments` match `pattern`, by the usual wildcard matching with
+/// backtracking to the last `**` only, so the time is at most the product
+/// of the lengths.
+fn matches(pattern: &[String], segments: &[String]) -> bool {
+    let (mut p, mut s) = (0, 0);
+    // The last `**` seen, and the first segment it has not yet matched
+    let mut backtrack = None;
+    while s < segments.len() {
+        match pattern.get(p).map(String::as_str) {
+            Some("**") => {
+                backtrack = Some((p, s));
+                p += 1;
+            }
+            Some(first) if first == "*" || first == segments[s] => {
+                p += 1;
+                s += 1;
+            }
+            _ => match backtrack {
+                Some((star, skipped)) => {
+                    backtrack = Some((star, skipped + 1));
+                    p = star + 1;
+                    s = skipped + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|rest| rest == "**")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_kind_for() {
+        let bindings = FieldBindings::new()
+            .bind("/catalog/groups/*/id", DatatypeKind::Token)
+            .bind("/**/props/*/value", DatatypeKind::String);
+
+        assert_eq!(
+            bindings.kind_for("/catalog/groups/0/id"),
+            Some(DatatypeKind::Token)
+        );
+        assert_eq!(bindings.kind_for("/catalog/groups/0/title"), None);
+        assert_eq!(
+            bindings.kind_for("/catalog/props/2/value"),
+            Some(DatatypeKind::String)
+        );
+        assert_eq!(
+            bindings.kind_for("/a/b/c/props/2/value"),
+            Some(DatatypeKind::String)
+        );
+    }
+
+    #[test]
+    fn test_globstar_backtracking() {
+        let bindings = FieldBindings::new().bind("/**/**/**/**/**/**/x", DatatypeKind::Token);
+        let deep = "/a".repeat(200);
+        assert_eq!(bindings.kind_for(&deep), None);
+        assert_eq!(
+            bindings.kind_for(&format!("{deep}/x")),
+            Some(DatatypeKind::Token)
+        );
+
+        let bindings = FieldBindings::new().bind("/a/**/b/*", DatatypeKind::Token);
+        assert!(bindings.kind_for("/a/b/0").is_some());
+        assert!(bindings.kind_for("/a/x/b/y/b/0").is_some());
+        assert!(bindings.kind_for("/a/b").is_none());
+        assert!(bindings.kind_for("/x/a/b/0").is_none());
+    }
+
+    #[test]
+    fn test_validate_numbers() {
+        let bindings = FieldBindings::new().bind("/*/count", DatatypeKind::PositiveInteger);
+        let errors = bindings.validate(&json!([{"count": 1}, {"count": 0}]));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].path(), "/1/count");
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let bindings = FieldBindings::new().bind("/**/uuid", DatatypeKind::Uuid);
+        let json = serde_json::to_string(&bindings).unwrap();
+        assert_eq!(json, r#"[{"path":"/**/uuid","kind":"UUIDDatatype"}]"#);
+        assert_eq!(
+            serde_json::from_str::<FieldBindings>(&json).unwrap(),
+            bindings
+        );
+        assert!(serde_json::from_str::<FieldBindings>(r#"[{"path":"/x","kind":"Nope"}]"#).is_err());
+    }
+}
@@ -1,12 +1,33 @@
+#[cfg(feature = "lenient_numbers")]
+use serde::Deserializer;
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::ops::Deref;
 
+#[cfg(feature = "lenient_numbers")]
+use crate::numbers::NativeOrLexical;
 use crate::{Base, Error, Metaschema, Validate};
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[cfg_attr(not(feature = "lenient_numbers"), derive(Deserialize))]
 #[serde(transparent)]
 pub struct BooleanDatatype(bool);
 
+/// With `lenient_numbers`, the strings `"true"` and `"false"` are accepted
+/// too.
+#[cfg(feature = "lenient_numbers")]
+impl<'de> Deserialize<'de> for BooleanDatatype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match NativeOrLexical::<bool>::deserialize(deserializer)? {
+            NativeOrLexical::Native(value) => Ok(Self(value)),
+            NativeOrLexical::Lexical(lexical) => {
+                Self::validate(&lexical).map_err(serde::de::Error::custom)?;
+                Ok(Self(lexical == "true"))
+            }
+        }
+    }
+}
+
 impl Metaschema for BooleanDatatype {
     fn description() -> Option<&'static str> {
         Some("A binary value that is either: true or false.")
@@ -32,6 +53,13 @@ impl Deref for BooleanDatatype {
     }
 }
 
+/// `true` or `false`.
+impl fmt::Display for BooleanDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 /// No validation
 impl From<bool> for BooleanDatatype {
     fn from(value: bool) -> Self {
@@ -74,4 +102,15 @@ mod tests {
         assert!(BooleanDatatype::validate("false").is_ok());
         assert!(BooleanDatatype::validate("1").is_err());
     }
+
+    #[cfg(feature = "lenient_numbers")]
+    #[test]
+    fn test_lenient_boolean() {
+        let from = |json: &str| serde_json::from_str::<BooleanDatatype>(json);
+        assert!(*from(r#""true""#).unwrap());
+        assert!(!*from(r#""false""#).unwrap());
+        assert!(!*from("false").unwrap());
+        assert!(from(r#""yes""#).is_err());
+        assert!(from("1").is_err());
+    }
 }
@@ -0,0 +1,224 @@
+//! Datatypes that borrow from the input.
+//!
+//! The string-backed datatypes are available here under their usual names
+//! as [Borrowed] wrappers around a `Cow<str>`.  Deserializing one from a
+//! `&str` or `&[u8]` input borrows the value rather than copying it unless
+//! it had to be unescaped, which saves an allocation per value when loading
+//! large catalogs.  Values are validated like the owned types, and can be
+//! converted to them with [Borrowed::into_owned].
+//!
+//! ```
+//! use oscal_types::borrowed::{TokenDatatype, UUIDDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Control<'a> {
+//!     #[serde(borrow)]
+//!     id: TokenDatatype<'a>,
+//!     #[serde(borrow)]
+//!     uuid: UUIDDatatype<'a>,
+//! }
+//!
+//! let json = br#"{"id": "ac-1", "uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"}"#;
+//! let control: Control = serde_json::from_slice(json).unwrap();
+//! assert_eq!(&*control.id, "ac-1");
+//! assert!(control.id.is_borrowed());
+//! assert!(serde_json::from_slice::<TokenDatatype>(br#""1ac""#).is_err());
+//! ```
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::registry::from_lexical;
+use crate::*;
+
+/// The validated lexical form of a `T`, borrowed where possible.
+pub struct Borrowed<'a, T> {
+    lexical: Cow<'a, str>,
+    datatype: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: OscalDatatype> Borrowed<'a, T> {
+    /// Validate `value` as a `T`.
+    pub fn new(value: impl Into<Cow<'a, str>>) -> Result<Self, Error> {
+        let lexical = value.into();
+        let result = T::validate(&lexical);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(T::KIND, result.is_ok());
+        result?;
+        Ok(Self {
+            lexical,
+            datatype: PhantomData,
+        })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.lexical
+    }
+
+    /// Whether the value still borrows from the input.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.lexical, Cow::Borrowed(_))
+    }
+
+    pub fn into_cow(self) -> Cow<'a, str> {
+        self.lexical
+    }
+
+    /// Copy the value into an owned `T`.
+    pub fn into_owned(self) -> T {
+        from_lexical(&self.lexical).expect("a validated lexical form")
+    }
+}
+
+impl<'a, T: OscalDatatype> TryFrom<&'a str> for Borrowed<'a, T> {
+    type Error = Error;
+    fn try_from(value: &'a str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for Borrowed<'_, T> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.lexical
+    }
+}
+
+impl<T> Clone for Borrowed<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            lexical: self.lexical.clone(),
+            datatype: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Borrowed<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lexical == other.lexical
+    }
+}
+
+impl<T> Eq for Borrowed<'_, T> {}
+
+impl<T> Hash for Borrowed<'_, T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.lexical.hash(state);
+    }
+}
+
+impl<T> PartialOrd for Borrowed<'_, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by lexical form.
+impl<T> Ord for Borrowed<'_, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lexical.cmp(&other.lexical)
+    }
+}
+
+impl<T> fmt::Debug for Borrowed<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Borrowed").field(&self.lexical).finish()
+    }
+}
+
+impl<T> Serialize for Borrowed<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.lexical)
+    }
+}
+
+impl<'de: 'a, 'a, T: OscalDatatype> Deserialize<'de> for Borrowed<'a, T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct CowVisitor;
+
+        impl<'de> Visitor<'de> for CowVisitor {
+            type Value = Cow<'de, str>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a string")
+            }
+
+            fn visit_borrowed_str<E: de::Error>(self, value: &'de str) -> Result<Self::Value, E> {
+                Ok(Cow::Borrowed(value))
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(value.to_owned()))
+            }
+
+            fn visit_string<E: de::Error>(self, value: String) -> Result<Self::Value, E> {
+                Ok(Cow::Owned(value))
+            }
+        }
+
+        let lexical: Cow<'de, str> = deserializer.deserialize_str(CowVisitor)?;
+        Self::new(lexical).map_err(de::Error::custom)
+    }
+}
+
+macro_rules! borrowed_aliases {
+    ($($t:ident),* $(,)?) => {
+        $(pub type $t<'a> = Borrowed<'a, crate::$t>;)*
+    };
+}
+
+borrowed_aliases!(
+    DateDatatype,
+    DateTimeDatatype,
+    DateTimeWithTimezoneDatatype,
+    DayTimeDurationDatatype,
+    YearMonthDurationDatatype,
+    TimezoneOffsetDatatype,
+    StringDatatype,
+    Base64Datatype,
+    EmailAddressDatatype,
+    HostnameDatatype,
+    IPV4AddressDatatype,
+    IPV6AddressDatatype,
+    IPV4CidrDatatype,
+    IPV6CidrDatatype,
+    MarkupLineDatatype,
+    MarkupMultilineDatatype,
+    TokenDatatype,
+    URIDatatype,
+    URIReferenceDatatype,
+    UUIDDatatype,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_borrowed() {
+        let json = r#"["https://example.com/a", "https:\/\/example.com\/b"]"#;
+        let uris: Vec<URIDatatype> = serde_json::from_str(json).unwrap();
+        assert!(uris[0].is_borrowed());
+        assert!(!uris[1].is_borrowed());
+        assert_eq!(&*uris[1], "https://example.com/b");
+        assert_eq!(
+            serde_json::to_string(&uris[0]).unwrap(),
+            r#""https://example.com/a""#
+        );
+
+        let token = TokenDatatype::try_from("ac-1").unwrap();
+        assert_eq!(
+            token.into_owned(),
+            crate::TokenDatatype::try_from("ac-1").unwrap()
+        );
+
+        assert!(serde_json::from_str::<UUIDDatatype>(r#""not-a-uuid""#).is_err());
+        assert!(serde_json::from_str::<StringDatatype>("42").is_err());
+    }
+}
@@ -0,0 +1,312 @@
+//! A C ABI over the validators, for tooling written in other languages.
+//!
+//! The functions here are declared in `include/oscal_types.h`.  Build a
+//! library to link against with
+//!
+//! ```text
+//! cargo rustc --release --features capi --crate-type cdylib
+//! ```
+//!
+//! or `--crate-type staticlib`.  Strings passed in are NUL-terminated UTF-8
+//! and are only borrowed for the duration of the call.  Error messages are
+//! returned through an optional `char **out_err`, and are owned by the
+//! caller, who frees them with [oscal_string_free].  Values constructed
+//! with [oscal_value_new] are opaque [OscalHandle]s, freed with
+//! [oscal_value_free].  A panic never unwinds into the caller: the function
+//! returns [OSCAL_ERROR] or null instead.
+//!
+//! ```c
+//! char *err = NULL;
+//! if (oscal_validate("UUIDDatatype", "not-a-uuid", &err) == OSCAL_INVALID) {
+//!     fprintf(stderr, "%s\n", err);
+//!     oscal_string_free(err);
+//! }
+//! ```
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::ptr;
+
+use crate::value::OscalValue;
+use crate::walk::lexical_form;
+use crate::*;
+
+/// The value is valid.
+pub const OSCAL_VALID: i32 = 0;
+/// The value is not valid for its datatype.
+pub const OSCAL_INVALID: i32 = 1;
+/// An argument was null or not UTF-8, the datatype name is unknown, or the
+/// library failed internally.
+pub const OSCAL_ERROR: i32 = -1;
+
+/// A value constructed by [oscal_value_new].
+pub struct OscalHandle {
+    value: OscalValue,
+    type_name: CString,
+    lexical: CString,
+}
+
+impl OscalHandle {
+    pub fn value(&self) -> &OscalValue {
+        &self.value
+    }
+}
+
+/// Run `f`, or `on_panic` if it panics, so that no panic unwinds across the
+/// C ABI.
+fn guard<R>(f: impl FnOnce() -> R, on_panic: impl FnOnce() -> R) -> R {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|_| on_panic())
+}
+
+/// A C string the caller owns.  Interior NULs, which a C string cannot
+/// hold, are dropped.
+fn owned(message: &str) -> *mut c_char {
+    let message = message.replace('\0', "");
+    CString::new(message).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Write `message` to `out_err` if the caller asked for it.
+///
+/// # Safety
+///
+/// `out_err` is null or valid for writes.
+unsafe fn report(out_err: *mut *mut c_char, message: &str) {
+    if !out_err.is_null() {
+        *out_err = owned(message);
+    }
+}
+
+/// Borrow a NUL-terminated UTF-8 string.
+///
+/// # Safety
+///
+/// `s` is null or points to a NUL-terminated string that outlives `'a`.
+unsafe fn borrow<'a>(s: *const c_char, argument: &str) -> Result<&'a str, String> {
+    if s.is_null() {
+        return Err(format!("{argument} is null"));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|_| format!("{argument} is not UTF-8"))
+}
+
+/// Parse `value` as the datatype named `type_name`, distinguishing bad
+/// arguments from invalid values.
+///
+/// # Safety
+///
+/// As [borrow], for both strings.
+unsafe fn parse(
+    type_name: *const c_char,
+    value: *const c_char,
+) -> Result<OscalValue, (i32, String)> {
+    let type_name = borrow(type_name, "type_name").map_err(|e| (OSCAL_ERROR, e))?;
+    let value = borrow(value, "value").map_err(|e| (OSCAL_ERROR, e))?;
+    let kind = type_name
+        .parse::<DatatypeKind>()
+        .map_err(|e| (OSCAL_ERROR, e.to_string()))?;
+    OscalValue::parse(kind, value).map_err(|e| (OSCAL_INVALID, format!("{kind}: {e}")))
+}
+
+/// Validate `value` as the datatype named `type_name`, e.g.
+/// `"DateDatatype"`.  Returns [OSCAL_VALID], [OSCAL_INVALID] or
+/// [OSCAL_ERROR], and on failure writes a message to `out_err` unless it is
+/// null.
+///
+/// # Safety
+///
+/// `type_name` and `value` are null or NUL-terminated strings, and
+/// `out_err` is null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn oscal_validate(
+    type_name: *const c_char,
+    value: *const c_char,
+    out_err: *mut *mut c_char,
+) -> i32 {
+    guard(
+        || match parse(type_name, value) {
+            Ok(_) => OSCAL_VALID,
+            Err((code, message)) => {
+                report(out_err, &message);
+                code
+            }
+        },
+        || {
+            report(out_err, "internal error");
+            OSCAL_ERROR
+        },
+    )
+}
+
+/// Construct a value of the datatype named `type_name`, or return null and
+/// write a message to `out_err` unless it is null.
+///
+/// # Safety
+///
+/// As [oscal_validate].
+#[no_mangle]
+pub unsafe extern "C" fn oscal_value_new(
+    type_name: *const c_char,
+    value: *const c_char,
+    out_err: *mut *mut c_char,
+) -> *mut OscalHandle {
+    guard(
+        || {
+            let value = match parse(type_name, value) {
+                Ok(value) => value,
+                Err((_, message)) => {
+                    report(out_err, &message);
+                    return ptr::null_mut();
+                }
+            };
+            let lexical = serde_json::to_value(&value)
+                .ok()
+                .and_then(|json| lexical_form(&json["value"]))
+                .unwrap_or_default();
+            Box::into_raw(Box::new(OscalHandle {
+                type_name: CString::new(value.type_name()).unwrap_or_default(),
+                lexical: CString::new(lexical.replace('\0', "")).unwrap_or_default(),
+                value,
+            }))
+        },
+        || {
+            report(out_err, "internal error");
+            ptr::null_mut()
+        },
+    )
+}
+
+/// The datatype name of `handle`, e.g. `"DateDatatype"`, borrowed from it.
+///
+/// # Safety
+///
+/// `handle` was returned by [oscal_value_new] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn oscal_value_type_name(handle: *const OscalHandle) -> *const c_char {
+    guard(
+        || match handle.as_ref() {
+            Some(handle) => handle.type_name.as_ptr(),
+            None => ptr::null(),
+        },
+        ptr::null,
+    )
+}
+
+/// The lexical form of `handle`, as it would be serialized, borrowed from
+/// it.
+///
+/// # Safety
+///
+/// As [oscal_value_type_name].
+#[no_mangle]
+pub unsafe extern "C" fn oscal_value_lexical(handle: *const OscalHandle) -> *const c_char {
+    guard(
+        || match handle.as_ref() {
+            Some(handle) => handle.lexical.as_ptr(),
+            None => ptr::null(),
+        },
+        ptr::null,
+    )
+}
+
+/// Free a value returned by [oscal_value_new].  Null is ignored.
+///
+/// # Safety
+///
+/// `handle` is null, or was returned by [oscal_value_new] and not yet
+/// freed.
+#[no_mangle]
+pub unsafe extern "C" fn oscal_value_free(handle: *mut OscalHandle) {
+    if !handle.is_null() {
+        guard(|| drop(Box::from_raw(handle)), || ());
+    }
+}
+
+/// Free a message written to an `out_err`.  Null is ignored.
+///
+/// # Safety
+///
+/// `s` is null, or was written to an `out_err` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn oscal_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        guard(|| drop(CString::from_raw(s)), || ());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn c(s: &str) -> CString {
+        CString::new(s).unwrap()
+    }
+
+    /// Take ownership of a message written to an `out_err`.
+    unsafe fn take(err: *mut c_char) -> String {
+        let message = CStr::from_ptr(err).to_str().unwrap().to_string();
+        oscal_string_free(err);
+        message
+    }
+
+    #[test]
+    fn test_validate() {
+        let date = c("DateDatatype");
+        unsafe {
+            let mut err = ptr::null_mut();
+            assert_eq!(
+                oscal_validate(date.as_ptr(), c("2024-02-10").as_ptr(), &mut err),
+                OSCAL_VALID
+            );
+            assert!(err.is_null());
+
+            // Without date_validation any string is a date, so use a UUID
+            let uuid = c("UUIDDatatype");
+            let status = oscal_validate(uuid.as_ptr(), c("not-a-uuid").as_ptr(), &mut err);
+            assert_eq!(status, OSCAL_INVALID);
+            assert!(take(err).starts_with("UUIDDatatype: "));
+
+            let status = oscal_validate(c("NoSuchDatatype").as_ptr(), c("x").as_ptr(), &mut err);
+            assert_eq!(status, OSCAL_ERROR);
+            take(err);
+            assert_eq!(
+                oscal_validate(ptr::null(), ptr::null(), ptr::null_mut()),
+                OSCAL_ERROR
+            );
+        }
+    }
+
+    #[test]
+    fn test_handles() {
+        unsafe {
+            let uuid = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+            let handle = oscal_value_new(
+                c("UUIDDatatype").as_ptr(),
+                c(uuid).as_ptr(),
+                ptr::null_mut(),
+            );
+            assert!(!handle.is_null());
+            assert_eq!(
+                CStr::from_ptr(oscal_value_lexical(handle)).to_str(),
+                Ok(uuid)
+            );
+            assert_eq!(
+                CStr::from_ptr(oscal_value_type_name(handle)).to_str(),
+                Ok("UUIDDatatype")
+            );
+            assert_eq!((*handle).value().kind(), DatatypeKind::Uuid);
+            oscal_value_free(handle);
+
+            let mut err = ptr::null_mut();
+            let count = c("PositiveIntegerDatatype");
+            assert!(oscal_value_new(count.as_ptr(), c("0").as_ptr(), &mut err).is_null());
+            assert_eq!(take(err), "PositiveIntegerDatatype: Number out of range");
+            oscal_value_free(ptr::null_mut());
+        }
+    }
+
+    #[test]
+    fn test_guard() {
+        assert_eq!(guard(|| OSCAL_VALID, || OSCAL_ERROR), OSCAL_VALID);
+        assert_eq!(guard(|| panic!("bug"), || OSCAL_ERROR), OSCAL_ERROR);
+    }
+}
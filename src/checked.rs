@@ -0,0 +1,97 @@
+//! Datatypes that always validate.
+//!
+//! Every datatype is available here under its usual name as a [Checked]
+//! wrapper.  Values are validated with [Validate::validate_strict] when
+//! constructed and deserialized, whatever crate features are enabled, so
+//! the behaviour is chosen where the type is imported.  See [crate::raw]
+//! for the opposite.
+//!
+//! ```
+//! use oscal_types::checked::DateDatatype;
+//!
+//! assert!(DateDatatype::try_from("2024-02-10").is_ok());
+//! assert!(DateDatatype::try_from("2024-02-30").is_err());
+//! assert!(serde_json::from_str::<DateDatatype>(r#""2024-02-30""#).is_err());
+//! ```
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::ops::Deref;
+
+use crate::macros::for_each_datatype;
+use crate::registry::from_lexical;
+use crate::walk::lexical_form;
+use crate::*;
+
+/// A `T` that passed [Validate::validate_strict].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Checked<T>(T);
+
+impl<T: OscalDatatype> Checked<T> {
+    /// Validate and parse the lexical form of a `T`.
+    pub fn new(value: &str) -> Result<Self, Error> {
+        T::validate_strict(value)?;
+        from_lexical(value).map(Self)
+    }
+
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T: OscalDatatype> TryFrom<&str> for Checked<T> {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for Checked<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Serialize> Serialize for Checked<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: OscalDatatype> Deserialize<'de> for Checked<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let lexical = lexical_form(&value)
+            .ok_or_else(|| serde::de::Error::custom("expected a string, number or boolean"))?;
+        Self::new(&lexical).map_err(serde::de::Error::custom)
+    }
+}
+
+macro_rules! checked_aliases {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        $(pub type $t = Checked<crate::$t>;)*
+    };
+}
+
+for_each_datatype!(checked_aliases);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked() {
+        let count = serde_json::from_str::<PositiveIntegerDatatype>("3").unwrap();
+        assert_eq!(**count, 3);
+        assert_eq!(serde_json::to_string(&count).unwrap(), "3");
+        assert!(serde_json::from_str::<PositiveIntegerDatatype>("0").is_err());
+        assert!(serde_json::from_str::<PositiveIntegerDatatype>("[]").is_err());
+
+        let date = DateDatatype::try_from("2024-02-10").unwrap();
+        assert_eq!(&**date, "2024-02-10");
+        assert_eq!(
+            date.into_inner(),
+            crate::DateDatatype::try_from("2024-02-10").unwrap()
+        );
+    }
+}
@@ -0,0 +1,243 @@
+//! Address blocks in CIDR notation, for network inventories.
+//!
+//! [IPV4CidrDatatype] and [IPV6CidrDatatype] hold an address and a prefix
+//! length written as `address/prefix-length`, e.g. `192.0.2.0/24` or
+//! `2001:db8::/32`.  The address may have host bits set, as interface
+//! addresses do; [IPV4CidrDatatype::network] clears them.
+//!
+//! CIDR blocks are not Metaschema datatypes, but like them they have a
+//! [crate::DatatypeKind] and take part in the name based dispatch.
+//!
+//! ```
+//! use oscal_types::cidr::IPV4CidrDatatype;
+//! use oscal_types::IPV4AddressDatatype;
+//!
+//! let subnet: IPV4CidrDatatype = serde_json::from_str(r#""192.0.2.17/24""#).unwrap();
+//! let host = IPV4AddressDatatype::try_from("192.0.2.200").unwrap();
+//! assert!(subnet.contains(host.to_ipv4()));
+//! assert_eq!(subnet.network().to_string(), "192.0.2.0");
+//! assert!("192.0.2.0/33".parse::<IPV4CidrDatatype>().is_err());
+//! ```
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::validate::validate_in_mode;
+use crate::*;
+
+/// Split `value` into its address and a prefix length of at most
+/// `max_prefix_len`.  The prefix length is decimal without leading zeros.
+fn split_cidr(value: &str, max_prefix_len: u8) -> Result<(&str, u8), Error> {
+    let invalid = |reason: &str| Err(Error::Cidr(format!("{value:?}: {reason}")));
+    let Some((addr, prefix_len)) = value.split_once('/') else {
+        return invalid("missing the /prefix-length");
+    };
+    let digits = !prefix_len.is_empty() && prefix_len.bytes().all(|b| b.is_ascii_digit());
+    if !digits || (prefix_len.len() > 1 && prefix_len.starts_with('0')) {
+        return invalid("the prefix length is not a decimal number");
+    }
+    match prefix_len.parse::<u8>() {
+        Ok(prefix_len) if prefix_len <= max_prefix_len => Ok((addr, prefix_len)),
+        _ => invalid(&format!(
+            "the prefix length is greater than {max_prefix_len}"
+        )),
+    }
+}
+
+macro_rules! cidr_impl {
+    ($t:ident, $addr:ty, $bits:ty, $max:expr, description = $d:expr) => {
+        #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+        #[serde(try_from = "&str")]
+        pub struct $t(String);
+
+        impl $t {
+            /// The block of `prefix_len` leading bits of `addr`.  Fails if
+            /// `prefix_len` is longer than the address.
+            pub fn new(addr: $addr, prefix_len: u8) -> Result<Self, Error> {
+                Self::try_from(format!("{addr}/{prefix_len}"))
+            }
+
+            /// The address as written, host bits included, or the
+            /// unspecified address for a value kept unchecked with
+            /// [ValidationMode::Off].
+            pub fn addr(&self) -> $addr {
+                split_cidr(&self.0, $max)
+                    .ok()
+                    .and_then(|(addr, _)| addr.parse().ok())
+                    .unwrap_or(<$addr>::UNSPECIFIED)
+            }
+
+            /// The prefix length, or the length of the address for a value
+            /// kept unchecked, so that such a block contains only
+            /// [Self::addr].
+            pub fn prefix_len(&self) -> u8 {
+                split_cidr(&self.0, $max).map_or($max, |(_, prefix_len)| prefix_len)
+            }
+
+            /// The leading bits that every address in the block shares.
+            fn mask(&self) -> $bits {
+                <$bits>::MAX
+                    .checked_shl(u32::from($max - self.prefix_len()))
+                    .unwrap_or(0)
+            }
+
+            /// The first address of the block: [Self::addr] with the host
+            /// bits cleared.
+            pub fn network(&self) -> $addr {
+                <$addr>::from(<$bits>::from(self.addr()) & self.mask())
+            }
+
+            /// Whether `addr` is in the block.
+            pub fn contains(&self, addr: $addr) -> bool {
+                <$bits>::from(addr) & self.mask() == <$bits>::from(self.network())
+            }
+        }
+
+        impl Validate for $t {
+            fn validate(value: &str) -> Result<(), Error> {
+                let (addr, _) = split_cidr(value, $max)?;
+                addr.parse::<$addr>()?;
+                Ok(())
+            }
+        }
+
+        impl Metaschema for $t {
+            fn _type() -> Option<&'static str> {
+                Some("string")
+            }
+            fn description() -> Option<&'static str> {
+                Some($d)
+            }
+        }
+
+        impl Base for $t {
+            fn base_type() -> String {
+                String::from("String")
+            }
+
+            fn ref_type() -> String {
+                String::from("str")
+            }
+        }
+
+        impl Deref for $t {
+            type Target = str;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $t {
+            type Err = Error;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::try_from(s)
+            }
+        }
+
+        impl TryFrom<&str> for $t {
+            type Error = Error;
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                let result = validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+                result.map(|_| Self(value.to_string()))
+            }
+        }
+
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+                result.map(|_| Self(value))
+            }
+        }
+    };
+}
+
+cidr_impl!(
+    IPV4CidrDatatype,
+    Ipv4Addr,
+    u32,
+    32,
+    description = "An Internet Protocol version 4 address block in the CIDR notation of RFC4632, e.g. 192.0.2.0/24."
+);
+
+cidr_impl!(
+    IPV6CidrDatatype,
+    Ipv6Addr,
+    u128,
+    128,
+    description = "An Internet Protocol version 6 address block in the notation of section 2.3 of RFC4291, e.g. 2001:db8::/32."
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ipv4_cidr() {
+        let block = IPV4CidrDatatype::new(Ipv4Addr::new(10, 1, 2, 3), 8).unwrap();
+        assert_eq!(&*block, "10.1.2.3/8");
+        assert_eq!(
+            (block.addr(), block.prefix_len()),
+            (Ipv4Addr::new(10, 1, 2, 3), 8)
+        );
+        assert_eq!(block.network(), Ipv4Addr::new(10, 0, 0, 0));
+        assert!(block.contains(Ipv4Addr::new(10, 255, 0, 1)));
+        assert!(!block.contains(Ipv4Addr::new(11, 0, 0, 0)));
+
+        let all: IPV4CidrDatatype = "0.0.0.0/0".parse().unwrap();
+        assert!(all.contains(Ipv4Addr::BROADCAST));
+        let host: IPV4CidrDatatype = "192.0.2.7/32".parse().unwrap();
+        assert!(host.contains(Ipv4Addr::new(192, 0, 2, 7)));
+        assert!(!host.contains(Ipv4Addr::new(192, 0, 2, 8)));
+
+        for invalid in [
+            "192.0.2.0",
+            "192.0.2.0/",
+            "192.0.2.0/024",
+            "192.0.2.0/+1",
+            "192.0.2/24",
+        ] {
+            assert!(invalid.parse::<IPV4CidrDatatype>().is_err(), "{invalid}");
+        }
+        let error = IPV4CidrDatatype::new(Ipv4Addr::LOCALHOST, 33).unwrap_err();
+        assert_eq!(error.code(), "cidr");
+    }
+
+    #[test]
+    fn test_ipv6_cidr() {
+        let block: IPV6CidrDatatype = serde_json::from_str(r#""2001:db8::1/32""#).unwrap();
+        assert_eq!(block.network(), "2001:db8::".parse::<Ipv6Addr>().unwrap());
+        assert!(block.contains("2001:db8:ffff::1".parse().unwrap()));
+        assert!(!block.contains("2001:db9::".parse().unwrap()));
+        assert_eq!(
+            serde_json::to_string(&block).unwrap(),
+            r#""2001:db8::1/32""#
+        );
+
+        let all = IPV6CidrDatatype::new(Ipv6Addr::UNSPECIFIED, 0).unwrap();
+        assert!(all.contains(Ipv6Addr::LOCALHOST));
+        assert!("::1/129".parse::<IPV6CidrDatatype>().is_err());
+        assert!("192.0.2.0/24".parse::<IPV6CidrDatatype>().is_err());
+    }
+
+    #[test]
+    fn test_dispatch_by_name() {
+        let kind: DatatypeKind = "IPV4CidrDatatype".parse().unwrap();
+        assert_eq!(kind, DatatypeKind::Ipv4Cidr);
+        assert!(kind.validate("192.0.2.0/24").is_ok());
+        assert!(validate_by_name("IPV6CidrDatatype", "2001:db8::/32").is_ok());
+        assert!(validate_by_name("IPV6CidrDatatype", "2001:db8::/129").is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! The source of the current time for the `new()` constructors.
+//!
+//! All constructors that read the time go through the [Clock] in
+//! [crate::config::Config::clock].  A [Clock::Fixed] clock makes generated
+//! documents reproducible:
+//!
+//! ```
+//! use chrono::DateTime;
+//! use oscal_types::clock::Clock;
+//! use oscal_types::{config, DateDatatype};
+//!
+//! let now = DateTime::parse_from_rfc3339("2024-02-10T23:30:00-05:00").unwrap();
+//! let mut fixed = config::Config::new();
+//! fixed.clock = Clock::Fixed(now);
+//! config::scoped(fixed, || assert_eq!(&*DateDatatype::new(), "2024-02-10"));
+//! ```
+//!
+//! Sandboxes often have no timezone data.  The system clock then uses UTC
+//! as the local timezone and records a [Warning::LocalTimezone].  Setting
+//! [crate::config::Config::default_offset] skips the local timezone lookup
+//! altogether.
+use chrono::{DateTime, FixedOffset, Local, Offset, TimeZone, Utc};
+
+use crate::config;
+use crate::warnings::{self, Warning};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Clock {
+    /// The system clock and local timezone.
+    #[default]
+    System,
+    /// A fixed instant.  Its offset is used as the local timezone.
+    Fixed(DateTime<FixedOffset>),
+}
+
+impl Clock {
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        match self {
+            Clock::System => Utc::now(),
+            Clock::Fixed(now) => now.with_timezone(&Utc),
+        }
+    }
+
+    /// The current time in the local timezone, or in UTC if there is no
+    /// timezone data to determine it.
+    pub fn now_local(&self) -> DateTime<FixedOffset> {
+        match self {
+            Clock::System => {
+                let now = Utc::now();
+                match local_offset(&now) {
+                    Ok(offset) => now.with_timezone(&offset),
+                    Err(message) => {
+                        warnings::emit(Warning::LocalTimezone(message));
+                        now.fixed_offset()
+                    }
+                }
+            }
+            Clock::Fixed(now) => *now,
+        }
+    }
+}
+
+/// The local offset at `now`, or why the local timezone is unknown.
+///
+/// Without a `TZ` variable or `/etc/localtime` chrono treats the local
+/// timezone as UTC, so report that rather than pass it off as local time.
+fn local_offset(now: &DateTime<Utc>) -> Result<FixedOffset, String> {
+    #[cfg(unix)]
+    if std::env::var_os("TZ").is_none() && !std::path::Path::new("/etc/localtime").exists() {
+        return Err("neither TZ nor /etc/localtime is set".to_string());
+    }
+    Ok(Local.offset_from_utc_datetime(&now.naive_utc()).fix())
+}
+
+/// The current time from the configured clock, in UTC.
+pub fn now_utc() -> DateTime<Utc> {
+    config::get().clock.now_utc()
+}
+
+/// The current time from the configured clock, at
+/// [config::Config::default_offset] if one is configured, otherwise in the
+/// local timezone.
+pub fn now() -> DateTime<FixedOffset> {
+    let config = config::get();
+    match config.default_offset {
+        Some(offset) => config.clock.now_utc().with_timezone(&offset),
+        None => config.clock.now_local(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_clock() {
+        let instant = DateTime::parse_from_rfc3339("2024-02-10T23:30:00-05:00").unwrap();
+        let clock = Clock::Fixed(instant);
+        assert_eq!(clock.now_utc().to_rfc3339(), "2024-02-11T04:30:00+00:00");
+        assert_eq!(clock.now_local(), instant);
+
+        let utc = config::Config {
+            clock,
+            default_offset: Some(FixedOffset::east_opt(0).unwrap()),
+            ..config::Config::new()
+        };
+        config::scoped(utc, || {
+            assert_eq!(now().to_rfc3339(), "2024-02-11T04:30:00+00:00");
+        });
+    }
+
+    #[test]
+    fn test_system_clock() {
+        let local = Clock::System.now_local();
+        let utc = Clock::System.now_utc();
+        assert!((utc - local.with_timezone(&Utc)).num_seconds().abs() < 60);
+    }
+}
@@ -0,0 +1,228 @@
+//! Runtime configuration.
+//!
+//! A process wide [Config] is read by the datatypes during deserialization.
+//! It can be replaced with [set], or overridden for the current thread with
+//! [scoped], which is handy for tests and per-request policies.
+//!
+//! With the `env-config` feature, [Config::from_env] builds a configuration
+//! from the environment and reports variables it cannot use, so it can be
+//! installed with [set] at startup.
+//!
+//! More settings may be added in any release, so a [Config] outside this
+//! crate starts from [Config::new] and has its fields assigned:
+//!
+//! ```
+//! use oscal_types::config;
+//!
+//! let mut trim = config::Config::new();
+//! trim.trim_strings = true;
+//! config::scoped(trim, || assert!(config::get().trim_strings));
+//! ```
+//!
+use chrono::FixedOffset;
+use std::cell::RefCell;
+use std::sync::RwLock;
+
+use crate::clock::Clock;
+use crate::dates::OffsetStyle;
+use crate::maybe::{PlaceholderKind, PlaceholderOutput};
+use crate::{Error, ValidationMode};
+
+/// The default namespace of OSCAL property and annotation names.
+pub const OSCAL_NAMESPACE: &str = "http://csrc.nist.gov/ns/oscal";
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Config {
+    /// Trim leading and trailing whitespace from [crate::StringDatatype]
+    /// values on deserialization instead of rejecting them.  Every trimmed
+    /// value is reported through [crate::warnings].
+    pub trim_strings: bool,
+    /// Collapse whitespace in [crate::StringDatatype] and
+    /// [crate::TokenDatatype] values on deserialization, as the XSD
+    /// `collapse` facet does: see [crate::StringDatatype::normalized].
+    /// Every changed value is reported through [crate::warnings] with its
+    /// original form.  Takes precedence over [Config::trim_strings].
+    pub collapse_whitespace: bool,
+    /// Offset used by the `new()` date and time constructors.  When unset,
+    /// dates and naive date-times use the local timezone and
+    /// [crate::DateTimeWithTimezoneDatatype] uses UTC.
+    pub default_offset: Option<FixedOffset>,
+    /// Namespace applied to names that do not declare one.  See
+    /// [Config::namespace].
+    pub default_namespace: Option<String>,
+    /// Accept [crate::Base64Datatype] values wrapped across lines, as YAML
+    /// block scalars and MIME style encoders produce, when deserializing
+    /// from human readable formats.  The line breaks are removed.
+    pub base64_line_breaks: bool,
+    /// Sentinels accepted as [crate::maybe::Maybe] placeholders, matched
+    /// ignoring case.  When unset, [crate::maybe::DEFAULT_SENTINELS] apply.
+    pub placeholder_sentinels: Option<Vec<(String, PlaceholderKind)>>,
+    /// How [crate::maybe::Maybe] placeholders are serialized.
+    pub placeholder_output: PlaceholderOutput,
+    /// Reject [crate::UUIDDatatype] values of any version other than 4
+    /// and 5, the versions OSCAL allows.  Any RFC 4122 UUID is accepted
+    /// otherwise.
+    pub strict_uuid_versions: bool,
+    /// Accept version 7 [crate::UUIDDatatype] values in addition to
+    /// versions 4 and 5 when [Config::strict_uuid_versions] is set.
+    pub uuid_v7: bool,
+    /// The source of the current time for the `new()` constructors.
+    pub clock: Clock,
+    /// How deeply markup blocks and inlines may nest, e.g. lists within
+    /// block quotes or emphasis within links.  Deeper markup fails with
+    /// [Error::MarkupTooComplex].
+    pub markup_max_depth: usize,
+    /// The largest markup value accepted, in bytes.
+    pub markup_max_size: usize,
+    /// How UTC offsets are written when date-times are serialized.
+    pub offset_style: OffsetStyle,
+    /// How values stored as their lexical form are checked when they are
+    /// constructed or deserialized.
+    pub validation_mode: ValidationMode,
+}
+
+impl Config {
+    pub const fn new() -> Self {
+        Self {
+            trim_strings: false,
+            collapse_whitespace: false,
+            default_offset: None,
+            default_namespace: None,
+            base64_line_breaks: false,
+            placeholder_sentinels: None,
+            placeholder_output: PlaceholderOutput::Sentinel,
+            strict_uuid_versions: false,
+            uuid_v7: false,
+            clock: Clock::System,
+            markup_max_depth: 64,
+            markup_max_size: 1 << 20,
+            offset_style: OffsetStyle::PreserveInput,
+            validation_mode: ValidationMode::Lenient,
+        }
+    }
+
+    /// The namespace in effect for names without an explicit one:
+    /// [Config::default_namespace] if set, otherwise [OSCAL_NAMESPACE].
+    pub fn namespace(&self) -> &str {
+        self.default_namespace.as_deref().unwrap_or(OSCAL_NAMESPACE)
+    }
+
+    /// Build a configuration from environment variables:
+    ///
+    /// - `OSCAL_TYPES_DEFAULT_TZ`: `Z`, `UTC` or a fixed offset such as
+    ///   `-05:00`.  Named zones like `America/New_York` are not supported.
+    /// - `OSCAL_TYPES_DEFAULT_NS`: the default namespace URI.
+    ///
+    /// Unset variables keep their defaults.
+    #[cfg(feature = "env-config")]
+    pub fn from_env() -> Result<Self, Error> {
+        let mut config = Self::new();
+        if let Ok(tz) = std::env::var("OSCAL_TYPES_DEFAULT_TZ") {
+            config.default_offset = Some(parse_offset(&tz)?);
+        }
+        if let Ok(ns) = std::env::var("OSCAL_TYPES_DEFAULT_NS") {
+            crate::URIDatatype::try_from(ns.as_str())?;
+            config.default_namespace = Some(ns);
+        }
+        Ok(config)
+    }
+}
+
+/// Parse `Z`, `UTC` or an RFC 3339 style numeric offset.
+pub fn parse_offset(value: &str) -> Result<FixedOffset, Error> {
+    match value {
+        "Z" | "UTC" => Ok(FixedOffset::east_opt(0).expect("zero offset")),
+        _ => value
+            .parse::<FixedOffset>()
+            .map_err(|_| Error::Config(format!("unsupported timezone offset {value:?}"))),
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+static GLOBAL: RwLock<Config> = RwLock::new(Config::new());
+
+thread_local! {
+    static SCOPED: RefCell<Option<Config>> = const { RefCell::new(None) };
+}
+
+/// The configuration in effect on this thread.
+pub fn get() -> Config {
+    SCOPED
+        .with(|scoped| scoped.borrow().clone())
+        .unwrap_or_else(|| match GLOBAL.read() {
+            Ok(config) => config.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        })
+}
+
+/// Call `f` with the configuration in effect on this thread, without
+/// cloning it as [get] does.
+pub(crate) fn read<R>(f: impl FnOnce(&Config) -> R) -> R {
+    SCOPED.with(|scoped| match &*scoped.borrow() {
+        Some(config) => f(config),
+        None => match GLOBAL.read() {
+            Ok(config) => f(&config),
+            Err(poisoned) => f(&poisoned.into_inner()),
+        },
+    })
+}
+
+/// Replace the process wide configuration.
+pub fn set(config: Config) {
+    match GLOBAL.write() {
+        Ok(mut global) => *global = config,
+        Err(poisoned) => *poisoned.into_inner() = config,
+    }
+}
+
+/// Run `f` with `config` in effect on the current thread only.
+pub fn scoped<R>(config: Config, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Config>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SCOPED.with(|scoped| *scoped.borrow_mut() = previous);
+        }
+    }
+
+    let _restore = Restore(SCOPED.with(|scoped| scoped.borrow_mut().replace(config)));
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scoped_config() {
+        let trim = Config {
+            trim_strings: true,
+            ..Config::new()
+        };
+        assert!(scoped(trim, || get().trim_strings));
+        assert!(!get().trim_strings);
+    }
+
+    #[test]
+    fn test_parse_offset() {
+        assert_eq!(parse_offset("Z").unwrap().local_minus_utc(), 0);
+        assert_eq!(parse_offset("-05:00").unwrap().local_minus_utc(), -5 * 3600);
+        assert!(parse_offset("America/New_York").is_err());
+    }
+
+    #[test]
+    fn test_namespace() {
+        assert_eq!(Config::new().namespace(), OSCAL_NAMESPACE);
+        let config = Config {
+            default_namespace: Some("https://fedramp.gov/ns/oscal".to_string()),
+            ..Config::new()
+        };
+        assert_eq!(config.namespace(), "https://fedramp.gov/ns/oscal");
+    }
+}
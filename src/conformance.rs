@@ -0,0 +1,242 @@
+//! Checking at startup that the build validates as a deployment requires.
+//!
+//! How strictly values are validated depends on the crate features chosen
+//! anywhere in the dependency tree and on the runtime [crate::config].
+//! [self_check] compares both with the guarantees an application needs and
+//! reports every one that is not met, so a misconfigured deployment fails
+//! fast instead of quietly accepting invalid documents.
+//!
+//! ```
+//! use oscal_types::conformance::{active_features, ConformanceRequirements};
+//! use oscal_types::{self_check, ValidationMode};
+//!
+//! let required = ConformanceRequirements {
+//!     strict_dates: true,
+//!     minimum_mode: ValidationMode::Lenient,
+//!     ..ConformanceRequirements::new()
+//! };
+//! match self_check(required) {
+//!     Ok(()) => assert!(active_features().contains(&"date_validation")),
+//!     // Built without date_validation: refuse to start
+//!     Err(report) => assert!(report.to_string().contains("date_validation")),
+//! }
+//! ```
+use serde::Serialize;
+use std::fmt;
+
+use crate::*;
+
+/// The validation guarantees a deployment requires.  [Self::new] requires
+/// none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConformanceRequirements {
+    /// Dates and date-times are parsed, not only matched against a pattern:
+    /// the `date_validation` feature, or the [ValidationMode::Strict] mode.
+    pub strict_dates: bool,
+    /// Values are held to the published Metaschema patterns: the
+    /// `regex_validation` feature.
+    pub patterns: bool,
+    /// Markup is parsed and checked against the OSCAL subset: the `markup`
+    /// feature.
+    pub markup: bool,
+    /// Internationalized hostnames are checked: the `idn` feature.
+    pub idn: bool,
+    /// Decimals keep the precision they were written with: the
+    /// `exact_decimal` feature.
+    pub exact_decimals: bool,
+    /// Numbers and booleans written as strings are rejected: the
+    /// `lenient_numbers` feature is off.
+    pub native_numbers: bool,
+    /// The weakest [crate::config::Config::validation_mode] allowed.
+    pub minimum_mode: ValidationMode,
+}
+
+impl ConformanceRequirements {
+    pub const fn new() -> Self {
+        Self {
+            strict_dates: false,
+            patterns: false,
+            markup: false,
+            idn: false,
+            exact_decimals: false,
+            native_numbers: false,
+            minimum_mode: ValidationMode::Off,
+        }
+    }
+}
+
+impl Default for ConformanceRequirements {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A requirement that is not met.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Unmet {
+    /// The [ConformanceRequirements] field, e.g. `"strict_dates"`.
+    pub requirement: &'static str,
+    /// What is missing, and how to provide it.
+    pub message: String,
+}
+
+/// Every unmet requirement, with the features and mode in effect.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConformanceReport {
+    pub unmet: Vec<Unmet>,
+    /// The validation features this crate was built with.
+    pub features: Vec<&'static str>,
+    pub mode: String,
+}
+
+impl fmt::Display for ConformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "oscal_types does not meet {} conformance requirement(s) \
+             (features: [{}], validation mode: {}):",
+            self.unmet.len(),
+            self.features.join(", "),
+            self.mode
+        )?;
+        for unmet in &self.unmet {
+            writeln!(f, "  {}: {}", unmet.requirement, unmet.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConformanceReport {}
+
+/// The validation features enabled in this build.
+pub fn active_features() -> Vec<&'static str> {
+    [
+        ("date_validation", cfg!(feature = "date_validation")),
+        ("exact_decimal", cfg!(feature = "exact_decimal")),
+        ("idn", cfg!(feature = "idn")),
+        ("lenient_numbers", cfg!(feature = "lenient_numbers")),
+        ("markup", cfg!(feature = "markup")),
+        ("regex_validation", cfg!(feature = "regex_validation")),
+    ]
+    .into_iter()
+    .filter_map(|(feature, enabled)| enabled.then_some(feature))
+    .collect()
+}
+
+/// How much checking `mode` does, for comparing modes.
+fn strength(mode: ValidationMode) -> u8 {
+    match mode {
+        ValidationMode::Off => 0,
+        ValidationMode::Lenient => 1,
+        ValidationMode::Strict => 2,
+    }
+}
+
+/// Check the compiled features and the configuration in effect on this
+/// thread against `required`.
+pub fn self_check(required: ConformanceRequirements) -> Result<(), ConformanceReport> {
+    let mode = config::read(|config| config.validation_mode);
+    let mut unmet = Vec::new();
+    let mut require = |requirement, met: bool, message: &str| {
+        if !met {
+            unmet.push(Unmet {
+                requirement,
+                message: message.to_string(),
+            });
+        }
+    };
+
+    let dates = match mode {
+        ValidationMode::Strict => true,
+        ValidationMode::Lenient => cfg!(feature = "date_validation"),
+        ValidationMode::Off => false,
+    };
+    require(
+        "strict_dates",
+        !required.strict_dates || dates,
+        "dates are not parsed; enable the `date_validation` feature and do not turn validation off",
+    );
+    require(
+        "patterns",
+        !required.patterns || (cfg!(feature = "regex_validation") && mode != ValidationMode::Off),
+        "patterns are not checked; enable the `regex_validation` feature",
+    );
+    require(
+        "markup",
+        !required.markup || cfg!(feature = "markup"),
+        "markup is not parsed; enable the `markup` feature",
+    );
+    require(
+        "idn",
+        !required.idn || cfg!(feature = "idn"),
+        "internationalized hostnames are not checked; enable the `idn` feature",
+    );
+    require(
+        "exact_decimals",
+        !required.exact_decimals || cfg!(feature = "exact_decimal"),
+        "decimals are rounded to f64; enable the `exact_decimal` feature",
+    );
+    require(
+        "native_numbers",
+        !required.native_numbers || !cfg!(feature = "lenient_numbers"),
+        "numbers written as strings are accepted; disable the `lenient_numbers` feature",
+    );
+    require(
+        "minimum_mode",
+        strength(mode) >= strength(required.minimum_mode),
+        &format!(
+            "the validation mode is {mode:?}, weaker than {:?}",
+            required.minimum_mode
+        ),
+    );
+
+    match unmet.is_empty() {
+        true => Ok(()),
+        false => Err(ConformanceReport {
+            unmet,
+            features: active_features(),
+            mode: format!("{mode:?}"),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_check() {
+        assert!(self_check(ConformanceRequirements::new()).is_ok());
+
+        let required = ConformanceRequirements {
+            strict_dates: true,
+            minimum_mode: ValidationMode::Strict,
+            ..ConformanceRequirements::new()
+        };
+        let report = self_check(required.clone()).unwrap_err();
+        let unmet: Vec<_> = report.unmet.iter().map(|u| u.requirement).collect();
+        match cfg!(feature = "date_validation") {
+            true => assert_eq!(unmet, ["minimum_mode"]),
+            false => assert_eq!(unmet, ["strict_dates", "minimum_mode"]),
+        }
+        assert!(report
+            .to_string()
+            .contains("minimum_mode: the validation mode is Lenient, weaker than Strict"));
+
+        let strict = config::Config {
+            validation_mode: ValidationMode::Strict,
+            ..config::Config::new()
+        };
+        assert!(config::scoped(strict, || self_check(required)).is_ok());
+
+        let off = config::Config {
+            validation_mode: ValidationMode::Off,
+            ..config::Config::new()
+        };
+        let dates = ConformanceRequirements {
+            strict_dates: true,
+            ..ConformanceRequirements::new()
+        };
+        assert!(config::scoped(off, || self_check(dates)).is_err());
+    }
+}
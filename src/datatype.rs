@@ -0,0 +1,231 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::{Debug, Display};
+
+use crate::de::PathError;
+use crate::macros::for_each_datatype;
+use crate::metapath::ToMetapathValue;
+use crate::registry::from_lexical;
+use crate::*;
+
+/// The full set of capabilities shared by every OSCAL datatype, for use as
+/// a single generic bound:
+///
+/// ```
+/// use oscal_types::{DatatypeKind, OscalDatatype};
+///
+/// fn kind_of<T: OscalDatatype>(_value: &T) -> DatatypeKind {
+///     T::KIND
+/// }
+/// ```
+pub trait OscalDatatype:
+    Base
+    + Metaschema
+    + Validate
+    + ToMetapathValue
+    + Serialize
+    + DeserializeOwned
+    + Clone
+    + Debug
+    + Display
+    + PartialEq
+    + Send
+    + Sync
+    + 'static
+{
+    const KIND: DatatypeKind;
+
+    /// Validate `value`, collecting every violation instead of stopping at
+    /// the first.  See [crate::report].
+    fn validate_detailed(value: &str) -> crate::report::ValidationReport {
+        crate::report::validate_detailed(Self::KIND, value)
+    }
+
+    /// Construct from the lexical form `value`, as [crate::value::OscalValue::parse],
+    /// blaming any failure on `path`, the JSON Pointer of the value in its
+    /// document:
+    ///
+    /// ```
+    /// use oscal_types::{DateTimeWithTimezoneDatatype, OscalDatatype};
+    ///
+    /// let error = DateTimeWithTimezoneDatatype::parse_with_context(
+    ///     "2024-02-30T00:00:00Z",
+    ///     "/metadata/last-modified",
+    /// )
+    /// .unwrap_err();
+    /// assert_eq!(error.path(), "/metadata/last-modified");
+    /// assert_eq!(
+    ///     error.message(),
+    ///     "DateTimeWithTimezoneDatatype: Date parsing error: input is out of range"
+    /// );
+    /// ```
+    fn parse_with_context(value: &str, path: &str) -> Result<Self, PathError> {
+        from_lexical::<Self>(value)
+            .map_err(|e| PathError::new(path, format!("{}: {e}", Self::KIND)))
+    }
+
+    /// Construct from the lexical form `value`, checked in `mode` rather
+    /// than the [config::Config::validation_mode] in effect:
+    ///
+    /// ```
+    /// use oscal_types::{DateDatatype, OscalDatatype, ValidationMode};
+    ///
+    /// let date = DateDatatype::parse_with_mode("2024-02-10Z", ValidationMode::Off).unwrap();
+    /// assert_eq!(&*date, "2024-02-10Z");
+    /// assert!(DateDatatype::parse_with_mode("2024-02-10Z", ValidationMode::Strict).is_err());
+    /// ```
+    fn parse_with_mode(value: &str, mode: ValidationMode) -> Result<Self, Error> {
+        let config = config::Config {
+            validation_mode: mode,
+            ..config::get()
+        };
+        config::scoped(config, || from_lexical::<Self>(value))
+    }
+}
+
+macro_rules! oscal_datatype_impl {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        $(
+            impl OscalDatatype for $t {
+                const KIND: DatatypeKind = DatatypeKind::$kind;
+            }
+        )*
+    };
+}
+
+for_each_datatype!(oscal_datatype_impl);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe<T: OscalDatatype>() -> (DatatypeKind, Option<&'static str>) {
+        (T::KIND, T::description())
+    }
+
+    macro_rules! assert_described {
+        ($($kind:ident => $t:ident),* $(,)?) => {
+            $(
+                let (kind, description) = describe::<$t>();
+                assert_eq!(kind, DatatypeKind::$kind);
+                assert!(description.is_some(), "{} has no description", kind);
+            )*
+        };
+    }
+
+    #[test]
+    fn test_display_is_lexical_form() {
+        fn display<T: OscalDatatype>(lexical: &str) -> String {
+            from_lexical::<T>(lexical).unwrap().to_string()
+        }
+        assert_eq!(display::<BooleanDatatype>("false"), "false");
+        assert_eq!(display::<IntegerDatatype>("-12"), "-12");
+        assert_eq!(display::<PositiveIntegerDatatype>("7"), "7");
+        assert_eq!(display::<DecimalDatatype>("2.5"), "2.5");
+        assert_eq!(display::<DateDatatype>("2024-02-10"), "2024-02-10");
+        assert_eq!(display::<URIDatatype>("urn:x:y"), "urn:x:y");
+        assert_eq!(display::<TokenDatatype>("ac-1"), "ac-1");
+        let uuid = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+        assert_eq!(display::<UUIDDatatype>(uuid), uuid);
+        assert_eq!(
+            format!("{}", TimezoneOffsetDatatype::try_from("+05:30").unwrap()),
+            "+05:30"
+        );
+    }
+
+    #[test]
+    fn test_parse_with_context() {
+        let uuid = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+        assert_eq!(
+            &*UUIDDatatype::parse_with_context(uuid, "/uuid").unwrap(),
+            uuid
+        );
+        let count = PositiveIntegerDatatype::parse_with_context("3", "/count").unwrap();
+        assert_eq!(*count, 3);
+
+        let error = PositiveIntegerDatatype::parse_with_context("0", "/props/2/value").unwrap_err();
+        assert_eq!(error.path(), "/props/2/value");
+        assert_eq!(
+            error.message(),
+            "PositiveIntegerDatatype: Number out of range"
+        );
+        let error = URIDatatype::parse_with_context("no scheme", "").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("URIDatatype: URI parsing error: "));
+    }
+
+    #[test]
+    fn test_parse_with_mode() {
+        let spaced = "2024-02-10 09:30";
+        let lenient = DateTimeDatatype::parse_with_mode(spaced, ValidationMode::Lenient);
+        assert_eq!(
+            lenient.is_ok(),
+            !cfg!(any(
+                feature = "date_validation",
+                feature = "regex_validation"
+            ))
+        );
+        assert!(DateTimeDatatype::parse_with_mode(spaced, ValidationMode::Strict).is_err());
+        let uuid = UUIDDatatype::parse_with_mode("TBD", ValidationMode::Off).unwrap();
+        assert_eq!((&*uuid, uuid.version()), ("TBD", 0));
+        assert!(IntegerDatatype::parse_with_mode("many", ValidationMode::Off).is_err());
+        assert_eq!(config::get().validation_mode, ValidationMode::Lenient);
+
+        let off = config::Config {
+            validation_mode: ValidationMode::Off,
+            ..config::Config::new()
+        };
+        let date: DateDatatype =
+            config::scoped(off, || serde_json::from_str(r#""2024-02-30""#)).unwrap();
+        assert_eq!(&*date, "2024-02-30");
+    }
+
+    #[test]
+    fn test_off_mode_accessors() {
+        let off = config::Config {
+            validation_mode: ValidationMode::Off,
+            ..config::Config::new()
+        };
+        config::scoped(off, || {
+            let date_time = DateTimeWithTimezoneDatatype::try_from("soon").unwrap();
+            assert_eq!(date_time.to_utc(), chrono::DateTime::UNIX_EPOCH);
+            assert_eq!(date_time.to_fixed_offset().offset().local_minus_utc(), 0);
+            assert_eq!(&*date_time.offset(), "+00:00");
+            let tbd = DateTimeWithTimezoneDatatype::parse_with_mode("TBD", ValidationMode::Off);
+            assert_eq!(&*tbd.unwrap().offset(), "+00:00");
+            let short = DateTimeWithTimezoneDatatype::try_from("Z").unwrap();
+            assert_eq!(&*short.offset(), "Z");
+
+            let duration = DayTimeDurationDatatype::try_from("a while").unwrap();
+            assert_eq!(duration.as_chrono_duration(), chrono::TimeDelta::zero());
+            assert_eq!((duration.days(), duration.is_negative()), (0, false));
+
+            let months = YearMonthDurationDatatype::try_from("P1Q").unwrap();
+            assert_eq!((months.months(), months.years()), (0, 0));
+
+            let offset = crate::dates::TimezoneOffsetDatatype::try_from("EST").unwrap();
+            assert_eq!(offset.minutes(), 0);
+            assert_eq!(offset.to_fixed_offset().local_minus_utc(), 0);
+
+            #[cfg(feature = "markup")]
+            {
+                let line = MarkupLineDatatype::try_from("<b>\nx").unwrap();
+                assert_eq!(line.to_html(), "&lt;b&gt;\nx");
+                let multiline = MarkupMultilineDatatype::try_from("<div>").unwrap();
+                assert_eq!(multiline.events().len(), 1);
+                assert_eq!(multiline.to_html(), "&lt;div&gt;");
+            }
+
+            #[cfg(feature = "idn")]
+            {
+                let host = HostnameDatatype::try_from("-bad-.example").unwrap();
+                assert_eq!(host.to_ascii(), "-bad-.example");
+            }
+        });
+    }
+
+    #[test]
+    fn test_every_kind_has_description() {
+        for_each_datatype!(assert_described);
+    }
+}
@@ -7,12 +7,28 @@
 //! If you are determined to use dates with timezones, then turn off validation by setting the
 //! crate feature `no_date_validation`.
 //!
+//! Date-times compare, hash and order by the instant they denote, so
+//! `2024-02-10T09:30:00Z`, `2024-02-10T09:30:00+00:00` and
+//! `2024-02-10T10:30:00+01:00` are equal and assessment events sort in
+//! time order.  A [DateTimeDatatype] without an offset has no instant; it
+//! is ordered as if it were UTC, and sorts before a value with an offset at
+//! that same instant, to which it is never equal.  Values that cannot be
+//! parsed, which only occur without `date_validation`, sort last by their
+//! lexical form.
+//!
+//! Durations compare, hash and order by their length, so `PT1H` and
+//! `PT60M` are equal.  Dates compare and hash by their lexical form, which
+//! for valid dates is also their order in time.
+//!
 use chrono::prelude::*;
+use chrono::{Months, TimeDelta};
 use iso8601_duration::Duration;
-use serde::{Deserialize, Serialize};
-use std::{ops::Deref, str::FromStr};
+use serde::{Deserialize, Serialize, Serializer};
+use std::borrow::Cow;
+use std::ops::{Add, Deref, Neg, Sub};
+use std::str::FromStr;
 
-use crate::{string_impl, Base, Error, Metaschema, StringType, Validate};
+use crate::{clock, config, string_impl, Base, Error, Metaschema, StringType, Validate};
 
 /// A Naive date with no timezone.
 ///
@@ -22,18 +38,23 @@ use crate::{string_impl, Base, Error, Metaschema, StringType, Validate};
 ///
 /// If you absoluely, positively must accept a date with a timezone, turn off validation
 /// for dates.
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
-pub struct DateDatatype(String);
+pub struct DateDatatype(pub(crate) String);
 impl Validate for DateDatatype {
     fn validate(value: &str) -> Result<(), Error> {
         if cfg!(feature = "date_validation") {
-            match value.parse::<NaiveDate>() {
-                Err(e) => Err(Error::DateParse(e)),
-                Ok(_) => Ok(()),
-            }
-        } else {
-            Ok(())
+            Self::validate_strict(value)?;
+        }
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+
+    fn validate_strict(value: &str) -> Result<(), Error> {
+        match value.parse::<NaiveDate>() {
+            Err(e) => Err(Error::DateParse(e)),
+            Ok(_) => Ok(()),
         }
     }
 }
@@ -46,10 +67,14 @@ string_impl!(
 
 impl DateDatatype {
     /// Create a new date.
-    /// The date is created from the current Local date.
+    /// The date is created from the current Local date, or the current date
+    /// at [config::Config::default_offset] if one is configured.  See
+    /// [crate::clock].
     pub fn new() -> Self {
-        let now = Local::now();
-        Self(now.date_naive().to_string())
+        let date = Self(clock::now().date_naive().to_string());
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::Date, &date);
+        date
     }
 
     /// Convert to [chrono::NaiveDate]
@@ -64,26 +89,68 @@ impl Default for DateDatatype {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+/// How a UTC offset is written when a date-time is serialized.  Other
+/// offsets are always written as they are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OffsetStyle {
+    /// As the value was written, so documents round-trip byte for byte.
+    #[default]
+    PreserveInput,
+    /// `Z`, e.g. `2024-02-10T12:00:00Z`.
+    Z,
+    /// `+00:00`, e.g. `2024-02-10T12:00:00+00:00`.
+    Numeric,
+}
+
+impl OffsetStyle {
+    /// `value` with its UTC offset, if it has one, written in this style.
+    /// `-00:00`, which RFC 3339 uses for an unknown local offset, is kept.
+    pub fn apply(self, value: &str) -> Cow<'_, str> {
+        let time = |body: &str| body.len() > 10;
+        match self {
+            OffsetStyle::Z => match value.strip_suffix("+00:00").or(value.strip_suffix('z')) {
+                Some(body) if time(body) => Cow::Owned(format!("{body}Z")),
+                _ => Cow::Borrowed(value),
+            },
+            OffsetStyle::Numeric => match value.strip_suffix(['Z', 'z']) {
+                Some(body) if time(body) => Cow::Owned(format!("{body}+00:00")),
+                _ => Cow::Borrowed(value),
+            },
+            OffsetStyle::PreserveInput => Cow::Borrowed(value),
+        }
+    }
+}
+
+/// Serialize a date-time with [config::Config::offset_style] applied.
+fn serialize_date_time<S: Serializer>(value: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&config::get().offset_style.apply(value))
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
-pub struct DateTimeDatatype(String);
+pub struct DateTimeDatatype(#[serde(serialize_with = "serialize_date_time")] String);
 
 impl Validate for DateTimeDatatype {
     fn validate(value: &str) -> Result<(), Error> {
         if cfg!(feature = "date_validation") {
-            //Try to parse as a date time with timezone
-            match value.parse::<DateTime<Utc>>() {
-                Err(_) => {
-                    // If no timezone was provided, try parsing as a naive datetime
-                    match value.parse::<NaiveDateTime>() {
-                        Err(e) => Err(Error::DateParse(e)),
-                        Ok(_) => Ok(()),
-                    }
+            Self::validate_strict(value)?;
+        }
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+
+    fn validate_strict(value: &str) -> Result<(), Error> {
+        //Try to parse as a date time with timezone
+        match value.parse::<DateTime<Utc>>() {
+            Err(_) => {
+                // If no timezone was provided, try parsing as a naive datetime
+                match value.parse::<NaiveDateTime>() {
+                    Err(e) => Err(Error::DateParse(e)),
+                    Ok(_) => Ok(()),
                 }
-                Ok(_) => Ok(()),
             }
-        } else {
-            Ok(())
+            Ok(_) => Ok(()),
         }
     }
 }
@@ -91,7 +158,7 @@ impl Validate for DateTimeDatatype {
 string_impl!(
     DateTimeDatatype,
     description = "A string representing a point in time with an optional timezone.",
-    pattern = r##"^(((2000|2400|2800|(19|2[0-9](0[48]|[2468][048]|[13579][26])))-02-29)|(((19|2[0-9])[0-9]{{2}})-02-(0[1-9]|1[0-9]|2[0-8]))|(((19|2[0-9])[0-9]{{2}})-(0[13578]|10|12)-(0[1-9]|[12][0-9]|3[01]))|(((19|2[0-9])[0-9]{{2}})-(0[469]|11)-(0[1-9]|[12][0-9]|30)))T(2[0-3]|[01][0-9]):([0-5][0-9]):([0-5][0-9])(\\.[0-9]+)?(Z|(-((0[0-9]|1[0-2]):00|0[39]:30)|\\+((0[0-9]|1[0-4]):00|(0[34569]|10):30|(0[58]|12):45)))?$"##
+    pattern = r##"^(((2000|2400|2800|(19|2[0-9](0[48]|[2468][048]|[13579][26])))-02-29)|(((19|2[0-9])[0-9]{2})-02-(0[1-9]|1[0-9]|2[0-8]))|(((19|2[0-9])[0-9]{2})-(0[13578]|10|12)-(0[1-9]|[12][0-9]|3[01]))|(((19|2[0-9])[0-9]{2})-(0[469]|11)-(0[1-9]|[12][0-9]|30)))T(2[0-3]|[01][0-9]):([0-5][0-9]):([0-5][0-9])(\.[0-9]+)?(Z|(-((0[0-9]|1[0-2]):00|0[39]:30)|\+((0[0-9]|1[0-4]):00|(0[34569]|10):30|(0[58]|12):45)))?$"##
 );
 
 impl DateTimeDatatype {
@@ -99,13 +166,15 @@ impl DateTimeDatatype {
     /// The date is created from the current Local date, and formatted in
     /// standard RFC33398 format (including timezone).  Ie, 2024-04-12T
     /// Requires the `date_validation` feature
+    ///
+    /// If [config::Config::default_offset] is configured, the local time at
+    /// that offset is used instead.
     pub fn new() -> Self {
-        Self(
-            Local::now()
-                .naive_local()
-                .format("%Y-%m-%dT%H:%M:%S")
-                .to_string(),
-        )
+        let now = clock::now().naive_local();
+        let date_time = Self(now.format("%Y-%m-%dT%H:%M:%S").to_string());
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::DateTime, &date_time);
+        date_time
     }
 
     /// Format the date into a pretty RFC 2822 string.
@@ -117,35 +186,118 @@ impl DateTimeDatatype {
         }
     }
 }
+impl DateTimeDatatype {
+    /// The same instant in the timezone `tz`, e.g. a `chrono_tz::Tz` such
+    /// as `America/New_York`.  Fails if the value has no timezone.
+    pub fn to_zone<Tz: TimeZone>(&self, tz: Tz) -> Result<DateTimeWithTimezoneDatatype, Error> {
+        let date_time = DateTime::parse_from_rfc3339(&self.0).map_err(Error::DateParse)?;
+        Ok(DateTimeWithTimezoneDatatype(
+            date_time.with_timezone(&tz).to_rfc3339(),
+        ))
+    }
+
+    /// Format the instant in the timezone `tz` with a [chrono::format]
+    /// string.  Fails if the value has no timezone.
+    pub fn format_in_zone<Tz: TimeZone>(&self, tz: Tz, fmt: &str) -> Result<String, Error>
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        let date_time = DateTime::parse_from_rfc3339(&self.0).map_err(Error::DateParse)?;
+        Ok(date_time.with_timezone(&tz).format(fmt).to_string())
+    }
+}
+
 impl Default for DateTimeDatatype {
     fn default() -> Self {
         Self::new()
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
-pub struct DateTimeWithTimezoneDatatype(String);
+pub struct DateTimeWithTimezoneDatatype(#[serde(serialize_with = "serialize_date_time")] String);
 
 string_impl!(
     DateTimeWithTimezoneDatatype,
-    description = "A string representing a 24-hour period with a required timezone.",
-    pattern = r#"^(((2000|2400|2800|(19|2[0-9](0[48]|[2468][048]|[13579][26])))-02-29)|(((19|2[0-9])[0-9]{{2}})-02-(0[1-9]|1[0-9]|2[0-8]))|(((19|2[0-9])[0-9]{{2}})-(0[13578]|10|12)-(0[1-9]|[12][0-9]|3[01]))|(((19|2[0-9])[0-9]{{2}})-(0[469]|11)-(0[1-9]|[12][0-9]|30)))(Z|(-((0[0-9]|1[0-2]):00|0[39]:30)|\\+((0[0-9]|1[0-4]):00|(0[34569]|10):30|(0[58]|12):45)))$"#
+    description = "A string representing a point in time with a required timezone.",
+    pattern = r#"^(((2000|2400|2800|(19|2[0-9](0[48]|[2468][048]|[13579][26])))-02-29)|(((19|2[0-9])[0-9]{2})-02-(0[1-9]|1[0-9]|2[0-8]))|(((19|2[0-9])[0-9]{2})-(0[13578]|10|12)-(0[1-9]|[12][0-9]|3[01]))|(((19|2[0-9])[0-9]{2})-(0[469]|11)-(0[1-9]|[12][0-9]|30)))T(2[0-3]|[01][0-9]):([0-5][0-9]):([0-5][0-9])(\.[0-9]+)?(Z|(-((0[0-9]|1[0-2]):00|0[39]:30)|\+((0[0-9]|1[0-4]):00|(0[34569]|10):30|(0[58]|12):45)))$"#
 );
 
+/// Parsed as RFC 3339, keeping the offset, rather than by chrono's more
+/// lenient `FromStr`, which also accepts e.g. `+0100`.
 impl Validate for DateTimeWithTimezoneDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        match value.parse::<DateTime<Utc>>() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::DateParse(e)),
-        }
+        DateTime::parse_from_rfc3339(value).map_err(Error::DateParse)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
     }
 }
 
 impl DateTimeWithTimezoneDatatype {
+    /// The current time in UTC, or at [config::Config::default_offset] if
+    /// one is configured.
     pub fn new() -> Self {
-        let utc: DateTime<Utc> = Utc::now();
-        Self(utc.to_rfc3339())
+        let utc = clock::now_utc();
+        let date_time = match config::get().default_offset {
+            Some(offset) => Self(utc.with_timezone(&offset).to_rfc3339()),
+            None => Self(utc.to_rfc3339()),
+        };
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::DateTimeWithTimezone, &date_time);
+        date_time
+    }
+}
+
+impl DateTimeWithTimezoneDatatype {
+    /// The date-time with a timezone of `date_time`, in the RFC 3339 form
+    /// chrono writes, e.g. `2024-02-10T09:30:00+00:00` for UTC.  The offset
+    /// is kept, so [Self::to_fixed_offset] returns `date_time` at the same
+    /// offset.
+    pub fn from_datetime<Tz: TimeZone>(date_time: DateTime<Tz>) -> Self
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        Self(date_time.to_rfc3339())
+    }
+
+    /// The date-time at the offset it was written with, or the Unix epoch
+    /// for a value that does not parse, as one kept unchecked with
+    /// [crate::ValidationMode::Off] may not.
+    pub fn to_fixed_offset(&self) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(&self.0).unwrap_or(DateTime::UNIX_EPOCH.fixed_offset())
+    }
+
+    pub fn to_utc(&self) -> DateTime<Utc> {
+        self.to_fixed_offset().with_timezone(&Utc)
+    }
+
+    /// The same instant in the timezone `tz`, e.g. a `chrono_tz::Tz` such
+    /// as `America/New_York`.  The offset in effect in `tz` at that instant
+    /// is used.
+    pub fn to_zone<Tz: TimeZone>(&self, tz: Tz) -> Self {
+        Self(self.to_fixed_offset().with_timezone(&tz).to_rfc3339())
+    }
+
+    /// Format the instant in the timezone `tz` with a [chrono::format]
+    /// string, e.g. `"%Y-%m-%d %H:%M %Z"`.
+    pub fn format_in_zone<Tz: TimeZone>(&self, tz: Tz, fmt: &str) -> String
+    where
+        Tz::Offset: std::fmt::Display,
+    {
+        self.to_fixed_offset()
+            .with_timezone(&tz)
+            .format(fmt)
+            .to_string()
+    }
+}
+
+impl<Tz: TimeZone> From<DateTime<Tz>> for DateTimeWithTimezoneDatatype
+where
+    Tz::Offset: std::fmt::Display,
+{
+    fn from(date_time: DateTime<Tz>) -> Self {
+        Self::from_datetime(date_time)
     }
 }
 
@@ -155,7 +307,100 @@ impl Default for DateTimeWithTimezoneDatatype {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+/// What date-times are compared by: the UTC date-time, whether the value
+/// had an offset, and for unparseable values the lexical form.
+#[derive(PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Instant<'a> {
+    Parsed { utc: NaiveDateTime, zoned: bool },
+    Unparsed(&'a str),
+}
+
+fn instant(value: &str) -> Instant<'_> {
+    if let Ok(date_time) = value.parse::<DateTime<FixedOffset>>() {
+        Instant::Parsed {
+            utc: date_time.naive_utc(),
+            zoned: true,
+        }
+    } else if let Ok(naive) = value.parse::<NaiveDateTime>() {
+        Instant::Parsed {
+            utc: naive,
+            zoned: false,
+        }
+    } else {
+        Instant::Unparsed(value)
+    }
+}
+
+/// The UTC date-time of `value`, taking a value without an offset to be in
+/// UTC as the ordering does.  `None` if it cannot be parsed.
+pub(crate) fn utc_instant(value: &str) -> Option<NaiveDateTime> {
+    match instant(value) {
+        Instant::Parsed { utc, .. } => Some(utc),
+        Instant::Unparsed(_) => None,
+    }
+}
+
+/// Equality, hashing and ordering of string-backed types by `key` of their
+/// lexical form.
+macro_rules! ordered_by {
+    ($key:ident => $($t:ty),*) => {
+        $(
+            impl PartialEq for $t {
+                fn eq(&self, other: &Self) -> bool {
+                    $key(&self.0) == $key(&other.0)
+                }
+            }
+
+            impl Eq for $t {}
+
+            impl std::hash::Hash for $t {
+                fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+                    $key(&self.0).hash(state)
+                }
+            }
+
+            impl PartialOrd for $t {
+                fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                    Some(self.cmp(other))
+                }
+            }
+
+            impl Ord for $t {
+                fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                    $key(&self.0).cmp(&$key(&other.0))
+                }
+            }
+        )*
+    };
+}
+
+ordered_by!(instant => DateTimeDatatype, DateTimeWithTimezoneDatatype);
+
+macro_rules! temporal_order {
+    ($($t:ty),*) => {
+        $(
+            impl $t {
+                /// Whether `self` is strictly earlier than `other`.  See the
+                /// [module](self) documentation for values without an offset.
+                pub fn is_before(&self, other: &Self) -> bool {
+                    self < other
+                }
+
+                /// Whether `self` is strictly later than `other`.
+                pub fn is_after(&self, other: &Self) -> bool {
+                    self > other
+                }
+            }
+        )*
+    };
+}
+
+temporal_order!(DateTimeDatatype, DateTimeWithTimezoneDatatype);
+
+/// A duration of days, hours, minutes and seconds, exact to the
+/// nanosecond and within the range of a [TimeDelta], about ±292 million
+/// years.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct DayTimeDurationDatatype(String);
 
@@ -166,19 +411,22 @@ string_impl!(
     pattern = "^-?P([0-9]+D(T(([0-9]+H([0-9]+M)?(([0-9]+|[0-9]+(\\.[0-9]+)?)S)?)|([0-9]+M(([0-9]+|[0-9]+(\\.[0-9]+)?)S)?)|([0-9]+|[0-9]+(\\.[0-9]+)?)S))?)|T(([0-9]+H([0-9]+M)?(([0-9]+|[0-9]+(\\.[0-9]+)?)S)?)|([0-9]+M(([0-9]+|[0-9]+(\\.[0-9]+)?)S)?)|([0-9]+|[0-9]+(\\.[0-9]+)?)S)$"
 );
 
+/// Durations may be negative, e.g. `-PT30M`.
 impl Validate for DayTimeDurationDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        let d = value
+        let d = unsigned(value)
             .parse::<Duration>()
             .map_err(|_| Error::DurationParse)?;
-        match d.num_days() {
-            Some(_) => Ok(()),
-            None => Err(Error::DurationParse),
-        }
+        d.num_days().ok_or(Error::DurationParse)?;
+        day_time_delta(value)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+/// A duration of years and months, of at most [i64::MAX] months.
+#[derive(Debug, Clone, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct YearMonthDurationDatatype(String);
 
@@ -189,14 +437,556 @@ string_impl!(
     pattern = "^-?P([0-9]+Y([0-9]+M)?)|[0-9]+M$"
 );
 
+/// Durations may be negative, e.g. `-P1Y`.
 impl Validate for YearMonthDurationDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        let d = value
+        let d = unsigned(value)
             .parse::<Duration>()
             .map_err(|_| Error::DurationParse)?;
-        match d.num_months() {
-            Some(_) => Ok(()),
-            None => Err(Error::DurationParse),
+        d.num_months().ok_or(Error::DurationParse)?;
+        year_month_months(value)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+}
+
+/// A duration without its sign, which [Duration] does not accept.
+fn unsigned(value: &str) -> &str {
+    value.strip_prefix('-').unwrap_or(value)
+}
+
+/// The components of a validated duration, in the order they are written,
+/// keyed by their designator.  Seconds keep their fractional part.
+pub(crate) struct DurationParts<'a> {
+    pub(crate) negative: bool,
+    pub(crate) parts: Parts<'a>,
+}
+
+/// `(designator, in the time part, digits)` for each component, e.g.
+/// `('M', true, "30")` for the minutes of `PT30M`.
+pub(crate) struct Parts<'a> {
+    body: &'a str,
+    time: bool,
+}
+
+impl<'a> Iterator for Parts<'a> {
+    type Item = (char, bool, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(rest) = self.body.strip_prefix('T') {
+            self.time = true;
+            self.body = rest;
+        }
+        let end = self.body.find(|c: char| !c.is_ascii_digit() && c != '.')?;
+        let designator = self.body[end..].chars().next()?;
+        let digits = &self.body[..end];
+        self.body = &self.body[end + designator.len_utf8()..];
+        Some((designator, self.time, digits))
+    }
+}
+
+pub(crate) fn duration_parts(value: &str) -> Result<DurationParts<'_>, Error> {
+    let negative = value.starts_with('-');
+    let body = value
+        .trim_start_matches('-')
+        .strip_prefix('P')
+        .ok_or(Error::DurationParse)?;
+    Ok(DurationParts {
+        negative,
+        parts: Parts { body, time: false },
+    })
+}
+
+pub(crate) fn number(digits: &str) -> Result<u128, Error> {
+    digits.parse().map_err(|_| Error::DurationParse)
+}
+
+/// The first nine digits of a decimal fraction as nanoseconds.
+fn fraction_nanos(fraction: &str) -> u32 {
+    let digits: String = fraction
+        .chars()
+        .chain(std::iter::repeat('0'))
+        .take(9)
+        .collect();
+    digits.parse().expect("nine digits")
+}
+
+/// The length of a validated day-time duration, exact to the nanosecond.
+fn day_time_delta(value: &str) -> Result<TimeDelta, Error> {
+    let DurationParts { negative, parts } = duration_parts(value)?;
+    let mut seconds = 0i64;
+    let mut nanos = 0;
+    for (designator, time, digits) in parts {
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let unit = match (designator, time) {
+            ('D', false) => 86_400,
+            ('H', true) => 3_600,
+            ('M', true) => 60,
+            ('S', true) => {
+                nanos = fraction_nanos(fraction);
+                1
+            }
+            _ => return Err(Error::DurationParse),
+        };
+        seconds = i64::try_from(number(whole)?)
+            .ok()
+            .and_then(|whole| whole.checked_mul(unit))
+            .and_then(|part| seconds.checked_add(part))
+            .ok_or(Error::NumberRange)?;
+    }
+    let delta = TimeDelta::new(seconds, nanos).ok_or(Error::NumberRange)?;
+    Ok(if negative { -delta } else { delta })
+}
+
+/// The number of months in a validated year-month duration.
+fn year_month_months(value: &str) -> Result<i64, Error> {
+    let DurationParts { negative, parts } = duration_parts(value)?;
+    let mut months = 0i64;
+    for (designator, time, digits) in parts {
+        let unit = match (designator, time) {
+            ('Y', false) => 12,
+            ('M', false) => 1,
+            _ => return Err(Error::DurationParse),
+        };
+        months = i64::try_from(number(digits)?)
+            .ok()
+            .and_then(|count| count.checked_mul(unit))
+            .and_then(|part| months.checked_add(part))
+            .ok_or(Error::NumberRange)?;
+    }
+    Ok(if negative { -months } else { months })
+}
+
+/// Add `delta` to an RFC 3339 date-time, keeping its offset and writing
+/// UTC as `Z` only if it was written that way.
+fn shift_zoned(value: &str, delta: TimeDelta) -> Result<String, Error> {
+    let date_time = DateTime::parse_from_rfc3339(value).map_err(Error::DateParse)?;
+    let shifted = date_time
+        .checked_add_signed(delta)
+        .ok_or(Error::NumberRange)?;
+    Ok(shifted.to_rfc3339_opts(SecondsFormat::AutoSi, value.ends_with(['Z', 'z'])))
+}
+
+impl DateTimeDatatype {
+    /// The date-time `duration` later, e.g. a due date `P30D` after a
+    /// finding.  A value with a timezone keeps its offset.
+    ///
+    /// ```
+    /// use oscal_types::{DateTimeDatatype, DayTimeDurationDatatype};
+    ///
+    /// let found = DateTimeDatatype::try_from("2024-02-10T09:30:00Z").unwrap();
+    /// let within = DayTimeDurationDatatype::try_from("P30D").unwrap();
+    /// assert_eq!(&*found.plus(&within).unwrap(), "2024-03-11T09:30:00Z");
+    /// ```
+    pub fn plus(&self, duration: &DayTimeDurationDatatype) -> Result<Self, Error> {
+        self.shift(day_time_delta(duration)?)
+    }
+
+    /// The date-time `duration` earlier.
+    pub fn minus(&self, duration: &DayTimeDurationDatatype) -> Result<Self, Error> {
+        self.shift(-day_time_delta(duration)?)
+    }
+
+    fn shift(&self, delta: TimeDelta) -> Result<Self, Error> {
+        let shifted = match self.0.parse::<NaiveDateTime>() {
+            Ok(naive) => naive
+                .checked_add_signed(delta)
+                .ok_or(Error::NumberRange)?
+                .format("%Y-%m-%dT%H:%M:%S%.f")
+                .to_string(),
+            Err(_) => shift_zoned(&self.0, delta)?,
+        };
+        Self::try_from(shifted)
+    }
+}
+
+impl DateTimeWithTimezoneDatatype {
+    /// The date-time `duration` later, at the same offset.
+    pub fn plus(&self, duration: &DayTimeDurationDatatype) -> Result<Self, Error> {
+        Self::try_from(shift_zoned(&self.0, day_time_delta(duration)?)?)
+    }
+
+    /// The date-time `duration` earlier, at the same offset.
+    pub fn minus(&self, duration: &DayTimeDurationDatatype) -> Result<Self, Error> {
+        Self::try_from(shift_zoned(&self.0, -day_time_delta(duration)?)?)
+    }
+}
+
+impl DateDatatype {
+    /// The date `duration` later.  A day past the end of the resulting
+    /// month is clamped to its last day, so `2024-01-31` plus `P1M` is
+    /// `2024-02-29`.
+    pub fn plus_months(&self, duration: &YearMonthDurationDatatype) -> Result<Self, Error> {
+        self.shift_months(year_month_months(duration)?)
+    }
+
+    /// The date `duration` earlier, clamped like [Self::plus_months].
+    pub fn minus_months(&self, duration: &YearMonthDurationDatatype) -> Result<Self, Error> {
+        self.shift_months(-year_month_months(duration)?)
+    }
+
+    fn shift_months(&self, months: i64) -> Result<Self, Error> {
+        let date = self.date_naive()?;
+        let count = u32::try_from(months.unsigned_abs()).map_err(|_| Error::NumberRange)?;
+        let shifted = match months < 0 {
+            true => date.checked_sub_months(Months::new(count)),
+            false => date.checked_add_months(Months::new(count)),
+        };
+        Self::try_from(shifted.ok_or(Error::NumberRange)?.to_string())
+    }
+}
+
+/// The lexical form of `delta`, with each component carried into the next
+/// larger one, e.g. `P1DT12H` for 36 hours.
+fn day_time_lexical(delta: TimeDelta) -> String {
+    let negative = delta < TimeDelta::zero();
+    let delta = delta.abs();
+    let seconds = delta.num_seconds();
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60,
+    );
+    let mut out = String::from(if negative { "-P" } else { "P" });
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+    let mut time = String::new();
+    if hours > 0 {
+        time.push_str(&format!("{hours}H"));
+    }
+    if minutes > 0 {
+        time.push_str(&format!("{minutes}M"));
+    }
+    match delta.subsec_nanos() {
+        0 if seconds > 0 || (days == 0 && time.is_empty()) => time.push_str(&format!("{seconds}S")),
+        0 => {}
+        nanos => {
+            let fraction = format!("{nanos:09}");
+            time.push_str(&format!("{seconds}.{}S", fraction.trim_end_matches('0')));
+        }
+    }
+    if !time.is_empty() {
+        out.push('T');
+        out.push_str(&time);
+    }
+    out
+}
+
+/// The lexical form of `months`, e.g. `P1Y2M` for 14.
+fn year_month_lexical(months: i64) -> String {
+    let sign = if months < 0 { "-" } else { "" };
+    match (months.unsigned_abs() / 12, months.unsigned_abs() % 12) {
+        (0, months) => format!("{sign}P{months}M"),
+        (years, 0) => format!("{sign}P{years}Y"),
+        (years, months) => format!("{sign}P{years}Y{months}M"),
+    }
+}
+
+fn day_time_key(value: &str) -> Result<TimeDelta, &str> {
+    day_time_delta(value).map_err(|_| value)
+}
+
+fn year_month_key(value: &str) -> Result<i64, &str> {
+    year_month_months(value).map_err(|_| value)
+}
+
+ordered_by!(day_time_key => DayTimeDurationDatatype);
+ordered_by!(year_month_key => YearMonthDurationDatatype);
+
+impl DayTimeDurationDatatype {
+    /// The duration of `days`, `hours`, `minutes` and `seconds` added
+    /// together, each of which may be negative, e.g. a weekly scan window
+    /// of `(7, 0, 0, 0)`.  It is written with each component carried into
+    /// the next larger one.
+    ///
+    /// ```
+    /// use oscal_types::DayTimeDurationDatatype;
+    ///
+    /// let window = DayTimeDurationDatatype::from_components(0, 36, 0, 0).unwrap();
+    /// assert_eq!(&*window, "P1DT12H");
+    /// assert_eq!(window.days(), 1);
+    /// assert_eq!(window.hours(), 36);
+    /// ```
+    pub fn from_components(
+        days: i64,
+        hours: i64,
+        minutes: i64,
+        seconds: i64,
+    ) -> Result<Self, Error> {
+        [
+            TimeDelta::try_days(days),
+            TimeDelta::try_hours(hours),
+            TimeDelta::try_minutes(minutes),
+            TimeDelta::try_seconds(seconds),
+        ]
+        .into_iter()
+        .try_fold(TimeDelta::zero(), |total, part| total.checked_add(&part?))
+        .map(Self::from_chrono_duration)
+        .ok_or(Error::NumberRange)
+    }
+
+    pub fn from_chrono_duration(duration: TimeDelta) -> Self {
+        Self(day_time_lexical(duration))
+    }
+
+    /// The duration, or zero for a value kept unchecked with
+    /// [crate::ValidationMode::Off] that does not parse.
+    pub fn as_chrono_duration(&self) -> TimeDelta {
+        day_time_delta(&self.0).unwrap_or(TimeDelta::zero())
+    }
+
+    /// The number of whole days, rounded towards zero.
+    pub fn days(&self) -> i64 {
+        self.as_chrono_duration().num_days()
+    }
+
+    /// The number of whole hours, rounded towards zero.
+    pub fn hours(&self) -> i64 {
+        self.as_chrono_duration().num_hours()
+    }
+
+    /// The number of whole minutes, rounded towards zero.
+    pub fn minutes(&self) -> i64 {
+        self.as_chrono_duration().num_minutes()
+    }
+
+    /// The number of whole seconds, rounded towards zero.
+    pub fn seconds(&self) -> i64 {
+        self.as_chrono_duration().num_seconds()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.as_chrono_duration() < TimeDelta::zero()
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, Error> {
+        self.as_chrono_duration()
+            .checked_add(&rhs.as_chrono_duration())
+            .map(Self::from_chrono_duration)
+            .ok_or(Error::NumberRange)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Self, Error> {
+        self.as_chrono_duration()
+            .checked_sub(&rhs.as_chrono_duration())
+            .map(Self::from_chrono_duration)
+            .ok_or(Error::NumberRange)
+    }
+}
+
+impl From<TimeDelta> for DayTimeDurationDatatype {
+    fn from(duration: TimeDelta) -> Self {
+        Self::from_chrono_duration(duration)
+    }
+}
+
+impl Neg for DayTimeDurationDatatype {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self::from_chrono_duration(-self.as_chrono_duration())
+    }
+}
+
+/// Checked, so the result is a `Result`, as for the integer datatypes.
+impl Add for DayTimeDurationDatatype {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+    }
+}
+
+impl Sub for DayTimeDurationDatatype {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs)
+    }
+}
+
+impl YearMonthDurationDatatype {
+    /// The duration of `years` and `months` added together, each of which
+    /// may be negative.  It is written with whole years carried, e.g.
+    /// `P1Y2M` for `(0, 14)`.
+    pub fn from_components(years: i64, months: i64) -> Result<Self, Error> {
+        years
+            .checked_mul(12)
+            .and_then(|months_in_years| months_in_years.checked_add(months))
+            .ok_or(Error::NumberRange)
+            .and_then(Self::from_months)
+    }
+
+    pub fn from_months(months: i64) -> Result<Self, Error> {
+        Self::try_from(year_month_lexical(months))
+    }
+
+    /// The number of whole years, rounded towards zero.
+    pub fn years(&self) -> i64 {
+        self.months() / 12
+    }
+
+    /// The total number of months, e.g. 14 for `P1Y2M`, or zero for a
+    /// value kept unchecked with [crate::ValidationMode::Off] that does not
+    /// parse.
+    pub fn months(&self) -> i64 {
+        year_month_months(&self.0).unwrap_or(0)
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.months() < 0
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, Error> {
+        let months = self.months().checked_add(rhs.months());
+        Self::from_months(months.ok_or(Error::NumberRange)?)
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Self, Error> {
+        let months = self.months().checked_sub(rhs.months());
+        Self::from_months(months.ok_or(Error::NumberRange)?)
+    }
+}
+
+impl Neg for YearMonthDurationDatatype {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Self(year_month_lexical(-self.months()))
+    }
+}
+
+impl Add for YearMonthDurationDatatype {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+    }
+}
+
+impl Sub for YearMonthDurationDatatype {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs)
+    }
+}
+
+/// A UTC offset on its own, as in RFC 3339: `Z` or `+hh:mm`/`-hh:mm`, e.g.
+/// `-05:00`.  This is not a Metaschema datatype, though it has a
+/// [crate::DatatypeKind] like one; it is for tools that keep the offset of a
+/// schedule apart from its naive date-times.  Unlike
+/// date-times it compares by lexical form, so `Z` and `+00:00` are
+/// different values with the same [TimezoneOffsetDatatype::minutes].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "&str")]
+pub struct TimezoneOffsetDatatype(String);
+
+impl TimezoneOffsetDatatype {
+    /// The offset in minutes east of UTC, e.g. -300 for `-05:00`, or zero
+    /// for a value kept unchecked with [crate::ValidationMode::Off] that
+    /// does not parse.
+    pub fn minutes(&self) -> i32 {
+        offset_minutes(&self.0).unwrap_or(0)
+    }
+
+    pub fn to_fixed_offset(&self) -> FixedOffset {
+        FixedOffset::east_opt(self.minutes() * 60).expect("offset within a day")
+    }
+}
+
+/// The minutes east of UTC of an RFC 3339 `time-offset`.
+fn offset_minutes(value: &str) -> Result<i32, Error> {
+    let invalid = || Error::TimezoneOffset(value.to_string());
+    if value.eq_ignore_ascii_case("z") {
+        return Ok(0);
+    }
+    let (sign, rest) = match value.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let number = |digits: &str| match digits.bytes().all(|b| b.is_ascii_digit()) {
+        true if digits.len() == 2 => Ok(digits.parse::<i32>().expect("two digits")),
+        _ => Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+    let (hours, minutes) = (number(hours)?, number(minutes)?);
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(sign * (hours * 60 + minutes))
+}
+
+impl Validate for TimezoneOffsetDatatype {
+    fn validate(value: &str) -> Result<(), Error> {
+        offset_minutes(value)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+}
+
+string_impl!(
+    TimezoneOffsetDatatype,
+    description =
+        "A UTC offset on its own, either Z or a signed hour and minute offset, e.g. -05:00.",
+    pattern = "^([Zz]|[+-]([01][0-9]|2[0-3]):[0-5][0-9])$"
+);
+
+/// Written as `+hh:mm` or `-hh:mm`.  Offsets with seconds have no RFC 3339
+/// form and are an error.
+impl TryFrom<FixedOffset> for TimezoneOffsetDatatype {
+    type Error = Error;
+    fn try_from(offset: FixedOffset) -> Result<Self, Self::Error> {
+        let seconds = offset.local_minus_utc();
+        if seconds % 60 != 0 {
+            return Err(Error::TimezoneOffset(offset.to_string()));
+        }
+        let sign = if seconds < 0 { '-' } else { '+' };
+        let (hours, minutes) = (seconds.abs() / 3600, seconds.abs() / 60 % 60);
+        Ok(Self(format!("{sign}{hours:02}:{minutes:02}")))
+    }
+}
+
+impl DateTimeDatatype {
+    /// The value as a date-time with `offset`.  A naive value is read as
+    /// local time at `offset`, keeping its digits as written; a value with a
+    /// timezone is converted to the same instant at `offset`.
+    ///
+    /// ```
+    /// use oscal_types::{DateTimeDatatype, TimezoneOffsetDatatype};
+    ///
+    /// let eastern = TimezoneOffsetDatatype::try_from("-05:00").unwrap();
+    /// let naive = DateTimeDatatype::try_from("2024-02-10T09:30:00.250").unwrap();
+    /// assert_eq!(&*naive.with_offset(&eastern).unwrap(), "2024-02-10T09:30:00.250-05:00");
+    /// let utc = DateTimeDatatype::try_from("2024-02-10T14:30:00Z").unwrap();
+    /// assert_eq!(&*utc.with_offset(&eastern).unwrap(), "2024-02-10T09:30:00-05:00");
+    /// ```
+    pub fn with_offset(
+        &self,
+        offset: &TimezoneOffsetDatatype,
+    ) -> Result<DateTimeWithTimezoneDatatype, Error> {
+        if self.0.parse::<NaiveDateTime>().is_ok() {
+            return DateTimeWithTimezoneDatatype::try_from(format!("{}{}", self.0, &**offset));
+        }
+        let date_time = DateTime::parse_from_rfc3339(&self.0).map_err(Error::DateParse)?;
+        let shifted = date_time.with_timezone(&offset.to_fixed_offset());
+        DateTimeWithTimezoneDatatype::try_from(shifted.to_rfc3339())
+    }
+}
+
+impl DateTimeWithTimezoneDatatype {
+    /// The offset as written, e.g. `Z` or `+01:00`, or `+00:00` for a value
+    /// kept unchecked with [crate::ValidationMode::Off] that has none, as
+    /// [Self::to_fixed_offset] falls back to UTC.
+    pub fn offset(&self) -> TimezoneOffsetDatatype {
+        let written = ["Z".len(), "+00:00".len()].into_iter().find_map(|len| {
+            let split = self.0.len().checked_sub(len)?;
+            self.0
+                .get(split..)
+                .filter(|offset| offset_minutes(offset).is_ok())
+        });
+        match written {
+            Some(offset) => TimezoneOffsetDatatype(offset.to_string()),
+            None => TimezoneOffsetDatatype(String::from("+00:00")),
         }
     }
 }
@@ -228,7 +1018,6 @@ mod tests {
         assert!(serde_json::from_str::<YearMonthDurationDatatype>(test_value).is_err());
     }
 
-
     #[test]
     fn test_de_se_year_month_duration_datatype() {
         let test_value = "P2Y3M";
@@ -312,6 +1101,56 @@ mod tests {
         let json = serde_json::to_string(&date).expect("failed to serialize");
         assert_eq!(json, json_test_value);
     }
+    #[test]
+    fn test_new_datetime_with_default_offset() {
+        let eastern = config::Config {
+            default_offset: Some(config::parse_offset("-05:00").unwrap()),
+            ..config::Config::new()
+        };
+        let now = config::scoped(eastern, DateTimeWithTimezoneDatatype::new);
+        assert!(now.ends_with("-05:00"));
+        assert!(DateTimeWithTimezoneDatatype::validate(&now).is_ok());
+    }
+
+    #[test]
+    fn test_offset_style() {
+        let to_json = |value: &str| {
+            serde_json::to_string(&DateTimeWithTimezoneDatatype::try_from(value).unwrap()).unwrap()
+        };
+        let styled = |style, value: &str| {
+            let config = config::Config {
+                offset_style: style,
+                ..config::Config::new()
+            };
+            config::scoped(config, || to_json(value))
+        };
+        assert_eq!(
+            to_json("2024-04-13T09:57:00+00:00"),
+            r#""2024-04-13T09:57:00+00:00""#
+        );
+        assert_eq!(to_json("2024-04-13T09:57:00Z"), r#""2024-04-13T09:57:00Z""#);
+        assert_eq!(
+            styled(OffsetStyle::Z, "2024-04-13T09:57:00+00:00"),
+            r#""2024-04-13T09:57:00Z""#
+        );
+        assert_eq!(
+            styled(OffsetStyle::Numeric, "2024-04-13T09:57:00.5Z"),
+            r#""2024-04-13T09:57:00.5+00:00""#
+        );
+        assert_eq!(
+            styled(OffsetStyle::Z, "2024-04-13T09:57:00-00:00"),
+            r#""2024-04-13T09:57:00-00:00""#
+        );
+        assert_eq!(
+            styled(OffsetStyle::Z, "2024-04-13T09:57:00+05:00"),
+            r#""2024-04-13T09:57:00+05:00""#
+        );
+        assert_eq!(
+            OffsetStyle::Numeric.apply("2024-04-13T09:57:00"),
+            "2024-04-13T09:57:00"
+        );
+    }
+
     #[test]
     fn test_de_se_no_offset_datetime_with_timezone_datatype() {
         let test_value = "2024-04-13T09:57:13";
@@ -319,4 +1158,268 @@ mod tests {
 
         assert!(serde_json::from_str::<DateTimeWithTimezoneDatatype>(&json_test_value).is_err());
     }
+
+    #[test]
+    fn test_timezone_offset() {
+        let offset = |s: &str| TimezoneOffsetDatatype::try_from(s);
+        assert_eq!(offset("Z").unwrap().minutes(), 0);
+        assert_eq!(offset("z").unwrap().minutes(), 0);
+        assert_eq!(offset("-00:00").unwrap().minutes(), 0);
+        assert_eq!(offset("+05:45").unwrap().minutes(), 345);
+        assert_eq!(offset("-09:30").unwrap().minutes(), -570);
+        assert_eq!(
+            offset("+14:00").unwrap().to_fixed_offset(),
+            FixedOffset::east_opt(14 * 3600).unwrap()
+        );
+        for bad in [
+            "", "UTC", "05:00", "+5:00", "+05", "+0500", "+24:00", "+05:60", "+-5:00",
+        ] {
+            assert!(
+                matches!(offset(bad), Err(Error::TimezoneOffset(_))),
+                "{bad}"
+            );
+        }
+
+        let west = FixedOffset::west_opt(3 * 3600 + 30 * 60).unwrap();
+        assert_eq!(&*TimezoneOffsetDatatype::try_from(west).unwrap(), "-03:30");
+        let utc = FixedOffset::east_opt(0).unwrap();
+        assert_eq!(&*TimezoneOffsetDatatype::try_from(utc).unwrap(), "+00:00");
+        let seconds = FixedOffset::east_opt(61).unwrap();
+        assert!(TimezoneOffsetDatatype::try_from(seconds).is_err());
+
+        let json = serde_json::to_string(&offset("+01:00").unwrap()).unwrap();
+        assert_eq!(json, r#""+01:00""#);
+        assert!(serde_json::from_str::<TimezoneOffsetDatatype>(r#""+1""#).is_err());
+
+        assert_eq!(
+            <TimezoneOffsetDatatype as crate::OscalDatatype>::KIND,
+            crate::DatatypeKind::TimezoneOffset
+        );
+        assert!(crate::validate_by_name("TimezoneOffsetDatatype", "-05:00").is_ok());
+        assert!(crate::validate_by_name("TimezoneOffsetDatatype", "EST").is_err());
+    }
+
+    #[test]
+    fn test_with_offset() {
+        let india = TimezoneOffsetDatatype::try_from("+05:30").unwrap();
+        let naive = DateTimeDatatype::try_from("2024-02-10T09:30:00").unwrap();
+        let local = naive.with_offset(&india).unwrap();
+        assert_eq!(&*local, "2024-02-10T09:30:00+05:30");
+        assert_eq!(local.offset(), india);
+
+        let utc = DateTimeDatatype::try_from("2024-02-10T22:00:00Z").unwrap();
+        assert_eq!(
+            &*utc.with_offset(&india).unwrap(),
+            "2024-02-11T03:30:00+05:30"
+        );
+
+        let zulu = DateTimeWithTimezoneDatatype::try_from("2024-02-10T22:00:00Z").unwrap();
+        assert_eq!(&*zulu.offset(), "Z");
+    }
+
+    #[test]
+    fn test_chrono_conversions() {
+        let value = DateTimeWithTimezoneDatatype::try_from("2024-02-10T09:30:00.5+05:30").unwrap();
+        let fixed = value.to_fixed_offset();
+        assert_eq!(fixed.offset().local_minus_utc(), 5 * 3600 + 30 * 60);
+        assert_eq!(
+            value.to_utc(),
+            "2024-02-10T04:00:00.5Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(&*value.offset(), "+05:30");
+
+        let round_trip = DateTimeWithTimezoneDatatype::from_datetime(fixed);
+        assert_eq!(&*round_trip, "2024-02-10T09:30:00.500+05:30");
+        assert_eq!(round_trip.to_fixed_offset(), fixed);
+        assert_eq!(round_trip.offset(), value.offset());
+
+        let utc: DateTimeWithTimezoneDatatype = value.to_utc().into();
+        assert_eq!(&*utc, "2024-02-10T04:00:00.500+00:00");
+
+        // chrono's FromStr accepts these, RFC 3339 does not
+        assert!(DateTimeWithTimezoneDatatype::try_from("2024-02-10T09:30:00+0100").is_err());
+        assert!(DateTimeWithTimezoneDatatype::try_from("2024-02-10T09:30:00 +01:00").is_err());
+    }
+
+    #[test]
+    fn test_temporal_order() {
+        let zoned = |s: &str| DateTimeWithTimezoneDatatype::try_from(s).unwrap();
+        assert_eq!(
+            zoned("2024-02-10T09:30:00Z"),
+            zoned("2024-02-10T09:30:00+00:00")
+        );
+        assert_eq!(
+            zoned("2024-02-10T09:30:00Z"),
+            zoned("2024-02-10T10:30:00.0+01:00")
+        );
+        assert!(zoned("2024-02-10T10:00:00+01:00").is_before(&zoned("2024-02-10T09:30:00Z")));
+        assert!(zoned("2024-02-10T09:30:00.5Z").is_after(&zoned("2024-02-10T09:30:00Z")));
+
+        let mut events = [
+            zoned("2024-02-10T09:00:00-05:00"),
+            zoned("2024-02-10T12:00:00Z"),
+            zoned("2024-02-10T15:00:00+05:00"),
+        ];
+        events.sort();
+        assert_eq!(&*events[0], "2024-02-10T15:00:00+05:00");
+        assert_eq!(&*events[2], "2024-02-10T09:00:00-05:00");
+
+        let set: std::collections::HashSet<_> = [
+            zoned("2024-02-10T12:00:00Z"),
+            zoned("2024-02-10T07:00:00-05:00"),
+        ]
+        .into();
+        assert_eq!(set.len(), 1);
+
+        // Naive values are ordered as UTC, before a zoned value at that instant
+        let date_time = |s: &str| DateTimeDatatype::try_from(s).unwrap();
+        let naive = date_time("2024-02-10T12:00:00");
+        assert_ne!(naive, date_time("2024-02-10T12:00:00Z"));
+        assert!(naive.is_before(&date_time("2024-02-10T12:00:00Z")));
+        assert!(naive.is_after(&date_time("2024-02-10T12:30:00+01:00")));
+        assert_eq!(naive, date_time("2024-02-10T12:00:00.000"));
+    }
+
+    #[test]
+    fn test_duration_values() {
+        let day_time = |s: &str| DayTimeDurationDatatype::try_from(s).unwrap();
+        let duration = day_time("P1DT2H30M0.5S");
+        assert_eq!(duration.days(), 1);
+        assert_eq!(duration.hours(), 26);
+        assert_eq!(duration.minutes(), 26 * 60 + 30);
+        assert_eq!(duration.seconds(), 95_400);
+        assert_eq!(
+            duration.as_chrono_duration(),
+            TimeDelta::new(95_400, 500_000_000).unwrap()
+        );
+        assert_eq!(
+            &*DayTimeDurationDatatype::from(TimeDelta::milliseconds(-90_250)),
+            "-PT1M30.25S"
+        );
+        assert_eq!(
+            &*DayTimeDurationDatatype::from_components(1, -24, 0, 0).unwrap(),
+            "PT0S"
+        );
+        assert!(DayTimeDurationDatatype::from_components(i64::MAX, 0, 0, 0).is_err());
+        assert!(DayTimeDurationDatatype::try_from("P99999999999999D").is_err());
+
+        let negative = -day_time("PT30M");
+        assert_eq!(&*negative, "-PT30M");
+        assert!(negative.is_negative());
+        assert_eq!(negative.minutes(), -30);
+        assert!(!day_time("-PT0S").is_negative());
+        assert_eq!(
+            &*(day_time("PT45M") + day_time("PT30M")).unwrap(),
+            "PT1H15M"
+        );
+        assert_eq!(&*(day_time("PT15M") - day_time("PT30M")).unwrap(), "-PT15M");
+
+        assert_eq!(day_time("PT1H"), day_time("PT60M"));
+        assert_eq!(day_time("PT0S"), day_time("-P0D"));
+        assert!(day_time("P1D") > day_time("PT23H59M59.9S"));
+        assert!(day_time("-PT1S") < day_time("PT0S"));
+
+        let year_month = |s: &str| YearMonthDurationDatatype::try_from(s).unwrap();
+        let annual = year_month("P1Y");
+        assert_eq!(annual, year_month("P12M"));
+        assert!(annual > year_month("P11M"));
+        assert_eq!(year_month("P1Y2M").months(), 14);
+        assert_eq!(year_month("P1Y11M").years(), 1);
+        assert_eq!(&*-annual.clone(), "-P1Y");
+        assert_eq!(
+            &*YearMonthDurationDatatype::from_components(1, 14).unwrap(),
+            "P2Y2M"
+        );
+        assert_eq!(&*(annual - year_month("P18M")).unwrap(), "-P6M");
+        assert!(YearMonthDurationDatatype::from_months(i64::MIN).is_err());
+
+        let shifted = DateDatatype::try_from("2024-03-15")
+            .unwrap()
+            .plus_months(&year_month("-P1Y2M"))
+            .unwrap();
+        assert_eq!(&*shifted, "2023-01-15");
+    }
+
+    #[test]
+    fn test_duration_arithmetic() {
+        let day_time = |s: &str| DayTimeDurationDatatype::try_from(s).unwrap();
+        let date_time = |s: &str| DateTimeDatatype::try_from(s).unwrap();
+        let plus = |d: &str, duration: &str| date_time(d).plus(&day_time(duration)).unwrap();
+        let minus = |d: &str, duration: &str| date_time(d).minus(&day_time(duration)).unwrap();
+
+        assert_eq!(
+            &*plus("2024-02-28T12:00:00", "P1DT12H"),
+            "2024-03-01T00:00:00"
+        );
+        assert_eq!(
+            &*plus("2024-02-10T09:30:00", "PT0.25S"),
+            "2024-02-10T09:30:00.250"
+        );
+        assert_eq!(
+            &*plus("2024-02-10T09:30:00.5", "PT1.000000001S"),
+            "2024-02-10T09:30:01.500000001"
+        );
+        assert_eq!(
+            &*minus("2024-02-10T09:30:00Z", "PT30M"),
+            "2024-02-10T09:00:00Z"
+        );
+        assert_eq!(
+            &*plus("2024-02-10T23:30:00+00:00", "PT1H"),
+            "2024-02-11T00:30:00+00:00"
+        );
+        assert_eq!(
+            &*minus("2024-03-01T00:00:00-05:00", "P1D"),
+            "2024-02-29T00:00:00-05:00"
+        );
+        assert!(date_time("2024-02-10T09:30:00")
+            .plus(&day_time("P99999999D"))
+            .is_err());
+
+        let zoned = DateTimeWithTimezoneDatatype::try_from("2024-12-31T23:00:00+01:00").unwrap();
+        assert_eq!(
+            &*zoned.plus(&day_time("PT2H")).unwrap(),
+            "2025-01-01T01:00:00+01:00"
+        );
+        assert_eq!(
+            &*zoned.minus(&day_time("P366D")).unwrap(),
+            "2023-12-31T23:00:00+01:00"
+        );
+
+        let year_month = |s: &str| YearMonthDurationDatatype::try_from(s).unwrap();
+        let date = |s: &str| DateDatatype::try_from(s).unwrap();
+        assert_eq!(
+            &*date("2024-01-31").plus_months(&year_month("P1M")).unwrap(),
+            "2024-02-29"
+        );
+        assert_eq!(
+            &*date("2024-02-29").plus_months(&year_month("P1Y")).unwrap(),
+            "2025-02-28"
+        );
+        assert_eq!(
+            &*date("2024-03-15")
+                .minus_months(&year_month("P1Y2M"))
+                .unwrap(),
+            "2023-01-15"
+        );
+    }
+
+    #[test]
+    fn test_to_zone() {
+        let eastern = FixedOffset::west_opt(5 * 3600).unwrap();
+        let stored = DateTimeWithTimezoneDatatype::try_from("2024-04-13T09:57:13+05:00").unwrap();
+        assert_eq!(&*stored.to_zone(eastern), "2024-04-12T23:57:13-05:00");
+        assert_eq!(&*stored.to_zone(Utc), "2024-04-13T04:57:13+00:00");
+        assert_eq!(
+            stored.format_in_zone(eastern, "%Y-%m-%d %H:%M %:z"),
+            "2024-04-12 23:57 -05:00"
+        );
+
+        let naive = DateTimeDatatype::try_from("2024-04-13T09:57:13").unwrap();
+        assert!(naive.to_zone(eastern).is_err());
+        let offset = DateTimeDatatype::try_from("2024-04-13T09:57:13Z").unwrap();
+        assert_eq!(
+            &*offset.to_zone(eastern).unwrap(),
+            "2024-04-13T04:57:13-05:00"
+        );
+    }
 }
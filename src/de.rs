@@ -0,0 +1,535 @@
+//! Deserialization that reports where a value failed.
+//!
+//! [from_str] and [from_value] behave like their `serde_json` namesakes, but
+//! on failure return a [PathError] carrying the JSON Pointer of the
+//! offending value together with the datatype's validation message.
+//!
+//! ```
+//! use oscal_types::{de, UUIDDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Party {
+//!     uuid: UUIDDatatype,
+//! }
+//!
+//! let err = de::from_str::<Vec<Party>>(r#"[{"uuid": "not-a-uuid"}]"#).err().unwrap();
+//! assert_eq!(err.path(), "/0/uuid");
+//! ```
+//!
+//! Deserialization stops at the first failure.  To report every invalid
+//! value in one pass, declare the leaves as [crate::tolerant::Tolerant] and
+//! use [from_str_collecting]: each invalid leaf is kept raw and its error
+//! collected with its path, so the rest of the document is still produced.
+//!
+//! ```
+//! use oscal_types::tolerant::Tolerant;
+//! use oscal_types::{de, URIDatatype, UUIDDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Party {
+//!     uuid: Tolerant<UUIDDatatype>,
+//!     website: Tolerant<URIDatatype>,
+//! }
+//!
+//! let json = r#"[
+//!     {"uuid": "not-a-uuid", "website": "https://example.gov"},
+//!     {"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "website": "example dot gov"}
+//! ]"#;
+//! let collected = de::from_str_collecting::<Vec<Party>>(json);
+//! let paths: Vec<_> = collected.errors.iter().map(|e| e.path()).collect();
+//! assert_eq!(paths, ["/0/uuid", "/1/website"]);
+//! assert!(collected.value.unwrap()[1].uuid.is_valid());
+//! ```
+use serde::de::{
+    self, DeserializeOwned, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde_json::{Map, Value};
+use std::cell::RefCell;
+use std::fmt;
+
+/// A deserialization error and the JSON Pointer of the value that caused it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PathError {
+    path: Option<String>,
+    message: String,
+}
+
+impl PathError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            path: Some(path.into()),
+            message: message.into(),
+        }
+    }
+
+    /// JSON Pointer of the offending value; empty for the document root.
+    pub fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or("")
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Attach `path` unless a deeper path is already known.
+    fn at(mut self, path: &str) -> Self {
+        self.path.get_or_insert_with(|| path.to_string());
+        self
+    }
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.path() {
+            "" => write!(f, "{}", self.message),
+            path => write!(f, "{}: {}", path, self.message),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl de::Error for PathError {
+    fn custom<T: fmt::Display>(msg: T) -> Self {
+        Self {
+            path: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Deserialize `T` from JSON text, reporting the path of any failure.
+pub fn from_str<T: DeserializeOwned>(s: &str) -> Result<T, PathError> {
+    let value = serde_json::from_str::<Value>(s).map_err(|e| PathError::new("", e.to_string()))?;
+    from_value(&value)
+}
+
+/// Deserialize `T` from a JSON value, reporting the path of any failure.
+pub fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T, PathError> {
+    T::deserialize(PathDeserializer::new(value, String::new()))
+}
+
+/// What [from_value_collecting] could read, and every failure.
+#[derive(Debug, Clone)]
+pub struct Collected<T> {
+    /// The document, if only [crate::tolerant::Tolerant] values were
+    /// invalid.
+    pub value: Option<T>,
+    /// Each failure with its path, in the order read.  A failure outside
+    /// a `Tolerant` value ends deserialization, so it is always last.
+    pub errors: Vec<PathError>,
+}
+
+impl<T> Collected<T> {
+    /// The document, or every failure if there was any.
+    pub fn into_result(self) -> Result<T, Vec<PathError>> {
+        match (self.value, self.errors.is_empty()) {
+            (Some(value), true) => Ok(value),
+            _ => Err(self.errors),
+        }
+    }
+}
+
+/// The path being deserialized and the failures collected so far, while
+/// [from_value_collecting] runs on this thread.
+struct Collector {
+    path: String,
+    errors: Vec<PathError>,
+}
+
+thread_local! {
+    static COLLECTOR: RefCell<Option<Collector>> = const { RefCell::new(None) };
+}
+
+/// Record `error` against the value being deserialized, if failures are
+/// being collected.  Returns whether it was recorded.
+pub(crate) fn collect(error: &crate::Error) -> bool {
+    COLLECTOR.with(|collector| match &mut *collector.borrow_mut() {
+        Some(collector) => {
+            let error = PathError::new(collector.path.clone(), error.to_string());
+            collector.errors.push(error);
+            true
+        }
+        None => false,
+    })
+}
+
+/// Deserialize `T` from JSON text, collecting the failure of every
+/// [crate::tolerant::Tolerant] value rather than stopping at the first.
+pub fn from_str_collecting<T: DeserializeOwned>(s: &str) -> Collected<T> {
+    match serde_json::from_str::<Value>(s) {
+        Ok(value) => from_value_collecting(&value),
+        Err(e) => Collected {
+            value: None,
+            errors: vec![PathError::new("", e.to_string())],
+        },
+    }
+}
+
+/// Deserialize `T` from a JSON value, collecting the failure of every
+/// [crate::tolerant::Tolerant] value rather than stopping at the first.
+pub fn from_value_collecting<T: DeserializeOwned>(value: &Value) -> Collected<T> {
+    let collector = Collector {
+        path: String::new(),
+        errors: Vec::new(),
+    };
+    let outer = COLLECTOR.with(|c| c.replace(Some(collector)));
+    let result = from_value::<T>(value);
+    let collector = COLLECTOR.with(|c| c.replace(outer));
+    let mut errors = collector.map(|c| c.errors).unwrap_or_default();
+    let value = match result {
+        Ok(value) => Some(value),
+        Err(e) => {
+            errors.push(e);
+            None
+        }
+    };
+    Collected { value, errors }
+}
+
+/// Append a reference token to a JSON Pointer, escaping `~` and `/`.
+pub fn push_pointer(pointer: &str, token: &str) -> String {
+    format!(
+        "{}/{}",
+        pointer,
+        token.replace('~', "~0").replace('/', "~1")
+    )
+}
+
+/// Deserializes a borrowed [Value], tracking the JSON Pointer of the value
+/// being visited so errors can be attributed to it.
+pub(crate) struct PathDeserializer<'a> {
+    value: &'a Value,
+    path: String,
+}
+
+impl<'a> PathDeserializer<'a> {
+    pub(crate) fn new(value: &'a Value, path: String) -> Self {
+        Self { value, path }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for PathDeserializer<'de> {
+    type Error = PathError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PathError> {
+        COLLECTOR.with(|collector| {
+            if let Some(collector) = &mut *collector.borrow_mut() {
+                collector.path.clone_from(&self.path);
+            }
+        });
+        let result = match self.value {
+            Value::Null => visitor.visit_unit(),
+            Value::Bool(b) => visitor.visit_bool(*b),
+            Value::Number(n) => match (n.as_u64(), n.as_i64(), n.as_f64()) {
+                (Some(u), _, _) => visitor.visit_u64(u),
+                (_, Some(i), _) => visitor.visit_i64(i),
+                (_, _, Some(f)) => visitor.visit_f64(f),
+                _ => Err(de::Error::custom("unrepresentable number")),
+            },
+            Value::String(s) => visitor.visit_borrowed_str(s),
+            Value::Array(items) => visitor.visit_seq(Seq {
+                items: items.iter().enumerate(),
+                path: &self.path,
+            }),
+            Value::Object(map) => visitor.visit_map(Entries::new(map, &self.path)),
+        };
+        result.map_err(|e| e.at(&self.path))
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, PathError> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => {
+                let path = self.path.clone();
+                visitor.visit_some(self).map_err(|e| e.at(&path))
+            }
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, PathError> {
+        let path = self.path.clone();
+        visitor.visit_newtype_struct(self).map_err(|e| e.at(&path))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PathError> {
+        let result = match self.value {
+            Value::String(variant) => visitor.visit_enum(Variant {
+                variant,
+                value: None,
+                path: &self.path,
+            }),
+            Value::Object(map) if map.len() == 1 => {
+                let (variant, value) = map.iter().next().expect("one entry");
+                visitor.visit_enum(Variant {
+                    variant,
+                    value: Some(value),
+                    path: &self.path,
+                })
+            }
+            _ => Err(de::Error::custom(
+                "expected a string or single key object for an enum",
+            )),
+        };
+        result.map_err(|e| e.at(&self.path))
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct Seq<'a, I> {
+    items: I,
+    path: &'a str,
+}
+
+impl<'de, I> SeqAccess<'de> for Seq<'_, I>
+where
+    I: Iterator<Item = (usize, &'de Value)>,
+{
+    type Error = PathError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, PathError> {
+        match self.items.next() {
+            Some((i, value)) => {
+                // Errors raised by the seed after the value was read (such as
+                // TryFrom validation) still belong to this element
+                let path = push_pointer(self.path, &i.to_string());
+                seed.deserialize(PathDeserializer::new(value, path.clone()))
+                    .map(Some)
+                    .map_err(|e| e.at(&path))
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+struct Entries<'de, 'a> {
+    entries: serde_json::map::Iter<'de>,
+    pending: Option<(&'de String, &'de Value)>,
+    path: &'a str,
+}
+
+impl<'de, 'a> Entries<'de, 'a> {
+    fn new(map: &'de Map<String, Value>, path: &'a str) -> Self {
+        Self {
+            entries: map.iter(),
+            pending: None,
+            path,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for Entries<'de, '_> {
+    type Error = PathError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, PathError> {
+        match self.entries.next() {
+            Some((key, value)) => {
+                self.pending = Some((key, value));
+                let key = de::value::BorrowedStrDeserializer::<PathError>::new(key);
+                seed.deserialize(key).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, PathError> {
+        let (key, value) = self
+            .pending
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+        let path = push_pointer(self.path, key);
+        seed.deserialize(PathDeserializer::new(value, path.clone()))
+            .map_err(|e| e.at(&path))
+    }
+}
+
+struct Variant<'de, 'a> {
+    variant: &'de str,
+    value: Option<&'de Value>,
+    path: &'a str,
+}
+
+impl<'de, 'a> EnumAccess<'de> for Variant<'de, 'a> {
+    type Error = PathError;
+    type Variant = VariantValue<'de>;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, Self::Variant), PathError> {
+        let name = de::value::BorrowedStrDeserializer::<PathError>::new(self.variant);
+        let variant = seed.deserialize(name)?;
+        let path = push_pointer(self.path, self.variant);
+        Ok((
+            variant,
+            VariantValue {
+                value: self.value,
+                path,
+            },
+        ))
+    }
+}
+
+struct VariantValue<'de> {
+    value: Option<&'de Value>,
+    path: String,
+}
+
+impl<'de> VariantValue<'de> {
+    fn content(self) -> Result<PathDeserializer<'de>, PathError> {
+        match self.value {
+            Some(value) => Ok(PathDeserializer::new(value, self.path)),
+            None => Err(de::Error::custom("expected enum variant content")),
+        }
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantValue<'de> {
+    type Error = PathError;
+
+    fn unit_variant(self) -> Result<(), PathError> {
+        match self.value {
+            None | Some(Value::Null) => Ok(()),
+            Some(_) => Err(de::Error::custom("expected a unit variant")),
+        }
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, PathError> {
+        let path = self.path.clone();
+        seed.deserialize(self.content()?).map_err(|e| e.at(&path))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, PathError> {
+        de::Deserializer::deserialize_seq(self.content()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, PathError> {
+        de::Deserializer::deserialize_map(self.content()?, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tolerant::Tolerant;
+    use crate::{DateDatatype, StringDatatype, UUIDDatatype};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Party {
+        uuid: UUIDDatatype,
+        name: Option<StringDatatype>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Metadata {
+        #[serde(rename = "last-modified")]
+        last_modified: DateDatatype,
+        parties: Vec<Party>,
+    }
+
+    #[test]
+    fn test_reports_path_of_nested_failure() {
+        let json = r#"{
+            "last-modified": "2024-02-10",
+            "parties": [
+                {"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"},
+                {"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "name": " padded"}
+            ]
+        }"#;
+        let err = from_str::<Metadata>(json).unwrap_err();
+        assert_eq!(err.path(), "/parties/1/name");
+        assert!(err.message().contains("whitespace"));
+    }
+
+    #[test]
+    fn test_reports_path_of_datatype_failure() {
+        let value = serde_json::json!({
+            "last-modified": "2024-02-10",
+            "parties": [{"uuid": "not-a-uuid"}]
+        });
+        let err = from_value::<Metadata>(&value).unwrap_err();
+        assert_eq!(err.path(), "/parties/0/uuid");
+        assert_eq!(
+            err.to_string(),
+            format!("/parties/0/uuid: {}", err.message())
+        );
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Reviewed {
+        #[allow(dead_code)]
+        sponsor: UUIDDatatype,
+        reviewers: Vec<Tolerant<UUIDDatatype>>,
+    }
+
+    #[test]
+    fn test_collects_every_tolerant_failure() {
+        let value = serde_json::json!({
+            "sponsor": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+            "reviewers": ["not-a-uuid", "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "a78f7e4c"]
+        });
+        let collected = from_value_collecting::<Reviewed>(&value);
+        let paths: Vec<_> = collected.errors.iter().map(PathError::path).collect();
+        assert_eq!(paths, ["/reviewers/0", "/reviewers/2"]);
+        assert!(collected.value.unwrap().reviewers[1].is_valid());
+
+        // Anything else still ends deserialization, after what was collected
+        let value = serde_json::json!({"sponsor": "a78f7e4c", "reviewers": ["not-a-uuid"]});
+        let errors = from_value_collecting::<Reviewed>(&value)
+            .into_result()
+            .unwrap_err();
+        let paths: Vec<_> = errors.iter().map(PathError::path).collect();
+        assert_eq!(paths, ["/reviewers/0", "/sponsor"]);
+        let errors = from_str_collecting::<Reviewed>("{")
+            .into_result()
+            .unwrap_err();
+        assert_eq!(errors.len(), 1);
+
+        // Nothing is collected outside a collecting call
+        assert!(!collect(&crate::Error::BooleanParse));
+        let json = r#"{"sponsor": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "reviewers": []}"#;
+        assert!(from_str_collecting::<Reviewed>(json).into_result().is_ok());
+    }
+
+    #[test]
+    fn test_success_and_escaping() {
+        let value = serde_json::json!({"last-modified": "2024-02-10", "parties": []});
+        assert!(from_value::<Metadata>(&value).is_ok());
+        assert_eq!(push_pointer("/a", "b/c~d"), "/a/b~1c~0d");
+    }
+}
@@ -0,0 +1,213 @@
+//! Explaining why two values differ.
+//!
+//! `PartialEq` on most datatypes compares lexical forms, and on date-times
+//! compares instants, so `2024-02-10T12:00:00Z` and `2024-02-10T07:00:00-05:00`
+//! are equal even though they are written differently.  [ExplainDiff]
+//! categorizes how two lexical forms differ for review and diff tooling.
+//!
+//! ```
+//! use oscal_types::{diff::{DiffExplanation, ExplainDiff}, DateTimeWithTimezoneDatatype};
+//!
+//! let a = DateTimeWithTimezoneDatatype::try_from("2024-02-10T12:00:00Z").unwrap();
+//! let b = DateTimeWithTimezoneDatatype::try_from("2024-02-10T07:00:00-05:00").unwrap();
+//! assert_eq!(a.explain_diff(&b), Some(DiffExplanation::Offset));
+//! assert_eq!(a.explain_diff(&a), None);
+//! ```
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime};
+use percent_encoding::percent_decode_str;
+
+use crate::lexical::collapse_whitespace;
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffExplanation {
+    /// The values differ only in letter case.
+    Case,
+    /// The values differ only in whitespace.
+    Whitespace,
+    /// The same point in time, written with different timezone offsets, or
+    /// with and without one.
+    Offset,
+    /// The same URI, with different percent-encoding.
+    Encoding,
+    /// The same value with a different lexical form, e.g. trailing zeros in
+    /// fractional seconds.
+    Lexical,
+    /// The values are different.
+    Value,
+}
+
+pub trait ExplainDiff {
+    /// Why `self` and `other` differ, or `None` if they are identical.
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation>;
+}
+
+fn explain_text(left: &str, right: &str) -> Option<DiffExplanation> {
+    if left == right {
+        None
+    } else if left.to_lowercase() == right.to_lowercase() {
+        Some(DiffExplanation::Case)
+    } else if collapse_whitespace(left) == collapse_whitespace(right) {
+        Some(DiffExplanation::Whitespace)
+    } else {
+        Some(DiffExplanation::Value)
+    }
+}
+
+impl ExplainDiff for StringDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        explain_text(self, other)
+    }
+}
+
+/// Valid UUIDs are stored in lowercase, however they are constructed or
+/// deserialized, so they can only differ in value.
+impl ExplainDiff for UUIDDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        match self == other {
+            true => None,
+            false => Some(DiffExplanation::Value),
+        }
+    }
+}
+
+fn explain_uri(left: &str, right: &str) -> Option<DiffExplanation> {
+    let decode = |s: &str| percent_decode_str(s).decode_utf8_lossy().into_owned();
+    match explain_text(left, right)? {
+        DiffExplanation::Case => Some(DiffExplanation::Case),
+        _ if decode(left) == decode(right) => Some(DiffExplanation::Encoding),
+        _ => Some(DiffExplanation::Value),
+    }
+}
+
+impl ExplainDiff for URIDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        explain_uri(self, other)
+    }
+}
+
+impl ExplainDiff for URIReferenceDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        explain_uri(self, other)
+    }
+}
+
+impl ExplainDiff for DateDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        if self == other {
+            return None;
+        }
+        match (self.parse::<NaiveDate>(), other.parse::<NaiveDate>()) {
+            (Ok(left), Ok(right)) if left == right => Some(DiffExplanation::Lexical),
+            _ => Some(DiffExplanation::Value),
+        }
+    }
+}
+
+fn explain_date_time(left: &str, right: &str) -> Option<DiffExplanation> {
+    if left == right {
+        return None;
+    }
+    let aware = |s: &str| s.parse::<DateTime<FixedOffset>>().ok();
+    let naive = |s: &str| match aware(s) {
+        Some(dt) => Some(dt.naive_local()),
+        None => s.parse::<NaiveDateTime>().ok(),
+    };
+    let explanation = match (aware(left), aware(right)) {
+        (Some(l), Some(r)) if l == r && l.offset() != r.offset() => DiffExplanation::Offset,
+        (Some(l), Some(r)) if l == r => DiffExplanation::Lexical,
+        (Some(_), Some(_)) => DiffExplanation::Value,
+        (l, r) => match (naive(left), naive(right)) {
+            (Some(nl), Some(nr)) if nl == nr && l.is_some() != r.is_some() => {
+                DiffExplanation::Offset
+            }
+            (Some(nl), Some(nr)) if nl == nr => DiffExplanation::Lexical,
+            _ => DiffExplanation::Value,
+        },
+    };
+    Some(explanation)
+}
+
+impl ExplainDiff for DateTimeDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        explain_date_time(self, other)
+    }
+}
+
+impl ExplainDiff for DateTimeWithTimezoneDatatype {
+    fn explain_diff(&self, other: &Self) -> Option<DiffExplanation> {
+        explain_date_time(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_string() {
+        let s = |v: &str| StringDatatype::try_from(v).unwrap();
+        assert_eq!(
+            s("Access Control").explain_diff(&s("access control")),
+            Some(DiffExplanation::Case)
+        );
+        assert_eq!(
+            s("a  b").explain_diff(&s("a b")),
+            Some(DiffExplanation::Whitespace)
+        );
+        assert_eq!(s("a").explain_diff(&s("b")), Some(DiffExplanation::Value));
+    }
+
+    #[test]
+    fn test_explain_date_time() {
+        let dt = |v: &str| DateTimeWithTimezoneDatatype::try_from(v).unwrap();
+        assert_eq!(
+            dt("2024-02-10T12:00:00Z").explain_diff(&dt("2024-02-10T07:00:00-05:00")),
+            Some(DiffExplanation::Offset)
+        );
+        assert_eq!(
+            dt("2024-02-10T12:00:00.50Z").explain_diff(&dt("2024-02-10T12:00:00.5Z")),
+            Some(DiffExplanation::Lexical)
+        );
+        assert_eq!(
+            dt("2024-02-10T12:00:00Z").explain_diff(&dt("2024-02-10T12:00:01Z")),
+            Some(DiffExplanation::Value)
+        );
+
+        let naive = |v: &str| DateTimeDatatype::try_from(v).unwrap();
+        assert_eq!(
+            naive("2024-02-10T12:00:00").explain_diff(&naive("2024-02-10T12:00:00Z")),
+            Some(DiffExplanation::Offset)
+        );
+    }
+
+    #[test]
+    fn test_explain_uri() {
+        let uri = |v: &str| URIDatatype::try_from(v).unwrap();
+        assert_eq!(
+            uri("https://a.gov/a").explain_diff(&uri("https://a.gov/b")),
+            Some(DiffExplanation::Value)
+        );
+        assert_eq!(
+            uri("https://a.gov/%7Euser").explain_diff(&uri("https://a.gov/~user")),
+            Some(DiffExplanation::Encoding)
+        );
+        assert_eq!(
+            uri("HTTPS://A.GOV/x").explain_diff(&uri("https://a.gov/x")),
+            Some(DiffExplanation::Case)
+        );
+    }
+
+    #[test]
+    fn test_explain_uuid() {
+        let upper = "8D6C1E1E-7C43-4D2B-9A8F-3E5B8C6A0F11";
+        let parsed: UUIDDatatype = serde_json::from_str(&format!("\"{upper}\"")).unwrap();
+        assert_eq!(&*parsed, upper.to_lowercase());
+        let lower = UUIDDatatype::try_from(upper.to_lowercase().as_str()).unwrap();
+        assert_eq!(parsed.explain_diff(&lower), None);
+        assert_eq!(
+            parsed.explain_diff(&UUIDDatatype::new()),
+            Some(DiffExplanation::Value)
+        );
+    }
+}
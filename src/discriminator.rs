@@ -0,0 +1,219 @@
+//! Enums over the tokens that discriminate OSCAL assemblies.
+//!
+//! Assemblies such as `party` and `role` carry a token, e.g. `party-type`
+//! or `role-id`, whose known values are listed by the model but which
+//! documents may extend.  [crate::discriminator!] declares an enum with a
+//! variant per known value and an `Unknown` variant that keeps any other
+//! token as written, so documents round-trip unchanged.  Each known value is
+//! checked to be a token at compile time.
+//!
+//! ```
+//! use oscal_types::discriminator;
+//! use oscal_types::registry::TypeRegistry;
+//!
+//! discriminator! {
+//!     /// The kind of a party.
+//!     pub enum PartyType {
+//!         Person = "person",
+//!         Organization = "organization",
+//!     }
+//! }
+//!
+//! assert_eq!("person".parse::<PartyType>().unwrap(), PartyType::Person);
+//! let robot: PartyType = serde_json::from_str(r#""robot""#).unwrap();
+//! assert!(!robot.is_known());
+//! assert_eq!(serde_json::to_string(&robot).unwrap(), r#""robot""#);
+//! assert!("not a token".parse::<PartyType>().is_err());
+//!
+//! let mut registry = TypeRegistry::builtin();
+//! PartyType::register(&mut registry);
+//! assert!(registry.validate("PartyType", "robot").is_ok());
+//! ```
+
+#[doc(hidden)]
+pub mod __private {
+    pub use serde;
+}
+
+/// Declare an enum over the known values of a discriminator token.
+///
+/// The enum gets an `Unknown(TokenDatatype)` variant for every other token,
+/// `FromStr` and conversions to and from [crate::TokenDatatype],
+/// `Display`, serde as the token, and the [crate::Validate],
+/// [crate::Metaschema] and [crate::Base] impls that
+/// [crate::registry::TypeInfo::of] needs.  See the
+/// [module](mod@crate::discriminator) for an example.
+#[macro_export]
+macro_rules! discriminator {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant,)+
+            /// A token that is not one of the known values.
+            Unknown($crate::TokenDatatype),
+        }
+
+        const _: () = {
+            $(assert!(
+                $crate::literal::is_token($value),
+                concat!("invalid token ", stringify!($value))
+            );)+
+        };
+
+        impl $name {
+            /// The known values, in declaration order.
+            pub const VALUES: &'static [&'static str] = &[$($value),+];
+
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $value,)+
+                    Self::Unknown(token) => token,
+                }
+            }
+
+            /// Whether this is one of the [Self::VALUES].
+            pub fn is_known(&self) -> bool {
+                !matches!(self, Self::Unknown(_))
+            }
+
+            /// Register this type in `registry` under its name.
+            pub fn register(registry: &mut $crate::registry::TypeRegistry) {
+                registry.register($crate::registry::TypeInfo::of::<Self>(stringify!($name)));
+            }
+        }
+
+        impl ::std::convert::From<$crate::TokenDatatype> for $name {
+            fn from(token: $crate::TokenDatatype) -> Self {
+                match &*token {
+                    $($value => Self::$variant,)+
+                    _ => Self::Unknown(token),
+                }
+            }
+        }
+
+        impl ::std::convert::From<$name> for $crate::TokenDatatype {
+            fn from(value: $name) -> Self {
+                match value {
+                    $name::Unknown(token) => token,
+                    known => $crate::TokenDatatype::try_from(known.as_str())
+                        .expect("known values are tokens"),
+                }
+            }
+        }
+
+        /// Fails only for values that are not tokens.
+        impl ::std::str::FromStr for $name {
+            type Err = $crate::Error;
+            fn from_str(value: &str) -> Result<Self, Self::Err> {
+                $crate::TokenDatatype::try_from(value).map(Self::from)
+            }
+        }
+
+        impl ::std::fmt::Display for $name {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl $crate::discriminator::__private::serde::Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: $crate::discriminator::__private::serde::Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> $crate::discriminator::__private::serde::Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: $crate::discriminator::__private::serde::Deserializer<'de>,
+            {
+                <$crate::TokenDatatype as $crate::discriminator::__private::serde::Deserialize>
+                    ::deserialize(deserializer)
+                    .map(Self::from)
+            }
+        }
+
+        impl $crate::Validate for $name {
+            fn validate(value: &str) -> Result<(), $crate::Error> {
+                <$crate::TokenDatatype as $crate::Validate>::validate(value)
+            }
+        }
+
+        impl $crate::Metaschema for $name {
+            fn _type() -> Option<&'static str> {
+                Some("string")
+            }
+            fn description() -> Option<&'static str> {
+                Some(concat!(
+                    "A token, one of: ",
+                    $crate::discriminator!(@join $($value),+)
+                ))
+            }
+        }
+
+        impl $crate::Base for $name {
+            fn base_type() -> String {
+                String::from(stringify!($name))
+            }
+            fn ref_type() -> String {
+                String::from(stringify!($name))
+            }
+        }
+    };
+    (@join $first:literal $(, $rest:literal)*) => {
+        concat!($first $(, ", ", $rest)*)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::registry::TypeRegistry;
+    use crate::*;
+
+    discriminator! {
+        enum RoleId {
+            Maintainer = "maintainer",
+            AssetOwner = "asset-owner",
+        }
+    }
+
+    #[test]
+    fn test_discriminator() {
+        assert_eq!("asset-owner".parse::<RoleId>().unwrap(), RoleId::AssetOwner);
+        let custom: RoleId = "x_reviewer".parse().unwrap();
+        assert_eq!(custom, RoleId::Unknown(token!("x_reviewer")));
+        assert_eq!(custom.to_string(), "x_reviewer");
+        assert!("1st".parse::<RoleId>().is_err());
+        assert_eq!(RoleId::VALUES, ["maintainer", "asset-owner"]);
+        assert_eq!(
+            TokenDatatype::from(RoleId::Maintainer),
+            token!("maintainer")
+        );
+
+        let roles: Vec<RoleId> = serde_json::from_str(r#"["maintainer", "auditor"]"#).unwrap();
+        assert!(roles[0].is_known() && !roles[1].is_known());
+        assert_eq!(
+            serde_json::to_string(&roles).unwrap(),
+            r#"["maintainer","auditor"]"#
+        );
+        assert!(serde_json::from_str::<RoleId>(r#""a b""#).is_err());
+
+        let mut registry = TypeRegistry::new();
+        RoleId::register(&mut registry);
+        let info = registry.get("RoleId").unwrap();
+        assert_eq!(
+            info.description(),
+            Some("A token, one of: maintainer, asset-owner")
+        );
+        let role = registry.construct("RoleId", "asset-owner").unwrap();
+        assert_eq!(role.downcast_ref::<RoleId>(), Some(&RoleId::AssetOwner));
+    }
+}
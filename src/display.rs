@@ -0,0 +1,327 @@
+//! Rendering values for people reviewing reports.
+//!
+//! [pretty] renders the lexical form of a value for reading rather than
+//! for round-tripping: date-times in the local timezone with the time
+//! relative to now, durations in words, UUIDs shortened and long URIs
+//! ellipsized in the middle.  Values that are not valid for their kind
+//! are rendered as they are.
+//!
+//! The local timezone and the current time come from [crate::clock::now],
+//! so a [crate::clock::Clock::Fixed] clock makes reports reproducible:
+//!
+//! ```
+//! use chrono::DateTime;
+//! use oscal_types::clock::Clock;
+//! use oscal_types::display::{pretty, PrettyOptions};
+//! use oscal_types::{config, DatatypeKind};
+//!
+//! let now = DateTime::parse_from_rfc3339("2024-02-13T12:00:00-05:00").unwrap();
+//! let mut fixed = config::Config::new();
+//! fixed.clock = Clock::Fixed(now);
+//! let options = PrettyOptions::new();
+//! config::scoped(fixed, || {
+//!     assert_eq!(
+//!         pretty("2024-02-10T14:30:00Z", DatatypeKind::DateTimeWithTimezone, &options),
+//!         "2024-02-10 09:30:00 -05:00 (3 days ago)"
+//!     );
+//! });
+//! assert_eq!(
+//!     pretty("P1DT2H30M", DatatypeKind::DayTimeDuration, &options),
+//!     "1 day, 2 hours, 30 minutes"
+//! );
+//! assert_eq!(
+//!     pretty("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", DatatypeKind::Uuid, &options),
+//!     "a78f7e4c…"
+//! );
+//! ```
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeDelta};
+
+use crate::clock;
+use crate::*;
+
+/// How [pretty] renders values.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrettyOptions {
+    /// The timezone date-times are shown in, or the local timezone of
+    /// [clock::now] when `None`.  Date-times without an offset are shown
+    /// as written.
+    pub timezone: Option<FixedOffset>,
+    /// Follow dates and date-times with the time relative to now, e.g.
+    /// `(3 days ago)`.
+    pub relative: bool,
+    /// Show UUIDs in full rather than only their first group.
+    pub full_uuids: bool,
+    /// The most chars a URI is shown with before its middle is replaced
+    /// with an ellipsis.
+    pub max_uri_length: usize,
+}
+
+impl PrettyOptions {
+    pub const fn new() -> Self {
+        Self {
+            timezone: None,
+            relative: true,
+            full_uuids: false,
+            max_uri_length: 60,
+        }
+    }
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Render `value`, the lexical form of a `kind` value, for reading.
+pub fn pretty(value: &str, kind: DatatypeKind, options: &PrettyOptions) -> String {
+    if kind.validate(value).is_err() {
+        return value.to_string();
+    }
+    match kind {
+        DatatypeKind::Date => pretty_date(value, options),
+        DatatypeKind::DateTime | DatatypeKind::DateTimeWithTimezone => {
+            pretty_date_time(value, options)
+        }
+        DatatypeKind::DayTimeDuration => match DayTimeDurationDatatype::try_from(value) {
+            Ok(duration) => humanize_day_time(duration.as_chrono_duration()),
+            Err(_) => value.to_string(),
+        },
+        DatatypeKind::YearMonthDuration => match YearMonthDurationDatatype::try_from(value) {
+            Ok(duration) => humanize_year_month(duration.months()),
+            Err(_) => value.to_string(),
+        },
+        DatatypeKind::Uuid if !options.full_uuids => match value.split_once('-') {
+            Some((first, _)) => format!("{first}…"),
+            None => value.to_string(),
+        },
+        DatatypeKind::Uri | DatatypeKind::UriReference => {
+            ellipsize_middle(value, options.max_uri_length)
+        }
+        _ => value.to_string(),
+    }
+}
+
+fn pretty_date(value: &str, options: &PrettyOptions) -> String {
+    let Ok(date) = value.parse::<NaiveDate>() else {
+        return value.to_string();
+    };
+    let mut pretty = date.format("%Y-%m-%d").to_string();
+    if options.relative {
+        let today = clock::now().date_naive();
+        let days = date.signed_duration_since(today).num_days();
+        let relative = match days {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            -1 => "yesterday".to_string(),
+            _ => relative(TimeDelta::days(days)),
+        };
+        pretty.push_str(&format!(" ({relative})"));
+    }
+    pretty
+}
+
+fn pretty_date_time(value: &str, options: &PrettyOptions) -> String {
+    let now = clock::now();
+    let (mut pretty, delta) = match value.parse::<DateTime<FixedOffset>>() {
+        Ok(date_time) => {
+            let timezone = options.timezone.unwrap_or(*now.offset());
+            let local = date_time.with_timezone(&timezone);
+            let pretty = local.format("%Y-%m-%d %H:%M:%S %:z").to_string();
+            (pretty, date_time.signed_duration_since(now))
+        }
+        Err(_) => match value.parse::<NaiveDateTime>() {
+            Ok(naive) => {
+                let pretty = naive.format("%Y-%m-%d %H:%M:%S").to_string();
+                (pretty, naive.signed_duration_since(now.naive_local()))
+            }
+            Err(_) => return value.to_string(),
+        },
+    };
+    if options.relative {
+        pretty.push_str(&format!(" ({})", relative(delta)));
+    }
+    pretty
+}
+
+/// `count unit`, pluralized.
+fn quantity(count: i64, unit: &str) -> String {
+    match count {
+        1 => format!("1 {unit}"),
+        _ => format!("{count} {unit}s"),
+    }
+}
+
+/// The largest whole unit of `delta`, e.g. `in 2 hours` or `3 days ago`.
+fn relative(delta: TimeDelta) -> String {
+    let seconds = delta.num_seconds().abs();
+    let amount = match seconds {
+        0..60 => return "just now".to_string(),
+        60..3_600 => quantity(seconds / 60, "minute"),
+        3_600..86_400 => quantity(seconds / 3_600, "hour"),
+        86_400..5_184_000 => quantity(seconds / 86_400, "day"),
+        5_184_000..63_072_000 => quantity(seconds / 2_592_000, "month"),
+        _ => quantity(seconds / 31_536_000, "year"),
+    };
+    match delta < TimeDelta::zero() {
+        true => format!("{amount} ago"),
+        false => format!("in {amount}"),
+    }
+}
+
+/// The non-zero components of `delta` in words, e.g. `1 day, 2 hours`.
+fn humanize_day_time(delta: TimeDelta) -> String {
+    let sign = if delta < TimeDelta::zero() { "-" } else { "" };
+    let delta = delta.abs();
+    let seconds = delta.num_seconds();
+    let mut parts: Vec<String> = [
+        (seconds / 86_400, "day"),
+        (seconds % 86_400 / 3_600, "hour"),
+        (seconds % 3_600 / 60, "minute"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count != 0)
+    .map(|(count, unit)| quantity(count, unit))
+    .collect();
+    let nanos = delta.subsec_nanos();
+    match (seconds % 60, nanos) {
+        (0, 0) if !parts.is_empty() => {}
+        (whole, 0) => parts.push(quantity(whole, "second")),
+        (whole, nanos) => {
+            let fraction = format!("{nanos:09}");
+            let fraction = fraction.trim_end_matches('0');
+            parts.push(format!("{whole}.{fraction} seconds"));
+        }
+    }
+    format!("{sign}{}", parts.join(", "))
+}
+
+/// Months in words, e.g. `1 year, 2 months`.
+fn humanize_year_month(months: i64) -> String {
+    let sign = if months < 0 { "-" } else { "" };
+    let months = months.abs();
+    let parts = match (months / 12, months % 12) {
+        (0, months) => quantity(months, "month"),
+        (years, 0) => quantity(years, "year"),
+        (years, months) => format!("{}, {}", quantity(years, "year"), quantity(months, "month")),
+    };
+    format!("{sign}{parts}")
+}
+
+/// `value` with its middle replaced by an ellipsis if it is longer than
+/// `max` chars, keeping more of the start than the end.
+fn ellipsize_middle(value: &str, max: usize) -> String {
+    let length = value.chars().count();
+    if length <= max {
+        return value.to_string();
+    }
+    let kept = max.saturating_sub(1);
+    let tail = kept / 3;
+    let head = kept - tail;
+    let start: String = value.chars().take(head).collect();
+    let end: String = value.chars().skip(length - tail).collect();
+    format!("{start}…{end}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+
+    fn fixed(now: &str) -> config::Config {
+        config::Config {
+            clock: Clock::Fixed(DateTime::parse_from_rfc3339(now).unwrap()),
+            ..config::Config::new()
+        }
+    }
+
+    #[test]
+    fn test_pretty_dates() {
+        let options = PrettyOptions::new();
+        config::scoped(fixed("2024-02-10T09:30:00+01:00"), || {
+            let pretty = |value, kind| pretty(value, kind, &options);
+            assert_eq!(
+                pretty("2024-02-10T09:30:30+01:00", DatatypeKind::DateTime),
+                "2024-02-10 09:30:30 +01:00 (just now)"
+            );
+            assert_eq!(
+                pretty("2024-02-10T11:00:00Z", DatatypeKind::DateTimeWithTimezone),
+                "2024-02-10 12:00:00 +01:00 (in 2 hours)"
+            );
+            assert_eq!(
+                pretty("2023-11-10T08:30:00", DatatypeKind::DateTime),
+                "2023-11-10 08:30:00 (3 months ago)"
+            );
+            assert_eq!(
+                pretty("2020-02-10", DatatypeKind::Date),
+                "2020-02-10 (4 years ago)"
+            );
+            assert_eq!(
+                pretty("2024-02-09", DatatypeKind::Date),
+                "2024-02-09 (yesterday)"
+            );
+            assert_eq!(
+                pretty("2024-02-30", DatatypeKind::Date),
+                "2024-02-30",
+                "invalid values are unchanged"
+            );
+        });
+
+        let options = PrettyOptions {
+            timezone: FixedOffset::west_opt(5 * 3600),
+            relative: false,
+            ..PrettyOptions::new()
+        };
+        assert_eq!(
+            pretty(
+                "2024-02-10T11:00:00Z",
+                DatatypeKind::DateTimeWithTimezone,
+                &options
+            ),
+            "2024-02-10 06:00:00 -05:00"
+        );
+    }
+
+    #[test]
+    fn test_pretty_durations() {
+        let options = PrettyOptions::new();
+        let pretty = |value, kind| pretty(value, kind, &options);
+        assert_eq!(pretty("PT1H", DatatypeKind::DayTimeDuration), "1 hour");
+        assert_eq!(pretty("PT0S", DatatypeKind::DayTimeDuration), "0 seconds");
+        assert_eq!(
+            pretty("-P2DT1.25S", DatatypeKind::DayTimeDuration),
+            "-2 days, 1.25 seconds"
+        );
+        assert_eq!(
+            pretty("P1Y2M", DatatypeKind::YearMonthDuration),
+            "1 year, 2 months"
+        );
+        assert_eq!(pretty("P24M", DatatypeKind::YearMonthDuration), "2 years");
+        assert_eq!(pretty("-P1M", DatatypeKind::YearMonthDuration), "-1 month");
+    }
+
+    #[test]
+    fn test_pretty_identifiers() {
+        let uuid = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+        let uri = "https://example.gov/oscal/catalogs/very/long/path/to/a/catalog/file.json";
+        let options = PrettyOptions {
+            max_uri_length: 30,
+            ..PrettyOptions::new()
+        };
+        assert_eq!(pretty(uuid, DatatypeKind::Uuid, &options), "a78f7e4c…");
+        let shortened = pretty(uri, DatatypeKind::Uri, &options);
+        assert_eq!(shortened, "https://example.gov/…file.json");
+        assert_eq!(shortened.chars().count(), 30);
+        assert_eq!(
+            pretty("#frag", DatatypeKind::UriReference, &options),
+            "#frag"
+        );
+
+        let full = PrettyOptions {
+            full_uuids: true,
+            ..PrettyOptions::new()
+        };
+        assert_eq!(pretty(uuid, DatatypeKind::Uuid, &full), uuid);
+        assert_eq!(pretty("a b", DatatypeKind::String, &full), "a b");
+    }
+}
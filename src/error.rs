@@ -8,22 +8,115 @@ pub enum Error {
     AddressParse(#[from] AddrParseError),
     #[error("Boolean parsing error")]
     BooleanParse,
-    #[error("UUID parsing error")]
+    #[error("UUID parsing error: {0}")]
     UuidParse(#[from] uuid::Error),
-    #[error("Date parsing error")]
+    #[error("Unsupported UUID version {0}")]
+    UuidVersion(usize),
+    #[error("Date parsing error: {0}")]
     DateParse(#[from] chrono::ParseError),
+    #[error("Number parsing error")]
+    NumberParse,
+    #[error("Number out of range")]
+    NumberRange,
     #[error("Duration parsing error")]
     DurationParse,
     #[error("String parsing error {0}")]
     StringParse(String),
-    #[error("URI parsing error")]
+    #[error("URI parsing error: {0}")]
     UriParse(#[from] fluent_uri::ParseError),
     #[error("URI must be absolute")]
     UriAbsolute,
+    #[error("URI path conversion error: {0}")]
+    UriPath(String),
+    #[error("Email address is missing an @")]
+    EmailMissingAt,
+    #[error("Invalid email local part: {0}")]
+    EmailLocalPart(String),
+    #[error("Invalid email domain: {0}")]
+    EmailDomain(String),
+    #[error("Email address is longer than 254 octets")]
+    EmailLength,
+    #[error("Invalid base64: {0}")]
+    Base64(String),
+    #[error("Invalid hostname {0}")]
+    Hostname(String),
+    #[error("Invalid CIDR block: {0}")]
+    Cidr(String),
     #[error("NCName illegal first char")]
     NCNameIllegalFirstChar,
     #[error("NCName illegal  char")]
     NCNameIllegalChar,
+    #[error("Markup error: {0}")]
+    Markup(String),
+    #[error("Markup too complex: {0}")]
+    MarkupTooComplex(String),
+    #[error("QName parsing error: {0}")]
+    QNameParse(String),
+    #[error("Unbound namespace prefix: {0:?}")]
+    UnboundPrefix(String),
+    #[error("Metapath cast error: {0}")]
+    MetapathCast(String),
     #[error("Not a recognized type: {0}")]
     UnrecognizedTypeName(String),
+    #[error("Invalid configuration: {0}")]
+    Config(String),
+    #[error("Archive decoding error: {0}")]
+    Archive(String),
+    #[error("I/O error: {0}")]
+    Io(String),
+    #[error("Value does not match the {0} pattern")]
+    PatternMismatch(&'static str),
+    #[error("Duplicate key at {0}")]
+    DuplicateKey(String),
+    #[error("Invalid timezone offset: {0}")]
+    TimezoneOffset(String),
+    #[error("Name is not registered: {0}")]
+    UnregisteredName(String),
+}
+
+impl Error {
+    /// A stable, machine-readable name for the kind of error, e.g.
+    /// `"uuid-version"`.
+    ///
+    /// The code is a `&'static str`, so it can be logged without `std`
+    /// formatting or allocation, e.g. by `defmt` as `{=str}` on embedded
+    /// targets.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::AddressParse(_) => "address-parse",
+            Error::BooleanParse => "boolean-parse",
+            Error::UuidParse(_) => "uuid-parse",
+            Error::UuidVersion(_) => "uuid-version",
+            Error::DateParse(_) => "date-parse",
+            Error::NumberParse => "number-parse",
+            Error::NumberRange => "number-range",
+            Error::DurationParse => "duration-parse",
+            Error::StringParse(_) => "string-parse",
+            Error::UriParse(_) => "uri-parse",
+            Error::UriAbsolute => "uri-absolute",
+            Error::UriPath(_) => "uri-path",
+            Error::EmailMissingAt => "email-missing-at",
+            Error::EmailLocalPart(_) => "email-local-part",
+            Error::EmailDomain(_) => "email-domain",
+            Error::EmailLength => "email-length",
+            Error::Base64(_) => "base64",
+            Error::Hostname(_) => "hostname",
+            Error::Cidr(_) => "cidr",
+            Error::NCNameIllegalFirstChar => "ncname-illegal-first-char",
+            Error::NCNameIllegalChar => "ncname-illegal-char",
+            Error::Markup(_) => "markup",
+            Error::MarkupTooComplex(_) => "markup-too-complex",
+            Error::QNameParse(_) => "qname-parse",
+            Error::UnboundPrefix(_) => "unbound-prefix",
+            Error::MetapathCast(_) => "metapath-cast",
+            Error::UnrecognizedTypeName(_) => "unrecognized-type-name",
+            Error::Config(_) => "config",
+            Error::Archive(_) => "archive",
+            Error::Io(_) => "io",
+            Error::PatternMismatch(_) => "pattern-mismatch",
+            Error::DuplicateKey(_) => "duplicate-key",
+            Error::TimezoneOffset(_) => "timezone-offset",
+            Error::UnregisteredName(_) => "unregistered-name",
+        }
+    }
 }
@@ -0,0 +1,248 @@
+//! Human readable help for datatypes, for tools to show when a value fails
+//! validation.
+//!
+//! The description and pattern come from each type's [Metaschema] and
+//! [StringType] metadata; the example and common mistakes are curated here.
+//!
+//! ```
+//! use oscal_types::{help::describe, DatatypeKind};
+//!
+//! let help = describe(DatatypeKind::Date);
+//! assert_eq!(help.example, "2024-02-10");
+//! assert!(DatatypeKind::Date.validate(help.example).is_ok());
+//! println!("{help}");
+//! ```
+use std::fmt;
+
+use crate::macros::for_each_datatype;
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DatatypeHelp {
+    pub kind: DatatypeKind,
+    pub description: Option<&'static str>,
+    /// The regular expression from the OSCAL JSON schema, for string types.
+    pub pattern: Option<&'static str>,
+    /// A valid value.
+    pub example: &'static str,
+    /// Invalid values people commonly try, each with the reason.
+    pub common_mistakes: &'static [&'static str],
+}
+
+macro_rules! description {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        fn description(kind: DatatypeKind) -> Option<&'static str> {
+            match kind {
+                $(DatatypeKind::$kind => $t::description(),)*
+            }
+        }
+    };
+}
+
+for_each_datatype!(description);
+
+macro_rules! pattern {
+    ($($kind:ident => $t:ident: $facets:ident),* $(,)?) => {
+        fn pattern(kind: DatatypeKind) -> Option<&'static str> {
+            match kind {
+                $(DatatypeKind::$kind => pattern!(@$facets $t),)*
+            }
+        }
+    };
+    (@string $t:ident) => {
+        <$t as StringType>::pattern()
+    };
+    (@$facets:ident $t:ident) => {
+        None
+    };
+}
+
+for_each_datatype!(pattern, facets);
+
+/// The curated example and common mistakes.
+fn curated(kind: DatatypeKind) -> (&'static str, &'static [&'static str]) {
+    match kind {
+        DatatypeKind::Boolean => (
+            "true",
+            &["\"True\" or \"yes\": only lowercase true and false"],
+        ),
+        DatatypeKind::Date => (
+            "2024-02-10",
+            &[
+                "\"02/10/2024\": dates are year-month-day",
+                "\"2024-02-30\": the day must exist in that month",
+            ],
+        ),
+        DatatypeKind::DateTime => (
+            "2024-02-10T09:30:00",
+            &["\"2024-02-10 09:30:00\": a T separates the date and time"],
+        ),
+        DatatypeKind::DateTimeWithTimezone => (
+            "2024-02-10T09:30:00Z",
+            &[
+                "\"2024-02-10T09:30:00\": a timezone, e.g. Z or -05:00, is required",
+                "\"2024-02-10\": a time is required",
+            ],
+        ),
+        DatatypeKind::DayTimeDuration => (
+            "P1DT2H30M",
+            &[
+                "\"P1M\": months are not allowed, use a year-month duration",
+                "\"1 day\": durations use ISO 8601 notation",
+            ],
+        ),
+        DatatypeKind::YearMonthDuration => {
+            ("P1Y6M", &["\"1 year\": durations use ISO 8601 notation"])
+        }
+        DatatypeKind::TimezoneOffset => (
+            "-05:00",
+            &[
+                "\"EST\": an offset from UTC, not a timezone name",
+                "\"-0500\": a colon separates the hours and minutes",
+            ],
+        ),
+        DatatypeKind::Decimal => ("3.14", &["\"1,000.5\": no thousands separators"]),
+        DatatypeKind::Integer => ("-42", &["\"4.0\": no fractional part, even if zero"]),
+        DatatypeKind::NonNegativeInteger => ("0", &["\"-1\": must be zero or more"]),
+        DatatypeKind::PositiveInteger => ("1", &["\"0\": must be one or more"]),
+        DatatypeKind::String => (
+            "Access Control",
+            &["\" padded \": no leading or trailing whitespace"],
+        ),
+        DatatypeKind::Base64 => (
+            "aGVsbG8=",
+            &[
+                "\"aGVsbG8\": padding to a multiple of four characters is required",
+                "\"aGVs-_8=\": the URL safe alphabet is not allowed",
+            ],
+        ),
+        DatatypeKind::EmailAddress => (
+            "security@agency.gov",
+            &["\"Jane <security@agency.gov>\": only the address, without a name"],
+        ),
+        DatatypeKind::Hostname => (
+            "www.agency.gov",
+            &[
+                "\"https://www.agency.gov\": only the host, without a scheme",
+                "\"-www.agency.gov\": labels cannot start or end with a hyphen",
+            ],
+        ),
+        DatatypeKind::Ipv4Address => ("192.168.0.1", &["\"192.168.0.1/24\": no prefix length"]),
+        DatatypeKind::Ipv6Address => ("2001:db8::1", &["\"[2001:db8::1]\": no brackets"]),
+        DatatypeKind::Ipv4Cidr => (
+            "192.0.2.0/24",
+            &["\"192.0.2.0/255.255.255.0\": a prefix length, not a netmask"],
+        ),
+        DatatypeKind::Ipv6Cidr => (
+            "2001:db8::/32",
+            &["\"2001:db8::\": a prefix length is required"],
+        ),
+        DatatypeKind::MarkupLine => (
+            "Use **strong** and `code`",
+            &[
+                "\"<b>bold</b>\": raw HTML is not allowed, use Markdown",
+                "line breaks are not allowed",
+            ],
+        ),
+        DatatypeKind::MarkupMultiline => (
+            "# Title\n\nA paragraph.",
+            &["\"<p>text</p>\": raw HTML is not allowed, use Markdown"],
+        ),
+        DatatypeKind::Token => (
+            "ac-1_smt.a",
+            &[
+                "\"ac 1\": no spaces",
+                "\"1ac\": must start with a letter or underscore",
+            ],
+        ),
+        DatatypeKind::Uri => (
+            "https://www.agency.gov/ssp.json",
+            &["\"ssp.json\": must be absolute, use a URI reference for relative links"],
+        ),
+        DatatypeKind::UriReference => (
+            "../catalog.json#ac-1",
+            &["\"a b.json\": spaces must be percent encoded"],
+        ),
+        DatatypeKind::Uuid => (
+            "cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53",
+            &["\"cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f5g\": only hexadecimal digits are allowed"],
+        ),
+    }
+}
+
+/// Help for the datatype `kind`.
+pub fn describe(kind: DatatypeKind) -> DatatypeHelp {
+    let (example, common_mistakes) = curated(kind);
+    DatatypeHelp {
+        kind,
+        description: description(kind),
+        pattern: pattern(kind),
+        example,
+        common_mistakes,
+    }
+}
+
+impl fmt::Display for DatatypeHelp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.kind)?;
+        if let Some(description) = self.description {
+            writeln!(f, "  {description}")?;
+        }
+        if let Some(pattern) = self.pattern {
+            writeln!(f, "  Pattern: {pattern}")?;
+        }
+        writeln!(f, "  Example: {:?}", self.example)?;
+        for mistake in self.common_mistakes {
+            writeln!(f, "  Not: {mistake}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe_every_kind() {
+        for &kind in DatatypeKind::ALL {
+            let help = describe(kind);
+            assert!(help.description.is_some(), "{kind}");
+            assert!(
+                kind.validate(help.example).is_ok(),
+                "{kind}: {}",
+                help.example
+            );
+            assert!(!help.common_mistakes.is_empty(), "{kind}");
+            // Markup is only checked with the markup feature, and dates with
+            // date_validation
+            let markup = matches!(
+                kind,
+                DatatypeKind::MarkupLine | DatatypeKind::MarkupMultiline
+            );
+            let date = matches!(
+                kind,
+                DatatypeKind::Date | DatatypeKind::DateTime | DatatypeKind::DateTimeWithTimezone
+            );
+            if (markup && !cfg!(feature = "markup")) || (date && !cfg!(feature = "date_validation"))
+            {
+                continue;
+            }
+            for mistake in help.common_mistakes {
+                if let Some((value, _)) = mistake.split_once("\": ") {
+                    assert!(kind.validate(&value[1..]).is_err(), "{kind}: {value}");
+                }
+            }
+        }
+        assert!(describe(DatatypeKind::Date).pattern.is_some());
+        assert!(describe(DatatypeKind::Integer).pattern.is_none());
+    }
+
+    #[test]
+    fn test_display() {
+        let help = describe(DatatypeKind::Ipv4Address).to_string();
+        assert!(help.starts_with("IPV4AddressDatatype\n"));
+        assert!(help.contains("  Example: \"192.168.0.1\"\n"));
+        assert!(help.contains("  Not: \"192.168.0.1/24\": no prefix length\n"));
+    }
+}
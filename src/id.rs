@@ -0,0 +1,200 @@
+//! Identifiers typed by the scope they are unique in.
+//!
+//! OSCAL identifies objects two ways: machine-oriented UUIDs, unique across
+//! every document and revision, and human-oriented tokens such as control
+//! ids, unique only within their document.  An [Id] carries its [Scope] in
+//! its type, so an identifier from one scope cannot be passed where another
+//! is expected:
+//!
+//! ```
+//! use oscal_types::id::{DocumentScope, GlobalScope, Id};
+//!
+//! fn find_control(id: &Id<DocumentScope>) -> &str {
+//!     id.as_str()
+//! }
+//!
+//! let control: Id<DocumentScope> = "ac-1".parse().unwrap();
+//! let party: Id<GlobalScope> = serde_json::from_str(r#""a78f7e4c-a27a-4b1e-901b-ebfecf2b0301""#).unwrap();
+//! assert_eq!(find_control(&control), "ac-1");
+//! assert_eq!(party.version(), 4);
+//! ```
+//!
+//! ```compile_fail
+//! # use oscal_types::id::{DocumentScope, GlobalScope, Id};
+//! # fn find_control(id: &Id<DocumentScope>) {}
+//! let party: Id<GlobalScope> = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301".parse().unwrap();
+//! find_control(&party);
+//! ```
+//!
+//! Crates built on this one can declare narrower scopes, e.g. ids unique
+//! within one catalog, by implementing [Scope].
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::str::FromStr;
+
+use crate::registry::from_lexical;
+use crate::*;
+
+/// Where the identifiers of a kind are unique, and the datatype they are
+/// written as.
+pub trait Scope: 'static {
+    type Value: OscalDatatype + Eq + Hash + Ord;
+    /// The scope's name, for debugging output.
+    const NAME: &'static str;
+}
+
+/// Unique across every document and revision: a [UUIDDatatype].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlobalScope {}
+
+impl Scope for GlobalScope {
+    type Value = UUIDDatatype;
+    const NAME: &'static str = "global";
+}
+
+/// Unique within one document: a [TokenDatatype].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DocumentScope {}
+
+impl Scope for DocumentScope {
+    type Value = TokenDatatype;
+    const NAME: &'static str = "document";
+}
+
+/// An identifier unique in the scope `S`.
+pub struct Id<S: Scope> {
+    value: S::Value,
+    scope: PhantomData<fn() -> S>,
+}
+
+impl<S: Scope> Id<S> {
+    pub fn new(value: S::Value) -> Self {
+        Self {
+            value,
+            scope: PhantomData,
+        }
+    }
+
+    /// The lexical form of the identifier.
+    pub fn as_str(&self) -> &str
+    where
+        S::Value: Deref<Target = str>,
+    {
+        &self.value
+    }
+
+    pub fn into_inner(self) -> S::Value {
+        self.value
+    }
+}
+
+impl<S: Scope> FromStr for Id<S> {
+    type Err = Error;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        from_lexical(value).map(Self::new)
+    }
+}
+
+impl<S: Scope> Deref for Id<S> {
+    type Target = S::Value;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<S: Scope> Clone for Id<S> {
+    fn clone(&self) -> Self {
+        Self::new(self.value.clone())
+    }
+}
+
+impl<S: Scope> PartialEq for Id<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<S: Scope> Eq for Id<S> {}
+
+impl<S: Scope> Hash for Id<S> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
+}
+
+impl<S: Scope> PartialOrd for Id<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S: Scope> Ord for Id<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+/// E.g. `Id<document>("ac-1")`.
+impl<S: Scope> fmt::Debug for Id<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Id<{}>({:?})", S::NAME, self.value.to_string())
+    }
+}
+
+impl<S: Scope> fmt::Display for Id<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<S: Scope> Serialize for Id<S> {
+    fn serialize<Ser: Serializer>(&self, serializer: Ser) -> Result<Ser::Ok, Ser::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, S: Scope> Deserialize<'de> for Id<S> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        S::Value::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    /// Unique within one catalog, written as a token.
+    enum CatalogScope {}
+
+    impl Scope for CatalogScope {
+        type Value = TokenDatatype;
+        const NAME: &'static str = "catalog";
+    }
+
+    #[test]
+    fn test_scoped_ids() {
+        let control: Id<DocumentScope> = "ac-1".parse().unwrap();
+        assert_eq!(format!("{control:?}"), r#"Id<document>("ac-1")"#);
+        assert_eq!(control.to_string(), "ac-1");
+        assert!("1ac".parse::<Id<DocumentScope>>().is_err());
+
+        let upper = "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301"
+            .parse::<Id<GlobalScope>>()
+            .unwrap();
+        let lower = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"
+            .parse::<Id<GlobalScope>>()
+            .unwrap();
+        assert_eq!(upper, lower);
+        assert!("ac-1".parse::<Id<GlobalScope>>().is_err());
+
+        let ids: BTreeSet<Id<CatalogScope>> = serde_json::from_str(r#"["b", "a", "b"]"#).unwrap();
+        assert_eq!(serde_json::to_string(&ids).unwrap(), r#"["a","b"]"#);
+        let token: TokenDatatype = ids.into_iter().next().unwrap().into_inner();
+        assert_eq!(&*token, "a");
+    }
+}
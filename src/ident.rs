@@ -0,0 +1,223 @@
+//! Rust identifiers for tokens.
+//!
+//! Code generators name fields, modules and types after Metaschema tokens
+//! such as `system-security-plan`.  The rules are kept here so that every
+//! generator agrees on them:
+//!
+//! - A token is split into words at `-`, `.`, `_`, any other character
+//!   that is not alphanumeric, and lowercase-to-uppercase boundaries, so
+//!   `partyUUID-ref` is `party`, `UUID` and `ref`.
+//! - [to_rust_ident] joins the lowercase words with `_`, and
+//!   [to_pascal_case] capitalizes each word and joins them.
+//! - A name starting with a digit is prefixed with `_`.
+//! - Keywords are escaped as raw identifiers, e.g. `r#type`, and the
+//!   keywords that cannot be raw identifiers get a trailing `_`, e.g.
+//!   `self_` and `Self_`.
+//!
+//! [from_rust_ident] reverses [to_rust_ident] for lowercase tokens with
+//! words separated by single `-`, the usual form in OSCAL, and reverses
+//! [to_pascal_case] too when no word after the first starts with a digit.
+//!
+//! ```
+//! use oscal_types::ident;
+//!
+//! assert_eq!(ident::to_rust_ident("system-security-plan"), "system_security_plan");
+//! assert_eq!(ident::to_pascal_case("system-security-plan"), "SystemSecurityPlan");
+//! assert_eq!(ident::to_rust_ident("type"), "r#type");
+//! assert_eq!(&*ident::from_rust_ident("r#type").unwrap(), "type");
+//! ```
+use crate::{Error, TokenDatatype};
+
+/// Strict and reserved keywords of Rust 2021 and 2024.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "crate",
+    "do", "dyn", "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl",
+    "in", "let", "loop", "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref",
+    "return", "self", "Self", "static", "struct", "super", "trait", "true", "try", "type",
+    "typeof", "unsafe", "unsized", "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that cannot be written as raw identifiers.
+const NOT_RAW: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+/// The words of `name`.
+fn words(name: &str) -> Vec<String> {
+    let chars: Vec<char> = name.chars().collect();
+    let mut words = vec![];
+    let mut word = String::new();
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let previous = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if !previous.is_uppercase() || next_is_lower {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Make `name` usable as an identifier.
+fn escape(mut name: String) -> String {
+    if name.is_empty() {
+        name.push('_');
+    } else if name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if NOT_RAW.contains(&name.as_str()) {
+        name.push('_');
+    } else if KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    }
+    name
+}
+
+/// The snake case identifier for `name`, e.g. `system_security_plan` for
+/// `system-security-plan`.
+pub fn to_rust_ident(name: &str) -> String {
+    escape(words(name).join("_").to_lowercase())
+}
+
+/// The upper camel case identifier for `name`, e.g. `SystemSecurityPlan`
+/// for `system-security-plan`.
+pub fn to_pascal_case(name: &str) -> String {
+    let pascal = words(name)
+        .iter()
+        .flat_map(|word| {
+            let mut chars = word.chars();
+            let first = chars.next().into_iter().flat_map(char::to_uppercase);
+            first.chain(chars.flat_map(char::to_lowercase))
+        })
+        .collect();
+    escape(pascal)
+}
+
+/// The lowercase, `-` separated token for a snake or upper camel case
+/// identifier, e.g. `system-security-plan` for `system_security_plan` or
+/// `SystemSecurityPlan`.
+pub fn from_rust_ident(ident: &str) -> Result<TokenDatatype, Error> {
+    let ident = ident.strip_prefix("r#").unwrap_or(ident);
+    let ident = match ident.strip_suffix('_') {
+        Some(keyword) if NOT_RAW.contains(&keyword) => keyword,
+        _ => ident,
+    };
+    let mut token = words(ident).join("-").to_lowercase();
+    if token.starts_with(|c: char| c.is_ascii_digit()) {
+        token.insert(0, '_');
+    }
+    match (token.is_empty(), ident) {
+        (false, _) => TokenDatatype::try_from(token),
+        (true, "_") => TokenDatatype::try_from(ident),
+        (true, _) => Err(Error::StringParse(format!("{ident:?} has no words"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_rust_ident() {
+        let cases = [
+            ("system-security-plan", "system_security_plan"),
+            ("ac-1", "ac_1"),
+            ("ac-1.a", "ac_1_a"),
+            ("control_id", "control_id"),
+            ("partyUUID-ref", "party_uuid_ref"),
+            ("URLValue", "url_value"),
+            ("camelCase", "camel_case"),
+            ("oscal-version", "oscal_version"),
+            ("_800-53", "_800_53"),
+            ("rev5", "rev5"),
+            ("a--b", "a_b"),
+            ("étape-un", "étape_un"),
+            ("type", "r#type"),
+            ("match", "r#match"),
+            ("async", "r#async"),
+            ("gen", "r#gen"),
+            ("union", "union"),
+            ("self", "self_"),
+            ("super", "super_"),
+            ("crate", "crate_"),
+            ("_", "__"),
+            ("Type", "r#type"),
+        ];
+        for (name, ident) in cases {
+            assert_eq!(to_rust_ident(name), ident, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_to_pascal_case() {
+        let cases = [
+            ("system-security-plan", "SystemSecurityPlan"),
+            ("ac-1", "Ac1"),
+            ("partyUUID-ref", "PartyUuidRef"),
+            ("URLValue", "UrlValue"),
+            ("_800-53", "_80053"),
+            ("étape-un", "ÉtapeUn"),
+            ("type", "Type"),
+            ("self", "Self_"),
+            ("_", "__"),
+        ];
+        for (name, ident) in cases {
+            assert_eq!(to_pascal_case(name), ident, "{name}");
+        }
+    }
+
+    #[test]
+    fn test_from_rust_ident() {
+        let cases = [
+            ("system_security_plan", "system-security-plan"),
+            ("SystemSecurityPlan", "system-security-plan"),
+            ("r#type", "type"),
+            ("Type", "type"),
+            ("self_", "self"),
+            ("Self_", "self"),
+            ("crate_", "crate"),
+            ("_800_53", "_800-53"),
+            ("ac_1", "ac-1"),
+            ("__", "_"),
+        ];
+        for (ident, name) in cases {
+            assert_eq!(&*from_rust_ident(ident).unwrap(), name, "{ident}");
+        }
+        assert!(from_rust_ident("").is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for name in [
+            "system-security-plan",
+            "ac-1",
+            "type",
+            "self",
+            "_800-53",
+            "x",
+            "_",
+        ] {
+            assert_eq!(&*from_rust_ident(&to_rust_ident(name)).unwrap(), name);
+        }
+        for name in ["system-security-plan", "import-profile", "self"] {
+            assert_eq!(&*from_rust_ident(&to_pascal_case(name)).unwrap(), name);
+        }
+        for keyword in KEYWORDS
+            .iter()
+            .filter(|k| k.chars().all(|c| c.is_lowercase()))
+        {
+            let ident = to_rust_ident(keyword);
+            assert!(ident.starts_with("r#") || ident.ends_with('_'), "{ident}");
+            assert_eq!(&*from_rust_ident(&ident).unwrap(), *keyword);
+        }
+    }
+}
@@ -0,0 +1,167 @@
+//! Indexes of values by key, for resolving references.
+//!
+//! Resolving `party-uuid`, `role-id` or `#ac-1` references means looking up
+//! objects by their UUID or token.  [build_index] builds such an index from
+//! typed values, [build_canonical_index] keys it by canonical form, and
+//! [index_objects] indexes the objects of a JSON array by one of their
+//! fields.  A key that appears twice is an [Error::DuplicateKey] with the
+//! JSON Pointer of the second occurrence.
+//!
+//! ```
+//! use oscal_types::{index::index_objects, DatatypeKind};
+//! use serde_json::json;
+//!
+//! let parties = json!([
+//!     {"uuid": "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301", "name": "Security Office"},
+//!     {"uuid": "cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53", "name": "Operations"},
+//! ]);
+//! let by_uuid = index_objects(parties.as_array().unwrap(), "uuid", DatatypeKind::Uuid).unwrap();
+//! assert_eq!(
+//!     by_uuid["a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"]["name"],
+//!     "Security Office"
+//! );
+//! ```
+use serde_json::Value;
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+use crate::de::push_pointer;
+use crate::normalize::{canonical, Canonicalize};
+use crate::walk::lexical_form;
+use crate::*;
+
+/// Index `items` by `key`.  The pointer of a duplicate is its position in
+/// `items`, e.g. `/3`.
+pub fn build_index<K, T>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> Result<HashMap<K, T>, Error>
+where
+    K: Eq + Hash,
+{
+    let mut index = HashMap::new();
+    for (position, item) in items.into_iter().enumerate() {
+        match index.entry(key(&item)) {
+            Entry::Occupied(_) => {
+                return Err(Error::DuplicateKey(push_pointer("", &position.to_string())))
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(item);
+            }
+        }
+    }
+    Ok(index)
+}
+
+/// Index `items` by the canonical form of `key`, so keys written
+/// differently but with the same value, such as IPv6 addresses with and
+/// without leading zeros, are duplicates.  Look values up by canonical key
+/// too:
+///
+/// ```
+/// use oscal_types::{index::build_canonical_index, normalize::Canonicalize};
+/// use oscal_types::IPV6AddressDatatype as Ipv6;
+///
+/// let hosts = ["2001:db8:0:0::0001", "2001:db8::2"];
+/// let index = build_canonical_index(hosts, |h| Ipv6::try_from(*h).unwrap()).unwrap();
+/// let key = Ipv6::try_from("2001:DB8::1").unwrap();
+/// assert_eq!(index[&key.canonicalize()], hosts[0]);
+/// ```
+pub fn build_canonical_index<K, T>(
+    items: impl IntoIterator<Item = T>,
+    mut key: impl FnMut(&T) -> K,
+) -> Result<HashMap<K, T>, Error>
+where
+    K: Canonicalize + Eq + Hash,
+{
+    build_index(items, |item| key(item).canonicalize())
+}
+
+/// Index the objects in `array` by the [canonical] form of their `field`,
+/// read as the datatype `kind`.  Items without the field, or that are not
+/// objects, are skipped.  A field value that is invalid is an error.  The
+/// pointer of a duplicate is that of its field, e.g. `/3/uuid`.
+pub fn index_objects<'a>(
+    array: &'a [Value],
+    field: &str,
+    kind: DatatypeKind,
+) -> Result<BTreeMap<String, &'a Value>, Error> {
+    let mut index = BTreeMap::new();
+    for (position, item) in array.iter().enumerate() {
+        let Some(lexical) = item.get(field).and_then(lexical_form) else {
+            continue;
+        };
+        let key = canonical(kind, &lexical)?;
+        if index.insert(key, item).is_some() {
+            let pointer = push_pointer("", &position.to_string());
+            return Err(Error::DuplicateKey(push_pointer(&pointer, field)));
+        }
+    }
+    Ok(index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_index() {
+        let ids = ["ac-1", "ac-2", "AC-1"];
+        let index = build_index(ids, |id| TokenDatatype::try_from(*id).unwrap()).unwrap();
+        assert_eq!(index.len(), 3);
+
+        let ids = ["ac-1", "ac-2", "ac-1"];
+        let error = build_index(ids, |id| TokenDatatype::try_from(*id).unwrap()).unwrap_err();
+        assert!(matches!(error, Error::DuplicateKey(ref pointer) if pointer == "/2"));
+        assert_eq!(error.code(), "duplicate-key");
+    }
+
+    #[test]
+    fn test_build_canonical_index() {
+        let uuid = |u: &&str| UUIDDatatype::try_from(*u).unwrap();
+        let uuids = [
+            "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+            "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301",
+        ];
+        let error = build_canonical_index(uuids, uuid).unwrap_err();
+        assert!(matches!(error, Error::DuplicateKey(ref pointer) if pointer == "/1"));
+
+        let hosts = ["2001:db8::1", "2001:DB8:0::0001"];
+        let host = |h: &&str| IPV6AddressDatatype::try_from(*h).unwrap();
+        assert!(build_index(hosts, host).is_ok());
+        assert!(build_canonical_index(hosts, host).is_err());
+
+        // Date-times at different offsets are equal without canonicalizing
+        let times = ["2024-02-10T09:30:00+01:00", "2024-02-10T08:30:00Z"];
+        let time = |t: &&str| DateTimeWithTimezoneDatatype::try_from(*t).unwrap();
+        assert!(build_index(times, time).is_err());
+    }
+
+    #[test]
+    fn test_index_objects() {
+        let array = json!([
+            {"id": "ac-1", "title": "Policy"},
+            {"title": "No id"},
+            "not an object",
+            {"id": "ac-2", "title": "Account Management"},
+        ]);
+        let array = array.as_array().unwrap();
+        let index = index_objects(array, "id", DatatypeKind::Token).unwrap();
+        assert_eq!(index.keys().collect::<Vec<_>>(), ["ac-1", "ac-2"]);
+        assert_eq!(index["ac-2"]["title"], "Account Management");
+
+        let duplicate = json!([{"a/b": "10.0.0.1"}, {"a/b": "10.0.0.1"}]);
+        let error = index_objects(
+            duplicate.as_array().unwrap(),
+            "a/b",
+            DatatypeKind::Ipv4Address,
+        )
+        .unwrap_err();
+        assert!(matches!(error, Error::DuplicateKey(ref pointer) if pointer == "/1/a~1b"));
+
+        let invalid = json!([{"id": "1ac"}]);
+        assert!(index_objects(invalid.as_array().unwrap(), "id", DatatypeKind::Token).is_err());
+    }
+}
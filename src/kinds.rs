@@ -0,0 +1,112 @@
+//! Runtime identification of datatypes.
+//!
+//! [DatatypeKind] names every datatype in the crate and dispatches the static
+//! [Base] and [Validate] functions by kind. It is generated from
+//! `for_each_datatype!`, so it can never fall out of sync with the types.
+//!
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::macros::for_each_datatype;
+use crate::*;
+
+macro_rules! datatype_kind {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub enum DatatypeKind {
+            $($kind,)*
+        }
+
+        impl DatatypeKind {
+            /// Every datatype, in declaration order.
+            pub const ALL: &'static [DatatypeKind] = &[$(DatatypeKind::$kind,)*];
+
+            /// The Rust type name, e.g. `DateDatatype`.
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(DatatypeKind::$kind => stringify!($t),)*
+                }
+            }
+
+            pub fn base_type(&self) -> String {
+                match self {
+                    $(DatatypeKind::$kind => $t::base_type(),)*
+                }
+            }
+
+            pub fn ref_type(&self) -> String {
+                match self {
+                    $(DatatypeKind::$kind => $t::ref_type(),)*
+                }
+            }
+
+            /// Validate `value` as the lexical form of this datatype.
+            pub fn validate(&self, value: &str) -> Result<(), Error> {
+                let result = match self {
+                    $(DatatypeKind::$kind => $t::validate(value),)*
+                };
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(*self, result.is_ok());
+                result
+            }
+        }
+
+        impl FromStr for DatatypeKind {
+            type Err = Error;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $(stringify!($t) => Ok(DatatypeKind::$kind),)*
+                    _ => crate::aliases::resolve(s)
+                        .ok_or_else(|| Error::UnrecognizedTypeName(s.to_owned())),
+                }
+            }
+        }
+    };
+}
+
+for_each_datatype!(datatype_kind);
+
+impl fmt::Display for DatatypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Serialized as the type name, e.g. `"DateDatatype"`.
+impl Serialize for DatatypeKind {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl<'de> Deserialize<'de> for DatatypeKind {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        name.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_kind_dispatches_by_name() {
+        for kind in DatatypeKind::ALL {
+            assert_eq!(kind.name().parse::<DatatypeKind>().unwrap(), *kind);
+            assert_eq!(get_base_type(kind.name()).unwrap(), kind.base_type());
+            assert_eq!(get_ref_type(kind.name()).unwrap(), kind.ref_type());
+        }
+        assert!("NotADatatype".parse::<DatatypeKind>().is_err());
+    }
+
+    #[test]
+    fn test_validate_by_name() {
+        assert!(validate_by_name("DateDatatype", "2024-02-10").is_ok());
+        #[cfg(feature = "date_validation")]
+        assert!(validate_by_name("DateDatatype", "2024-02-30").is_err());
+        assert!(validate_by_name("PositiveIntegerDatatype", "0").is_err());
+        assert!(validate_by_name("NoSuchDatatype", "x").is_err());
+    }
+}
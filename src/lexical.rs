@@ -0,0 +1,230 @@
+//! Conversion between the JSON and XML lexical spaces.
+//!
+//! The datatypes store their JSON lexical form.  XML differs in a few ways:
+//! most XSD types collapse whitespace before validation, `xs:boolean`
+//! accepts `1` and `0`, and `xs:base64Binary` may contain whitespace.
+//! [XmlLexical] converts between the two so every XML reader and writer
+//! applies the same rules.
+//!
+//! Markup datatypes are not covered: their XML form is element content, not
+//! a lexical string.
+use crate::*;
+
+pub trait XmlLexical: Sized {
+    /// The canonical XML lexical form of the value.
+    fn to_xml_lexical(&self) -> String;
+
+    /// Parse an XML lexical form, applying the XSD whitespace rules of the
+    /// datatype before validation.
+    fn from_xml_lexical(value: &str) -> Result<Self, Error>;
+}
+
+/// The XSD `collapse` whitespace facet: tabs, line feeds and carriage
+/// returns become spaces, runs of spaces become one space, and leading and
+/// trailing spaces are removed.
+pub fn collapse_whitespace(value: &str) -> String {
+    value
+        .split([' ', '\t', '\n', '\r'])
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl XmlLexical for BooleanDatatype {
+    fn to_xml_lexical(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+        match collapse_whitespace(value).as_str() {
+            "true" | "1" => Ok(Self::from(true)),
+            "false" | "0" => Ok(Self::from(false)),
+            _ => Err(Error::BooleanParse),
+        }
+    }
+}
+
+macro_rules! xml_lexical_number {
+    ($t:ty, $n:ty) => {
+        impl XmlLexical for $t {
+            fn to_xml_lexical(&self) -> String {
+                self.to_string()
+            }
+
+            fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+                let value = collapse_whitespace(value);
+                <$t>::validate(&value)?;
+                let number = value.parse::<$n>().map_err(|_| Error::NumberParse)?;
+                Self::try_from(number).map_err(|_| Error::NumberRange)
+            }
+        }
+    };
+}
+
+/// Keeps the exact lexical form with the `exact_decimal` feature.
+impl XmlLexical for DecimalDatatype {
+    fn to_xml_lexical(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+        Self::try_from(collapse_whitespace(value).as_str())
+    }
+}
+
+xml_lexical_number!(IntegerDatatype, i64);
+xml_lexical_number!(NonNegativeIntegerDatatype, u64);
+xml_lexical_number!(PositiveIntegerDatatype, u64);
+
+/// Types whose XSD base type collapses whitespace.
+macro_rules! xml_lexical_collapse {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl XmlLexical for $t {
+                fn to_xml_lexical(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+                    Self::try_from(collapse_whitespace(value).as_str())
+                }
+            }
+        )*
+    };
+}
+
+xml_lexical_collapse!(
+    DateDatatype,
+    DayTimeDurationDatatype,
+    YearMonthDurationDatatype,
+    TimezoneOffsetDatatype,
+    EmailAddressDatatype,
+    HostnameDatatype,
+    IPV4AddressDatatype,
+    IPV6AddressDatatype,
+    IPV4CidrDatatype,
+    IPV6CidrDatatype,
+    TokenDatatype,
+    URIDatatype,
+    URIReferenceDatatype,
+    UUIDDatatype,
+);
+
+/// Rewrite the `xs:dateTime` end of day form `24:00:00` as midnight of the
+/// next day, which is the only form the JSON datatypes accept.
+fn end_of_day(value: &str) -> Option<String> {
+    let (date, time) = value.split_once('T')?;
+    let rest = time.strip_prefix("24:00:00")?;
+    let rest = match rest.strip_prefix('.') {
+        Some(fraction) => fraction.trim_start_matches('0'),
+        None => rest,
+    };
+    if rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+    let next = date.parse::<chrono::NaiveDate>().ok()?.succ_opt()?;
+    Some(format!("{next}T00:00:00{rest}"))
+}
+
+macro_rules! xml_lexical_date_time {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl XmlLexical for $t {
+                fn to_xml_lexical(&self) -> String {
+                    self.to_string()
+                }
+
+                fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+                    let value = collapse_whitespace(value);
+                    match end_of_day(&value) {
+                        Some(midnight) => Self::try_from(midnight.as_str()),
+                        None => Self::try_from(value.as_str()),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+xml_lexical_date_time!(DateTimeDatatype, DateTimeWithTimezoneDatatype);
+
+/// `xs:string` preserves whitespace, so the value is taken as is.
+impl XmlLexical for StringDatatype {
+    fn to_xml_lexical(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+        Self::try_from(value)
+    }
+}
+
+/// `xs:base64Binary` allows whitespace anywhere; it is removed.
+impl XmlLexical for Base64Datatype {
+    fn to_xml_lexical(&self) -> String {
+        self.to_string()
+    }
+
+    fn from_xml_lexical(value: &str) -> Result<Self, Error> {
+        Self::from_wrapped(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(collapse_whitespace("  a \t\n b  "), "a b");
+        assert_eq!(collapse_whitespace("\n"), "");
+    }
+
+    #[test]
+    fn test_boolean_xml_lexical() {
+        assert!(*BooleanDatatype::from_xml_lexical(" 1 ").unwrap());
+        assert!(!*BooleanDatatype::from_xml_lexical("false").unwrap());
+        assert!(BooleanDatatype::from_xml_lexical("yes").is_err());
+        assert_eq!(BooleanDatatype::from(true).to_xml_lexical(), "true");
+    }
+
+    #[test]
+    fn test_number_xml_lexical() {
+        assert_eq!(*IntegerDatatype::from_xml_lexical("\n  -42 ").unwrap(), -42);
+        assert!(PositiveIntegerDatatype::from_xml_lexical("0").is_err());
+        assert_eq!(
+            DecimalDatatype::try_from(0.5).unwrap().to_xml_lexical(),
+            "0.5"
+        );
+    }
+
+    #[test]
+    fn test_end_of_day() {
+        assert_eq!(
+            &*DateTimeWithTimezoneDatatype::from_xml_lexical("2024-02-29T24:00:00.000Z").unwrap(),
+            "2024-03-01T00:00:00Z"
+        );
+        assert_eq!(
+            &*DateTimeDatatype::from_xml_lexical("2024-12-31T24:00:00").unwrap(),
+            "2025-01-01T00:00:00"
+        );
+        #[cfg(feature = "date_validation")]
+        assert!(DateTimeDatatype::from_xml_lexical("2024-12-31T24:00:00.5").is_err());
+    }
+
+    #[test]
+    fn test_collapsed_types() {
+        let uuid = UUIDDatatype::from_xml_lexical("\n  a78f7e4c-a27a-4b1e-901b-ebfecf2b0301\n");
+        assert!(uuid.is_ok());
+        assert_eq!(
+            &*DateDatatype::from_xml_lexical(" 2024-02-10 ").unwrap(),
+            "2024-02-10"
+        );
+        assert!(TokenDatatype::from_xml_lexical("a  b").is_err());
+        assert_eq!(
+            &*Base64Datatype::from_xml_lexical("aGVs\n bG8=").unwrap(),
+            "aGVsbG8="
+        );
+        assert!(StringDatatype::from_xml_lexical(" padded").is_err());
+    }
+}
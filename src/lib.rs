@@ -1,25 +1,91 @@
+pub use aliases::*;
 pub use base::*;
 pub use boolean::*;
+pub use cidr::{IPV4CidrDatatype, IPV6CidrDatatype};
+pub use conformance::self_check;
+pub use datatype::OscalDatatype;
 pub use dates::*;
 pub use error::Error;
+pub use kinds::DatatypeKind;
 pub(crate) use macros::*;
 pub use numbers::*;
 pub use strings::*;
+pub use truncate::Truncate;
 pub use uris::*;
 pub use uuid::*;
 pub use validate::*;
 
+pub mod aliases;
+pub mod anonymize;
+pub mod archive;
 pub mod base;
+pub mod bindings;
 pub mod boolean;
+pub mod borrowed;
+#[cfg(feature = "capi")]
+pub mod capi;
+pub mod checked;
+pub mod cidr;
+pub mod clock;
+pub mod config;
+pub mod conformance;
+pub mod datatype;
 pub mod dates;
+pub mod de;
+pub mod diff;
+pub mod discriminator;
+pub mod display;
 pub mod error;
+pub mod help;
+pub mod id;
+pub mod ident;
+pub mod index;
+pub mod kinds;
+pub mod lexical;
+pub mod literal;
 pub(crate) mod macros;
+#[cfg(feature = "markup")]
+pub mod markup;
+pub mod maybe;
+pub mod metapath;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod mutate;
+pub mod namespaced;
 pub mod nc_name;
+pub mod normalize;
 pub mod numbers;
+pub mod partial;
+pub mod patch;
+pub mod patterns;
+pub mod prop;
+#[cfg(feature = "provenance")]
+pub mod provenance;
+pub mod qname;
+pub mod raw;
+pub mod registry;
+pub mod report;
+pub mod scan;
+pub mod schema;
+pub mod search;
+pub mod shared;
+#[cfg(feature = "similarity")]
+pub mod similarity;
+pub mod small_map;
 pub mod strings;
+pub mod temporal;
+pub mod tolerant;
+pub mod truncate;
 pub mod uris;
 pub mod uuid;
 pub mod validate;
+pub mod value;
+pub mod walk;
+pub mod warnings;
+#[cfg(feature = "xml")]
+pub mod xml;
+#[cfg(feature = "yaml")]
+pub mod yaml;
 
 pub trait Metaschema {
     fn _type() -> Option<&'static str> {
@@ -58,48 +124,22 @@ pub trait StringType {
     fn content_encoding() -> Option<&'static str> {
         None
     }
+    /// The [StringType::pattern], compiled once.
+    #[cfg(feature = "regex_validation")]
+    fn pattern_regex() -> Option<&'static regex::Regex> {
+        None
+    }
 }
 
 pub fn get_base_type(name: &str) -> Result<String, Error> {
-    match name {
-        "BooleanDatatype" => Ok(BooleanDatatype::base_type()),
-        "DateDatatype" => Ok(DateDatatype::base_type()),
-        "DateTimeDatatype" => Ok(DateTimeDatatype::base_type()),
-        "DateTimeWithTimezoneDatatype" => Ok(DateTimeWithTimezoneDatatype::base_type()),
-        "DayTimeDurationDatatype" => Ok(DayTimeDurationDatatype::base_type()),
-        "DecimalDatatype" => Ok(DecimalDatatype::base_type()),
-        "IntegerDatatype" => Ok(IntegerDatatype::base_type()),
-        "NonNegativeIntegerDatatype" => Ok(NonNegativeIntegerDatatype::base_type()),
-        "PositiveIntegerDatatype" => Ok(PositiveIntegerDatatype::base_type()),
-        "StringDatatype" => Ok(StringDatatype::base_type()),
-        "Base64Datatype" => Ok(Base64Datatype::base_type()),
-        "EmailAddressDatatype" => Ok(EmailAddressDatatype::base_type()),
-        "TokenDatatype" => Ok(TokenDatatype::base_type()),
-        "URIDatatype" => Ok(URIDatatype::base_type()),
-        "URIReferenceDatatype" => Ok(URIReferenceDatatype::base_type()),
-        "UUIDDatatype" => Ok(UUIDDatatype::base_type()),
-        _ => Err(Error::UnrecognizedTypeName(name.to_owned())),
-    }
+    Ok(name.parse::<DatatypeKind>()?.base_type())
 }
 
 pub fn get_ref_type(name: &str) -> Result<String, Error> {
-    match name {
-        "BooleanDatatype" => Ok(BooleanDatatype::ref_type()),
-        "DateDatatype" => Ok(DateDatatype::ref_type()),
-        "DateTimeDatatype" => Ok(DateTimeDatatype::ref_type()),
-        "DateTimeWithTimezoneDatatype" => Ok(DateTimeWithTimezoneDatatype::ref_type()),
-        "DayTimeDurationDatatype" => Ok(DayTimeDurationDatatype::ref_type()),
-        "DecimalDatatype" => Ok(DecimalDatatype::ref_type()),
-        "IntegerDatatype" => Ok(IntegerDatatype::ref_type()),
-        "NonNegativeIntegerDatatype" => Ok(NonNegativeIntegerDatatype::ref_type()),
-        "PositiveIntegerDatatype" => Ok(PositiveIntegerDatatype::ref_type()),
-        "StringDatatype" => Ok(StringDatatype::ref_type()),
-        "Base64Datatype" => Ok(Base64Datatype::ref_type()),
-        "EmailAddressDatatype" => Ok(EmailAddressDatatype::ref_type()),
-        "TokenDatatype" => Ok(TokenDatatype::ref_type()),
-        "URIDatatype" => Ok(URIDatatype::ref_type()),
-        "URIReferenceDatatype" => Ok(URIReferenceDatatype::ref_type()),
-        "UUIDDatatype" => Ok(UUIDDatatype::ref_type()),
-        _ => Err(Error::UnrecognizedTypeName(name.to_owned())),
-    }
+    Ok(name.parse::<DatatypeKind>()?.ref_type())
+}
+
+/// Validate `value` against the datatype named `name`, e.g. `"DateDatatype"`.
+pub fn validate_by_name(name: &str, value: &str) -> Result<(), Error> {
+    name.parse::<DatatypeKind>()?.validate(value)
 }
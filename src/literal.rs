@@ -0,0 +1,236 @@
+//! Datatype literals checked at compile time.
+//!
+//! [uuid!](crate::uuid!), [date!](crate::date!) and [token!](crate::token!)
+//! build a datatype from a string literal.  The literal is checked by a
+//! `const fn` while compiling, so a typo in a fixture is a build error
+//! rather than a panic in a test:
+//!
+//! ```
+//! use oscal_types::{date, token, uuid};
+//!
+//! let id = uuid!("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+//! let published = date!("2024-02-10");
+//! let control = token!("ac-1");
+//! assert_eq!(&*control, "ac-1");
+//! ```
+//!
+//! ```compile_fail
+//! let published = oscal_types::date!("2024-02-30");
+//! ```
+//!
+//! The compile time checks are the strictest rules of each datatype, so a
+//! literal is valid in every build and configuration: UUIDs are version 4,
+//! 5 or 7 with the RFC 4122 variant, and dates are in the years 1900 to 2999
+//! the Metaschema pattern allows.  The value is not validated again when it
+//! is built, so a literal never panics.
+use crate::nc_name::NCName;
+use crate::{DateDatatype, TokenDatatype, UUIDDatatype};
+
+const fn is_hex(b: u8) -> bool {
+    b.is_ascii_hexdigit()
+}
+
+/// Whether `value` is a hyphenated version 4, 5 or 7 UUID of the RFC 4122
+/// variant.
+pub const fn is_uuid(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() != 36 {
+        return false;
+    }
+    let mut i = 0;
+    while i < 36 {
+        let valid = match i {
+            8 | 13 | 18 | 23 => bytes[i] == b'-',
+            _ => is_hex(bytes[i]),
+        };
+        if !valid {
+            return false;
+        }
+        i += 1;
+    }
+    matches!(bytes[14], b'4' | b'5' | b'7')
+        && matches!(bytes[19], b'8' | b'9' | b'a' | b'b' | b'A' | b'B')
+}
+
+const fn digits(bytes: &[u8], start: usize, len: usize) -> Option<u32> {
+    let mut n = 0;
+    let mut i = start;
+    while i < start + len {
+        if !bytes[i].is_ascii_digit() {
+            return None;
+        }
+        n = n * 10 + (bytes[i] - b'0') as u32;
+        i += 1;
+    }
+    Some(n)
+}
+
+/// Whether `value` is a `YYYY-MM-DD` date that exists, in the years 1900 to
+/// 2999.
+pub const fn is_date(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    let (Some(year), Some(month), Some(day)) = (
+        digits(bytes, 0, 4),
+        digits(bytes, 5, 2),
+        digits(bytes, 8, 2),
+    ) else {
+        return false;
+    };
+    if year < 1900 || year > 2999 {
+        return false;
+    }
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    day >= 1 && day <= days
+}
+
+/// Decode the UTF-8 character starting at `i`, returning it and the index
+/// after it.
+const fn decode(bytes: &[u8], i: usize) -> (char, usize) {
+    let first = bytes[i] as u32;
+    let (len, mut code) = match first {
+        0x00..=0x7F => (1, first),
+        0xC0..=0xDF => (2, first & 0x1F),
+        0xE0..=0xEF => (3, first & 0x0F),
+        _ => (4, first & 0x07),
+    };
+    let mut j = 1;
+    while j < len {
+        code = (code << 6) | (bytes[i + j] as u32 & 0x3F);
+        j += 1;
+    }
+    match char::from_u32(code) {
+        Some(c) => (c, i + len),
+        None => (char::REPLACEMENT_CHARACTER, i + len),
+    }
+}
+
+/// Whether `value` is a non-empty NCName, the lexical space of
+/// [crate::TokenDatatype].
+pub const fn is_token(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    if bytes.is_empty() {
+        return false;
+    }
+    let mut i = 0;
+    while i < bytes.len() {
+        let (c, next) = decode(bytes, i);
+        let valid = match i {
+            0 => NCName::is_valid_start_char(c),
+            _ => NCName::is_valid_char(c),
+        };
+        if !valid {
+            return false;
+        }
+        i = next;
+    }
+    true
+}
+
+/// Constructors for the macros, which have already checked the literal.
+#[doc(hidden)]
+pub mod __private {
+    use super::*;
+
+    pub fn uuid(value: &str) -> UUIDDatatype {
+        UUIDDatatype(value.to_ascii_lowercase())
+    }
+
+    pub fn date(value: &str) -> DateDatatype {
+        DateDatatype(value.to_string())
+    }
+
+    pub fn token(value: &str) -> TokenDatatype {
+        TokenDatatype(value.to_string())
+    }
+}
+
+/// A [crate::UUIDDatatype] from a literal checked at compile time.
+#[macro_export]
+macro_rules! uuid {
+    ($value:literal) => {{
+        const _: () = assert!(
+            $crate::literal::is_uuid($value),
+            concat!("invalid UUID literal ", stringify!($value))
+        );
+        $crate::literal::__private::uuid($value)
+    }};
+}
+
+/// A [crate::DateDatatype] from a literal checked at compile time.
+#[macro_export]
+macro_rules! date {
+    ($value:literal) => {{
+        const _: () = assert!(
+            $crate::literal::is_date($value),
+            concat!("invalid date literal ", stringify!($value))
+        );
+        $crate::literal::__private::date($value)
+    }};
+}
+
+/// A [crate::TokenDatatype] from a literal checked at compile time.
+#[macro_export]
+macro_rules! token {
+    ($value:literal) => {{
+        const _: () = assert!(
+            $crate::literal::is_token($value),
+            concat!("invalid token literal ", stringify!($value))
+        );
+        $crate::literal::__private::token($value)
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_const_checks() {
+        assert!(is_uuid("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"));
+        assert!(!is_uuid("a78f7e4c-a27a-1b1e-901b-ebfecf2b0301"));
+        assert!(!is_uuid("a78f7e4ca27a4b1e901bebfecf2b0301"));
+        assert!(!is_uuid("a78f7e4c-a27a-4b1e-c01b-ebfecf2b0301"));
+        assert!(is_uuid("018f2c1e-7b2a-7c3d-8e4f-0123456789ab"));
+        assert!(is_date("2024-02-29"));
+        assert!(!is_date("2023-02-29"));
+        assert!(!is_date("2024-2-10"));
+        assert!(!is_date("1850-01-01"));
+        assert!(is_date("2000-02-29"));
+        assert!(!is_date("2100-02-29"));
+        assert!(is_token("ac-1_smt.a"));
+        assert!(is_token("ü-1"));
+        assert!(!is_token("1ac"));
+        assert!(!is_token("a b"));
+        assert!(!is_token(""));
+    }
+
+    #[test]
+    fn test_macros() {
+        let id = crate::uuid!("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+        assert_eq!(id.version(), 4);
+        // Built without run time validation, so no configuration rejects it
+        let strict = crate::config::Config {
+            strict_uuid_versions: true,
+            ..crate::config::Config::new()
+        };
+        let v7 = crate::config::scoped(strict, || {
+            crate::uuid!("018F2C1E-7B2A-7C3D-8E4F-0123456789AB")
+        });
+        assert_eq!(
+            (&*v7, v7.version()),
+            ("018f2c1e-7b2a-7c3d-8e4f-0123456789ab", 7)
+        );
+        assert_eq!(&*crate::date!("2024-02-10"), "2024-02-10");
+        assert_eq!(&*crate::token!("ac-1"), "ac-1");
+    }
+}
@@ -0,0 +1,56 @@
+/// The single list of every datatype in the crate.
+///
+/// Invokes `$callback!` with one `Kind => TypeName` entry per datatype. All
+/// name based dispatch (see [crate::kinds::DatatypeKind]) is generated from
+/// this list, so a new datatype only needs to be added here.
+///
+/// `for_each_datatype!(callback, facets)` appends to each entry the trait
+/// holding its schema facets, as `Kind => TypeName: facets`: `string` for
+/// [crate::StringType], `number` for [crate::NumberType], and `plain` for
+/// neither.
+macro_rules! for_each_datatype {
+    ($callback:ident) => {
+        $crate::macros::for_each_datatype! { @list $callback, plain_entries }
+    };
+    ($callback:ident, facets) => {
+        $crate::macros::for_each_datatype! { @list $callback, facet_entries }
+    };
+    (@list $callback:ident, $entries:ident) => {
+        $crate::macros::for_each_datatype! {
+            @$entries $callback;
+            Boolean => BooleanDatatype: plain,
+            Date => DateDatatype: string,
+            DateTime => DateTimeDatatype: string,
+            DateTimeWithTimezone => DateTimeWithTimezoneDatatype: string,
+            DayTimeDuration => DayTimeDurationDatatype: string,
+            YearMonthDuration => YearMonthDurationDatatype: string,
+            TimezoneOffset => TimezoneOffsetDatatype: string,
+            Decimal => DecimalDatatype: plain,
+            Integer => IntegerDatatype: number,
+            NonNegativeInteger => NonNegativeIntegerDatatype: number,
+            PositiveInteger => PositiveIntegerDatatype: number,
+            String => StringDatatype: string,
+            Base64 => Base64Datatype: string,
+            EmailAddress => EmailAddressDatatype: string,
+            Hostname => HostnameDatatype: string,
+            Ipv4Address => IPV4AddressDatatype: string,
+            Ipv6Address => IPV6AddressDatatype: string,
+            Ipv4Cidr => IPV4CidrDatatype: plain,
+            Ipv6Cidr => IPV6CidrDatatype: plain,
+            MarkupLine => MarkupLineDatatype: string,
+            MarkupMultiline => MarkupMultilineDatatype: string,
+            Token => TokenDatatype: string,
+            Uri => URIDatatype: plain,
+            UriReference => URIReferenceDatatype: plain,
+            Uuid => UUIDDatatype: plain,
+        }
+    };
+    (@plain_entries $callback:ident; $($kind:ident => $t:ident: $facets:ident),* $(,)?) => {
+        $callback! { $($kind => $t),* }
+    };
+    (@facet_entries $callback:ident; $($kind:ident => $t:ident: $facets:ident),* $(,)?) => {
+        $callback! { $($kind => $t: $facets),* }
+    };
+}
+
+pub(crate) use for_each_datatype;
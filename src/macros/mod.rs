@@ -1 +1,4 @@
+pub(crate) mod for_each_datatype;
 pub(crate) mod string_impl;
+
+pub(crate) use for_each_datatype::for_each_datatype;
@@ -14,6 +14,14 @@ macro_rules! string_impl {
             fn pattern() -> Option<&'static str> {
                 Some($pattern)
             }
+            #[cfg(feature = "regex_validation")]
+            fn pattern_regex() -> Option<&'static ::regex::Regex> {
+                static PATTERN: ::std::sync::LazyLock<::regex::Regex> =
+                    ::std::sync::LazyLock::new(|| {
+                        ::regex::Regex::new($pattern).expect("invalid pattern")
+                    });
+                Some(&PATTERN)
+            }
             fn content_encoding() -> Option<&'static str> {
                 Some($encoding)
             }
@@ -36,6 +44,12 @@ macro_rules! string_impl {
             }
         }
 
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
         impl FromStr for $t {
             type Err = Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -43,15 +57,33 @@ macro_rules! string_impl {
             }
         }
 
+        /// Copies `value` once, and only if it is valid.
         impl TryFrom<&str> for $t {
             type Error = Error;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
-                match Self::validate(value) {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
                     Ok(()) => Ok(Self(value.to_string())),
                     Err(e) => Err(e),
                 }
             }
         }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
     };
 
     (  $t:ty, description = $d:expr, format = $format:expr , pattern =  $pattern:expr, content_encoding = $encoding:expr ) => {
@@ -71,6 +103,14 @@ macro_rules! string_impl {
             fn pattern() -> Option<&'static str> {
                 Some($pattern)
             }
+            #[cfg(feature = "regex_validation")]
+            fn pattern_regex() -> Option<&'static ::regex::Regex> {
+                static PATTERN: ::std::sync::LazyLock<::regex::Regex> =
+                    ::std::sync::LazyLock::new(|| {
+                        ::regex::Regex::new($pattern).expect("invalid pattern")
+                    });
+                Some(&PATTERN)
+            }
             fn content_encoding() -> Option<&'static str> {
                 Some($encoding)
             }
@@ -93,6 +133,12 @@ macro_rules! string_impl {
             }
         }
 
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
         impl FromStr for $t {
             type Err = Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -100,15 +146,33 @@ macro_rules! string_impl {
             }
         }
 
+        /// Copies `value` once, and only if it is valid.
         impl TryFrom<&str> for $t {
             type Error = Error;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
-                match Self::validate(value) {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
                     Ok(()) => Ok(Self(value.to_string())),
                     Err(e) => Err(e),
                 }
             }
         }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
     };
 
     (  $t:ty, description = $d:expr, format = $format:expr, pattern =  $pattern:expr ) => {
@@ -128,6 +192,14 @@ macro_rules! string_impl {
             fn pattern() -> Option<&'static str> {
                 Some($pattern)
             }
+            #[cfg(feature = "regex_validation")]
+            fn pattern_regex() -> Option<&'static ::regex::Regex> {
+                static PATTERN: ::std::sync::LazyLock<::regex::Regex> =
+                    ::std::sync::LazyLock::new(|| {
+                        ::regex::Regex::new($pattern).expect("invalid pattern")
+                    });
+                Some(&PATTERN)
+            }
             fn content_encoding() -> Option<&'static str> {
                 None
             }
@@ -150,6 +222,12 @@ macro_rules! string_impl {
             }
         }
 
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
         impl FromStr for $t {
             type Err = Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -157,15 +235,33 @@ macro_rules! string_impl {
             }
         }
 
+        /// Copies `value` once, and only if it is valid.
         impl TryFrom<&str> for $t {
             type Error = Error;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
-                match Self::validate(value) {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
                     Ok(()) => Ok(Self(value.to_string())),
                     Err(e) => Err(e),
                 }
             }
         }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
     };
     (  $t:ty, description = $d:expr, pattern =  $pattern:expr ) => {
         impl Metaschema for $t {
@@ -184,6 +280,14 @@ macro_rules! string_impl {
             fn pattern() -> Option<&'static str> {
                 Some($pattern)
             }
+            #[cfg(feature = "regex_validation")]
+            fn pattern_regex() -> Option<&'static ::regex::Regex> {
+                static PATTERN: ::std::sync::LazyLock<::regex::Regex> =
+                    ::std::sync::LazyLock::new(|| {
+                        ::regex::Regex::new($pattern).expect("invalid pattern")
+                    });
+                Some(&PATTERN)
+            }
             fn content_encoding() -> Option<&'static str> {
                 None
             }
@@ -206,6 +310,12 @@ macro_rules! string_impl {
             }
         }
 
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
         impl FromStr for $t {
             type Err = Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -213,15 +323,33 @@ macro_rules! string_impl {
             }
         }
 
+        /// Copies `value` once, and only if it is valid.
         impl TryFrom<&str> for $t {
             type Error = Error;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
-                match Self::validate(value) {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
                     Ok(()) => Ok(Self(value.to_string())),
                     Err(e) => Err(e),
                 }
             }
         }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
     };
     (  $t:ty, description = $d:expr, format =  $format:expr ) => {
         impl Metaschema for $t {
@@ -262,6 +390,92 @@ macro_rules! string_impl {
             }
         }
 
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl FromStr for $t {
+            type Err = Error;
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::try_from(s)
+            }
+        }
+
+        /// Copies `value` once, and only if it is valid.
+        impl TryFrom<&str> for $t {
+            type Error = Error;
+            fn try_from(value: &str) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value.to_string())),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
+    };
+    (  $t:ty, description = $d:expr ) => {
+        impl Metaschema for $t {
+            fn _type() -> Option<&'static str> {
+                Some("string")
+            }
+            fn description() -> Option<&'static str> {
+                Some($d)
+            }
+        }
+
+        impl StringType for $t {
+            fn format() -> Option<&'static str> {
+                None
+            }
+            fn pattern() -> Option<&'static str> {
+                None
+            }
+            fn content_encoding() -> Option<&'static str> {
+                None
+            }
+        }
+
+        impl Base for $t {
+            fn base_type() -> String {
+                String::from("String")
+            }
+
+            fn ref_type() -> String {
+                String::from("str")
+            }
+        }
+
+        impl Deref for $t {
+            type Target = str;
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl ::std::fmt::Display for $t {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
         impl FromStr for $t {
             type Err = Error;
             fn from_str(s: &str) -> Result<Self, Self::Err> {
@@ -269,14 +483,32 @@ macro_rules! string_impl {
             }
         }
 
+        /// Copies `value` once, and only if it is valid.
         impl TryFrom<&str> for $t {
             type Error = Error;
             fn try_from(value: &str) -> Result<Self, Self::Error> {
-                match Self::validate(value) {
+                let result = $crate::validate::validate_in_mode::<Self>(value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
                     Ok(()) => Ok(Self(value.to_string())),
                     Err(e) => Err(e),
                 }
             }
         }
+
+        /// Takes ownership of `value` rather than copying it.
+        impl TryFrom<String> for $t {
+            type Error = Error;
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                let result = $crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                $crate::metrics::record(<$t as $crate::OscalDatatype>::KIND, result.is_ok());
+                match result {
+                    Ok(()) => Ok(Self(value)),
+                    Err(e) => Err(e),
+                }
+            }
+        }
     };
 }
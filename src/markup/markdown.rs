@@ -0,0 +1,372 @@
+//! Writing [Event]s back out as OSCAL Markdown.
+//!
+//! The output parses back to the same events.  Text is escaped wherever it
+//! could be read as markup, so the result favours fidelity over looking
+//! hand-written.
+use super::{Event, Tag};
+
+enum Node {
+    Element(Tag, Vec<Node>),
+    Leaf(Event),
+}
+
+impl Node {
+    fn is_inline(&self) -> bool {
+        match self {
+            Node::Element(tag, _) => matches!(
+                tag,
+                Tag::Emphasis
+                    | Tag::Strong
+                    | Tag::Subscript
+                    | Tag::Superscript
+                    | Tag::Link { .. }
+                    | Tag::Image { .. }
+            ),
+            Node::Leaf(event) => !matches!(event, Event::Rule),
+        }
+    }
+}
+
+/// Nest the events.  Unbalanced events are tolerated.
+fn tree(events: &[Event]) -> Vec<Node> {
+    let mut top = vec![];
+    let mut stack: Vec<(Tag, Vec<Node>)> = vec![];
+    let push = |node, stack: &mut Vec<(Tag, Vec<Node>)>, top: &mut Vec<Node>| match stack.last_mut()
+    {
+        Some((_, children)) => children.push(node),
+        None => top.push(node),
+    };
+    for event in events {
+        match event {
+            Event::Start(tag) => stack.push((tag.clone(), vec![])),
+            Event::End(_) => {
+                if let Some((tag, children)) = stack.pop() {
+                    push(Node::Element(tag, children), &mut stack, &mut top);
+                }
+            }
+            event => push(Node::Leaf(event.clone()), &mut stack, &mut top),
+        }
+    }
+    while let Some((tag, children)) = stack.pop() {
+        push(Node::Element(tag, children), &mut stack, &mut top);
+    }
+    top
+}
+
+fn escape_text(text: &str, out: &mut String) {
+    let chars: Vec<char> = text.chars().collect();
+    for (i, &c) in chars.iter().enumerate() {
+        let escape = match c {
+            '\\' | '`' | '*' | '[' | ']' | '<' | '>' | '~' | '^' | '|' => true,
+            '{' => chars.get(i + 1) == Some(&'{'),
+            // Intraword underscores never delimit emphasis
+            '_' => {
+                !(i > 0
+                    && chars[i - 1].is_alphanumeric()
+                    && chars.get(i + 1).is_some_and(|n| n.is_alphanumeric()))
+            }
+            _ => false,
+        };
+        if escape {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+}
+
+/// Escape a line that would otherwise start a heading, list or rule.
+fn escape_line_start(line: &str) -> String {
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if line.starts_with(['#', '-', '+', '=']) {
+        format!("\\{line}")
+    } else if digits > 0 && line[digits..].starts_with(['.', ')']) {
+        format!("{}\\{}", &line[..digits], &line[digits..])
+    } else {
+        line.to_string()
+    }
+}
+
+fn code_span(code: &str, out: &mut String) {
+    let mut longest = 0;
+    let mut run = 0;
+    for c in code.chars() {
+        run = if c == '`' { run + 1 } else { 0 };
+        longest = longest.max(run);
+    }
+    let fence = "`".repeat(longest + 1);
+    let pad = code.starts_with('`')
+        || code.ends_with('`')
+        || (code.starts_with(' ') && code.ends_with(' ') && !code.trim().is_empty());
+    let pad = if pad { " " } else { "" };
+    out.push_str(&format!("{fence}{pad}{code}{pad}{fence}"));
+}
+
+/// Wrap content in delimiters, keeping leading whitespace outside them so
+/// the opener still flanks the content.  Emphasis closers must flank the
+/// content too, so `trim_end` moves trailing whitespace out as well.
+fn delimited(open: &str, close: &str, inner: &str, trim_end: bool, out: &mut String) {
+    let start = inner.trim_start();
+    let core = if trim_end { start.trim_end() } else { start };
+    if core.is_empty() {
+        out.push_str(inner);
+        return;
+    }
+    out.push_str(&inner[..inner.len() - start.len()]);
+    out.push_str(open);
+    out.push_str(core);
+    out.push_str(close);
+    out.push_str(&start[core.len()..]);
+}
+
+fn destination(dest: &str, title: &Option<String>, out: &mut String) {
+    out.push('(');
+    if dest.is_empty() || dest.contains(char::is_whitespace) {
+        out.push('<');
+        out.push_str(dest);
+        out.push('>');
+    } else {
+        for c in dest.chars() {
+            if matches!(c, '\\' | '(' | ')' | '<' | '>') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+    if let Some(title) = title {
+        out.push_str(" \"");
+        for c in title.chars() {
+            if matches!(c, '\\' | '"') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out.push('"');
+    }
+    out.push(')');
+}
+
+fn inlines<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> String {
+    let mut out = String::new();
+    for node in nodes {
+        match node {
+            Node::Leaf(Event::Text(text)) => escape_text(text, &mut out),
+            Node::Leaf(Event::Code(code)) if !code.is_empty() => code_span(code, &mut out),
+            Node::Leaf(Event::Insert { kind, id_ref }) => {
+                out.push_str(&format!("{{{{ insert: {kind}, {id_ref} }}}}"))
+            }
+            Node::Leaf(Event::SoftBreak) => out.push('\n'),
+            Node::Leaf(Event::HardBreak) => out.push_str("\\\n"),
+            Node::Leaf(_) => {}
+            Node::Element(tag, children) => {
+                let inner = inlines(children);
+                // Avoid runs of the same delimiter, which read differently,
+                // and nesting it, where a closer can be taken as an opener
+                let star = inner.trim().starts_with('*')
+                    || inner.trim().ends_with('*')
+                    || children
+                        .iter()
+                        .any(|child| matches!(child, Node::Element(t, _) if t == tag));
+                match tag {
+                    Tag::Emphasis if star => delimited("_", "_", &inner, true, &mut out),
+                    Tag::Emphasis => delimited("*", "*", &inner, true, &mut out),
+                    Tag::Strong if star => delimited("__", "__", &inner, true, &mut out),
+                    Tag::Strong => delimited("**", "**", &inner, true, &mut out),
+                    Tag::Subscript => delimited("~", "~", &inner, false, &mut out),
+                    Tag::Superscript => delimited("^", "^", &inner, false, &mut out),
+                    Tag::Link { dest, title } => {
+                        out.push('[');
+                        out.push_str(&inner);
+                        out.push(']');
+                        destination(dest, title, &mut out);
+                    }
+                    Tag::Image { src, title } => {
+                        out.push_str("![");
+                        out.push_str(&inner);
+                        out.push(']');
+                        destination(src, title, &mut out);
+                    }
+                    _ => out.push_str(&inner),
+                }
+            }
+        }
+    }
+    out
+}
+
+fn paragraph<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> String {
+    inlines(nodes)
+        .split('\n')
+        .map(escape_line_start)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Prefix the first line of `content` with `first` and indent the rest to
+/// match.
+fn hang(first: &str, content: &str) -> String {
+    let indent = " ".repeat(first.len());
+    content
+        .split('\n')
+        .enumerate()
+        .map(|(i, line)| match (i, line.is_empty()) {
+            (0, _) => format!("{first}{line}").trim_end().to_string(),
+            (_, true) => String::new(),
+            (_, false) => format!("{indent}{line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn table_row(cells: &[Node]) -> String {
+    let cells: Vec<String> = cells
+        .iter()
+        .map(|cell| match cell {
+            Node::Element(_, children) => inlines(children),
+            leaf => inlines([leaf]),
+        })
+        .collect();
+    format!("| {} |", cells.join(" | "))
+}
+
+fn block(node: &Node) -> String {
+    let Node::Element(tag, children) = node else {
+        // The only block leaf
+        return "---".to_string();
+    };
+    match tag {
+        Tag::Paragraph => paragraph(children),
+        Tag::Heading(level) => format!("{} {}", "#".repeat(*level as usize), inlines(children))
+            .trim_end()
+            .to_string(),
+        Tag::BlockQuote => blocks(children, "\n\n")
+            .split('\n')
+            .map(|line| match line.is_empty() {
+                true => ">".to_string(),
+                false => format!("> {line}"),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        Tag::CodeBlock(info) => {
+            let mut code: String = children
+                .iter()
+                .filter_map(|child| match child {
+                    Node::Leaf(Event::Text(text)) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect();
+            if !code.is_empty() && !code.ends_with('\n') {
+                code.push('\n');
+            }
+            let longest = code
+                .split('\n')
+                .map(|line| line.trim_start().chars().take_while(|&c| c == '`').count())
+                .max()
+                .unwrap_or(0);
+            let fence = "`".repeat(longest.max(2) + 1);
+            let info = info.as_deref().unwrap_or("");
+            format!("{fence}{info}\n{code}{fence}")
+        }
+        Tag::List(start) => {
+            let tight = children.iter().all(|item| match item {
+                Node::Element(_, content) => !content
+                    .iter()
+                    .any(|c| matches!(c, Node::Element(Tag::Paragraph, _))),
+                Node::Leaf(_) => true,
+            });
+            let separator = if tight { "\n" } else { "\n\n" };
+            children
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    let marker = match start {
+                        Some(start) => format!("{}. ", start + i as u64),
+                        None => "- ".to_string(),
+                    };
+                    let content = match item {
+                        Node::Element(_, content) => blocks(content, separator),
+                        leaf => block(leaf),
+                    };
+                    hang(&marker, &content)
+                })
+                .collect::<Vec<_>>()
+                .join(separator)
+        }
+        Tag::Table => {
+            let mut lines = vec![];
+            for (i, row) in children.iter().enumerate() {
+                let cells = match row {
+                    Node::Element(_, cells) => cells.as_slice(),
+                    Node::Leaf(_) => &[],
+                };
+                lines.push(table_row(cells));
+                if i == 0 {
+                    let columns = cells.len().max(1);
+                    lines.push(format!("|{}", " --- |".repeat(columns)));
+                }
+            }
+            lines.join("\n")
+        }
+        _ => blocks(children, "\n\n"),
+    }
+}
+
+fn blocks(nodes: &[Node], separator: &str) -> String {
+    let mut chunks = vec![];
+    let mut run = vec![];
+    for node in nodes {
+        if node.is_inline() {
+            run.push(node);
+            continue;
+        }
+        if !run.is_empty() {
+            chunks.push(paragraph(run.drain(..)));
+        }
+        chunks.push(block(node));
+    }
+    if !run.is_empty() {
+        chunks.push(paragraph(run));
+    }
+    chunks.join(separator)
+}
+
+/// Write events as OSCAL Markdown.
+pub fn to_markdown(events: &[Event]) -> String {
+    blocks(&tree(events), "\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markup::{parse_line, parse_multiline};
+
+    fn round_trip(markdown: &str) -> String {
+        let events = parse_multiline(markdown).unwrap();
+        let written = to_markdown(&events);
+        assert_eq!(parse_multiline(&written).unwrap(), events, "{written}");
+        written
+    }
+
+    #[test]
+    fn test_round_trip() {
+        assert_eq!(
+            round_trip("# Title\n\nSome *text*\nwrapped."),
+            "# Title\n\nSome *text*\nwrapped."
+        );
+        assert_eq!(round_trip("* a\n* b\n  1. c"), "- a\n- b\n  1. c");
+        assert_eq!(round_trip("3) a\n\n4) b"), "3. a\n\n4. b");
+        assert_eq!(round_trip("> quote\n>\n> more"), "> quote\n>\n> more");
+        assert_eq!(round_trip("~~~rust\n```\n~~~"), "````rust\n```\n````");
+        round_trip("***both*** and ***a* b** and _**a**_");
+        round_trip("H~2~O x^2^ `` a`b `` {{ insert: param, ac-1_prm_1 }}");
+        round_trip("[a *link*](<a b> \"say \\\"hi\\\"\") ![alt](x.png) <https://a.gov>");
+        round_trip("a  \nb\n\n---\n\n\\# not a heading\n\\- not a list\n1\\. not a list");
+        round_trip("| a | b \\| c |\n|---|---|\n| 1 | *2* |");
+        round_trip(r"\*not em\* snake_case \_x\_ \[x\] \<b\> \{{ x }}");
+    }
+
+    #[test]
+    fn test_line() {
+        let events = parse_line("**a** [b](c) \\# d").unwrap();
+        assert_eq!(parse_line(&to_markdown(&events)).unwrap(), events);
+    }
+}
@@ -0,0 +1,1277 @@
+//! Parsing of the OSCAL Markdown subset.
+//!
+//! OSCAL markup is CommonMark restricted to what the OSCAL XML markup
+//! elements can represent.  Markup-line holds inline content: emphasis,
+//! strong, code, links, images, `~sub~`, `^sup^` and `{{ insert: param, id }}`
+//! parameter insertions.  Markup-multiline adds headings, paragraphs, lists,
+//! block quotes, fenced code blocks, tables and thematic breaks.
+//!
+//! Raw HTML and link reference definitions have no OSCAL representation and
+//! are rejected.  Indented code blocks are not recognized; use fenced code
+//! blocks instead.
+//!
+//! Markup nested deeper than [crate::config::Config::markup_max_depth], or
+//! larger than [crate::config::Config::markup_max_size], fails with
+//! [Error::MarkupTooComplex] instead of exhausting the stack or CPU.  The
+//! limits in effect when a value's events are produced apply, so a value
+//! accepted under larger limits may later render as plain text.
+//!
+//! ```
+//! use oscal_types::MarkupLineDatatype;
+//!
+//! let line = MarkupLineDatatype::try_from("Use **strong** `code`").unwrap();
+//! assert_eq!(line.to_html(), "Use <strong>strong</strong> <code>code</code>");
+//! assert!(MarkupLineDatatype::try_from("<script>x</script>").is_err());
+//! ```
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::{config, Error, MarkupLineDatatype, MarkupMultilineDatatype};
+
+mod markdown;
+mod xml;
+
+pub use markdown::to_markdown;
+pub use xml::{parse_xml_line, parse_xml_multiline};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tag {
+    Paragraph,
+    Heading(u8),
+    BlockQuote,
+    /// A fenced code block with its info string.
+    CodeBlock(Option<String>),
+    /// A list, with the start number of ordered lists.
+    List(Option<u64>),
+    Item,
+    Table,
+    TableHead,
+    TableRow,
+    TableCell,
+    Emphasis,
+    Strong,
+    Subscript,
+    Superscript,
+    Link {
+        dest: String,
+        title: Option<String>,
+    },
+    /// An image; the events inside are its alt text.
+    Image {
+        src: String,
+        title: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Start(Tag),
+    End(Tag),
+    Text(String),
+    Code(String),
+    /// A `{{ insert: param, id }}` parameter insertion.
+    Insert {
+        kind: String,
+        id_ref: String,
+    },
+    SoftBreak,
+    HardBreak,
+    Rule,
+}
+
+fn markup_error(message: &str) -> Error {
+    Error::Markup(message.to_string())
+}
+
+fn too_deep() -> Error {
+    Error::MarkupTooComplex("nested too deeply".to_string())
+}
+
+/// The configured nesting limit, after checking `value` against the size
+/// limit.
+pub(crate) fn max_depth(value: &str) -> Result<usize, Error> {
+    let config = config::get();
+    if value.len() > config.markup_max_size {
+        return Err(Error::MarkupTooComplex(format!(
+            "{} bytes exceeds the limit of {}",
+            value.len(),
+            config.markup_max_size
+        )));
+    }
+    Ok(config.markup_max_depth)
+}
+
+/// Parse markup-line content.
+pub fn parse_line(value: &str) -> Result<Vec<Event>, Error> {
+    let depth = max_depth(value)?;
+    if value.contains(['\n', '\r']) {
+        return Err(markup_error("markup-line must be a single line"));
+    }
+    let chars: Vec<char> = value.chars().collect();
+    let mut events = vec![];
+    inlines(&chars, &mut events, depth)?;
+    Ok(events)
+}
+
+/// Parse markup-multiline content.
+pub fn parse_multiline(value: &str) -> Result<Vec<Event>, Error> {
+    let depth = max_depth(value)?;
+    let normalized = value.replace("\r\n", "\n");
+    let lines: Vec<&str> = normalized.split('\n').collect();
+    let mut events = vec![];
+    blocks(&lines, &mut events, depth)?;
+    Ok(events)
+}
+
+/// The events of markup that does not parse: the whole value as text.  A
+/// value kept unchecked with [crate::ValidationMode::Off] may not parse, and
+/// neither may one accepted under a larger [crate::config::scoped] limit
+/// once that scope has ended, as the limits apply whenever markup is parsed.
+fn unparsed(value: &str) -> Vec<Event> {
+    match value.is_empty() {
+        true => vec![],
+        false => vec![Event::Text(value.to_string())],
+    }
+}
+
+impl MarkupLineDatatype {
+    /// The parsed inline events.
+    pub fn events(&self) -> Vec<Event> {
+        parse_line(self).unwrap_or_else(|_| unparsed(self))
+    }
+
+    /// The content rendered as HTML.
+    pub fn to_html(&self) -> String {
+        to_html(&self.events())
+    }
+
+    /// The content as OSCAL XML inline markup.
+    pub fn to_xml_fragment(&self) -> String {
+        to_html(&self.events())
+    }
+
+    /// Convert OSCAL XML inline markup to Markdown.
+    pub fn from_xml_fragment(xml: &str) -> Result<Self, Error> {
+        Self::try_from(to_markdown(&parse_xml_line(xml)?).as_str())
+    }
+}
+
+impl MarkupMultilineDatatype {
+    /// The parsed block and inline events.
+    pub fn events(&self) -> Vec<Event> {
+        parse_multiline(self).unwrap_or_else(|_| unparsed(self))
+    }
+
+    /// The content rendered as HTML.
+    pub fn to_html(&self) -> String {
+        to_html(&self.events())
+    }
+
+    /// The content as OSCAL XML block markup, e.g. `<p>...</p>`.
+    pub fn to_xml_fragment(&self) -> String {
+        to_html(&self.events())
+    }
+
+    /// Convert OSCAL XML block markup to Markdown.
+    pub fn from_xml_fragment(xml: &str) -> Result<Self, Error> {
+        Self::try_from(to_markdown(&parse_xml_multiline(xml)?).as_str())
+    }
+}
+
+// Inline parsing
+
+static INSERT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\{\{\s*insert:\s*([A-Za-z_][\w.-]*)\s*,\s*([A-Za-z_][\w.-]*)\s*\}\}")
+        .expect("insert pattern")
+});
+
+fn flush(text: &mut String, events: &mut Vec<Event>) {
+    if !text.is_empty() {
+        events.push(Event::Text(std::mem::take(text)));
+    }
+}
+
+fn run_len(chars: &[char], start: usize, c: char) -> usize {
+    chars[start..].iter().take_while(|&&x| x == c).count()
+}
+
+/// The start of the next run of exactly `n` backticks at or after `start`.
+fn find_code_close(chars: &[char], start: usize, n: usize) -> Option<usize> {
+    let mut i = start;
+    while i < chars.len() {
+        if chars[i] == '`' {
+            let m = run_len(chars, i, '`');
+            if m == n {
+                return Some(i);
+            }
+            i += m;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+/// Skip an escape or code span at `i`, returning the index after it.
+fn skip_literal(chars: &[char], i: usize) -> Option<usize> {
+    match chars[i] {
+        '\\' => Some(i + 2),
+        '`' => {
+            let n = run_len(chars, i, '`');
+            Some(find_code_close(chars, i + n, n).map_or(i + n, |close| close + n))
+        }
+        _ => None,
+    }
+}
+
+/// The index of the `]` matching the `[` at `open`.  Brackets nested more
+/// than `max_depth` deep are not matched, which keeps runs of unmatched
+/// brackets from taking quadratic time.
+fn find_bracket_close(chars: &[char], open: usize, max_depth: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut i = open;
+    while i < chars.len() {
+        if let Some(next) = skip_literal(chars, i) {
+            i = next;
+            continue;
+        }
+        match chars[i] {
+            '[' if depth == max_depth => return None,
+            '[' => depth += 1,
+            ']' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+struct LinkParts {
+    label_end: usize,
+    dest: String,
+    title: Option<String>,
+    end: usize,
+}
+
+/// Parse `[label](dest "title")` starting at the `[` at `open`.
+fn link_parts(chars: &[char], open: usize, max_depth: usize) -> Option<LinkParts> {
+    let label_end = find_bracket_close(chars, open, max_depth)?;
+    if chars.get(label_end + 1) != Some(&'(') {
+        return None;
+    }
+    let skip_spaces = |mut i: usize| {
+        while chars.get(i).is_some_and(|c| c.is_whitespace()) {
+            i += 1;
+        }
+        i
+    };
+    let mut i = skip_spaces(label_end + 2);
+    let mut dest = String::new();
+    if chars.get(i) == Some(&'<') {
+        i += 1;
+        while *chars.get(i)? != '>' {
+            dest.push(chars[i]);
+            i += 1;
+        }
+        i += 1;
+    } else {
+        let mut parens = 0;
+        while let Some(&c) = chars.get(i) {
+            match c {
+                '\\' if chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) => {
+                    dest.push(chars[i + 1]);
+                    i += 2;
+                    continue;
+                }
+                '(' => parens += 1,
+                ')' if parens == 0 => break,
+                ')' => parens -= 1,
+                c if c.is_whitespace() => break,
+                _ => {}
+            }
+            dest.push(c);
+            i += 1;
+        }
+    }
+    i = skip_spaces(i);
+    let mut title = None;
+    if let Some(&quote) = chars.get(i).filter(|c| matches!(c, '"' | '\'' | '(')) {
+        let close = if quote == '(' { ')' } else { quote };
+        let mut t = String::new();
+        i += 1;
+        while *chars.get(i)? != close {
+            if chars[i] == '\\' && chars.get(i + 1).is_some_and(|n| n.is_ascii_punctuation()) {
+                i += 1;
+            }
+            t.push(chars[i]);
+            i += 1;
+        }
+        title = Some(t);
+        i = skip_spaces(i + 1);
+    }
+    match chars.get(i) {
+        Some(')') => Some(LinkParts {
+            label_end,
+            dest,
+            title,
+            end: i + 1,
+        }),
+        _ => None,
+    }
+}
+
+/// The end of the emphasis run closing the `n` long run of `c` opened at
+/// `open`.
+fn find_emphasis_close(chars: &[char], open: usize, c: char, n: usize) -> Option<usize> {
+    let mut i = open + n;
+    while i < chars.len() {
+        if let Some(next) = skip_literal(chars, i) {
+            i = next;
+            continue;
+        }
+        if chars[i] == c {
+            let m = run_len(chars, i, c);
+            let after = chars.get(i + m);
+            let right_flanking = !chars[i - 1].is_whitespace()
+                && (c != '_' || !after.is_some_and(|a| a.is_alphanumeric()));
+            if m == n && right_flanking {
+                return Some(i);
+            }
+            i += m;
+        } else {
+            i += 1;
+        }
+    }
+    None
+}
+
+fn plain_text(events: &[Event]) -> String {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            Event::Text(t) | Event::Code(t) => Some(t.as_str()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn is_autolink(content: &str) -> bool {
+    if content.contains(char::is_whitespace) || content.is_empty() {
+        return false;
+    }
+    match content.split_once(':') {
+        Some((scheme, _)) => {
+            scheme.len() >= 2
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '.' | '-'))
+        }
+        None => content.contains('@'),
+    }
+}
+
+/// Parse inline content, nesting at most `depth` levels.
+fn inlines(chars: &[char], events: &mut Vec<Event>, depth: usize) -> Result<(), Error> {
+    let depth = depth.checked_sub(1).ok_or_else(too_deep)?;
+    // Emphasis runs already known to have no closer.  Later openers only
+    // search a suffix of the same range, so they cannot find one either.
+    let mut unclosed: Vec<(char, usize)> = vec![];
+    let last_gt = chars.iter().rposition(|&c| c == '>');
+    let mut text = String::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '\\' => match chars.get(i + 1) {
+                Some('\n') => {
+                    flush(&mut text, events);
+                    events.push(Event::HardBreak);
+                    i += 2;
+                    continue;
+                }
+                Some(&next) if next.is_ascii_punctuation() => {
+                    text.push(next);
+                    i += 2;
+                    continue;
+                }
+                _ => {}
+            },
+            '\n' => {
+                let hard = text.ends_with("  ");
+                text.truncate(text.trim_end_matches(' ').len());
+                flush(&mut text, events);
+                events.push(match hard {
+                    true => Event::HardBreak,
+                    false => Event::SoftBreak,
+                });
+                i += 1;
+                continue;
+            }
+            '`' => {
+                let n = run_len(chars, i, '`');
+                match find_code_close(chars, i + n, n) {
+                    Some(close) => {
+                        let code: String = chars[i + n..close]
+                            .iter()
+                            .map(|&c| if c == '\n' { ' ' } else { c })
+                            .collect();
+                        let code = match code.len() > 2
+                            && code.starts_with(' ')
+                            && code.ends_with(' ')
+                            && !code.trim().is_empty()
+                        {
+                            true => code[1..code.len() - 1].to_string(),
+                            false => code,
+                        };
+                        flush(&mut text, events);
+                        events.push(Event::Code(code));
+                        i = close + n;
+                    }
+                    None => {
+                        text.extend(std::iter::repeat_n('`', n));
+                        i += n;
+                    }
+                }
+                continue;
+            }
+            '{' if chars.get(i + 1) == Some(&'{') => {
+                // An insertion only holds names, whitespace and separators,
+                // so stop at anything else to stay linear
+                let end = (i + 2..chars.len())
+                    .find(|&j| {
+                        let c = chars[j];
+                        !(c.is_alphanumeric()
+                            || c.is_whitespace()
+                            || matches!(c, '_' | '.' | '-' | ':' | ',' | '}'))
+                    })
+                    .unwrap_or(chars.len());
+                let rest: String = chars[i..end].iter().collect();
+                if let Some(captures) = INSERT.captures(&rest) {
+                    flush(&mut text, events);
+                    events.push(Event::Insert {
+                        kind: captures[1].to_string(),
+                        id_ref: captures[2].to_string(),
+                    });
+                    i += captures[0].chars().count();
+                    continue;
+                }
+            }
+            '!' if chars.get(i + 1) == Some(&'[') => {
+                if let Some(parts) = link_parts(chars, i + 1, depth) {
+                    let mut alt = vec![];
+                    inlines(&chars[i + 2..parts.label_end], &mut alt, depth)?;
+                    let tag = Tag::Image {
+                        src: parts.dest,
+                        title: parts.title,
+                    };
+                    flush(&mut text, events);
+                    events.push(Event::Start(tag.clone()));
+                    events.push(Event::Text(plain_text(&alt)));
+                    events.push(Event::End(tag));
+                    i = parts.end;
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some(parts) = link_parts(chars, i, depth) {
+                    let tag = Tag::Link {
+                        dest: parts.dest,
+                        title: parts.title,
+                    };
+                    flush(&mut text, events);
+                    events.push(Event::Start(tag.clone()));
+                    inlines(&chars[i + 1..parts.label_end], events, depth)?;
+                    events.push(Event::End(tag));
+                    i = parts.end;
+                    continue;
+                }
+            }
+            '<' => {
+                // Autolinks contain no whitespace or `<`, so stop there to
+                // stay linear
+                let len = chars[i + 1..]
+                    .iter()
+                    .position(|&c| c == '>' || c == '<' || c.is_whitespace())
+                    .filter(|&len| chars[i + 1 + len] == '>');
+                if let Some(len) = len {
+                    let content: String = chars[i + 1..i + 1 + len].iter().collect();
+                    if is_autolink(&content) {
+                        let dest = match content.contains(':') {
+                            true => content.clone(),
+                            false => format!("mailto:{content}"),
+                        };
+                        let tag = Tag::Link { dest, title: None };
+                        flush(&mut text, events);
+                        events.push(Event::Start(tag.clone()));
+                        events.push(Event::Text(content));
+                        events.push(Event::End(tag));
+                        i += len + 2;
+                        continue;
+                    }
+                }
+                let tag_start = chars
+                    .get(i + 1)
+                    .is_some_and(|&n| n.is_ascii_alphabetic() || matches!(n, '/' | '!' | '?'));
+                if tag_start && last_gt.is_some_and(|gt| gt > i) {
+                    return Err(markup_error("raw HTML is not allowed"));
+                }
+            }
+            '*' | '_' => {
+                let n = run_len(chars, i, c);
+                let before = i.checked_sub(1).map(|b| chars[b]);
+                let left_flanking = chars.get(i + n).is_some_and(|a| !a.is_whitespace())
+                    && (c != '_' || !before.is_some_and(|b| b.is_alphanumeric()));
+                if n <= 3 && left_flanking && !unclosed.contains(&(c, n)) {
+                    let close = find_emphasis_close(chars, i, c, n);
+                    if close.is_none() {
+                        unclosed.push((c, n));
+                    }
+                    if let Some(close) = close {
+                        let tags = match n {
+                            1 => vec![Tag::Emphasis],
+                            2 => vec![Tag::Strong],
+                            _ => vec![Tag::Strong, Tag::Emphasis],
+                        };
+                        flush(&mut text, events);
+                        events.extend(tags.iter().cloned().map(Event::Start));
+                        inlines(&chars[i + n..close], events, depth)?;
+                        events.extend(tags.into_iter().rev().map(Event::End));
+                        i = close + n;
+                        continue;
+                    }
+                }
+                text.extend(std::iter::repeat_n(c, n));
+                i += n;
+                continue;
+            }
+            '~' | '^'
+                if chars
+                    .get(i + 1)
+                    .is_some_and(|&n| n != c && !n.is_whitespace())
+                    && (i == 0 || chars[i - 1] != c) =>
+            {
+                let close = (i + 1..chars.len()).find(|&j| chars[j] == c && chars[j - 1] != '\\');
+                if let Some(close) = close {
+                    let tag = match c {
+                        '~' => Tag::Subscript,
+                        _ => Tag::Superscript,
+                    };
+                    flush(&mut text, events);
+                    events.push(Event::Start(tag.clone()));
+                    inlines(&chars[i + 1..close], events, depth)?;
+                    events.push(Event::End(tag));
+                    i = close + 1;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        text.push(c);
+        i += 1;
+    }
+    flush(&mut text, events);
+    Ok(())
+}
+
+// Block parsing
+
+fn indent(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
+/// The line without up to three spaces of indentation, if it is not
+/// indented further.
+fn block_start(line: &str) -> Option<&str> {
+    match indent(line) {
+        0..=3 => Some(line.trim_start_matches(' ')),
+        _ => None,
+    }
+}
+
+fn fence(line: &str) -> Option<(char, usize, &str)> {
+    let line = block_start(line)?;
+    let c = line.chars().next().filter(|c| matches!(c, '`' | '~'))?;
+    let n = line.chars().take_while(|&x| x == c).count();
+    (n >= 3).then(|| (c, n, line[n..].trim()))
+}
+
+fn heading(line: &str) -> Option<(u8, &str)> {
+    let line = block_start(line)?;
+    let level = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[level..];
+    match level {
+        1..=6 if rest.is_empty() || rest.starts_with(' ') => {
+            let content = rest.trim();
+            let content = match content.trim_end_matches('#') {
+                stripped if stripped.is_empty() || stripped.ends_with(' ') => stripped.trim_end(),
+                _ => content,
+            };
+            Some((level as u8, content))
+        }
+        _ => None,
+    }
+}
+
+fn is_rule(line: &str) -> bool {
+    let Some(line) = block_start(line) else {
+        return false;
+    };
+    ['-', '*', '_'].iter().any(|&c| {
+        line.chars().filter(|&x| x == c).count() >= 3 && line.chars().all(|x| x == c || x == ' ')
+    })
+}
+
+fn is_quote(line: &str) -> bool {
+    block_start(line).is_some_and(|l| l.starts_with('>'))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Marker {
+    Bullet(char),
+    Ordered(char),
+}
+
+/// The list marker of an item line, the start number of ordered items, and
+/// the offset of the item content.
+fn list_marker(line: &str) -> Option<(Marker, Option<u64>, usize)> {
+    let rest = block_start(line)?;
+    let lead = line.len() - rest.len();
+    let (marker, start, width) = match rest.chars().next()? {
+        c @ ('-' | '*' | '+') => (Marker::Bullet(c), None, 1),
+        _ => {
+            let digits = rest.chars().take_while(|c| c.is_ascii_digit()).count();
+            let delimiter = rest[digits..].chars().next()?;
+            if !(1..=9).contains(&digits) || !matches!(delimiter, '.' | ')') {
+                return None;
+            }
+            let start = rest[..digits].parse().ok()?;
+            (Marker::Ordered(delimiter), Some(start), digits + 1)
+        }
+    };
+    let after = &rest[width..];
+    if after.is_empty() {
+        return Some((marker, start, lead + width));
+    }
+    match indent(after) {
+        0 => None,
+        spaces @ 1..=4 => Some((marker, start, lead + width + spaces)),
+        _ => Some((marker, start, lead + width + 1)),
+    }
+}
+
+fn table_cells(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let line = match line.strip_suffix('|') {
+        Some(stripped) if !stripped.ends_with('\\') => stripped,
+        _ => line,
+    };
+    let mut cells = vec![];
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            c => cell.push(c),
+        }
+    }
+    cells.push(cell.trim().to_string());
+    cells
+}
+
+fn is_table_delimiter(line: &str) -> bool {
+    line.contains('-')
+        && table_cells(line).iter().all(|cell| {
+            let cell = cell.trim_start_matches(':').trim_end_matches(':');
+            !cell.is_empty() && cell.chars().all(|c| c == '-')
+        })
+}
+
+fn is_table_start(lines: &[&str], i: usize) -> bool {
+    lines[i].contains('|') && lines.get(i + 1).is_some_and(|l| is_table_delimiter(l))
+}
+
+fn is_reference_definition(line: &str) -> bool {
+    static DEFINITION: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"^ {0,3}\[[^\]]+\]:").expect("definition pattern"));
+    DEFINITION.is_match(line)
+}
+
+/// Whether `lines[i]` starts a block other than a paragraph.
+fn interrupts_paragraph(lines: &[&str], i: usize) -> bool {
+    let line = lines[i];
+    heading(line).is_some()
+        || fence(line).is_some()
+        || is_quote(line)
+        || is_rule(line)
+        || list_marker(line).is_some_and(|(_, start, _)| start.is_none_or(|s| s == 1))
+        || is_table_start(lines, i)
+}
+
+fn inline_block(
+    content: &str,
+    tag: Tag,
+    events: &mut Vec<Event>,
+    depth: usize,
+) -> Result<(), Error> {
+    let chars: Vec<char> = content.chars().collect();
+    events.push(Event::Start(tag.clone()));
+    inlines(&chars, events, depth)?;
+    events.push(Event::End(tag));
+    Ok(())
+}
+
+/// Parse block content, nesting at most `depth` levels.
+fn blocks(lines: &[&str], events: &mut Vec<Event>, depth: usize) -> Result<(), Error> {
+    let depth = depth.checked_sub(1).ok_or_else(too_deep)?;
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        if is_blank(line) {
+            i += 1;
+        } else if is_reference_definition(line) {
+            return Err(markup_error("link reference definitions are not allowed"));
+        } else if let Some((c, n, info)) = fence(line) {
+            let fence_indent = indent(line);
+            let mut code = vec![];
+            i += 1;
+            while i < lines.len()
+                && !fence(lines[i]).is_some_and(|(x, m, rest)| x == c && m >= n && rest.is_empty())
+            {
+                let content = lines[i];
+                code.push(&content[indent(content).min(fence_indent)..]);
+                i += 1;
+            }
+            i += 1;
+            let info = info.split_whitespace().next().map(String::from);
+            let tag = Tag::CodeBlock(info);
+            events.push(Event::Start(tag.clone()));
+            if !code.is_empty() {
+                events.push(Event::Text(code.join("\n") + "\n"));
+            }
+            events.push(Event::End(tag));
+        } else if let Some((level, content)) = heading(line) {
+            inline_block(content, Tag::Heading(level), events, depth)?;
+            i += 1;
+        } else if is_rule(line) {
+            events.push(Event::Rule);
+            i += 1;
+        } else if is_quote(line) {
+            let mut quoted = vec![];
+            while i < lines.len() && is_quote(lines[i]) {
+                let content = block_start(lines[i]).expect("quote")[1..].to_string();
+                quoted.push(
+                    content
+                        .strip_prefix(' ')
+                        .map(String::from)
+                        .unwrap_or(content),
+                );
+                i += 1;
+            }
+            let quoted: Vec<&str> = quoted.iter().map(String::as_str).collect();
+            events.push(Event::Start(Tag::BlockQuote));
+            blocks(&quoted, events, depth)?;
+            events.push(Event::End(Tag::BlockQuote));
+        } else if let Some((marker, start, _)) = list_marker(line) {
+            i = list(lines, i, marker, start, events, depth)?;
+        } else if is_table_start(lines, i) {
+            events.push(Event::Start(Tag::Table));
+            let mut row_tag = Tag::TableHead;
+            let mut row = i;
+            while row < lines.len()
+                && !is_blank(lines[row])
+                && (row == i || !interrupts_paragraph(lines, row))
+            {
+                if row != i + 1 {
+                    events.push(Event::Start(row_tag.clone()));
+                    for cell in table_cells(lines[row]) {
+                        inline_block(&cell, Tag::TableCell, events, depth)?;
+                    }
+                    events.push(Event::End(row_tag));
+                    row_tag = Tag::TableRow;
+                }
+                row += 1;
+            }
+            events.push(Event::End(Tag::Table));
+            i = row;
+        } else {
+            let mut paragraph = vec![line.trim_start()];
+            i += 1;
+            let mut tag = Tag::Paragraph;
+            while i < lines.len() && !is_blank(lines[i]) {
+                let next = lines[i].trim();
+                if !next.is_empty() && next.chars().all(|c| c == '=') {
+                    tag = Tag::Heading(1);
+                    i += 1;
+                    break;
+                }
+                if !next.is_empty() && next.chars().all(|c| c == '-') {
+                    tag = Tag::Heading(2);
+                    i += 1;
+                    break;
+                }
+                if interrupts_paragraph(lines, i) {
+                    break;
+                }
+                paragraph.push(lines[i].trim_start());
+                i += 1;
+            }
+            let content = paragraph.join("\n");
+            inline_block(content.trim_end(), tag, events, depth)?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse the list starting at `lines[i]`, returning the index after it.
+fn list(
+    lines: &[&str],
+    mut i: usize,
+    marker: Marker,
+    start: Option<u64>,
+    events: &mut Vec<Event>,
+    depth: usize,
+) -> Result<usize, Error> {
+    let mut items: Vec<Vec<String>> = vec![];
+    let mut tight = true;
+    while let Some((_, _, offset)) = list_marker(lines[i]).filter(|(m, _, _)| *m == marker) {
+        let first = lines[i].get(offset..).unwrap_or("");
+        let mut item = vec![first.to_string()];
+        i += 1;
+        while i < lines.len() {
+            let line = lines[i];
+            if is_blank(line) {
+                let next = (i..lines.len()).find(|&j| !is_blank(lines[j]));
+                match next {
+                    Some(j) if indent(lines[j]) >= offset => {
+                        item.extend((i..j).map(|_| String::new()));
+                        tight = false;
+                        i = j;
+                    }
+                    _ => break,
+                }
+            } else if indent(line) >= offset {
+                item.push(line[offset..].to_string());
+                i += 1;
+            } else if list_marker(line).is_some() || interrupts_paragraph(lines, i) {
+                break;
+            } else {
+                // Lazy paragraph continuation
+                item.push(line.trim_start().to_string());
+                i += 1;
+            }
+        }
+        items.push(item);
+
+        let next = (i..lines.len()).find(|&j| !is_blank(lines[j]));
+        match next {
+            Some(j) if list_marker(lines[j]).is_some_and(|(m, _, _)| m == marker) => {
+                if j > i {
+                    tight = false;
+                }
+                i = j;
+            }
+            _ => break,
+        }
+    }
+
+    let tag = Tag::List(start);
+    events.push(Event::Start(tag.clone()));
+    for item in items {
+        let lines: Vec<&str> = item.iter().map(String::as_str).collect();
+        let mut content = vec![];
+        blocks(&lines, &mut content, depth)?;
+        events.push(Event::Start(Tag::Item));
+        let mut depth = 0;
+        for event in content {
+            let top_paragraph = tight && depth == 0;
+            match &event {
+                Event::Start(Tag::Paragraph) if top_paragraph => {}
+                Event::End(Tag::Paragraph) if tight && depth == 1 => {}
+                _ => {
+                    if let Event::Start(_) = event {
+                        depth += 1;
+                    }
+                    if let Event::End(_) = event {
+                        depth -= 1;
+                    }
+                    events.push(event);
+                    continue;
+                }
+            }
+            // Track the skipped paragraph
+            if let Event::Start(_) = event {
+                depth += 1;
+            } else {
+                depth -= 1;
+            }
+        }
+        events.push(Event::End(Tag::Item));
+    }
+    events.push(Event::End(tag));
+    Ok(i)
+}
+
+// HTML rendering
+
+fn escape_html(text: &str, out: &mut String) {
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+}
+
+fn attribute(out: &mut String, name: &str, value: &str) {
+    out.push(' ');
+    out.push_str(name);
+    out.push_str("=\"");
+    escape_html(value, out);
+    out.push('"');
+}
+
+/// Render events as HTML, using the element names of OSCAL XML markup.  The
+/// output is also a valid OSCAL XML markup fragment.
+pub fn to_html(events: &[Event]) -> String {
+    let mut out = String::new();
+    let mut in_head = false;
+    let mut in_image = false;
+    for event in events {
+        match event {
+            Event::Start(tag) => match tag {
+                Tag::Paragraph => out.push_str("<p>"),
+                Tag::Heading(level) => out.push_str(&format!("<h{level}>")),
+                Tag::BlockQuote => out.push_str("<blockquote>"),
+                Tag::CodeBlock(info) => {
+                    out.push_str("<pre><code");
+                    if let Some(info) = info {
+                        attribute(&mut out, "class", &format!("language-{info}"));
+                    }
+                    out.push('>');
+                }
+                Tag::List(None) => out.push_str("<ul>"),
+                Tag::List(Some(1)) => out.push_str("<ol>"),
+                Tag::List(Some(start)) => out.push_str(&format!("<ol start=\"{start}\">")),
+                Tag::Item => out.push_str("<li>"),
+                Tag::Table => out.push_str("<table>"),
+                Tag::TableHead => {
+                    in_head = true;
+                    out.push_str("<tr>");
+                }
+                Tag::TableRow => out.push_str("<tr>"),
+                Tag::TableCell => out.push_str(if in_head { "<th>" } else { "<td>" }),
+                Tag::Emphasis => out.push_str("<em>"),
+                Tag::Strong => out.push_str("<strong>"),
+                Tag::Subscript => out.push_str("<sub>"),
+                Tag::Superscript => out.push_str("<sup>"),
+                Tag::Link { dest, title } => {
+                    out.push_str("<a");
+                    attribute(&mut out, "href", dest);
+                    if let Some(title) = title {
+                        attribute(&mut out, "title", title);
+                    }
+                    out.push('>');
+                }
+                Tag::Image { src, .. } => {
+                    out.push_str("<img");
+                    attribute(&mut out, "src", src);
+                    in_image = true;
+                }
+            },
+            Event::End(tag) => match tag {
+                Tag::Paragraph => out.push_str("</p>"),
+                Tag::Heading(level) => out.push_str(&format!("</h{level}>")),
+                Tag::BlockQuote => out.push_str("</blockquote>"),
+                Tag::CodeBlock(_) => out.push_str("</code></pre>"),
+                Tag::List(None) => out.push_str("</ul>"),
+                Tag::List(Some(_)) => out.push_str("</ol>"),
+                Tag::Item => out.push_str("</li>"),
+                Tag::Table => out.push_str("</table>"),
+                Tag::TableHead => {
+                    in_head = false;
+                    out.push_str("</tr>");
+                }
+                Tag::TableRow => out.push_str("</tr>"),
+                Tag::TableCell => out.push_str(if in_head { "</th>" } else { "</td>" }),
+                Tag::Emphasis => out.push_str("</em>"),
+                Tag::Strong => out.push_str("</strong>"),
+                Tag::Subscript => out.push_str("</sub>"),
+                Tag::Superscript => out.push_str("</sup>"),
+                Tag::Link { .. } => out.push_str("</a>"),
+                Tag::Image { title, .. } => {
+                    if let Some(title) = title {
+                        attribute(&mut out, "title", title);
+                    }
+                    out.push_str("/>");
+                    in_image = false;
+                }
+            },
+            Event::Text(text) if in_image => attribute(&mut out, "alt", text),
+            Event::Text(text) => escape_html(text, &mut out),
+            Event::Code(code) => {
+                out.push_str("<code>");
+                escape_html(code, &mut out);
+                out.push_str("</code>");
+            }
+            Event::Insert { kind, id_ref } => {
+                out.push_str("<insert");
+                attribute(&mut out, "type", kind);
+                attribute(&mut out, "id-ref", id_ref);
+                out.push_str("/>");
+            }
+            Event::SoftBreak => out.push('\n'),
+            Event::HardBreak => out.push_str("<br/>"),
+            Event::Rule => out.push_str("<hr/>"),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line_html(value: &str) -> String {
+        to_html(&parse_line(value).unwrap())
+    }
+
+    fn html(value: &str) -> String {
+        to_html(&parse_multiline(value).unwrap())
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            line_html("*em* and **strong**"),
+            "<em>em</em> and <strong>strong</strong>"
+        );
+        assert_eq!(line_html("***both***"), "<strong><em>both</em></strong>");
+        assert_eq!(line_html("snake_case_name"), "snake_case_name");
+        assert_eq!(line_html("2 * 3 * 4"), "2 * 3 * 4");
+        assert_eq!(line_html(r"\*literal\*"), "*literal*");
+        assert_eq!(line_html("`a < b`"), "<code>a &lt; b</code>");
+        assert_eq!(
+            line_html("H~2~O and x^2^"),
+            "H<sub>2</sub>O and x<sup>2</sup>"
+        );
+        assert_eq!(
+            line_html("[NIST](https://nist.gov \"home\")"),
+            "<a href=\"https://nist.gov\" title=\"home\">NIST</a>"
+        );
+        assert_eq!(
+            line_html("![logo](logo.png)"),
+            "<img src=\"logo.png\" alt=\"logo\"/>"
+        );
+        assert_eq!(
+            line_html("<https://a.gov>"),
+            "<a href=\"https://a.gov\">https://a.gov</a>"
+        );
+        assert_eq!(
+            line_html("{{ insert: param, ac-1_prm_1 }}"),
+            "<insert type=\"param\" id-ref=\"ac-1_prm_1\"/>"
+        );
+        assert_eq!(line_html("[not a link]"), "[not a link]");
+    }
+
+    #[test]
+    fn test_rejected() {
+        assert!(parse_line("a <b>bold</b>").is_err());
+        assert!(parse_line("two\nlines").is_err());
+        assert!(parse_multiline("text\n\n[ref]: https://a.gov").is_err());
+        assert!(parse_line("a < b").is_ok());
+    }
+
+    #[test]
+    fn test_blocks() {
+        assert_eq!(
+            html("# Title\n\nSome *text*\nwrapped."),
+            "<h1>Title</h1><p>Some <em>text</em>\nwrapped.</p>"
+        );
+        assert_eq!(html("Setext\n---"), "<h2>Setext</h2>");
+        assert_eq!(html("- a\n- b"), "<ul><li>a</li><li>b</li></ul>");
+        assert_eq!(
+            html("3. a\n\n4. b"),
+            "<ol start=\"3\"><li><p>a</p></li><li><p>b</p></li></ol>"
+        );
+        assert_eq!(html("- a\n  - b"), "<ul><li>a<ul><li>b</li></ul></li></ul>");
+        assert_eq!(
+            html("> quoted\n> text"),
+            "<blockquote><p>quoted\ntext</p></blockquote>"
+        );
+        assert_eq!(
+            html("```rust\nfn x() {}\n```"),
+            "<pre><code class=\"language-rust\">fn x() {}\n</code></pre>"
+        );
+        assert_eq!(html("a\n\n***\n\nb"), "<p>a</p><hr/><p>b</p>");
+        assert_eq!(
+            html("| a | b |\n|---|:-:|\n| 1 | 2 |"),
+            "<table><tr><th>a</th><th>b</th></tr><tr><td>1</td><td>2</td></tr></table>"
+        );
+    }
+
+    #[test]
+    fn test_limits() {
+        let deep = "> ".repeat(100) + "x";
+        assert!(matches!(
+            parse_multiline(&deep),
+            Err(Error::MarkupTooComplex(_))
+        ));
+        let deep = "*a ".repeat(100) + &"*".repeat(100);
+        assert!(parse_line(&deep).is_ok());
+        // Brackets nested past the limit are left as text
+        let deep = "[".repeat(100) + "x" + &"](y)".repeat(100);
+        assert!(
+            matches!(&parse_line(&deep).unwrap()[0], Event::Text(text) if text.starts_with('['))
+        );
+
+        let strict = config::Config {
+            markup_max_depth: 3,
+            markup_max_size: 16,
+            ..config::Config::new()
+        };
+        config::scoped(strict, || {
+            assert!(parse_multiline("- - x").is_err());
+            assert!(parse_multiline("- x").is_ok());
+            assert!(parse_line(&"x".repeat(17)).is_err());
+            assert!(parse_xml_multiline("<ul><li><p>x</p></li></ul>").is_err());
+        });
+
+        // Accepted under a larger limit, rendered as text under the default
+        let generous = config::Config {
+            markup_max_depth: 200,
+            ..config::Config::new()
+        };
+        let deep = "> ".repeat(100) + "x";
+        let quoted = config::scoped(generous, || {
+            MarkupMultilineDatatype::try_from(deep.as_str())
+        })
+        .unwrap();
+        assert_eq!(quoted.events(), [Event::Text(deep)]);
+        assert!(quoted.to_html().ends_with('x'));
+    }
+
+    /// Pathological input for naive parsers must finish quickly.
+    #[test]
+    fn test_linear_inputs() {
+        let n = 20_000;
+        for line in [
+            "[".repeat(n),
+            "*a ".repeat(n),
+            "_a ".repeat(n),
+            "**a ".repeat(n),
+            "{{".repeat(n),
+            "<".repeat(n) + ">",
+            "![".repeat(n),
+            "`a ``b ".repeat(n),
+        ] {
+            let _ = parse_line(&line);
+        }
+        for multiline in [
+            "- ".repeat(n),
+            "> ".repeat(n),
+            "- a\n".repeat(n),
+            "\n".repeat(n),
+        ] {
+            let _ = parse_multiline(&multiline);
+        }
+        let _ = parse_xml_multiline(&"<p>".repeat(n));
+    }
+
+    /// Random input must never panic, and whatever parses must survive the
+    /// Markdown and XML round trips.
+    #[test]
+    fn test_fuzz() {
+        const PIECES: &[&str] = &[
+            "a",
+            " ",
+            "\n",
+            "\n\n",
+            "*",
+            "_",
+            "`",
+            "[",
+            "]",
+            "(",
+            ")",
+            "!",
+            "<",
+            ">",
+            "~",
+            "^",
+            "\\",
+            "#",
+            "-",
+            "1.",
+            "|",
+            "{{ insert: param, p1 }}",
+            "  ",
+            "---",
+            "```",
+            "> ",
+            "http://a.gov",
+            "\"",
+            "=",
+        ];
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        for _ in 0..2000 {
+            let len = next() % 40;
+            let input: String = (0..len)
+                .map(|_| PIECES[(next() % PIECES.len() as u64) as usize])
+                .collect();
+            let line = input.replace('\n', " ");
+            if let Ok(events) = parse_line(&line) {
+                let written = to_markdown(&events);
+                assert_eq!(
+                    parse_line(&written).ok(),
+                    Some(events),
+                    "{line:?} -> {written:?}"
+                );
+            }
+            if let Ok(events) = parse_multiline(&input) {
+                let _ = to_markdown(&events);
+                let html = to_html(&events);
+                assert!(parse_xml_multiline(&html).is_ok(), "{input:?} -> {html:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_datatypes() {
+        let multiline = MarkupMultilineDatatype::try_from("Para one.\n\nPara two.").unwrap();
+        assert_eq!(multiline.to_html(), "<p>Para one.</p><p>Para two.</p>");
+        assert!(MarkupMultilineDatatype::try_from("<div>raw</div>").is_err());
+
+        let xml = multiline.to_xml_fragment();
+        assert_eq!(
+            MarkupMultilineDatatype::from_xml_fragment(&xml).unwrap(),
+            multiline
+        );
+        let line = MarkupLineDatatype::from_xml_fragment("Fish &amp; <em>chips</em>").unwrap();
+        assert_eq!(&*line, "Fish & *chips*");
+        assert_eq!(line.to_xml_fragment(), "Fish &amp; <em>chips</em>");
+    }
+}
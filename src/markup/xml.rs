@@ -0,0 +1,561 @@
+//! Reading OSCAL XML markup fragments into [Event]s.
+//!
+//! The reader handles elements, attributes, predefined and character entity
+//! references, comments and CDATA sections, which covers the markup written
+//! by OSCAL tools.  Namespace prefixes are ignored and DTDs are not
+//! supported.
+use super::{max_depth, too_deep, Event, Tag};
+use crate::Error;
+
+fn xml_error(message: String) -> Error {
+    Error::Markup(message)
+}
+
+enum Node {
+    Element {
+        name: String,
+        attributes: Vec<(String, String)>,
+        children: Vec<Node>,
+    },
+    Text(String),
+}
+
+impl Node {
+    fn name(&self) -> Option<&str> {
+        match self {
+            Node::Element { name, .. } => Some(name),
+            Node::Text(_) => None,
+        }
+    }
+
+    fn attribute(&self, key: &str) -> Option<&str> {
+        match self {
+            Node::Element { attributes, .. } => attributes
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            Node::Text(_) => None,
+        }
+    }
+
+    fn children(&self) -> &[Node] {
+        match self {
+            Node::Element { children, .. } => children,
+            Node::Text(_) => &[],
+        }
+    }
+
+    fn text_content(&self) -> String {
+        match self {
+            Node::Element { children, .. } => children.iter().map(Node::text_content).collect(),
+            Node::Text(text) => text.clone(),
+        }
+    }
+
+    fn is_blank(&self) -> bool {
+        matches!(self, Node::Text(text) if text.trim().is_empty())
+    }
+}
+
+/// The name without its namespace prefix.
+fn local(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn unescape(text: &str) -> Result<String, Error> {
+    let mut out = String::new();
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        let semi = rest[amp..]
+            .find(';')
+            .ok_or_else(|| xml_error("unterminated entity reference".to_string()))?;
+        let entity = &rest[amp + 1..amp + semi];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => entity.strip_prefix('#').and_then(|n| n.parse().ok()),
+            }
+            .and_then(char::from_u32),
+        };
+        out.push(c.ok_or_else(|| xml_error(format!("unknown entity &{entity};")))?);
+        rest = &rest[amp + semi + 1..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+struct Reader<'a> {
+    rest: &'a str,
+    /// How many more elements may nest.
+    depth: usize,
+}
+
+impl Reader<'_> {
+    fn skip_past(&mut self, end: &str) -> Result<(), Error> {
+        let at = self
+            .rest
+            .find(end)
+            .ok_or_else(|| xml_error(format!("missing {end:?}")))?;
+        self.rest = &self.rest[at + end.len()..];
+        Ok(())
+    }
+
+    /// The nodes up to the closing tag of `parent`, or the end of input.
+    fn nodes(&mut self, parent: Option<&str>) -> Result<Vec<Node>, Error> {
+        let mut nodes = vec![];
+        loop {
+            if self.rest.is_empty() {
+                return match parent {
+                    Some(name) => Err(xml_error(format!("unclosed element <{name}>"))),
+                    None => Ok(nodes),
+                };
+            }
+            if let Some(rest) = self.rest.strip_prefix("<!--") {
+                self.rest = rest;
+                self.skip_past("-->")?;
+            } else if let Some(rest) = self.rest.strip_prefix("<![CDATA[") {
+                let end = rest
+                    .find("]]>")
+                    .ok_or_else(|| xml_error("unterminated CDATA section".to_string()))?;
+                nodes.push(Node::Text(rest[..end].to_string()));
+                self.rest = &rest[end + 3..];
+            } else if let Some(rest) = self.rest.strip_prefix("<?") {
+                self.rest = rest;
+                self.skip_past("?>")?;
+            } else if let Some(rest) = self.rest.strip_prefix("</") {
+                let end = rest
+                    .find('>')
+                    .ok_or_else(|| xml_error("unterminated closing tag".to_string()))?;
+                let name = local(rest[..end].trim());
+                self.rest = &rest[end + 1..];
+                return match parent {
+                    Some(parent) if parent == name => Ok(nodes),
+                    _ => Err(xml_error(format!("unexpected closing tag </{name}>"))),
+                };
+            } else if let Some(rest) = self.rest.strip_prefix('<') {
+                self.rest = rest;
+                nodes.push(self.element()?);
+            } else {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                nodes.push(Node::Text(unescape(&self.rest[..end])?));
+                self.rest = &self.rest[end..];
+            }
+        }
+    }
+
+    /// An element, after its `<`.
+    fn element(&mut self) -> Result<Node, Error> {
+        let end = self
+            .rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>')
+            .unwrap_or(self.rest.len());
+        let name = local(&self.rest[..end]).to_string();
+        if name.is_empty() {
+            return Err(xml_error("missing element name".to_string()));
+        }
+        self.rest = &self.rest[end..];
+        let mut attributes = vec![];
+        loop {
+            self.rest = self.rest.trim_start();
+            if let Some(rest) = self.rest.strip_prefix("/>") {
+                self.rest = rest;
+                return Ok(Node::Element {
+                    name,
+                    attributes,
+                    children: vec![],
+                });
+            }
+            if let Some(rest) = self.rest.strip_prefix('>') {
+                self.rest = rest;
+                self.depth = self.depth.checked_sub(1).ok_or_else(too_deep)?;
+                let children = self.nodes(Some(&name))?;
+                self.depth += 1;
+                return Ok(Node::Element {
+                    name,
+                    attributes,
+                    children,
+                });
+            }
+            let malformed = || xml_error(format!("malformed attribute in <{name}>"));
+            let eq = self.rest.find('=').ok_or_else(malformed)?;
+            let key = self.rest[..eq].trim();
+            if key.is_empty() || key.contains(|c: char| c.is_whitespace() || c == '>') {
+                return Err(malformed());
+            }
+            let value = self.rest[eq + 1..].trim_start();
+            let quote = value
+                .chars()
+                .next()
+                .filter(|c| matches!(c, '"' | '\''))
+                .ok_or_else(malformed)?;
+            let close = value[1..].find(quote).ok_or_else(malformed)? + 1;
+            if key != "xmlns" && !key.starts_with("xmlns:") {
+                attributes.push((local(key).to_string(), unescape(&value[1..close])?));
+            }
+            self.rest = &value[close + 1..];
+        }
+    }
+}
+
+fn read(xml: &str) -> Result<Vec<Node>, Error> {
+    let depth = max_depth(xml)?;
+    Reader { rest: xml, depth }.nodes(None)
+}
+
+fn heading_level(name: &str) -> Option<u8> {
+    match name.as_bytes() {
+        [b'h', level @ b'1'..=b'6'] => Some(level - b'0'),
+        _ => None,
+    }
+}
+
+fn is_block(name: &str) -> bool {
+    matches!(
+        name,
+        "p" | "blockquote" | "pre" | "ul" | "ol" | "li" | "table" | "tr" | "th" | "td" | "hr"
+    ) || heading_level(name).is_some()
+}
+
+/// Merge adjacent text, drop redundant breaks and trim the whitespace XML
+/// indentation leaves around inline content.
+fn tidy(events: Vec<Event>) -> Vec<Event> {
+    let mut tidied: Vec<Event> = vec![];
+    for event in events {
+        match (tidied.last_mut(), event) {
+            (Some(Event::Text(last)), Event::Text(text)) => last.push_str(&text),
+            (Some(Event::SoftBreak), Event::SoftBreak) => {}
+            (None, Event::SoftBreak) => {}
+            (_, event) => tidied.push(event),
+        }
+    }
+    if tidied.last() == Some(&Event::SoftBreak) {
+        tidied.pop();
+    }
+    if let Some(Event::Text(first)) = tidied.first_mut() {
+        *first = first.trim_start().to_string();
+    }
+    if let Some(Event::Text(last)) = tidied.last_mut() {
+        *last = last.trim_end().to_string();
+    }
+    tidied.retain(|event| !matches!(event, Event::Text(text) if text.is_empty()));
+    tidied
+}
+
+fn text(text: &str, single_line: bool, events: &mut Vec<Event>) {
+    let pieces: Vec<&str> = text.split('\n').collect();
+    for (i, piece) in pieces.iter().enumerate() {
+        let mut piece = *piece;
+        if i > 0 {
+            events.push(match single_line {
+                true => Event::Text(" ".to_string()),
+                false => Event::SoftBreak,
+            });
+            piece = piece.trim_start();
+        }
+        if i + 1 < pieces.len() {
+            piece = piece.trim_end();
+        }
+        if !piece.is_empty() {
+            events.push(Event::Text(piece.to_string()));
+        }
+    }
+}
+
+fn wrapped(
+    tag: Tag,
+    children: &[Node],
+    single_line: bool,
+    events: &mut Vec<Event>,
+) -> Result<(), Error> {
+    events.push(Event::Start(tag.clone()));
+    for child in children {
+        inline(child, single_line, events)?;
+    }
+    events.push(Event::End(tag));
+    Ok(())
+}
+
+fn inline(node: &Node, single_line: bool, events: &mut Vec<Event>) -> Result<(), Error> {
+    let name = match node {
+        Node::Text(content) => {
+            text(content, single_line, events);
+            return Ok(());
+        }
+        Node::Element { name, .. } => name.as_str(),
+    };
+    let children = node.children();
+    let required = |key: &str| {
+        node.attribute(key)
+            .map(String::from)
+            .ok_or_else(|| xml_error(format!("<{name}> requires {key:?}")))
+    };
+    match name {
+        "em" | "i" => wrapped(Tag::Emphasis, children, single_line, events)?,
+        "strong" | "b" => wrapped(Tag::Strong, children, single_line, events)?,
+        "sub" => wrapped(Tag::Subscript, children, single_line, events)?,
+        "sup" => wrapped(Tag::Superscript, children, single_line, events)?,
+        "code" => events.push(Event::Code(node.text_content().replace('\n', " "))),
+        "q" => {
+            events.push(Event::Text("\"".to_string()));
+            for child in children {
+                inline(child, single_line, events)?;
+            }
+            events.push(Event::Text("\"".to_string()));
+        }
+        "a" => {
+            let tag = Tag::Link {
+                dest: required("href")?,
+                title: node.attribute("title").map(String::from),
+            };
+            wrapped(tag, children, single_line, events)?;
+        }
+        "img" => {
+            let tag = Tag::Image {
+                src: required("src")?,
+                title: node.attribute("title").map(String::from),
+            };
+            events.push(Event::Start(tag.clone()));
+            events.push(Event::Text(node.attribute("alt").unwrap_or("").to_string()));
+            events.push(Event::End(tag));
+        }
+        "insert" => events.push(Event::Insert {
+            kind: required("type")?,
+            id_ref: required("id-ref")?,
+        }),
+        "br" if !single_line => events.push(Event::HardBreak),
+        name if is_block(name) || name == "br" => {
+            return Err(xml_error(format!("<{name}> is not allowed here")));
+        }
+        name => return Err(xml_error(format!("unsupported element <{name}>"))),
+    }
+    Ok(())
+}
+
+/// Inline content, wrapped in a paragraph unless `bare`.
+fn inline_run(run: &[&Node], bare: bool, events: &mut Vec<Event>) -> Result<(), Error> {
+    let mut content = vec![];
+    for node in run {
+        inline(node, false, &mut content)?;
+    }
+    let content = tidy(content);
+    if content.is_empty() {
+        return Ok(());
+    }
+    if !bare {
+        events.push(Event::Start(Tag::Paragraph));
+    }
+    events.extend(content);
+    if !bare {
+        events.push(Event::End(Tag::Paragraph));
+    }
+    Ok(())
+}
+
+/// Block content.  Inline content between blocks is wrapped in paragraphs
+/// unless `bare`, as in the items of tight lists.
+fn blocks(nodes: &[Node], bare: bool, events: &mut Vec<Event>) -> Result<(), Error> {
+    let mut run = vec![];
+    for node in nodes {
+        match node.name() {
+            Some(name) if is_block(name) => {
+                inline_run(&run, bare, events)?;
+                run.clear();
+                block(node, name, events)?;
+            }
+            _ => run.push(node),
+        }
+    }
+    inline_run(&run, bare, events)
+}
+
+/// The element children of `node`, which must all be named one of `names`.
+fn elements<'a>(node: &'a Node, names: &[&str]) -> Result<Vec<&'a Node>, Error> {
+    let mut elements = vec![];
+    for child in node.children().iter().filter(|child| !child.is_blank()) {
+        match child.name() {
+            Some(name) if names.contains(&name) => elements.push(child),
+            _ => {
+                let parent = node.name().unwrap_or_default();
+                return Err(xml_error(format!("unexpected content in <{parent}>")));
+            }
+        }
+    }
+    Ok(elements)
+}
+
+fn block(node: &Node, name: &str, events: &mut Vec<Event>) -> Result<(), Error> {
+    let children = node.children();
+    match name {
+        "p" => inline_run(&children.iter().collect::<Vec<_>>(), false, events)?,
+        "blockquote" => {
+            events.push(Event::Start(Tag::BlockQuote));
+            blocks(children, false, events)?;
+            events.push(Event::End(Tag::BlockQuote));
+        }
+        "pre" => {
+            let significant: Vec<&Node> = children.iter().filter(|c| !c.is_blank()).collect();
+            let info = match significant.as_slice() {
+                [code] if code.name() == Some("code") => code
+                    .attribute("class")
+                    .and_then(|class| class.strip_prefix("language-"))
+                    .map(String::from),
+                _ => None,
+            };
+            let mut code = node.text_content();
+            if !code.is_empty() && !code.ends_with('\n') {
+                code.push('\n');
+            }
+            let tag = Tag::CodeBlock(info);
+            events.push(Event::Start(tag.clone()));
+            if !code.is_empty() {
+                events.push(Event::Text(code));
+            }
+            events.push(Event::End(tag));
+        }
+        "ul" | "ol" => {
+            let start = match name {
+                "ol" => Some(match node.attribute("start") {
+                    Some(start) => start
+                        .trim()
+                        .parse()
+                        .map_err(|_| xml_error(format!("invalid list start {start:?}")))?,
+                    None => 1,
+                }),
+                _ => None,
+            };
+            let tag = Tag::List(start);
+            events.push(Event::Start(tag.clone()));
+            for item in elements(node, &["li"])? {
+                events.push(Event::Start(Tag::Item));
+                blocks(item.children(), true, events)?;
+                events.push(Event::End(Tag::Item));
+            }
+            events.push(Event::End(tag));
+        }
+        "table" => {
+            let mut rows = vec![];
+            for child in elements(node, &["tr", "thead", "tbody", "tfoot"])? {
+                match child.name() {
+                    Some("tr") => rows.push(child),
+                    _ => rows.extend(elements(child, &["tr"])?),
+                }
+            }
+            events.push(Event::Start(Tag::Table));
+            for (i, row) in rows.into_iter().enumerate() {
+                // Markdown tables always start with a header row
+                let tag = match i {
+                    0 => Tag::TableHead,
+                    _ => Tag::TableRow,
+                };
+                events.push(Event::Start(tag.clone()));
+                for cell in elements(row, &["th", "td"])? {
+                    let mut content = vec![];
+                    for child in cell.children() {
+                        inline(child, true, &mut content)?;
+                    }
+                    events.push(Event::Start(Tag::TableCell));
+                    events.extend(tidy(content));
+                    events.push(Event::End(Tag::TableCell));
+                }
+                events.push(Event::End(tag));
+            }
+            events.push(Event::End(Tag::Table));
+        }
+        "hr" => events.push(Event::Rule),
+        _ => match heading_level(name) {
+            Some(level) => {
+                let mut content = vec![];
+                for child in children {
+                    inline(child, true, &mut content)?;
+                }
+                events.push(Event::Start(Tag::Heading(level)));
+                events.extend(tidy(content));
+                events.push(Event::End(Tag::Heading(level)));
+            }
+            None => return Err(xml_error(format!("<{name}> is not allowed here"))),
+        },
+    }
+    Ok(())
+}
+
+/// Parse an OSCAL XML markup-line fragment, the inline content of e.g. a
+/// `<title>` element.
+pub fn parse_xml_line(xml: &str) -> Result<Vec<Event>, Error> {
+    let mut events = vec![];
+    for node in read(xml)? {
+        inline(&node, true, &mut events)?;
+    }
+    Ok(tidy(events))
+}
+
+/// Parse an OSCAL XML markup-multiline fragment, the block content of e.g. a
+/// `<description>` element.
+pub fn parse_xml_multiline(xml: &str) -> Result<Vec<Event>, Error> {
+    let mut events = vec![];
+    blocks(&read(xml)?, false, &mut events)?;
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markup::{parse_line, parse_multiline, to_html};
+
+    #[test]
+    fn test_round_trip() {
+        for markdown in [
+            "# Title\n\nSome *text* and **strong**\nwrapped.",
+            "- a\n- b\n  1. c\n  2. d",
+            "3. a\n\n4. b",
+            "> quoted {{ insert: param, ac-1_prm_1 }}",
+            "```rust\nfn x() {}\n```",
+            "a  \nb\n\n---\n\n[NIST](https://nist.gov \"home\") ![logo](logo.png)",
+            "| a | b |\n|---|---|\n| `1` | H~2~O |",
+        ] {
+            let events = parse_multiline(markdown).unwrap();
+            assert_eq!(parse_xml_multiline(&to_html(&events)).unwrap(), events);
+        }
+        let events = parse_line("x^2^ & <https://a.gov>").unwrap();
+        assert_eq!(parse_xml_line(&to_html(&events)).unwrap(), events);
+    }
+
+    #[test]
+    fn test_read() {
+        let xml = r#"
+            <!-- generated -->
+            <o:p xmlns:o="http://csrc.nist.gov/ns/oscal/1.0">
+                Fish &amp; <o:em>chips</o:em>&#x21;
+            </o:p>
+            <ul>
+                <li><p>loose</p></li>
+            </ul>
+            <pre><![CDATA[a < b]]></pre>"#;
+        assert_eq!(
+            to_html(&parse_xml_multiline(xml).unwrap()),
+            "<p>Fish &amp; <em>chips</em>!</p><ul><li><p>loose</p></li></ul>\
+             <pre><code>a &lt; b\n</code></pre>"
+        );
+        assert_eq!(
+            parse_xml_line("<b>bold</b>\n  text").unwrap(),
+            parse_line("**bold** text").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_rejected() {
+        assert!(parse_xml_line("<p>block</p>").is_err());
+        assert!(parse_xml_line("a<br/>b").is_err());
+        assert!(parse_xml_multiline("<div>x</div>").is_err());
+        assert!(parse_xml_multiline("<p>unclosed").is_err());
+        assert!(parse_xml_multiline("<p>x</em>").is_err());
+        assert!(parse_xml_multiline("<p>&nbsp;</p>").is_err());
+        assert!(parse_xml_multiline("<ul>text</ul>").is_err());
+        assert!(parse_xml_multiline("<insert type=\"param\"/>").is_err());
+    }
+}
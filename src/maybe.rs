@@ -0,0 +1,184 @@
+//! Values that may be a placeholder instead of real data.
+//!
+//! Spreadsheets and other loose sources write `N/A`, `TBD` or `Unknown` in
+//! fields that should hold a date or a number.  [Maybe] keeps that intent
+//! instead of failing validation or inventing a value.
+//!
+//! The accepted sentinels are configured with
+//! [crate::config::Config::placeholder_sentinels], and how placeholders are
+//! written back with [crate::config::Config::placeholder_output].
+//!
+//! ```
+//! use oscal_types::maybe::{Maybe, PlaceholderKind};
+//! use oscal_types::DateDatatype;
+//!
+//! let date: Maybe<DateDatatype> = serde_json::from_str(r#""TBD""#).unwrap();
+//! assert_eq!(date, Maybe::Placeholder(PlaceholderKind::ToBeDetermined));
+//!
+//! let date: Maybe<DateDatatype> = serde_json::from_str(r#""2024-02-10""#).unwrap();
+//! assert!(date.known().is_some());
+//! ```
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PlaceholderKind {
+    NotApplicable,
+    ToBeDetermined,
+    Unknown,
+    /// An empty string or `null`.
+    Empty,
+}
+
+impl PlaceholderKind {
+    /// The sentinel written for this placeholder.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PlaceholderKind::NotApplicable => "N/A",
+            PlaceholderKind::ToBeDetermined => "TBD",
+            PlaceholderKind::Unknown => "Unknown",
+            PlaceholderKind::Empty => "",
+        }
+    }
+}
+
+/// Sentinels accepted when [crate::config::Config::placeholder_sentinels]
+/// is not set.
+pub const DEFAULT_SENTINELS: &[(&str, PlaceholderKind)] = &[
+    ("N/A", PlaceholderKind::NotApplicable),
+    ("NA", PlaceholderKind::NotApplicable),
+    ("TBD", PlaceholderKind::ToBeDetermined),
+    ("Unknown", PlaceholderKind::Unknown),
+    ("", PlaceholderKind::Empty),
+];
+
+/// How placeholders are serialized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlaceholderOutput {
+    /// The sentinel string, e.g. `"N/A"`.
+    #[default]
+    Sentinel,
+    /// `null`, dropping the distinction between placeholder kinds.
+    Null,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Maybe<T> {
+    Known(T),
+    Placeholder(PlaceholderKind),
+}
+
+impl<T> Maybe<T> {
+    pub fn known(&self) -> Option<&T> {
+        match self {
+            Maybe::Known(value) => Some(value),
+            Maybe::Placeholder(_) => None,
+        }
+    }
+
+    pub fn is_placeholder(&self) -> bool {
+        matches!(self, Maybe::Placeholder(_))
+    }
+
+    pub fn into_option(self) -> Option<T> {
+        match self {
+            Maybe::Known(value) => Some(value),
+            Maybe::Placeholder(_) => None,
+        }
+    }
+}
+
+impl<T> From<T> for Maybe<T> {
+    fn from(value: T) -> Self {
+        Maybe::Known(value)
+    }
+}
+
+/// The placeholder `value` stands for, if it is one of the configured
+/// sentinels.  Matching ignores case and surrounding whitespace.
+pub fn placeholder(value: &str) -> Option<PlaceholderKind> {
+    let value = value.trim();
+    let matches = |sentinel: &str| sentinel.eq_ignore_ascii_case(value);
+    match config::get().placeholder_sentinels {
+        Some(sentinels) => sentinels
+            .iter()
+            .find(|(sentinel, _)| matches(sentinel))
+            .map(|(_, kind)| *kind),
+        None => DEFAULT_SENTINELS
+            .iter()
+            .find(|(sentinel, _)| matches(sentinel))
+            .map(|(_, kind)| *kind),
+    }
+}
+
+impl<T: Serialize> Serialize for Maybe<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Maybe::Known(value) => value.serialize(serializer),
+            Maybe::Placeholder(kind) => match config::get().placeholder_output {
+                PlaceholderOutput::Sentinel => serializer.serialize_str(kind.as_str()),
+                PlaceholderOutput::Null => serializer.serialize_none(),
+            },
+        }
+    }
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for Maybe<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let kind = match &value {
+            Value::Null => Some(PlaceholderKind::Empty),
+            Value::String(s) => placeholder(s),
+            _ => None,
+        };
+        match kind {
+            Some(kind) => Ok(Maybe::Placeholder(kind)),
+            // Deserialize from a borrowed value so `try_from = "&str"`
+            // datatypes work
+            None => T::deserialize(&value)
+                .map(Maybe::Known)
+                .map_err(serde::de::Error::custom),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NonNegativeIntegerDatatype, UUIDDatatype};
+
+    #[test]
+    fn test_default_sentinels() {
+        let n: Maybe<NonNegativeIntegerDatatype> = serde_json::from_str(r#"" n/a ""#).unwrap();
+        assert_eq!(n, Maybe::Placeholder(PlaceholderKind::NotApplicable));
+        let n: Maybe<NonNegativeIntegerDatatype> = serde_json::from_str("null").unwrap();
+        assert_eq!(n, Maybe::Placeholder(PlaceholderKind::Empty));
+        let n: Maybe<NonNegativeIntegerDatatype> = serde_json::from_str("3").unwrap();
+        assert_eq!(*n.into_option().unwrap(), 3);
+        assert!(serde_json::from_str::<Maybe<UUIDDatatype>>(r#""soon""#).is_err());
+    }
+
+    #[test]
+    fn test_configured_sentinels_and_output() {
+        let policy = config::Config {
+            placeholder_sentinels: Some(vec![(
+                "pending".to_string(),
+                PlaceholderKind::ToBeDetermined,
+            )]),
+            placeholder_output: PlaceholderOutput::Null,
+            ..config::Config::new()
+        };
+        config::scoped(policy, || {
+            let uuid: Maybe<UUIDDatatype> = serde_json::from_str(r#""Pending""#).unwrap();
+            assert_eq!(uuid, Maybe::Placeholder(PlaceholderKind::ToBeDetermined));
+            assert!(serde_json::from_str::<Maybe<UUIDDatatype>>(r#""TBD""#).is_err());
+            assert_eq!(serde_json::to_string(&uuid).unwrap(), "null");
+        });
+
+        let uuid: Maybe<UUIDDatatype> = Maybe::Placeholder(PlaceholderKind::ToBeDetermined);
+        assert_eq!(serde_json::to_string(&uuid).unwrap(), r#""TBD""#);
+    }
+}
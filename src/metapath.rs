@@ -0,0 +1,279 @@
+//! Primitive views of datatype values for Metapath evaluators.
+//!
+//! Metapath constraint expressions compare atomic values.  Every datatype
+//! reduces to one of the primitive types here: booleans and numbers keep
+//! their value, and all other datatypes become their lexical string.
+//! [MetapathValue::cast] applies the Metaschema casting rules, which follow
+//! XPath 3.1.
+//!
+//! ```
+//! use oscal_types::metapath::{MetapathType, MetapathValue, ToMetapathValue};
+//! use oscal_types::{NonNegativeIntegerDatatype, StringDatatype};
+//!
+//! let count = NonNegativeIntegerDatatype::try_from(3u64).unwrap();
+//! assert_eq!(count.to_metapath_value(), MetapathValue::Integer(3));
+//!
+//! let text = StringDatatype::try_from("2.5").unwrap().to_metapath_value();
+//! assert_eq!(text.cast(MetapathType::Decimal).unwrap(), MetapathValue::Decimal(2.5));
+//! assert_eq!(text.cast(MetapathType::Integer).unwrap_err().to_string(),
+//!     "Metapath cast error: \"2.5\" is not a valid integer");
+//! ```
+use std::fmt;
+
+use crate::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MetapathType {
+    String,
+    Integer,
+    Decimal,
+    Boolean,
+}
+
+impl fmt::Display for MetapathType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MetapathType::String => "string",
+            MetapathType::Integer => "integer",
+            MetapathType::Decimal => "decimal",
+            MetapathType::Boolean => "boolean",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetapathValue {
+    String(String),
+    /// Wide enough for both [IntegerDatatype] and
+    /// [NonNegativeIntegerDatatype].
+    Integer(i128),
+    Decimal(f64),
+    Boolean(bool),
+}
+
+fn cast_error(value: &MetapathValue, to: MetapathType) -> Error {
+    Error::MetapathCast(format!("{:?} is not a valid {to}", value.to_string()))
+}
+
+/// The `xs:decimal` lexical space: no exponent, no special values.
+fn is_decimal_lexical(value: &str) -> bool {
+    let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+    let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+    (!whole.is_empty() || !fraction.is_empty())
+        && whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+}
+
+impl MetapathValue {
+    pub fn metapath_type(&self) -> MetapathType {
+        match self {
+            MetapathValue::String(_) => MetapathType::String,
+            MetapathValue::Integer(_) => MetapathType::Integer,
+            MetapathValue::Decimal(_) => MetapathType::Decimal,
+            MetapathValue::Boolean(_) => MetapathType::Boolean,
+        }
+    }
+
+    /// Cast to another primitive type.
+    ///
+    /// - Anything casts to a string, as its canonical lexical form.
+    /// - Numbers cast to booleans by comparing with zero.
+    /// - Booleans cast to numbers as 1 and 0.
+    /// - Decimals cast to integers by truncating towards zero.
+    /// - Strings are trimmed and must be in the lexical space of the target
+    ///   type: `true`, `false`, `1` or `0` for booleans, and no exponents
+    ///   for decimals.
+    pub fn cast(&self, to: MetapathType) -> Result<MetapathValue, Error> {
+        let error = || cast_error(self, to);
+        let value = match (self, to) {
+            (_, MetapathType::String) => MetapathValue::String(self.to_string()),
+            (value, to) if value.metapath_type() == to => value.clone(),
+            (MetapathValue::Integer(n), MetapathType::Decimal) => MetapathValue::Decimal(*n as f64),
+            (MetapathValue::Integer(n), MetapathType::Boolean) => MetapathValue::Boolean(*n != 0),
+            (MetapathValue::Decimal(n), MetapathType::Integer) if n.is_finite() => {
+                MetapathValue::Integer(n.trunc() as i128)
+            }
+            (MetapathValue::Decimal(_), MetapathType::Integer) => return Err(error()),
+            (MetapathValue::Decimal(n), MetapathType::Boolean) => {
+                MetapathValue::Boolean(*n != 0.0 && !n.is_nan())
+            }
+            (MetapathValue::Boolean(b), MetapathType::Integer) => {
+                MetapathValue::Integer(*b as i128)
+            }
+            (MetapathValue::Boolean(b), MetapathType::Decimal) => {
+                MetapathValue::Decimal(*b as u8 as f64)
+            }
+            (MetapathValue::String(s), to) => {
+                let s = s.trim();
+                match to {
+                    MetapathType::Integer => {
+                        MetapathValue::Integer(s.parse().map_err(|_| error())?)
+                    }
+                    MetapathType::Decimal if is_decimal_lexical(s) => {
+                        MetapathValue::Decimal(s.parse().map_err(|_| error())?)
+                    }
+                    MetapathType::Boolean => match s {
+                        "true" | "1" => MetapathValue::Boolean(true),
+                        "false" | "0" => MetapathValue::Boolean(false),
+                        _ => return Err(error()),
+                    },
+                    _ => return Err(error()),
+                }
+            }
+            _ => return Err(error()),
+        };
+        Ok(value)
+    }
+}
+
+/// The canonical lexical form.
+impl fmt::Display for MetapathValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MetapathValue::String(s) => f.write_str(s),
+            MetapathValue::Integer(n) => write!(f, "{n}"),
+            MetapathValue::Decimal(n) => write!(f, "{n}"),
+            MetapathValue::Boolean(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+pub trait ToMetapathValue {
+    /// The primitive type values of this datatype reduce to.
+    const METAPATH_TYPE: MetapathType;
+
+    fn to_metapath_value(&self) -> MetapathValue;
+}
+
+impl ToMetapathValue for BooleanDatatype {
+    const METAPATH_TYPE: MetapathType = MetapathType::Boolean;
+    fn to_metapath_value(&self) -> MetapathValue {
+        MetapathValue::Boolean(**self)
+    }
+}
+
+impl ToMetapathValue for DecimalDatatype {
+    const METAPATH_TYPE: MetapathType = MetapathType::Decimal;
+    fn to_metapath_value(&self) -> MetapathValue {
+        MetapathValue::Decimal(**self)
+    }
+}
+
+macro_rules! integer_metapath_value {
+    ($($t:ident),* $(,)?) => {
+        $(
+            impl ToMetapathValue for $t {
+                const METAPATH_TYPE: MetapathType = MetapathType::Integer;
+                fn to_metapath_value(&self) -> MetapathValue {
+                    MetapathValue::Integer(**self as i128)
+                }
+            }
+        )*
+    };
+}
+
+integer_metapath_value!(
+    IntegerDatatype,
+    NonNegativeIntegerDatatype,
+    PositiveIntegerDatatype
+);
+
+macro_rules! string_metapath_value {
+    ($($t:ident),* $(,)?) => {
+        $(
+            impl ToMetapathValue for $t {
+                const METAPATH_TYPE: MetapathType = MetapathType::String;
+                fn to_metapath_value(&self) -> MetapathValue {
+                    MetapathValue::String((**self).to_string())
+                }
+            }
+        )*
+    };
+}
+
+string_metapath_value!(
+    DateDatatype,
+    DateTimeDatatype,
+    DateTimeWithTimezoneDatatype,
+    DayTimeDurationDatatype,
+    YearMonthDurationDatatype,
+    TimezoneOffsetDatatype,
+    StringDatatype,
+    Base64Datatype,
+    EmailAddressDatatype,
+    HostnameDatatype,
+    IPV4AddressDatatype,
+    IPV6AddressDatatype,
+    IPV4CidrDatatype,
+    IPV6CidrDatatype,
+    MarkupLineDatatype,
+    MarkupMultilineDatatype,
+    TokenDatatype,
+    URIDatatype,
+    URIReferenceDatatype,
+    UUIDDatatype,
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_datatype_values() {
+        let flag = BooleanDatatype::from(true);
+        assert_eq!(flag.to_metapath_value(), MetapathValue::Boolean(true));
+        let n = IntegerDatatype::from(-4i64);
+        assert_eq!(n.to_metapath_value(), MetapathValue::Integer(-4));
+        let date = DateDatatype::try_from("2024-02-10").unwrap();
+        assert_eq!(
+            date.to_metapath_value(),
+            MetapathValue::String("2024-02-10".to_string())
+        );
+        assert_eq!(DateDatatype::METAPATH_TYPE, MetapathType::String);
+    }
+
+    #[test]
+    fn test_casts() {
+        let cast = |value: MetapathValue, to| value.cast(to).unwrap();
+        assert_eq!(
+            cast(MetapathValue::Decimal(2.0), MetapathType::String),
+            MetapathValue::String("2".to_string())
+        );
+        assert_eq!(
+            cast(MetapathValue::Decimal(-2.7), MetapathType::Integer),
+            MetapathValue::Integer(-2)
+        );
+        assert_eq!(
+            cast(MetapathValue::Integer(0), MetapathType::Boolean),
+            MetapathValue::Boolean(false)
+        );
+        assert_eq!(
+            cast(MetapathValue::Boolean(true), MetapathType::Decimal),
+            MetapathValue::Decimal(1.0)
+        );
+        assert_eq!(
+            cast(
+                MetapathValue::String(" 1 ".to_string()),
+                MetapathType::Boolean
+            ),
+            MetapathValue::Boolean(true)
+        );
+        assert_eq!(
+            cast(
+                MetapathValue::String(".5".to_string()),
+                MetapathType::Decimal
+            ),
+            MetapathValue::Decimal(0.5)
+        );
+
+        let string = |s: &str| MetapathValue::String(s.to_string());
+        assert!(string("1e3").cast(MetapathType::Decimal).is_err());
+        assert!(string("NaN").cast(MetapathType::Decimal).is_err());
+        assert!(string("yes").cast(MetapathType::Boolean).is_err());
+        assert!(MetapathValue::Decimal(f64::INFINITY)
+            .cast(MetapathType::Integer)
+            .is_err());
+    }
+}
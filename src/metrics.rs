@@ -0,0 +1,119 @@
+//! Validation telemetry.
+//!
+//! Every validation performed through [DatatypeKind::validate], or through
+//! a constructor of a string based datatype (`TryFrom<&str>`, `FromStr`,
+//! deserialization), is reported to the installed [MetricsSink].  Booleans
+//! and numbers are only reported through [DatatypeKind::validate].
+//! The default sink discards everything; [CountingSink] keeps in-process
+//! totals that can be exported to any metrics system.
+//!
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::DatatypeKind;
+
+pub trait MetricsSink: Send + Sync {
+    /// Called once per validated value.
+    fn record_validation(&self, kind: DatatypeKind, valid: bool);
+}
+
+/// Discards all measurements.
+#[derive(Debug, Default)]
+pub struct NoopSink;
+
+impl MetricsSink for NoopSink {
+    fn record_validation(&self, _kind: DatatypeKind, _valid: bool) {}
+}
+
+/// Counts validations and failures per datatype.
+#[derive(Debug)]
+pub struct CountingSink {
+    counters: HashMap<DatatypeKind, (AtomicU64, AtomicU64)>,
+}
+
+impl CountingSink {
+    pub fn new() -> Self {
+        Self {
+            counters: DatatypeKind::ALL
+                .iter()
+                .map(|kind| (*kind, (AtomicU64::new(0), AtomicU64::new(0))))
+                .collect(),
+        }
+    }
+
+    /// Number of values of `kind` validated.
+    pub fn validated(&self, kind: DatatypeKind) -> u64 {
+        self.counters[&kind].0.load(Ordering::Relaxed)
+    }
+
+    /// Number of values of `kind` that failed validation.
+    pub fn failed(&self, kind: DatatypeKind) -> u64 {
+        self.counters[&kind].1.load(Ordering::Relaxed)
+    }
+}
+
+impl Default for CountingSink {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsSink for CountingSink {
+    fn record_validation(&self, kind: DatatypeKind, valid: bool) {
+        let (validated, failed) = &self.counters[&kind];
+        validated.fetch_add(1, Ordering::Relaxed);
+        if !valid {
+            failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+static SINK: LazyLock<RwLock<Arc<dyn MetricsSink>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(NoopSink)));
+
+/// Install `sink` as the process wide metrics sink.
+pub fn set_sink(sink: Arc<dyn MetricsSink>) {
+    match SINK.write() {
+        Ok(mut current) => *current = sink,
+        Err(poisoned) => *poisoned.into_inner() = sink,
+    }
+}
+
+pub(crate) fn record(kind: DatatypeKind, valid: bool) {
+    let sink = match SINK.read() {
+        Ok(sink) => sink.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    sink.record_validation(kind, valid);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StringDatatype, URIDatatype, UUIDDatatype};
+
+    #[test]
+    fn test_counting_sink() {
+        let sink = Arc::new(CountingSink::new());
+        set_sink(sink.clone());
+
+        // Without date_validation any string is a date, so use UUIDs
+        assert!(UUIDDatatype::try_from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").is_ok());
+        assert!(UUIDDatatype::try_from("not-a-uuid").is_err());
+        assert!(URIDatatype::try_from("relative/path").is_err());
+        assert!(DatatypeKind::Uri.validate("https://a.gov").is_ok());
+        assert!(serde_json::from_str::<StringDatatype>(r#""ok""#).is_ok());
+        assert!(serde_json::from_str::<StringDatatype>(r#"" padded ""#).is_err());
+
+        set_sink(Arc::new(NoopSink));
+
+        // Other tests may validate concurrently, so only lower bounds hold
+        assert!(sink.validated(DatatypeKind::Uuid) >= 2);
+        assert!(sink.failed(DatatypeKind::Uuid) >= 1);
+        assert!(sink.validated(DatatypeKind::Uri) >= 2);
+        assert!(sink.failed(DatatypeKind::Uri) >= 1);
+        assert!(sink.validated(DatatypeKind::String) >= 2);
+        assert!(sink.failed(DatatypeKind::String) >= 1);
+    }
+}
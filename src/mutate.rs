@@ -0,0 +1,254 @@
+//! Corrupted values for negative testing.
+//!
+//! [mutate] applies datatype specific corruptions to a valid lexical value
+//! (flip a UUID digit, push a date out of range, drop a URI scheme, ...).
+//! Every returned [Mutation] has been checked against the datatype's
+//! validator, so each one is guaranteed to be rejected.  Datatypes whose
+//! validation is permissive may yield few or no mutations.
+//!
+//! ```
+//! use oscal_types::{mutate::mutate, DatatypeKind};
+//!
+//! for m in mutate(DatatypeKind::Date, "2024-02-10") {
+//!     assert!(DatatypeKind::Date.validate(&m.value).is_err(), "{}", m.description);
+//! }
+//! ```
+use crate::DatatypeKind;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mutation {
+    /// The corrupted value
+    pub value: String,
+    /// What was done to the original value
+    pub description: String,
+}
+
+/// All corruptions of `valid` that the `kind` validator rejects.
+pub fn mutate(kind: DatatypeKind, valid: &str) -> Vec<Mutation> {
+    let mut candidates = generic(valid);
+    candidates.extend(specific(kind, valid));
+    candidates
+        .into_iter()
+        .filter(|m| m.value != valid && kind.validate(&m.value).is_err())
+        .collect()
+}
+
+fn mutation(value: impl Into<String>, description: impl Into<String>) -> Mutation {
+    Mutation {
+        value: value.into(),
+        description: description.into(),
+    }
+}
+
+fn generic(valid: &str) -> Vec<Mutation> {
+    vec![
+        mutation("", "replaced with an empty string"),
+        mutation(format!(" {valid}"), "added a leading space"),
+        mutation(format!("{valid} "), "added a trailing space"),
+        mutation(format!("{valid}\u{0}"), "appended a NUL character"),
+        mutation(format!("{valid}\n{valid}"), "duplicated across two lines"),
+    ]
+}
+
+fn specific(kind: DatatypeKind, valid: &str) -> Vec<Mutation> {
+    use DatatypeKind::*;
+
+    match kind {
+        Boolean => vec![
+            mutation("True", "capitalized the literal"),
+            mutation("yes", "used a YAML 1.1 style boolean"),
+            mutation("1", "used a numeric boolean"),
+        ],
+        Uuid => {
+            let mut mutations = vec![
+                mutation(valid.replace('-', ""), "removed the hyphens"),
+                mutation(drop_last(valid), "dropped the last digit"),
+            ];
+            if let Some(i) = valid.find(|c: char| c.is_ascii_hexdigit()) {
+                mutations.push(mutation(
+                    replace_at(valid, i, 'g'),
+                    format!("replaced the digit at byte {i} with a non-hex character"),
+                ));
+            }
+            mutations
+        }
+        Date | DateTime | DateTimeWithTimezone => {
+            let mut mutations = vec![
+                mutation(
+                    valid.replacen('-', "/", 2),
+                    "used slashes as date separators",
+                ),
+                mutation(drop_last(valid), "dropped the last character"),
+            ];
+            if let (Some(year), Some(day)) = (valid.get(..5), valid.get(7..)) {
+                mutations.push(mutation(
+                    format!("{year}13{day}"),
+                    "pushed the month out of range",
+                ));
+            }
+            if let (Some(month), Some(rest)) = (valid.get(..8), valid.get(10..)) {
+                mutations.push(mutation(
+                    format!("{month}32{rest}"),
+                    "pushed the day out of range",
+                ));
+            }
+            if let Some(t) = valid.find('T') {
+                mutations.push(mutation(
+                    format!("{}T25{}", &valid[..t], valid.get(t + 3..).unwrap_or("")),
+                    "pushed the hour out of range",
+                ));
+            }
+            if kind == DateTimeWithTimezone {
+                mutations.push(mutation(strip_offset(valid), "removed the timezone offset"));
+            }
+            mutations
+        }
+        DayTimeDuration | YearMonthDuration => vec![
+            mutation(
+                valid.trim_start_matches(['-', 'P']),
+                "removed the P designator",
+            ),
+            mutation(format!("{valid}X"), "appended an unknown designator"),
+            mutation("P", "removed every component"),
+        ],
+        TimezoneOffset => vec![
+            mutation(valid.replacen(':', "", 1), "removed the colon"),
+            mutation(format!("{valid}:00"), "added seconds"),
+            mutation("UTC", "used a timezone name"),
+        ],
+        Decimal => vec![
+            mutation(format!("{valid}e3"), "added an exponent"),
+            mutation(format!("{valid}.."), "doubled the decimal point"),
+            mutation("NaN", "replaced with NaN"),
+        ],
+        Integer | NonNegativeInteger | PositiveInteger => vec![
+            mutation(format!("{valid}.5"), "added a fractional part"),
+            mutation("99999999999999999999", "exceeded 64 bits"),
+            mutation("-1", "used a negative number"),
+            mutation("0", "used zero"),
+        ],
+        Ipv4Address => vec![
+            mutation("256.0.0.1", "pushed an octet out of range"),
+            mutation(format!("{valid}.1"), "added a fifth octet"),
+        ],
+        Ipv6Address => vec![
+            mutation(format!("{valid}::1"), "used a second :: compression"),
+            mutation(valid.replacen(':', ":g", 1), "inserted a non-hex digit"),
+        ],
+        Ipv4Cidr | Ipv6Cidr => vec![
+            mutation(format!("{valid}0"), "pushed the prefix length out of range"),
+            mutation(
+                valid.replacen('/', "/0", 1),
+                "added a leading zero to the prefix length",
+            ),
+            mutation(
+                valid.split('/').next().unwrap_or(valid),
+                "removed the prefix length",
+            ),
+        ],
+        Token => vec![
+            mutation(format!("1{valid}"), "started with a digit"),
+            mutation(format!("ns:{valid}"), "added a namespace prefix"),
+            mutation(format!("{valid} x"), "inserted a space"),
+        ],
+        Uri => vec![
+            mutation(strip_scheme(valid), "dropped the scheme"),
+            mutation(valid.replacen(':', " :", 1), "inserted a space"),
+        ],
+        UriReference => vec![
+            mutation(format!("{valid} x"), "inserted a space"),
+            mutation(format!("{valid}%zz"), "added an invalid percent escape"),
+        ],
+        String | Base64 | EmailAddress | Hostname | MarkupLine | MarkupMultiline => vec![
+            mutation(format!("@{valid}@"), "wrapped in @ characters"),
+            mutation(format!("{valid}==="), "appended padding"),
+        ],
+    }
+}
+
+fn drop_last(value: &str) -> String {
+    let mut chars = value.chars();
+    chars.next_back();
+    chars.as_str().to_string()
+}
+
+fn replace_at(value: &str, index: usize, c: char) -> String {
+    let mut replaced = value.to_string();
+    let width = value[index..].chars().next().map_or(0, char::len_utf8);
+    replaced.replace_range(index..index + width, c.encode_utf8(&mut [0; 4]));
+    replaced
+}
+
+fn strip_offset(value: &str) -> String {
+    match value.rfind(['Z', '+', '-']) {
+        Some(i) if value.find('T').is_some_and(|t| i > t) => value[..i].to_string(),
+        _ => value.to_string(),
+    }
+}
+
+fn strip_scheme(value: &str) -> String {
+    match value.split_once(':') {
+        Some((_, rest)) => rest.trim_start_matches('/').to_string(),
+        None => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mutations_are_invalid() {
+        let mut samples = vec![
+            (DatatypeKind::Boolean, "true"),
+            (DatatypeKind::Uuid, "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"),
+            (DatatypeKind::DayTimeDuration, "P4DT23H10S"),
+            (DatatypeKind::TimezoneOffset, "+05:30"),
+            (DatatypeKind::PositiveInteger, "12"),
+            (DatatypeKind::Ipv4Address, "10.0.0.1"),
+            (DatatypeKind::Ipv4Cidr, "10.0.0.0/8"),
+            (DatatypeKind::Ipv6Cidr, "2001:db8::/32"),
+            (DatatypeKind::Token, "access-control"),
+            (DatatypeKind::Uri, "https://fedramp.gov/ns/oscal"),
+        ];
+        // Without date_validation dates are not parsed, so nothing is
+        // guaranteed to be rejected.
+        if cfg!(feature = "date_validation") {
+            samples.extend([
+                (DatatypeKind::Date, "2024-02-10"),
+                (DatatypeKind::DateTime, "2024-04-13T09:57:13"),
+                (
+                    DatatypeKind::DateTimeWithTimezone,
+                    "2024-04-13T09:57:13+05:00",
+                ),
+            ]);
+        }
+        for (kind, valid) in samples {
+            assert!(kind.validate(valid).is_ok());
+            let mutations = mutate(kind, valid);
+            assert!(!mutations.is_empty(), "no mutations for {kind}");
+            for m in mutations {
+                assert!(
+                    kind.validate(&m.value).is_err(),
+                    "{kind}: {}",
+                    m.description
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_specific_mutations() {
+        let descriptions: Vec<std::string::String> = mutate(DatatypeKind::Uri, "https://a.gov/x")
+            .into_iter()
+            .map(|m| m.description)
+            .collect();
+        assert!(descriptions.contains(&"dropped the scheme".to_string()));
+
+        #[cfg(feature = "date_validation")]
+        {
+            let day = mutate(DatatypeKind::Date, "2024-02-10");
+            assert!(day.iter().any(|m| m.value == "2024-02-32"));
+        }
+    }
+}
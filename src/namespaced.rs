@@ -0,0 +1,192 @@
+//! Validation of prop and annotation names against per-namespace
+//! allow-lists.
+//!
+//! Whether a name like `cloud-service-model` is valid depends on the
+//! namespace it is in: its `ns`, or the default namespace when `ns` is
+//! absent (see [crate::config::Config::namespace]).  A
+//! [NamespacedValidation] holds the allowed names of each registered
+//! namespace.  Names in namespaces without an allow-list are accepted, so
+//! only the extensions a tool knows about are checked.
+//!
+//! ```
+//! use oscal_types::namespaced::{NamespacedValidation, FEDRAMP_NAMESPACE};
+//! use oscal_types::TokenDatatype;
+//!
+//! let mut validation = NamespacedValidation::fedramp();
+//! validation.register("https://example.gov/ns/oscal", ["data-center"]).unwrap();
+//!
+//! let name = TokenDatatype::try_from("cloud-service-model").unwrap();
+//! assert!(validation.validate(Some(FEDRAMP_NAMESPACE), &name).is_ok());
+//! assert!(validation.validate(Some("https://example.gov/ns/oscal"), &name).is_err());
+//! // The OSCAL namespace has no allow-list
+//! assert!(validation.validate(None, &name).is_ok());
+//! ```
+use std::collections::{HashMap, HashSet};
+
+use crate::prop::QualifiedToken;
+use crate::qname::NamespaceContext;
+use crate::*;
+
+/// The namespace of FedRAMP extension names.
+pub const FEDRAMP_NAMESPACE: &str = "https://fedramp.gov/ns/oscal";
+
+/// FedRAMP prop names in [FEDRAMP_NAMESPACE], as a starting point.  Tools
+/// can [register](NamespacedValidation::register) more as the FedRAMP
+/// extensions grow.
+pub const FEDRAMP_NAMES: &[&str] = &[
+    "authorization-type",
+    "cloud-deployment-model",
+    "cloud-service-model",
+    "control-origination",
+    "fully-operational-date",
+    "interconnection-security",
+    "privilege-level",
+    "scan-type",
+    "security-eauth-level",
+    "sensitivity",
+    "vendor-name",
+];
+
+/// Allowed names by namespace.
+#[derive(Debug, Clone, Default)]
+pub struct NamespacedValidation {
+    allowed: HashMap<String, HashSet<TokenDatatype>>,
+}
+
+impl NamespacedValidation {
+    /// No allow-lists, so every name is accepted.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The [FEDRAMP_NAMES] registered in [FEDRAMP_NAMESPACE].
+    pub fn fedramp() -> Self {
+        let mut validation = Self::new();
+        validation
+            .register(FEDRAMP_NAMESPACE, FEDRAMP_NAMES.iter().copied())
+            .expect("FedRAMP names are tokens");
+        validation
+    }
+
+    /// Allow `names` in `namespace`, in addition to any names registered
+    /// before.  Fails, registering nothing, if the namespace is not a URI
+    /// or a name is not a token.
+    pub fn register<'a>(
+        &mut self,
+        namespace: &str,
+        names: impl IntoIterator<Item = &'a str>,
+    ) -> Result<(), Error> {
+        let namespace = URIDatatype::try_from(namespace)?;
+        let names = names
+            .into_iter()
+            .map(TokenDatatype::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.allowed
+            .entry(namespace.to_string())
+            .or_default()
+            .extend(names);
+        Ok(())
+    }
+
+    /// Whether `namespace` has an allow-list.
+    pub fn is_registered(&self, namespace: &str) -> bool {
+        self.allowed.contains_key(namespace)
+    }
+
+    /// Check `name` in `namespace`, or in the configured default namespace
+    /// when `namespace` is `None`, as for a prop without an `ns`.
+    pub fn validate(&self, namespace: Option<&str>, name: &TokenDatatype) -> Result<(), Error> {
+        let config = config::get();
+        let namespace = namespace.unwrap_or_else(|| config.namespace());
+        match self.allowed.get(namespace) {
+            Some(names) if !names.contains(name) => Err(Error::UnregisteredName(format!(
+                "{} in {namespace}",
+                &**name
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Check a prop or annotation name, as [NamespacedValidation::validate].
+    pub fn validate_qualified(&self, name: &QualifiedToken) -> Result<(), Error> {
+        self.validate(name.ns().map(|ns| &**ns), name.name())
+    }
+
+    /// Resolve `prefix:local` or `local` in `context` and check the local
+    /// name in the namespace it resolves to.
+    pub fn validate_qname(&self, context: &NamespaceContext, qname: &str) -> Result<(), Error> {
+        let (namespace, local) = context.resolve(qname)?;
+        self.validate(Some(&namespace), &TokenDatatype::try_from(&*local)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(value: &str) -> TokenDatatype {
+        TokenDatatype::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut validation = NamespacedValidation::new();
+        assert!(validation.validate(None, &token("anything")).is_ok());
+
+        validation
+            .register(config::OSCAL_NAMESPACE, ["marking", "label"])
+            .unwrap();
+        assert!(validation.validate(None, &token("marking")).is_ok());
+        let error = validation.validate(None, &token("colour")).unwrap_err();
+        assert!(matches!(error, Error::UnregisteredName(ref name)
+            if name == "colour in http://csrc.nist.gov/ns/oscal"));
+        assert_eq!(error.code(), "unregistered-name");
+
+        // Nothing is registered if any name is invalid
+        assert!(validation
+            .register("https://example.gov/ns", ["ok", "not ok"])
+            .is_err());
+        assert!(!validation.is_registered("https://example.gov/ns"));
+        assert!(validation.register("not a uri", ["ok"]).is_err());
+    }
+
+    #[test]
+    fn test_default_namespace() {
+        let validation = NamespacedValidation::fedramp();
+        let name = token("cloud-service-model");
+        assert!(validation.validate(None, &token("not-fedramp")).is_ok());
+
+        let config = config::Config {
+            default_namespace: Some(FEDRAMP_NAMESPACE.to_string()),
+            ..config::Config::new()
+        };
+        let bare = QualifiedToken::new(token("not-fedramp"));
+        assert!(validation.validate_qualified(&bare).is_ok());
+        config::scoped(config, || {
+            assert!(validation.validate_qualified(&bare).is_err());
+            assert!(validation.validate(None, &name).is_ok());
+            assert!(validation.validate(None, &token("not-fedramp")).is_err());
+        });
+    }
+
+    #[test]
+    fn test_validate_qname() {
+        let validation = NamespacedValidation::fedramp();
+        let mut context = NamespaceContext::oscal();
+        context
+            .bind("fedramp", FEDRAMP_NAMESPACE.try_into().unwrap())
+            .unwrap();
+
+        assert!(validation
+            .validate_qname(&context, "fedramp:cloud-service-model")
+            .is_ok());
+        assert!(validation
+            .validate_qname(&context, "fedramp:marking")
+            .is_err());
+        assert!(validation.validate_qname(&context, "marking").is_ok());
+        assert!(matches!(
+            validation.validate_qname(&context, "other:marking"),
+            Err(Error::UnboundPrefix(_))
+        ));
+    }
+}
@@ -6,7 +6,7 @@ use crate::{Base, Error};
 
 #[derive(Debug, Clone, PartialEq, Serialize)]
 #[serde(transparent)]
-pub struct NCName(String);
+pub struct NCName(pub(crate) String);
 
 impl Base for NCName {
     fn base_type() -> String {
@@ -21,18 +21,24 @@ impl NCName {
     /// Returns `true` if `c` is a legal `NCNameStartChar` as defined
     /// by the ["Namespaces in XML 1.1"
     /// specification](https://www.w3.org/TR/xml-names11/#NT-NameStartChar).
-    pub fn is_valid_start_char(c: char) -> bool {
+    pub const fn is_valid_start_char(c: char) -> bool {
         matches!(c, 'a'..='z' | 'A'..='Z' | '_' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}' | '\u{370}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
     }
 
     /// Returns `true` if `c` is a legal `NCNameChar` as defined by
     /// the ["Namespaces in XML 1.1"
     /// specification](https://www.w3.org/TR/xml11/#NT-NameChar).
-    pub fn is_valid_char(c: char) -> bool {
+    pub const fn is_valid_char(c: char) -> bool {
         matches!(c, 'a'..='z' | 'A'..='Z' | '_' | '-' | '.' | '\u{B7}' | '0'..='9' | '\u{C0}'..='\u{D6}' | '\u{D8}'..='\u{F6}' | '\u{F8}'..='\u{2FF}' | '\u{300}'..='\u{37D}' | '\u{37F}'..='\u{1FFF}' | '\u{200C}'..='\u{200D}' | '\u{203F}'..='\u{2040}' | '\u{2070}'..='\u{218F}' | '\u{2C00}'..='\u{2FEF}' | '\u{3001}'..='\u{D7FF}' | '\u{F900}'..='\u{FDCF}' | '\u{FDF0}'..='\u{FFFD}' | '\u{10000}'..='\u{EFFFF}')
     }
 
     pub fn new_if_valid(value: &str) -> Result<Self, Error> {
+        Self::validate(value)?;
+        Ok(Self(value.to_string()))
+    }
+
+    /// Check that `value` is an NCName without copying it.
+    pub fn validate(value: &str) -> Result<(), Error> {
         let mut first = true;
 
         for c in value.chars() {
@@ -45,7 +51,22 @@ impl NCName {
                 return Err(Error::NCNameIllegalChar);
             }
         }
-        Ok(Self(value.to_string()))
+        Ok(())
+    }
+
+    /// The snake case Rust identifier for this name.  See [crate::ident].
+    pub fn to_rust_ident(&self) -> String {
+        crate::ident::to_rust_ident(self)
+    }
+
+    /// The upper camel case Rust identifier for this name.
+    pub fn to_pascal_case(&self) -> String {
+        crate::ident::to_pascal_case(self)
+    }
+
+    /// The name for a snake or upper camel case Rust identifier.
+    pub fn from_rust_ident(ident: &str) -> Result<Self, Error> {
+        crate::ident::from_rust_ident(ident).and_then(|token| Self::new_if_valid(&token))
     }
 }
 
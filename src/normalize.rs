@@ -0,0 +1,317 @@
+//! Canonical lexical forms, for comparing documents.
+//!
+//! Many datatypes have several spellings of the same value.  [canonical]
+//! picks one, and [normalize_document] rewrites every bound value in a JSON
+//! document to it, so a textual diff of two normalized documents shows only
+//! real changes.  Typed values compare the same way with
+//! [Canonicalize::equivalent].
+//!
+//! ```
+//! use oscal_types::{bindings::FieldBindings, normalize::normalize_document, DatatypeKind};
+//! use serde_json::json;
+//!
+//! let bindings = FieldBindings::new()
+//!     .bind("/**/uuid", DatatypeKind::Uuid)
+//!     .bind("/**/last-modified", DatatypeKind::DateTimeWithTimezone);
+//! let mut doc = json!({"catalog": {
+//!     "uuid": "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301",
+//!     "metadata": {"last-modified": "2024-02-10T23:30:00-05:00"}
+//! }});
+//! normalize_document(&mut doc, &bindings);
+//! assert_eq!(doc, json!({"catalog": {
+//!     "uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+//!     "metadata": {"last-modified": "2024-02-11T04:30:00Z"}
+//! }}));
+//! ```
+use chrono::{DateTime, Timelike, Utc};
+use serde_json::Value;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::bindings::FieldBindings;
+use crate::dates::{duration_parts, number, DurationParts};
+use crate::registry::from_lexical;
+use crate::uris::UriComparison;
+use crate::walk::walk_mut;
+use crate::*;
+
+/// The canonical lexical form of `value` as the datatype `kind`:
+///
+/// - UUIDs in lowercase.
+/// - Dates with a `+00:00` offset written with `Z`.
+/// - Date-times with an offset in UTC, written with `Z`, and without
+///   trailing zeros in fractional seconds.
+/// - IPv6 addresses compressed as in RFC 5952, and IPv4 addresses as
+///   written by [Ipv4Addr].
+/// - Durations with each component carried into the next larger one,
+///   e.g. `PT36H` as `P1DT12H` and `P14M` as `P1Y2M`.
+/// - Hostnames, and the domain of email addresses, in lowercase.
+/// - URIs normalized as by [UriComparison::Syntax].
+///
+/// Other values are already canonical and returned unchanged.  Invalid
+/// values are an error.
+pub fn canonical(kind: DatatypeKind, value: &str) -> Result<String, Error> {
+    kind.validate(value)?;
+    let canonical = match kind {
+        DatatypeKind::Uuid => value.to_ascii_lowercase(),
+        DatatypeKind::Date => match value.strip_suffix("+00:00") {
+            Some(date) => format!("{date}Z"),
+            None => value.to_string(),
+        },
+        DatatypeKind::DateTime | DatatypeKind::DateTimeWithTimezone => {
+            match DateTime::parse_from_rfc3339(value) {
+                Ok(date_time) => utc_date_time(date_time.with_timezone(&Utc)),
+                // A date-time without a timezone is kept as written
+                Err(_) => value.to_string(),
+            }
+        }
+        DatatypeKind::Ipv4Address => value.parse::<Ipv4Addr>()?.to_string(),
+        DatatypeKind::Ipv6Address => value.parse::<Ipv6Addr>()?.to_string(),
+        DatatypeKind::DayTimeDuration => day_time_duration(value)?,
+        DatatypeKind::YearMonthDuration => year_month_duration(value)?,
+        DatatypeKind::Hostname => value.to_lowercase(),
+        DatatypeKind::EmailAddress => match value.rsplit_once('@') {
+            Some((local, domain)) => format!("{local}@{}", domain.to_lowercase()),
+            None => value.to_string(),
+        },
+        DatatypeKind::Uri => URIDatatype::try_from(value)?.normalized(UriComparison::Syntax),
+        _ => value.to_string(),
+    };
+    Ok(canonical)
+}
+
+/// Datatypes whose values have more than one lexical form.
+pub trait Canonicalize: OscalDatatype {
+    /// The [canonical] lexical form of the value.  A value that was never
+    /// validated, and is invalid, is returned as written.
+    fn canonical_form(&self) -> String;
+
+    /// The value in its canonical lexical form.
+    fn canonicalize(&self) -> Self {
+        from_lexical(&self.canonical_form()).unwrap_or_else(|_| self.clone())
+    }
+
+    /// Whether `self` and `other` are the same value, however they are
+    /// written.  URIs are compared at [UriComparison::Syntax]; call
+    /// [URIDatatype::equivalent] directly to choose another level.
+    fn equivalent(&self, other: &Self) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+}
+
+impl Canonicalize for BooleanDatatype {
+    fn canonical_form(&self) -> String {
+        self.to_string()
+    }
+}
+
+macro_rules! canonicalize_impl {
+    ($($t:ident),* $(,)?) => {
+        $(
+            impl Canonicalize for $t {
+                fn canonical_form(&self) -> String {
+                    canonical(Self::KIND, self).unwrap_or_else(|_| self.to_string())
+                }
+            }
+        )*
+    };
+}
+
+canonicalize_impl!(
+    DateDatatype,
+    DateTimeDatatype,
+    DateTimeWithTimezoneDatatype,
+    DayTimeDurationDatatype,
+    YearMonthDurationDatatype,
+    EmailAddressDatatype,
+    HostnameDatatype,
+    IPV4AddressDatatype,
+    IPV6AddressDatatype,
+    URIDatatype,
+    URIReferenceDatatype,
+    UUIDDatatype,
+);
+
+fn utc_date_time(date_time: DateTime<Utc>) -> String {
+    let fraction = format!("{:09}", date_time.nanosecond());
+    let fraction = fraction.trim_end_matches('0');
+    let seconds = date_time.format("%Y-%m-%dT%H:%M:%S");
+    match fraction.is_empty() {
+        true => format!("{seconds}Z"),
+        false => format!("{seconds}.{fraction}Z"),
+    }
+}
+
+fn day_time_duration(value: &str) -> Result<String, Error> {
+    let DurationParts { negative, parts } = duration_parts(value)?;
+    let mut seconds = 0u128;
+    let mut fraction = "";
+    for (designator, time, digits) in parts {
+        let (whole, rest) = digits.split_once('.').unwrap_or((digits, ""));
+        let unit = match (designator, time) {
+            ('D', false) => 86_400,
+            ('H', true) => 3_600,
+            ('M', true) => 60,
+            ('S', true) => {
+                fraction = rest.trim_end_matches('0');
+                1
+            }
+            _ => return Err(Error::DurationParse),
+        };
+        seconds += number(whole)? * unit;
+    }
+    let (days, hours, minutes, seconds) = (
+        seconds / 86_400,
+        seconds % 86_400 / 3_600,
+        seconds % 3_600 / 60,
+        seconds % 60,
+    );
+    let mut out = String::from(if negative { "-P" } else { "P" });
+    if days > 0 {
+        out.push_str(&format!("{days}D"));
+    }
+    let mut time = String::new();
+    if hours > 0 {
+        time.push_str(&format!("{hours}H"));
+    }
+    if minutes > 0 {
+        time.push_str(&format!("{minutes}M"));
+    }
+    match (seconds, fraction.is_empty()) {
+        (_, false) => time.push_str(&format!("{seconds}.{fraction}S")),
+        (0, true) if days == 0 && time.is_empty() => time.push_str("0S"),
+        (0, true) => {}
+        _ => time.push_str(&format!("{seconds}S")),
+    }
+    if !time.is_empty() {
+        out.push('T');
+        out.push_str(&time);
+    }
+    if out == "-PT0S" {
+        out.remove(0);
+    }
+    Ok(out)
+}
+
+fn year_month_duration(value: &str) -> Result<String, Error> {
+    let DurationParts { negative, parts } = duration_parts(value)?;
+    let mut months = 0u128;
+    for (designator, time, digits) in parts {
+        months += match (designator, time) {
+            ('Y', false) => number(digits)? * 12,
+            ('M', false) => number(digits)?,
+            _ => return Err(Error::DurationParse),
+        };
+    }
+    let sign = if negative && months > 0 { "-" } else { "" };
+    Ok(match (months / 12, months % 12) {
+        (0, months) => format!("{sign}P{months}M"),
+        (years, 0) => format!("{sign}P{years}Y"),
+        (years, months) => format!("{sign}P{years}Y{months}M"),
+    })
+}
+
+/// Rewrite every string value in `value` whose path is bound to its
+/// [canonical] form.  Values that are invalid, or not strings, are left as
+/// they are; use [FieldBindings::validate] to find them.
+pub fn normalize_document(value: &mut Value, bindings: &FieldBindings) {
+    walk_mut(value, bindings, |_, kind, value| {
+        if let Value::String(text) = value {
+            if let Ok(canonical) = canonical(kind, text) {
+                *text = canonical;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_canonical() {
+        let canon = |kind, value| canonical(kind, value).expect(value);
+        assert_eq!(
+            canon(
+                DatatypeKind::DateTimeWithTimezone,
+                "2024-02-10T09:30:00.500+01:00"
+            ),
+            "2024-02-10T08:30:00.5Z"
+        );
+        assert_eq!(
+            canon(DatatypeKind::DateTime, "2024-02-10T09:30:00"),
+            "2024-02-10T09:30:00"
+        );
+        assert_eq!(
+            canon(DatatypeKind::Ipv6Address, "2001:DB8:0:0:0:0:0:1"),
+            "2001:db8::1"
+        );
+        assert_eq!(canon(DatatypeKind::DayTimeDuration, "PT36H"), "P1DT12H");
+        assert_eq!(
+            canon(DatatypeKind::DayTimeDuration, "PT90.50S"),
+            "PT1M30.5S"
+        );
+        assert_eq!(canon(DatatypeKind::DayTimeDuration, "P0D"), "PT0S");
+        assert_eq!(canon(DatatypeKind::YearMonthDuration, "P14M"), "P1Y2M");
+        assert_eq!(canon(DatatypeKind::YearMonthDuration, "P2Y0M"), "P2Y");
+        assert_eq!(
+            canon(DatatypeKind::EmailAddress, "Ops@Agency.GOV"),
+            "Ops@agency.gov"
+        );
+        assert_eq!(
+            canon(DatatypeKind::Uri, "HTTP://A.gov/a/../b"),
+            "http://a.gov/b"
+        );
+        if !cfg!(feature = "date_validation") {
+            assert_eq!(canon(DatatypeKind::Date, "2024-02-10+00:00"), "2024-02-10Z");
+            assert_eq!(
+                canon(DatatypeKind::Date, "2024-02-10-00:00"),
+                "2024-02-10-00:00"
+            );
+        }
+        assert_eq!(canon(DatatypeKind::String, "As Is"), "As Is");
+        assert!(canonical(DatatypeKind::Uuid, "not a uuid").is_err());
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let upper = UUIDDatatype::try_from("A78F7E4C-A27A-4B1E-901B-EBFECF2B0301").unwrap();
+        let lower = UUIDDatatype::try_from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").unwrap();
+        assert!(upper.equivalent(&lower));
+        assert_eq!(&*upper.canonicalize(), &*lower);
+
+        let utc = DateTimeWithTimezoneDatatype::try_from("2024-04-13T09:57:13Z").unwrap();
+        let offset = DateTimeWithTimezoneDatatype::try_from("2024-04-13T09:57:13+00:00").unwrap();
+        let later = DateTimeWithTimezoneDatatype::try_from("2024-04-13T09:57:14Z").unwrap();
+        assert!(utc.equivalent(&offset));
+        assert!(!utc.equivalent(&later));
+        assert_eq!(&*offset.canonicalize(), &*utc);
+
+        let uri = URIDatatype::try_from("HTTPS://Example.com/a/./b").unwrap();
+        let other = URIDatatype::try_from("https://example.com/a/b").unwrap();
+        assert!(Canonicalize::equivalent(&uri, &other));
+
+        let yes = BooleanDatatype::from(true);
+        assert!(yes.equivalent(&BooleanDatatype::from(true)));
+        assert!(!yes.equivalent(&BooleanDatatype::from(false)));
+    }
+
+    #[test]
+    fn test_normalize_document() {
+        let bindings = FieldBindings::new()
+            .bind("/**/address", DatatypeKind::Ipv6Address)
+            .bind("/**/count", DatatypeKind::PositiveInteger);
+        let mut doc = json!({"hosts": [
+            {"address": "FE80:0:0::1", "count": 2},
+            {"address": "not an address"}
+        ]});
+        normalize_document(&mut doc, &bindings);
+        assert_eq!(
+            doc,
+            json!({"hosts": [
+                {"address": "fe80::1", "count": 2},
+                {"address": "not an address"}
+            ]})
+        );
+    }
+}
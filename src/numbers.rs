@@ -1,13 +1,103 @@
-use serde::{Deserialize, Serialize};
-use std::ops::Deref;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+#[cfg(feature = "lenient_numbers")]
+use std::marker::PhantomData;
+use std::ops::{Add, Deref, RangeInclusive, Sub};
 
-use crate::{Base, Error};
+use crate::warnings::{self, Warning};
+use crate::{Base, Error, Validate};
 
 use super::{DecimalType, Metaschema, NumberType};
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(transparent)]
-pub struct DecimalDatatype(f64);
+/// A number or boolean written either as its native JSON value or as a
+/// string holding its lexical form, e.g. `100` or `"100"`, which the
+/// Metaschema also allows.  Needs a self-describing format such as JSON.
+#[cfg(feature = "lenient_numbers")]
+pub(crate) enum NativeOrLexical<N> {
+    Native(N),
+    Lexical(String),
+}
+
+#[cfg(feature = "lenient_numbers")]
+impl<'de, N: Deserialize<'de>> Deserialize<'de> for NativeOrLexical<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::value::{
+            BoolDeserializer, F64Deserializer, I128Deserializer, I64Deserializer,
+            MapAccessDeserializer, U128Deserializer, U64Deserializer,
+        };
+        use serde::de::{MapAccess, Visitor};
+
+        struct NativeOrLexicalVisitor<N>(PhantomData<N>);
+
+        impl<'de, N: Deserialize<'de>> Visitor<'de> for NativeOrLexicalVisitor<N> {
+            type Value = NativeOrLexical<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a number, a boolean or a string holding one")
+            }
+
+            fn visit_bool<E: serde::de::Error>(self, v: bool) -> Result<Self::Value, E> {
+                N::deserialize(BoolDeserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, v: i64) -> Result<Self::Value, E> {
+                N::deserialize(I64Deserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<Self::Value, E> {
+                N::deserialize(U64Deserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_i128<E: serde::de::Error>(self, v: i128) -> Result<Self::Value, E> {
+                N::deserialize(I128Deserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_u128<E: serde::de::Error>(self, v: u128) -> Result<Self::Value, E> {
+                N::deserialize(U128Deserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_f64<E: serde::de::Error>(self, v: f64) -> Result<Self::Value, E> {
+                N::deserialize(F64Deserializer::new(v)).map(NativeOrLexical::Native)
+            }
+
+            /// `serde_json` with `arbitrary_precision` hands numbers over as
+            /// a map.
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                N::deserialize(MapAccessDeserializer::new(map)).map(NativeOrLexical::Native)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Ok(NativeOrLexical::Lexical(v.to_string()))
+            }
+
+            fn visit_string<E: serde::de::Error>(self, v: String) -> Result<Self::Value, E> {
+                Ok(NativeOrLexical::Lexical(v))
+            }
+        }
+
+        deserializer.deserialize_any(NativeOrLexicalVisitor(PhantomData))
+    }
+}
+
+/// A decimal held as an `f64`.
+///
+/// With the `exact_decimal` feature the lexical form a value was read from
+/// is kept as well, and written back unchanged, so values with more
+/// precision than an `f64` such as `0.1000000000000000055` round-trip.
+/// JSON numbers in exponent notation are kept as the plain decimal they
+/// stand for, e.g. `1e3` as `1000`.
+/// Comparisons and arithmetic always use the `f64`.
+///
+/// Decimals are always finite and totally ordered, so they can be map keys:
+/// `0` and `-0` are equal.
+#[derive(Debug, Clone)]
+pub struct DecimalDatatype {
+    value: f64,
+    #[cfg(feature = "exact_decimal")]
+    lexical: Option<serde_json::Number>,
+}
 
 impl DecimalType for DecimalDatatype {}
 
@@ -32,26 +122,262 @@ impl Base for DecimalDatatype {
 impl Deref for DecimalDatatype {
     type Target = f64;
     fn deref(&self) -> &Self::Target {
-        &self.0
+        &self.value
     }
 }
 
-impl From<f64> for DecimalDatatype {
-    fn from(value: f64) -> Self {
-        Self(value)
+/// `NaN` and infinite values have no decimal lexical form and are rejected
+/// with [Error::NumberRange].
+impl TryFrom<f64> for DecimalDatatype {
+    type Error = Error;
+    fn try_from(value: f64) -> Result<Self, Self::Error> {
+        Self::checked(value)
     }
 }
 impl TryFrom<&f64> for DecimalDatatype {
     type Error = Error;
     fn try_from(value: &f64) -> Result<Self, Self::Error> {
-        Ok(Self(*value))
+        Self::checked(*value)
+    }
+}
+
+/// Parses the XSD decimal lexical form, keeping it with `exact_decimal`.
+/// Values too large for an `f64` are rejected with [Error::NumberParse].
+impl TryFrom<&str> for DecimalDatatype {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Self::validate(value)?;
+        let number = value
+            .parse::<f64>()
+            .ok()
+            .filter(|number| number.is_finite())
+            .ok_or(Error::NumberParse)?;
+        #[cfg(feature = "exact_decimal")]
+        return Ok(Self {
+            value: number,
+            lexical: value.parse().ok(),
+        });
+        #[cfg(not(feature = "exact_decimal"))]
+        Ok(Self::new(number))
+    }
+}
+
+impl DecimalDatatype {
+    const fn new(value: f64) -> Self {
+        Self {
+            value,
+            #[cfg(feature = "exact_decimal")]
+            lexical: None,
+        }
+    }
+
+    /// The value as an `f64`, rounded if it was read with more precision.
+    pub fn to_f64(&self) -> f64 {
+        self.value
+    }
+
+    /// The lexical form the value was read from.  Only kept with the
+    /// `exact_decimal` feature, and only for values read from JSON numbers
+    /// or valid JSON number text.
+    pub fn exact(&self) -> Option<&str> {
+        #[cfg(feature = "exact_decimal")]
+        return self.lexical.as_ref().map(|number| number.as_str());
+        #[cfg(not(feature = "exact_decimal"))]
+        None
+    }
+}
+
+impl DecimalDatatype {
+    /// The value with a single zero, for comparison.
+    fn comparison_key(&self) -> f64 {
+        match self.value {
+            0.0 => 0.0,
+            v => v,
+        }
+    }
+}
+
+impl PartialEq for DecimalDatatype {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for DecimalDatatype {}
+
+impl Hash for DecimalDatatype {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.comparison_key().to_bits().hash(state);
+    }
+}
+
+impl PartialOrd for DecimalDatatype {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for DecimalDatatype {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.comparison_key().total_cmp(&other.comparison_key())
+    }
+}
+
+/// The exact lexical form when it was kept, otherwise the shortest form
+/// that reads back as the same `f64`.
+impl fmt::Display for DecimalDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.exact() {
+            Some(lexical) => f.write_str(lexical),
+            None => write!(f, "{}", self.value),
+        }
+    }
+}
+
+impl Serialize for DecimalDatatype {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(feature = "exact_decimal")]
+        if let Some(number) = &self.lexical {
+            return number.serialize(serializer);
+        }
+        serializer.serialize_f64(self.value)
+    }
+}
+
+/// With `lenient_numbers`, strings holding a decimal are accepted too.
+impl<'de> Deserialize<'de> for DecimalDatatype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[cfg(feature = "exact_decimal")]
+        type Native = serde_json::Number;
+        #[cfg(not(feature = "exact_decimal"))]
+        type Native = f64;
+        #[cfg(feature = "lenient_numbers")]
+        let native = match NativeOrLexical::<Native>::deserialize(deserializer)? {
+            NativeOrLexical::Native(native) => native,
+            NativeOrLexical::Lexical(lexical) => {
+                return Self::try_from(lexical.as_str()).map_err(serde::de::Error::custom)
+            }
+        };
+        #[cfg(not(feature = "lenient_numbers"))]
+        let native = Native::deserialize(deserializer)?;
+        #[cfg(feature = "exact_decimal")]
+        {
+            let number = native;
+            let value = number
+                .as_f64()
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| serde::de::Error::custom(Error::NumberRange))?;
+            Ok(Self {
+                value,
+                lexical: plain_decimal(number),
+            })
+        }
+        #[cfg(not(feature = "exact_decimal"))]
+        Ok(Self::new(native))
+    }
+}
+
+/// Exponents beyond this are far outside the `f64` range, so rewriting them
+/// as a plain decimal would only pad the value with zeros.
+#[cfg(feature = "exact_decimal")]
+const MAX_PLAIN_EXPONENT: u32 = 400;
+
+/// Rewrite a JSON number in exponent notation as the plain decimal it
+/// stands for, e.g. `1.5e3` as `1500`, keeping every digit, since the XSD
+/// decimal lexical form has no exponent.  `None` if the exponent is beyond
+/// [MAX_PLAIN_EXPONENT].
+#[cfg(feature = "exact_decimal")]
+fn plain_decimal(number: serde_json::Number) -> Option<serde_json::Number> {
+    let Some((mantissa, exponent)) = number.as_str().split_once(['e', 'E']) else {
+        return Some(number);
+    };
+    let exponent = exponent
+        .parse::<i64>()
+        .ok()
+        .filter(|e| e.unsigned_abs() <= u64::from(MAX_PLAIN_EXPONENT))?;
+    let (sign, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => ("-", mantissa),
+        None => ("", mantissa),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{whole}{fraction}");
+    // Where the decimal point falls in `digits`
+    let point = whole.len() as i64 + exponent;
+    let plain = if point <= 0 {
+        format!("0.{}{digits}", "0".repeat(point.unsigned_abs() as usize))
+    } else if point as usize >= digits.len() {
+        format!("{digits}{}", "0".repeat(point as usize - digits.len()))
+    } else {
+        let (whole, fraction) = digits.split_at(point as usize);
+        format!("{whole}.{fraction}")
+    };
+    let plain = plain.trim_start_matches('0');
+    let zero = if plain.is_empty() || plain.starts_with('.') {
+        "0"
+    } else {
+        ""
+    };
+    format!("{sign}{zero}{plain}").parse().ok()
+}
+
+impl DecimalDatatype {
+    /// Clamp the result of a calculation into `range`, e.g. a score that
+    /// float error has pushed slightly past its bounds.  A
+    /// [Warning::Clamped] is recorded if the value changed.  `NaN` and
+    /// infinite values, and a range that is empty or has a `NaN` bound, are
+    /// rejected with [Error::NumberRange].
+    pub fn from_f64_clamped(value: f64, range: RangeInclusive<f64>) -> Result<Self, Error> {
+        // `f64::clamp` panics on an empty range
+        if !value.is_finite() || range.is_empty() {
+            return Err(Error::NumberRange);
+        }
+        let clamped = value.clamp(*range.start(), *range.end());
+        if clamped != value {
+            warnings::emit(Warning::Clamped {
+                datatype: "DecimalDatatype",
+                original: value.to_string(),
+                clamped: clamped.to_string(),
+            });
+        }
+        // Avoid writing `-0`
+        Ok(Self::new(if clamped == 0.0 { 0.0 } else { clamped }))
+    }
+}
+
+/// Accepts the XSD decimal lexical form: an optional sign, digits and an
+/// optional fractional part.  Exponents, `NaN` and `INF` are not allowed.
+impl Validate for DecimalDatatype {
+    fn validate(value: &str) -> Result<(), Error> {
+        let digits = value.strip_prefix(['+', '-']).unwrap_or(value);
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        if (whole.is_empty() && fraction.is_empty()) || !all_digits(whole) || !all_digits(fraction)
+        {
+            return Err(Error::NumberParse);
+        }
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[cfg_attr(not(feature = "lenient_numbers"), derive(Deserialize))]
 #[serde(transparent)]
 pub struct IntegerDatatype(i64);
 
+/// With `lenient_numbers`, strings holding an integer are accepted too.
+#[cfg(feature = "lenient_numbers")]
+impl<'de> Deserialize<'de> for IntegerDatatype {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match NativeOrLexical::<i64>::deserialize(deserializer)? {
+            NativeOrLexical::Native(value) => Ok(Self(value)),
+            NativeOrLexical::Lexical(lexical) => lexical
+                .parse::<i64>()
+                .map(Self)
+                .map_err(|_| serde::de::Error::custom(Error::NumberParse)),
+        }
+    }
+}
+
 impl Base for IntegerDatatype {
     fn base_type() -> String {
         String::from("i64")
@@ -68,6 +394,12 @@ impl Deref for IntegerDatatype {
     }
 }
 
+impl fmt::Display for IntegerDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 impl From<i64> for IntegerDatatype {
     fn from(value: i64) -> Self {
         Self(value)
@@ -83,7 +415,23 @@ impl TryFrom<&i64> for IntegerDatatype {
 
 impl NumberType for IntegerDatatype {}
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+impl Metaschema for IntegerDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("integer")
+    }
+    fn description() -> Option<&'static str> {
+        Some("A whole number value.")
+    }
+}
+
+impl Validate for IntegerDatatype {
+    fn validate(value: &str) -> Result<(), Error> {
+        value.parse::<i64>().map_err(|_| Error::NumberParse)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 #[serde(transparent)]
 pub struct NonNegativeIntegerDatatype(u64);
 
@@ -104,26 +452,32 @@ impl Deref for NonNegativeIntegerDatatype {
     }
 }
 
-impl From<u64> for NonNegativeIntegerDatatype {
-    fn from(value: u64) -> Self {
-        Self(value)
+impl NumberType for NonNegativeIntegerDatatype {
+    fn minimum() -> Option<i64> {
+        Some(0)
     }
 }
 
-impl TryFrom<&u64> for NonNegativeIntegerDatatype {
-    type Error = Error;
-    fn try_from(value: &u64) -> Result<Self, Self::Error> {
-        Ok(Self(*value))
+impl Metaschema for NonNegativeIntegerDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("integer")
+    }
+    fn description() -> Option<&'static str> {
+        Some("An integer value that is equal to or greater than 0.")
     }
 }
 
-impl NumberType for NonNegativeIntegerDatatype {
-    fn minimum() -> Option<i64> {
-        Some(0)
+impl Validate for NonNegativeIntegerDatatype {
+    fn validate(value: &str) -> Result<(), Error> {
+        if value.starts_with('-') {
+            return Err(Error::NumberRange);
+        }
+        value.parse::<u64>().map_err(|_| Error::NumberParse)?;
+        Ok(())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 #[serde(transparent)]
 pub struct PositiveIntegerDatatype(u64);
 
@@ -144,16 +498,23 @@ impl Deref for PositiveIntegerDatatype {
     }
 }
 
-impl From<u64> for PositiveIntegerDatatype {
-    fn from(value: u64) -> Self {
-        Self(value)
+impl PositiveIntegerDatatype {
+    /// The indices `1..=self`, in order.
+    pub fn range_to(&self) -> impl Iterator<Item = PositiveIntegerDatatype> {
+        (1..=self.0).map(Self)
     }
-}
 
-impl TryFrom<&u64> for PositiveIntegerDatatype {
-    type Error = Error;
-    fn try_from(value: &u64) -> Result<Self, Self::Error> {
-        Ok(Self(*value))
+    /// The next index, or `None` at `u64::MAX`.
+    pub fn successor(&self) -> Option<Self> {
+        self.0.checked_add(1).map(Self)
+    }
+
+    /// The previous index, or `None` at 1.
+    pub fn predecessor(&self) -> Option<Self> {
+        match self.0 {
+            0 | 1 => None,
+            n => Some(Self(n - 1)),
+        }
     }
 }
 
@@ -162,3 +523,453 @@ impl NumberType for PositiveIntegerDatatype {
         Some(1)
     }
 }
+
+impl Metaschema for PositiveIntegerDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("integer")
+    }
+    fn description() -> Option<&'static str> {
+        Some("An integer value that is greater than 0.")
+    }
+}
+
+impl Validate for PositiveIntegerDatatype {
+    fn validate(value: &str) -> Result<(), Error> {
+        if value.starts_with('-') {
+            return Err(Error::NumberRange);
+        }
+        match value.parse::<u64>().map_err(|_| Error::NumberParse)? {
+            0 => Err(Error::NumberRange),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Checked conversions to and from the unsigned integers produced by
+/// counting and indexing.  Values that do not fit the target, or fall below
+/// the datatype minimum, are rejected with [Error::NumberRange].
+macro_rules! integer_conversions {
+    ($t:ty, $n:ty) => {
+        impl TryFrom<usize> for $t {
+            type Error = Error;
+            fn try_from(value: usize) -> Result<Self, Self::Error> {
+                Self::try_from(value as u128)
+            }
+        }
+
+        impl TryFrom<u128> for $t {
+            type Error = Error;
+            fn try_from(value: u128) -> Result<Self, Self::Error> {
+                let minimum = <$t as NumberType>::minimum().unwrap_or(i64::MIN);
+                match <$n>::try_from(value) {
+                    Ok(number) if minimum <= 0 || value >= minimum as u128 => Ok(Self(number)),
+                    _ => Err(Error::NumberRange),
+                }
+            }
+        }
+
+        impl $t {
+            /// The value as a `usize`, if it fits on this target.
+            pub fn as_usize(&self) -> Result<usize, Error> {
+                usize::try_from(self.0).map_err(|_| Error::NumberRange)
+            }
+        }
+    };
+}
+
+integer_conversions!(IntegerDatatype, i64);
+integer_conversions!(NonNegativeIntegerDatatype, u64);
+integer_conversions!(PositiveIntegerDatatype, u64);
+
+/// Checked construction of the unsigned integer types.  Values outside the
+/// [NumberType] bounds are rejected with [Error::NumberRange], including
+/// when deserializing, where negative numbers are also rejected.
+macro_rules! bounded_unsigned {
+    ($t:ident) => {
+        impl TryFrom<u64> for $t {
+            type Error = Error;
+            fn try_from(value: u64) -> Result<Self, Self::Error> {
+                let n = i128::from(value);
+                let below = <$t as NumberType>::minimum().is_some_and(|min| n < i128::from(min));
+                let above = <$t as NumberType>::maximum().is_some_and(|max| n > i128::from(max));
+                match below || above {
+                    true => Err(Error::NumberRange),
+                    false => Ok(Self(value)),
+                }
+            }
+        }
+
+        impl TryFrom<&u64> for $t {
+            type Error = Error;
+            fn try_from(value: &u64) -> Result<Self, Self::Error> {
+                Self::try_from(*value)
+            }
+        }
+
+        impl fmt::Display for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        /// With `lenient_numbers`, strings holding an integer are accepted
+        /// too.
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                #[cfg(feature = "lenient_numbers")]
+                let value = match NativeOrLexical::<i128>::deserialize(deserializer)? {
+                    NativeOrLexical::Native(value) => value,
+                    NativeOrLexical::Lexical(lexical) => {
+                        Self::validate(&lexical).map_err(serde::de::Error::custom)?;
+                        lexical
+                            .parse::<i128>()
+                            .map_err(|_| serde::de::Error::custom(Error::NumberParse))?
+                    }
+                };
+                #[cfg(not(feature = "lenient_numbers"))]
+                let value = i128::deserialize(deserializer)?;
+                u64::try_from(value)
+                    .map_err(|_| Error::NumberRange)
+                    .and_then(Self::try_from)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+bounded_unsigned!(NonNegativeIntegerDatatype);
+bounded_unsigned!(PositiveIntegerDatatype);
+
+/// Checked arithmetic.  Results that overflow, or fall outside the
+/// [NumberType] bounds, are [Error::NumberRange].  The `+` and `-`
+/// operators are checked too, and so return a `Result`.
+macro_rules! integer_arithmetic {
+    ($t:ident, $n:ty) => {
+        impl $t {
+            fn checked(value: Option<$n>) -> Result<Self, Error> {
+                let value = value.ok_or(Error::NumberRange)?;
+                Self::try_from(value).map_err(|_| Error::NumberRange)
+            }
+
+            pub fn checked_add(&self, rhs: $n) -> Result<Self, Error> {
+                Self::checked(self.0.checked_add(rhs))
+            }
+
+            pub fn checked_sub(&self, rhs: $n) -> Result<Self, Error> {
+                Self::checked(self.0.checked_sub(rhs))
+            }
+
+            pub fn checked_mul(&self, rhs: $n) -> Result<Self, Error> {
+                Self::checked(self.0.checked_mul(rhs))
+            }
+        }
+
+        impl Add for $t {
+            type Output = Result<Self, Error>;
+            fn add(self, rhs: Self) -> Self::Output {
+                self.checked_add(rhs.0)
+            }
+        }
+
+        impl Sub for $t {
+            type Output = Result<Self, Error>;
+            fn sub(self, rhs: Self) -> Self::Output {
+                self.checked_sub(rhs.0)
+            }
+        }
+    };
+}
+
+integer_arithmetic!(IntegerDatatype, i64);
+integer_arithmetic!(NonNegativeIntegerDatatype, u64);
+integer_arithmetic!(PositiveIntegerDatatype, u64);
+
+/// Checked arithmetic.  Results that are not finite are
+/// [Error::NumberRange].
+impl DecimalDatatype {
+    fn checked(value: f64) -> Result<Self, Error> {
+        match value.is_finite() {
+            true => Ok(Self::new(value)),
+            false => Err(Error::NumberRange),
+        }
+    }
+
+    pub fn checked_add(&self, rhs: f64) -> Result<Self, Error> {
+        Self::checked(self.value + rhs)
+    }
+
+    pub fn checked_sub(&self, rhs: f64) -> Result<Self, Error> {
+        Self::checked(self.value - rhs)
+    }
+
+    pub fn checked_mul(&self, rhs: f64) -> Result<Self, Error> {
+        Self::checked(self.value * rhs)
+    }
+
+    pub fn checked_div(&self, rhs: f64) -> Result<Self, Error> {
+        Self::checked(self.value / rhs)
+    }
+}
+
+impl Add for DecimalDatatype {
+    type Output = Result<Self, Error>;
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(rhs.value)
+    }
+}
+
+impl Sub for DecimalDatatype {
+    type Output = Result<Self, Error>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(rhs.value)
+    }
+}
+
+/// Saturating conversion from signed arithmetic.  Values below the datatype
+/// minimum become the minimum, and a [Warning::Clamped] is recorded.
+macro_rules! saturating_conversion {
+    ($t:ident) => {
+        impl $t {
+            pub fn from_i64_saturating(value: i64) -> Self {
+                let minimum = <$t as NumberType>::minimum().unwrap_or(0);
+                if value < minimum {
+                    warnings::emit(Warning::Clamped {
+                        datatype: stringify!($t),
+                        original: value.to_string(),
+                        clamped: minimum.to_string(),
+                    });
+                }
+                Self(value.max(minimum) as u64)
+            }
+        }
+    };
+}
+
+saturating_conversion!(NonNegativeIntegerDatatype);
+saturating_conversion!(PositiveIntegerDatatype);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_decimal() {
+        assert!(DecimalDatatype::validate("1.5").is_ok());
+        assert!(DecimalDatatype::validate("-.5").is_ok());
+        assert!(DecimalDatatype::validate("+10").is_ok());
+        assert!(DecimalDatatype::validate("1e5").is_err());
+        assert!(DecimalDatatype::validate("NaN").is_err());
+        assert!(DecimalDatatype::validate(".").is_err());
+    }
+
+    #[test]
+    fn test_decimal_lexical() {
+        let value = DecimalDatatype::try_from("-.5").unwrap();
+        assert_eq!(value.to_f64(), -0.5);
+        assert!(DecimalDatatype::try_from("1e5").is_err());
+
+        let json = "0.1000000000000000055";
+        let value: DecimalDatatype = serde_json::from_str(json).unwrap();
+        assert_eq!(value, DecimalDatatype::try_from(0.1).unwrap());
+        if cfg!(feature = "exact_decimal") {
+            assert_eq!(value.exact(), Some(json));
+            assert_eq!(value.to_string(), json);
+            assert_eq!(serde_json::to_string(&value).unwrap(), json);
+        } else {
+            assert_eq!(value.exact(), None);
+            assert_eq!(serde_json::to_string(&value).unwrap(), "0.1");
+        }
+        let sum = (value + DecimalDatatype::try_from(1.0).unwrap()).unwrap();
+        assert_eq!(sum.exact(), None);
+
+        for (json, plain) in [
+            ("1e3", "1000"),
+            ("-2.50E-3", "-0.00250"),
+            ("0.5e1", "5"),
+            ("12.5e+1", "125"),
+            ("1.25e1", "12.5"),
+            ("0e5", "0"),
+        ] {
+            let value: DecimalDatatype = serde_json::from_str(json).unwrap();
+            if cfg!(feature = "exact_decimal") {
+                assert_eq!(value.exact(), Some(plain));
+                assert_eq!(serde_json::to_string(&value).unwrap(), plain);
+            }
+            assert_eq!(value, DecimalDatatype::try_from(plain).unwrap());
+        }
+        let tiny: DecimalDatatype = serde_json::from_str("1e-1000").unwrap();
+        assert_eq!((tiny.exact(), tiny.to_f64()), (None, 0.0));
+
+        let huge = "9".repeat(400);
+        assert!(matches!(
+            DecimalDatatype::try_from(huge.as_str()),
+            Err(Error::NumberParse)
+        ));
+        assert!(serde_json::from_str::<DecimalDatatype>(&huge).is_err());
+        assert!(serde_json::from_str::<DecimalDatatype>("1e400").is_err());
+    }
+
+    #[test]
+    fn test_decimal_ordering() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<DecimalDatatype> = [1.5, -0.0, 0.0, -2.0]
+            .into_iter()
+            .map(|value| DecimalDatatype::try_from(value).unwrap())
+            .collect();
+        let values: Vec<f64> = set.iter().map(|d| d.to_f64()).collect();
+        assert_eq!(values, [-2.0, 0.0, 1.5]);
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                DecimalDatatype::try_from(value),
+                Err(Error::NumberRange)
+            ));
+            assert!(DecimalDatatype::try_from(&value).is_err());
+        }
+    }
+
+    #[test]
+    fn test_validate_integers() {
+        assert!(IntegerDatatype::validate("-3").is_ok());
+        assert!(IntegerDatatype::validate("3.0").is_err());
+        assert!(NonNegativeIntegerDatatype::validate("0").is_ok());
+        assert!(NonNegativeIntegerDatatype::validate("-1").is_err());
+        assert!(PositiveIntegerDatatype::validate("1").is_ok());
+        assert!(PositiveIntegerDatatype::validate("0").is_err());
+    }
+
+    #[test]
+    fn test_usize_conversions() {
+        let count = NonNegativeIntegerDatatype::try_from(0usize).unwrap();
+        assert_eq!(count.as_usize().unwrap(), 0);
+        assert!(PositiveIntegerDatatype::try_from(0usize).is_err());
+        assert_eq!(*PositiveIntegerDatatype::try_from(7u128).unwrap(), 7);
+        assert!(NonNegativeIntegerDatatype::try_from(u64::MAX as u128 + 1).is_err());
+        assert!(IntegerDatatype::try_from(i64::MAX as u128 + 1).is_err());
+        assert!(IntegerDatatype::from(-1).as_usize().is_err());
+    }
+
+    #[test]
+    fn test_positive_integer_ranges() {
+        let three = PositiveIntegerDatatype::try_from(3u64).unwrap();
+        let indices: Vec<u64> = three.range_to().map(|i| *i).collect();
+        assert_eq!(indices, vec![1, 2, 3]);
+        assert_eq!(*three.successor().unwrap(), 4);
+        assert_eq!(*three.predecessor().unwrap(), 2);
+        assert!(PositiveIntegerDatatype::try_from(1u64)
+            .unwrap()
+            .predecessor()
+            .is_none());
+        assert!(PositiveIntegerDatatype::try_from(u64::MAX)
+            .unwrap()
+            .successor()
+            .is_none());
+    }
+
+    #[test]
+    fn test_unsigned_minimums() {
+        assert!(matches!(
+            PositiveIntegerDatatype::try_from(0u64),
+            Err(Error::NumberRange)
+        ));
+        assert_eq!(*NonNegativeIntegerDatatype::try_from(0u64).unwrap(), 0);
+
+        let de = |json| serde_json::from_str::<PositiveIntegerDatatype>(json);
+        assert_eq!(*de("5").unwrap(), 5);
+        assert_eq!(de("0").unwrap_err().to_string(), "Number out of range");
+        assert_eq!(de("-1").unwrap_err().to_string(), "Number out of range");
+        assert!(de("1.5").is_err());
+        assert!(serde_json::from_str::<NonNegativeIntegerDatatype>("-1").is_err());
+        assert!(serde_json::from_str::<NonNegativeIntegerDatatype>("0").is_ok());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let one = PositiveIntegerDatatype::try_from(1u64).unwrap();
+        let two = PositiveIntegerDatatype::try_from(2u64).unwrap();
+        assert_eq!(*(one.clone() + two.clone()).unwrap(), 3);
+        assert_eq!(*(two.clone() - one.clone()).unwrap(), 1);
+        assert!(matches!(one.clone() - one.clone(), Err(Error::NumberRange)));
+        assert!(one < two);
+        assert!(two.checked_mul(u64::MAX).is_err());
+
+        let zero = NonNegativeIntegerDatatype::try_from(0u64).unwrap();
+        assert!(zero.checked_sub(1).is_err());
+        assert_eq!(*zero.checked_add(4).unwrap(), 4);
+
+        let n = IntegerDatatype::from(-5);
+        assert_eq!(*n.checked_sub(5).unwrap(), -10);
+        assert!(IntegerDatatype::from(i64::MAX).checked_add(1).is_err());
+        assert_eq!(
+            [3, -1, 2].map(IntegerDatatype::from).iter().max(),
+            Some(&IntegerDatatype::from(3))
+        );
+
+        let score = DecimalDatatype::try_from(0.5).unwrap();
+        assert_eq!(
+            *(score.clone() + DecimalDatatype::try_from(0.25).unwrap()).unwrap(),
+            0.75
+        );
+        assert!(score.checked_div(0.0).is_err());
+        assert!(score < DecimalDatatype::try_from(1.0).unwrap());
+    }
+
+    #[test]
+    fn test_clamped_constructors() {
+        warnings::take();
+        let score = DecimalDatatype::from_f64_clamped(100.3, 0.0..=100.0).unwrap();
+        assert_eq!(*score, 100.0);
+        let score = DecimalDatatype::from_f64_clamped(-0.0001, 0.0..=100.0).unwrap();
+        assert_eq!(score.to_string(), "0");
+        assert_eq!(
+            *DecimalDatatype::from_f64_clamped(42.5, 0.0..=100.0).unwrap(),
+            42.5
+        );
+        assert!(DecimalDatatype::from_f64_clamped(f64::NAN, 0.0..=100.0).is_err());
+        assert!(DecimalDatatype::from_f64_clamped(1.0, 100.0..=0.0).is_err());
+        assert!(DecimalDatatype::from_f64_clamped(1.0, f64::NAN..=100.0).is_err());
+        assert!(DecimalDatatype::from_f64_clamped(1.0, 0.0..=f64::NAN).is_err());
+
+        assert_eq!(*PositiveIntegerDatatype::from_i64_saturating(-3), 1);
+        assert_eq!(*NonNegativeIntegerDatatype::from_i64_saturating(-3), 0);
+        assert_eq!(*NonNegativeIntegerDatatype::from_i64_saturating(3), 3);
+
+        let warnings = warnings::take();
+        assert_eq!(warnings.len(), 4);
+        assert_eq!(
+            warnings[0],
+            Warning::Clamped {
+                datatype: "DecimalDatatype",
+                original: "100.3".to_string(),
+                clamped: "100".to_string(),
+            }
+        );
+        assert_eq!(
+            warnings[2].to_string(),
+            "PositiveIntegerDatatype: clamped -3 to 1"
+        );
+    }
+
+    #[cfg(feature = "lenient_numbers")]
+    #[test]
+    fn test_lenient_numbers() {
+        let from = |json: &str| serde_json::from_str::<IntegerDatatype>(json);
+        assert_eq!(*from("-7").unwrap(), -7);
+        assert_eq!(*from(r#""-7""#).unwrap(), -7);
+        assert!(from(r#""7.5""#).is_err());
+
+        let from = |json: &str| serde_json::from_str::<PositiveIntegerDatatype>(json);
+        assert_eq!(*from(r#""100""#).unwrap(), 100);
+        assert!(from(r#""0""#).is_err());
+        assert!(from("0").is_err());
+        assert_eq!(
+            *serde_json::from_str::<NonNegativeIntegerDatatype>(r#""0""#).unwrap(),
+            0
+        );
+
+        let from = |json: &str| serde_json::from_str::<DecimalDatatype>(json);
+        assert_eq!(*from(r#"".5""#).unwrap(), 0.5);
+        assert_eq!(*from("2.25").unwrap(), 2.25);
+        assert!(from(r#""1e5""#).is_err());
+    }
+}
@@ -0,0 +1,579 @@
+//! Validation that reports how much of the input is valid.
+//!
+//! Editors validating as the user types need more than pass or fail: they
+//! need to know where a value goes wrong.  [ValidatePartial] checks the
+//! lexical form of the parser-based datatypes and, on failure, reports the
+//! byte offset up to which the input is valid and what was expected there.
+//! An input that is a valid but incomplete prefix, such as `2024-02`, is
+//! valid up to its length.
+//!
+//! ```
+//! use oscal_types::partial::ValidatePartial;
+//! use oscal_types::DateTimeWithTimezoneDatatype;
+//!
+//! let error = DateTimeWithTimezoneDatatype::validate_partial("2024-02-10T25:00:00Z").unwrap_err();
+//! assert_eq!(error.valid_up_to, 11);
+//! assert_eq!(error.expected, "an hour from 00 to 23");
+//!
+//! let error = DateTimeWithTimezoneDatatype::validate_partial("2024-02-10T09:30").unwrap_err();
+//! assert_eq!(error.valid_up_to, 16);
+//! assert_eq!(error.expected, "`:`");
+//! ```
+//!
+//! The checks follow [Validate::validate_strict], whatever the crate
+//! features, but dates and times are held to the forms of the Metaschema
+//! patterns: years have four digits and the other fields two, seconds run
+//! from 00 to 59, and `T` and `Z` are upper case.  chrono also accepts
+//! e.g. `2024-2-10` or a space for the `T`.
+use chrono::NaiveDate;
+use fluent_uri::Uri;
+use std::fmt;
+
+use crate::nc_name::NCName;
+use crate::*;
+
+/// Where and why a value stops being valid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PartialError {
+    /// The byte offset of the first invalid character or field, or the
+    /// length of an input that ends too soon.
+    pub valid_up_to: usize,
+    /// What would have been valid at `valid_up_to`, e.g. `` `-` `` or
+    /// `a month from 01 to 12`.
+    pub expected: &'static str,
+}
+
+impl fmt::Display for PartialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected {} at byte {}", self.expected, self.valid_up_to)
+    }
+}
+
+impl std::error::Error for PartialError {}
+
+pub trait ValidatePartial {
+    /// Validate `value`, reporting how much of it is valid on failure.
+    fn validate_partial(value: &str) -> Result<(), PartialError>;
+}
+
+/// A position in the value being validated.
+struct Cursor<'a> {
+    value: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn new(value: &'a str) -> Self {
+        Self { value, pos: 0 }
+    }
+
+    fn fail_at<T>(&self, pos: usize, expected: &'static str) -> Result<T, PartialError> {
+        Err(PartialError {
+            valid_up_to: pos,
+            expected,
+        })
+    }
+
+    fn fail<T>(&self, expected: &'static str) -> Result<T, PartialError> {
+        self.fail_at(self.pos, expected)
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.value[self.pos..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let matched = self.peek() == Some(c);
+        if matched {
+            self.pos += c.len_utf8();
+        }
+        matched
+    }
+
+    fn literal(&mut self, c: char, expected: &'static str) -> Result<(), PartialError> {
+        match self.eat(c) {
+            true => Ok(()),
+            false => self.fail(expected),
+        }
+    }
+
+    /// Exactly `count` characters matching `accept`.
+    fn exactly(
+        &mut self,
+        count: usize,
+        accept: fn(&char) -> bool,
+        expected: &'static str,
+    ) -> Result<&'a str, PartialError> {
+        let start = self.pos;
+        for _ in 0..count {
+            match self.peek() {
+                Some(c) if accept(&c) => self.pos += 1,
+                _ => return self.fail(expected),
+            }
+        }
+        Ok(&self.value[start..self.pos])
+    }
+
+    /// One or more ASCII digits.
+    fn digits(&mut self, expected: &'static str) -> Result<&'a str, PartialError> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        match self.pos > start {
+            true => Ok(&self.value[start..self.pos]),
+            false => self.fail(expected),
+        }
+    }
+
+    /// A two digit number no greater than `max`.
+    fn two_digits(&mut self, max: u32, expected: &'static str) -> Result<u32, PartialError> {
+        let start = self.pos;
+        let number = self.exactly(2, char::is_ascii_digit, expected)?;
+        match number.parse::<u32>() {
+            Ok(n) if n <= max => Ok(n),
+            _ => self.fail_at(start, expected),
+        }
+    }
+
+    fn end(&self) -> Result<(), PartialError> {
+        match self.pos == self.value.len() {
+            true => Ok(()),
+            false => self.fail("the end of the value"),
+        }
+    }
+}
+
+fn date(c: &mut Cursor) -> Result<(), PartialError> {
+    let year = c.exactly(4, char::is_ascii_digit, "a four digit year")?;
+    c.literal('-', "`-`")?;
+    let month = c.two_digits(12, "a month from 01 to 12")?;
+    if month == 0 {
+        return c.fail_at(c.pos - 2, "a month from 01 to 12");
+    }
+    c.literal('-', "`-`")?;
+    let day_start = c.pos;
+    let day = c.two_digits(31, "a day of the month")?;
+    let year = year.parse().expect("four digits");
+    match NaiveDate::from_ymd_opt(year, month, day) {
+        Some(_) => Ok(()),
+        None => c.fail_at(day_start, "a day of the month"),
+    }
+}
+
+fn time(c: &mut Cursor) -> Result<(), PartialError> {
+    c.two_digits(23, "an hour from 00 to 23")?;
+    c.literal(':', "`:`")?;
+    c.two_digits(59, "minutes from 00 to 59")?;
+    c.literal(':', "`:`")?;
+    c.two_digits(59, "seconds from 00 to 59")?;
+    if c.eat('.') {
+        c.digits("a fraction of a second")?;
+    }
+    Ok(())
+}
+
+/// `Z` or `+hh:mm`/`-hh:mm`.
+fn offset(c: &mut Cursor) -> Result<(), PartialError> {
+    if c.eat('Z') {
+        return Ok(());
+    }
+    if !(c.eat('+') || c.eat('-')) {
+        return c.fail("`Z`, `+` or `-`");
+    }
+    c.two_digits(23, "offset hours from 00 to 23")?;
+    c.literal(':', "`:`")?;
+    c.two_digits(59, "offset minutes from 00 to 59")?;
+    Ok(())
+}
+
+fn date_time(value: &str, offset_required: bool) -> Result<(), PartialError> {
+    let mut c = Cursor::new(value);
+    date(&mut c)?;
+    c.literal('T', "`T`")?;
+    time(&mut c)?;
+    if offset_required || c.pos < value.len() {
+        offset(&mut c)?;
+    }
+    c.end()
+}
+
+/// Components such as `3D` or `1.5S`, each designator at most once and in
+/// the order of `designators`.  Only seconds may have a fraction.
+fn duration_components(
+    c: &mut Cursor,
+    designators: &[char],
+    stop: Option<char>,
+) -> Result<bool, PartialError> {
+    let mut remaining = designators;
+    let mut any = false;
+    while c.pos < c.value.len() && c.peek() != stop {
+        c.digits("a number")?;
+        let fraction = c.pos;
+        if c.eat('.') {
+            c.digits("a fraction")?;
+        }
+        let designator = c.peek();
+        match remaining.iter().position(|&d| Some(d) == designator) {
+            Some(i) if remaining[i] == 'S' || c.pos == fraction => {
+                c.pos += 1;
+                remaining = &remaining[i + 1..];
+                any = true;
+            }
+            Some(_) => return c.fail_at(fraction, "a designator"),
+            None => return c.fail("a designator"),
+        }
+    }
+    Ok(any)
+}
+
+fn day_time_duration(value: &str) -> Result<(), PartialError> {
+    let mut c = Cursor::new(value);
+    c.eat('-');
+    c.literal('P', "`P`")?;
+    let days = duration_components(&mut c, &['D'], Some('T'))?;
+    if c.eat('T') {
+        if !duration_components(&mut c, &['H', 'M', 'S'], None)? {
+            return c.fail("a number");
+        }
+    } else if !days {
+        return c.fail("a number or `T`");
+    }
+    c.end()
+}
+
+fn year_month_duration(value: &str) -> Result<(), PartialError> {
+    let mut c = Cursor::new(value);
+    c.eat('-');
+    c.literal('P', "`P`")?;
+    if !duration_components(&mut c, &['Y', 'M'], None)? {
+        return c.fail("a number");
+    }
+    c.end()
+}
+
+fn uuid(value: &str) -> Result<(), PartialError> {
+    let mut c = Cursor::new(value);
+    let braced = c.eat('{');
+    if !braced && value.starts_with("urn:uuid:") {
+        c.pos = "urn:uuid:".len();
+    }
+    let hex = char::is_ascii_hexdigit;
+    c.exactly(8, hex, "a hexadecimal digit")?;
+    let hyphens = c.eat('-');
+    for (i, len) in [4, 4, 4, 12].into_iter().enumerate() {
+        if i > 0 && hyphens {
+            c.literal('-', "`-`")?;
+        }
+        if i == 1 {
+            let (strict, v7) = config::read(|c| (c.strict_uuid_versions, c.uuid_v7));
+            match c.peek().and_then(|v| v.to_digit(16)) {
+                _ if !strict => {}
+                None | Some(4 | 5) => {}
+                Some(7) if v7 => {}
+                Some(_) if v7 => return c.fail("version 4, 5 or 7"),
+                Some(_) => return c.fail("version 4 or 5"),
+            }
+        }
+        c.exactly(len, hex, "a hexadecimal digit")?;
+    }
+    if braced {
+        c.literal('}', "`}`")?;
+    }
+    c.end()
+}
+
+/// The scheme of an absolute URI, `ALPHA *( ALPHA / DIGIT / "+" / "-" / "." ) ":"`.
+fn scheme(value: &str) -> Result<(), PartialError> {
+    let mut c = Cursor::new(value);
+    c.exactly(1, char::is_ascii_alphabetic, "a scheme")?;
+    while c
+        .peek()
+        .is_some_and(|s| s.is_ascii_alphanumeric() || matches!(s, '+' | '-' | '.'))
+    {
+        c.pos += 1;
+    }
+    c.literal(':', "`:` after the scheme")
+}
+
+/// fluent-uri only exposes the position of a syntax error through its
+/// message, e.g. "unexpected character at index 12".
+fn uri_reference(value: &str) -> Result<(), PartialError> {
+    let Err(e) = Uri::parse(value) else {
+        return Ok(());
+    };
+    let message = e.to_string();
+    let expected = if message.starts_with("invalid percent-encoded octet") {
+        "a percent-encoded octet"
+    } else if message.starts_with("invalid IP literal") {
+        "an IP literal"
+    } else {
+        "a character allowed in a URI"
+    };
+    let valid_up_to = message
+        .rsplit(' ')
+        .next()
+        .and_then(|index| index.parse().ok())
+        .unwrap_or(0);
+    Err(PartialError {
+        valid_up_to,
+        expected,
+    })
+}
+
+fn ncname(value: &str) -> Result<(), PartialError> {
+    let mut chars = value.char_indices();
+    if let Some((_, first)) = chars.next() {
+        if !NCName::is_valid_start_char(first) {
+            return Cursor::new(value).fail("a letter or `_`");
+        }
+    }
+    match chars.find(|&(_, c)| !NCName::is_valid_char(c)) {
+        Some((at, _)) => Cursor::new(value).fail_at(at, "a name character"),
+        None => Ok(()),
+    }
+}
+
+impl ValidatePartial for DateDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        let mut c = Cursor::new(value);
+        date(&mut c)?;
+        c.end()
+    }
+}
+
+/// The offset is optional.
+impl ValidatePartial for DateTimeDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        date_time(value, false)
+    }
+}
+
+impl ValidatePartial for DateTimeWithTimezoneDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        date_time(value, true)
+    }
+}
+
+impl ValidatePartial for DayTimeDurationDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        day_time_duration(value)
+    }
+}
+
+impl ValidatePartial for YearMonthDurationDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        year_month_duration(value)
+    }
+}
+
+/// Accepts the forms [UUIDDatatype] does, with the version it requires.
+impl ValidatePartial for UUIDDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        uuid(value)
+    }
+}
+
+impl ValidatePartial for URIDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        uri_reference(value)?;
+        scheme(value)
+    }
+}
+
+impl ValidatePartial for URIReferenceDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        uri_reference(value)
+    }
+}
+
+impl ValidatePartial for NCName {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        ncname(value)
+    }
+}
+
+impl ValidatePartial for TokenDatatype {
+    fn validate_partial(value: &str) -> Result<(), PartialError> {
+        ncname(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn partial<T: ValidatePartial>(value: &str) -> Option<(usize, &'static str)> {
+        T::validate_partial(value)
+            .err()
+            .map(|e| (e.valid_up_to, e.expected))
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(partial::<DateDatatype>("2024-02-29"), None);
+        assert_eq!(partial::<DateDatatype>("2024-02"), Some((7, "`-`")));
+        assert_eq!(
+            partial::<DateDatatype>("2023-02-29"),
+            Some((8, "a day of the month"))
+        );
+        assert_eq!(
+            partial::<DateDatatype>("2024-00-01"),
+            Some((5, "a month from 01 to 12"))
+        );
+        assert_eq!(
+            partial::<DateDatatype>("24-02-10"),
+            Some((2, "a four digit year"))
+        );
+        assert_eq!(
+            partial::<DateDatatype>("2024-02-10Z"),
+            Some((10, "the end of the value"))
+        );
+
+        assert_eq!(partial::<DateTimeDatatype>("2024-02-10T09:30:00"), None);
+        assert_eq!(
+            partial::<DateTimeDatatype>("2024-02-10T09:30:00.5-05:00"),
+            None
+        );
+        assert_eq!(
+            partial::<DateTimeDatatype>("2024-02-10T09:30:00.Z"),
+            Some((20, "a fraction of a second"))
+        );
+        assert_eq!(
+            partial::<DateTimeDatatype>("2024-02-10 09:30:00"),
+            Some((10, "`T`"))
+        );
+        assert_eq!(
+            partial::<DateTimeWithTimezoneDatatype>("2024-02-10T09:30:00"),
+            Some((19, "`Z`, `+` or `-`"))
+        );
+        assert_eq!(
+            partial::<DateTimeWithTimezoneDatatype>("2024-02-10T09:30:00+0100"),
+            Some((22, "`:`"))
+        );
+    }
+
+    #[test]
+    fn test_durations() {
+        for valid in ["P1D", "PT1H", "P1DT2H30M", "PT1.5S", "P1DT0.25S", "-PT30M"] {
+            assert_eq!(partial::<DayTimeDurationDatatype>(valid), None, "{valid}");
+            assert!(DayTimeDurationDatatype::try_from(valid).is_ok(), "{valid}");
+        }
+        assert_eq!(
+            partial::<DayTimeDurationDatatype>("P"),
+            Some((1, "a number or `T`"))
+        );
+        assert_eq!(
+            partial::<DayTimeDurationDatatype>("P1DT"),
+            Some((4, "a number"))
+        );
+        assert_eq!(
+            partial::<DayTimeDurationDatatype>("PT1S2M"),
+            Some((5, "a designator"))
+        );
+        assert_eq!(
+            partial::<DayTimeDurationDatatype>("PT1.5M"),
+            Some((3, "a designator"))
+        );
+        assert_eq!(
+            partial::<DayTimeDurationDatatype>("P1Y"),
+            Some((2, "a designator"))
+        );
+
+        assert_eq!(partial::<YearMonthDurationDatatype>("P1Y2M"), None);
+        assert_eq!(partial::<YearMonthDurationDatatype>("-P1Y"), None);
+        assert_eq!(
+            partial::<YearMonthDurationDatatype>("--P1Y"),
+            Some((1, "`P`"))
+        );
+        assert_eq!(
+            partial::<YearMonthDurationDatatype>("P2M1Y"),
+            Some((4, "a designator"))
+        );
+    }
+
+    #[test]
+    fn test_uuid() {
+        let v4 = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+        for valid in [
+            v4.to_string(),
+            v4.to_uppercase(),
+            v4.replace('-', ""),
+            format!("{{{v4}}}"),
+            format!("urn:uuid:{v4}"),
+        ] {
+            assert_eq!(partial::<UUIDDatatype>(&valid), None, "{valid}");
+        }
+        assert_eq!(partial::<UUIDDatatype>("a78f7e4c-a27a"), Some((13, "`-`")));
+        let v1 = "a78f7e4c-a27a-1b1e-901b-ebfecf2b0301";
+        assert_eq!(partial::<UUIDDatatype>(v1), None);
+        let strict = config::Config {
+            strict_uuid_versions: true,
+            ..config::Config::new()
+        };
+        assert_eq!(
+            config::scoped(strict, || partial::<UUIDDatatype>(v1)),
+            Some((14, "version 4 or 5"))
+        );
+        assert_eq!(
+            partial::<UUIDDatatype>("a78f7e4c-a27a-4b1e-901b-ebfecf2b030g"),
+            Some((35, "a hexadecimal digit"))
+        );
+    }
+
+    #[test]
+    fn test_uris() {
+        assert_eq!(
+            partial::<URIDatatype>("https://example.gov/a b"),
+            Some((21, "a character allowed in a URI"))
+        );
+        assert_eq!(
+            partial::<URIDatatype>("https://example.gov/%zz"),
+            Some((20, "a percent-encoded octet"))
+        );
+        assert_eq!(
+            partial::<URIDatatype>("example.gov/a"),
+            Some((11, "`:` after the scheme"))
+        );
+        assert_eq!(partial::<URIDatatype>("/a"), Some((0, "a scheme")));
+        assert_eq!(partial::<URIReferenceDatatype>("/a#b"), None);
+    }
+
+    #[test]
+    fn test_ncname() {
+        assert_eq!(partial::<TokenDatatype>("ac-1"), None);
+        assert_eq!(
+            partial::<TokenDatatype>("1ac"),
+            Some((0, "a letter or `_`"))
+        );
+        assert_eq!(partial::<NCName>("ac:1"), Some((2, "a name character")));
+    }
+
+    /// Values accepted by validate_partial are accepted by validate_strict,
+    /// and the other way round.
+    #[test]
+    fn test_agrees_with_validate() {
+        fn agree<T: ValidatePartial + Validate>(values: &[&str]) {
+            for value in values {
+                assert_eq!(
+                    T::validate_partial(value).is_ok(),
+                    T::validate_strict(value).is_ok(),
+                    "{value}"
+                );
+            }
+        }
+        agree::<DateDatatype>(&["2024-02-10", "2024-02-30", "2024-13-10", ""]);
+        agree::<DateTimeWithTimezoneDatatype>(&[
+            "2024-02-10T09:30:00Z",
+            "2024-02-10T09:30:00.123+05:30",
+            "2024-02-10T09:30Z",
+            "2024-02-10_09:30:00Z",
+        ]);
+        agree::<UUIDDatatype>(&[
+            "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+            "a78f7e4c-a27a-1b1e-901b-ebfecf2b0301",
+            "a78f7e4c-a27a-4b1e-901b",
+        ]);
+        agree::<URIDatatype>(&["urn:ietf:rfc:3986", "https://a.gov/%41", "a.gov"]);
+    }
+}
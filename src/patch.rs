@@ -0,0 +1,261 @@
+//! Patching documents without breaking their datatypes.
+//!
+//! [apply_patch_validated] applies a JSON Patch (RFC 6902) or a JSON Merge
+//! Patch (RFC 7396) and validates every value it writes against the
+//! [FieldBindings].  The document is only changed when the whole patch
+//! applies and every written value is valid.
+//!
+//! ```
+//! use oscal_types::{bindings::FieldBindings, patch::apply_patch_validated, DatatypeKind};
+//! use serde_json::json;
+//!
+//! let bindings = FieldBindings::new().bind("/**/last-modified", DatatypeKind::DateTimeWithTimezone);
+//! let mut doc = json!({"metadata": {"last-modified": "2024-02-10T12:00:00Z"}});
+//!
+//! let patch = json!({"metadata": {"last-modified": "yesterday"}});
+//! let errors = apply_patch_validated(&mut doc, &patch, &bindings).unwrap_err();
+//! assert_eq!(errors[0].path(), "/metadata/last-modified");
+//!
+//! let patch = json!([{"op": "replace", "path": "/metadata/last-modified", "value": "2024-03-01T00:00:00Z"}]);
+//! apply_patch_validated(&mut doc, &patch, &bindings).unwrap();
+//! assert_eq!(doc["metadata"]["last-modified"], "2024-03-01T00:00:00Z");
+//! ```
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use crate::bindings::FieldBindings;
+use crate::de::{push_pointer, PathError};
+
+/// Apply `patch` to `doc`, validating every value it writes.
+///
+/// An array is applied as a JSON Patch, anything else as a JSON Merge Patch.
+/// On failure `doc` is left unchanged and every invalid value is reported,
+/// or the first operation that could not be applied.
+pub fn apply_patch_validated(
+    doc: &mut Value,
+    patch: &Value,
+    bindings: &FieldBindings,
+) -> Result<(), Vec<PathError>> {
+    let mut patched = doc.clone();
+    let mut errors = vec![];
+    match patch {
+        Value::Array(operations) => {
+            for operation in operations {
+                if let Err(e) = apply_operation(&mut patched, operation, bindings, &mut errors) {
+                    errors.push(e);
+                    return Err(errors);
+                }
+            }
+        }
+        _ => merge(&mut patched, patch, "", bindings, &mut errors),
+    }
+    match errors.is_empty() {
+        true => {
+            *doc = patched;
+            Ok(())
+        }
+        false => Err(errors),
+    }
+}
+
+fn merge(
+    target: &mut Value,
+    patch: &Value,
+    pointer: &str,
+    bindings: &FieldBindings,
+    errors: &mut Vec<PathError>,
+) {
+    let Value::Object(members) = patch else {
+        *target = patch.clone();
+        errors.extend(bindings.validate_at(target, pointer));
+        return;
+    };
+    if !target.is_object() {
+        *target = Value::Object(Map::new());
+    }
+    let Value::Object(map) = target else {
+        unreachable!()
+    };
+    for (key, value) in members {
+        if value.is_null() {
+            map.remove(key);
+        } else {
+            let child = map.entry(key.as_str()).or_insert(Value::Null);
+            merge(child, value, &push_pointer(pointer, key), bindings, errors);
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+enum Operation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+fn apply_operation(
+    doc: &mut Value,
+    operation: &Value,
+    bindings: &FieldBindings,
+    errors: &mut Vec<PathError>,
+) -> Result<(), PathError> {
+    let operation = Operation::deserialize(operation)
+        .map_err(|e| PathError::new("", format!("invalid patch operation: {e}")))?;
+    let written = match operation {
+        Operation::Add { path, value } => add(doc, &path, value)?,
+        Operation::Remove { path } => {
+            remove(doc, &path)?;
+            return Ok(());
+        }
+        Operation::Replace { path, value } => {
+            *lookup(doc, &path)? = value;
+            path
+        }
+        Operation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(PathError::new(path, "cannot move a value into itself"));
+            }
+            let value = remove(doc, &from)?;
+            add(doc, &path, value)?
+        }
+        Operation::Copy { from, path } => {
+            let value = lookup(doc, &from)?.clone();
+            add(doc, &path, value)?
+        }
+        Operation::Test { path, value } => {
+            return match *lookup(doc, &path)? == value {
+                true => Ok(()),
+                false => Err(PathError::new(path, "test failed")),
+            };
+        }
+    };
+    if let Some(value) = doc.pointer(&written) {
+        errors.extend(bindings.validate_at(value, &written));
+    }
+    Ok(())
+}
+
+fn lookup<'a>(doc: &'a mut Value, path: &str) -> Result<&'a mut Value, PathError> {
+    doc.pointer_mut(path)
+        .ok_or_else(|| PathError::new(path, "no such value"))
+}
+
+/// Split a JSON Pointer into its parent and its unescaped last token.
+fn split_last(path: &str) -> Result<(&str, String), PathError> {
+    let (parent, token) = path
+        .rsplit_once('/')
+        .ok_or_else(|| PathError::new(path, "invalid JSON Pointer"))?;
+    Ok((parent, token.replace("~1", "/").replace("~0", "~")))
+}
+
+fn array_index(path: &str, token: &str, len: usize) -> Result<usize, PathError> {
+    match token.parse::<usize>() {
+        Ok(index) if index <= len && (index == 0 || !token.starts_with('0')) => Ok(index),
+        _ => Err(PathError::new(path, "invalid array index")),
+    }
+}
+
+/// Add `value` at `path`, returning the JSON Pointer it was written to.
+fn add(doc: &mut Value, path: &str, value: Value) -> Result<String, PathError> {
+    if path.is_empty() {
+        *doc = value;
+        return Ok(String::new());
+    }
+    let (parent, token) = split_last(path)?;
+    match lookup(doc, parent)? {
+        Value::Object(map) => {
+            map.insert(token, value);
+            Ok(path.to_string())
+        }
+        Value::Array(items) => {
+            let index = match token.as_str() {
+                "-" => items.len(),
+                _ => array_index(path, &token, items.len())?,
+            };
+            items.insert(index, value);
+            Ok(push_pointer(parent, &index.to_string()))
+        }
+        _ => Err(PathError::new(path, "parent is not an object or array")),
+    }
+}
+
+fn remove(doc: &mut Value, path: &str) -> Result<Value, PathError> {
+    if path.is_empty() {
+        return Ok(std::mem::take(doc));
+    }
+    let (parent, token) = split_last(path)?;
+    let removed = match lookup(doc, parent)? {
+        Value::Object(map) => map.remove(&token),
+        Value::Array(items) => match array_index(path, &token, items.len()) {
+            Ok(index) if index < items.len() => Some(items.remove(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+    removed.ok_or_else(|| PathError::new(path, "no such value"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DatatypeKind;
+    use serde_json::json;
+
+    fn bindings() -> FieldBindings {
+        FieldBindings::new()
+            .bind("/**/uuid", DatatypeKind::Uuid)
+            .bind("/**/props/*/name", DatatypeKind::Token)
+    }
+
+    #[test]
+    fn test_merge_patch() {
+        let mut doc = json!({"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "title": "A"});
+        let patch = json!({"title": null, "props": [{"name": "ac-1"}]});
+        apply_patch_validated(&mut doc, &patch, &bindings()).unwrap();
+        assert_eq!(
+            doc,
+            json!({"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "props": [{"name": "ac-1"}]})
+        );
+
+        let patch = json!({"uuid": "nope", "props": [{"name": "a b"}]});
+        let errors = apply_patch_validated(&mut doc, &patch, &bindings()).unwrap_err();
+        let paths: Vec<_> = errors.iter().map(PathError::path).collect();
+        assert_eq!(paths, ["/props/0/name", "/uuid"]);
+        assert_eq!(doc["uuid"], "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+    }
+
+    #[test]
+    fn test_json_patch() {
+        let mut doc = json!({"props": [{"name": "ac-1"}], "old": {"uuid": "x"}});
+        let patch = json!([
+            {"op": "test", "path": "/props/0/name", "value": "ac-1"},
+            {"op": "add", "path": "/props/-", "value": {"name": "ac-2"}},
+            {"op": "copy", "from": "/props/1", "path": "/props/0"},
+            {"op": "remove", "path": "/old"},
+        ]);
+        apply_patch_validated(&mut doc, &patch, &bindings()).unwrap();
+        assert_eq!(
+            doc,
+            json!({"props": [{"name": "ac-2"}, {"name": "ac-1"}, {"name": "ac-2"}]})
+        );
+
+        let patch = json!([
+            {"op": "add", "path": "/props/-", "value": {"name": "1x"}},
+            {"op": "replace", "path": "/uuid", "value": "y"},
+        ]);
+        let errors = apply_patch_validated(&mut doc, &patch, &bindings()).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].path(), "/props/3/name");
+        assert_eq!(errors[1].message(), "no such value");
+        assert_eq!(doc["props"].as_array().unwrap().len(), 3);
+
+        let patch = json!([{"op": "move", "from": "/props", "path": "/props/0"}]);
+        assert!(apply_patch_validated(&mut doc, &patch, &bindings()).is_err());
+        let patch = json!([{"op": "frobnicate", "path": ""}]);
+        assert!(apply_patch_validated(&mut doc, &patch, &bindings()).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+//! Datatype patterns that are safe to hand to any regex engine.
+//!
+//! The Metaschema patterns of [StringType::pattern] are published for
+//! interoperability, but some (the date, date-time and duration patterns,
+//! IPv6) are large alternations of overlapping branches.  A backtracking
+//! engine, as in most languages' standard libraries, can take time
+//! quadratic or worse in the length of an adversarial input to reject it.
+//! This crate only matches patterns with the `regex` crate, which runs in
+//! time linear in the input, and validates those datatypes with a parser.
+//!
+//! [safe_patterns] is for tools that pass patterns on, e.g. into a JSON
+//! Schema consumed by another service: each datatype with a pattern has
+//! either one that a backtracking engine matches in linear time, or is
+//! marked [SafePattern::ParserValidated], meaning it should be validated
+//! with this crate (or an equivalent parser) rather than by pattern.
+//!
+//! ```
+//! use oscal_types::patterns::{safe_pattern, SafePattern};
+//! use oscal_types::DatatypeKind;
+//!
+//! assert_eq!(
+//!     safe_pattern(DatatypeKind::DateTimeWithTimezone),
+//!     Some(SafePattern::ParserValidated)
+//! );
+//! assert!(matches!(
+//!     safe_pattern(DatatypeKind::Token),
+//!     Some(SafePattern::Pattern(_))
+//! ));
+//! assert_eq!(safe_pattern(DatatypeKind::Integer), None);
+//! ```
+use serde::Serialize;
+
+use crate::*;
+
+/// A pattern to use in place of a datatype's declared pattern.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case", tag = "kind", content = "pattern")]
+pub enum SafePattern {
+    /// Matches exactly the values the declared pattern matches, without
+    /// ambiguous branches or nested repetition.
+    Pattern(&'static str),
+    /// No safe pattern is known; validate with the datatype's parser.
+    ParserValidated,
+}
+
+impl SafePattern {
+    pub fn pattern(&self) -> Option<&'static str> {
+        match self {
+            SafePattern::Pattern(pattern) => Some(pattern),
+            SafePattern::ParserValidated => None,
+        }
+    }
+}
+
+/// `^.+@.+$`, choosing the first `@` after the first character so only one
+/// way to match is ever tried.
+const EMAIL_ADDRESS: &str = "^[^\n][^@\n]*@[^\n]+$";
+
+/// The declared pattern with both alternatives anchored, as intended.
+/// Declared, `[0-9]+M$` is only anchored at the end.
+const YEAR_MONTH_DURATION: &str = "^-?P[0-9]+(Y([0-9]+M)?|M)$";
+
+/// The declared pattern of `kind`, if it has one.
+pub fn declared_pattern(kind: DatatypeKind) -> Option<&'static str> {
+    match kind {
+        DatatypeKind::Date => DateDatatype::pattern(),
+        DatatypeKind::DateTime => DateTimeDatatype::pattern(),
+        DatatypeKind::DateTimeWithTimezone => DateTimeWithTimezoneDatatype::pattern(),
+        DatatypeKind::DayTimeDuration => DayTimeDurationDatatype::pattern(),
+        DatatypeKind::YearMonthDuration => YearMonthDurationDatatype::pattern(),
+        DatatypeKind::TimezoneOffset => TimezoneOffsetDatatype::pattern(),
+        DatatypeKind::String => StringDatatype::pattern(),
+        DatatypeKind::Base64 => Base64Datatype::pattern(),
+        DatatypeKind::EmailAddress => EmailAddressDatatype::pattern(),
+        DatatypeKind::Hostname => HostnameDatatype::pattern(),
+        DatatypeKind::Ipv4Address => IPV4AddressDatatype::pattern(),
+        DatatypeKind::Ipv6Address => IPV6AddressDatatype::pattern(),
+        DatatypeKind::MarkupLine => MarkupLineDatatype::pattern(),
+        DatatypeKind::MarkupMultiline => MarkupMultilineDatatype::pattern(),
+        DatatypeKind::Token => TokenDatatype::pattern(),
+        DatatypeKind::Boolean
+        | DatatypeKind::Decimal
+        | DatatypeKind::Integer
+        | DatatypeKind::NonNegativeInteger
+        | DatatypeKind::PositiveInteger
+        | DatatypeKind::Ipv4Cidr
+        | DatatypeKind::Ipv6Cidr
+        | DatatypeKind::Uri
+        | DatatypeKind::UriReference
+        | DatatypeKind::Uuid => None,
+    }
+}
+
+/// The safe replacement for the declared pattern of `kind`, or `None` if
+/// it has no pattern.  Declared patterns that are already safe are
+/// returned as they are.
+pub fn safe_pattern(kind: DatatypeKind) -> Option<SafePattern> {
+    let declared = declared_pattern(kind)?;
+    Some(match kind {
+        DatatypeKind::Date
+        | DatatypeKind::DateTime
+        | DatatypeKind::DateTimeWithTimezone
+        | DatatypeKind::DayTimeDuration
+        | DatatypeKind::Ipv6Address => SafePattern::ParserValidated,
+        DatatypeKind::EmailAddress => SafePattern::Pattern(EMAIL_ADDRESS),
+        DatatypeKind::YearMonthDuration => SafePattern::Pattern(YEAR_MONTH_DURATION),
+        _ => SafePattern::Pattern(declared),
+    })
+}
+
+/// [safe_pattern] for every datatype with a declared pattern.
+pub fn safe_patterns() -> Vec<(DatatypeKind, SafePattern)> {
+    DatatypeKind::ALL
+        .iter()
+        .filter_map(|kind| Some((*kind, safe_pattern(*kind)?)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn regex(pattern: &str) -> Regex {
+        Regex::new(pattern).unwrap()
+    }
+
+    #[test]
+    fn test_safe_patterns_agree() {
+        let samples = [
+            (DatatypeKind::EmailAddress, "a@b", true),
+            (DatatypeKind::EmailAddress, "@a@b", true),
+            (DatatypeKind::EmailAddress, "a@b@", true),
+            (DatatypeKind::EmailAddress, "a@", false),
+            (DatatypeKind::EmailAddress, "@a", false),
+            (DatatypeKind::EmailAddress, "a@\nb", false),
+            (DatatypeKind::YearMonthDuration, "P1Y", true),
+            (DatatypeKind::YearMonthDuration, "-P1Y2M", true),
+            (DatatypeKind::YearMonthDuration, "P3M", true),
+            (DatatypeKind::YearMonthDuration, "P1M2Y", false),
+        ];
+        for (kind, value, valid) in samples {
+            let safe = safe_pattern(kind).unwrap().pattern().unwrap();
+            assert_eq!(regex(safe).is_match(value), valid, "{kind} {value:?}");
+            assert_eq!(
+                regex(declared_pattern(kind).unwrap()).is_match(value),
+                valid,
+                "{kind} {value:?}"
+            );
+        }
+        // Declared, the second alternative is not anchored at the start
+        assert!(regex(declared_pattern(DatatypeKind::YearMonthDuration).unwrap()).is_match("x3M"));
+        assert!(!regex(YEAR_MONTH_DURATION).is_match("x3M"));
+
+        let kinds: Vec<_> = safe_patterns().into_iter().map(|(kind, _)| kind).collect();
+        assert!(kinds.contains(&DatatypeKind::Token));
+        assert!(!kinds.contains(&DatatypeKind::Uuid));
+        assert_eq!(
+            serde_json::to_value(safe_pattern(DatatypeKind::Date)).unwrap(),
+            serde_json::json!({"kind": "parser-validated"})
+        );
+    }
+
+    /// Inputs that make a backtracking engine retry many ways to match.
+    /// Matching and validating each takes time linear in its length here,
+    /// so the whole test is quick; a backtracking engine would not finish.
+    #[test]
+    fn test_adversarial_inputs() {
+        let n = 20_000;
+        let inputs = [
+            format!("{}X", "9".repeat(n)),
+            format!("PT{}X", "9".repeat(n)),
+            format!("P{}DT{}.{}X", "9".repeat(n), "9".repeat(n), "9".repeat(n)),
+            format!("{}\n", "a@".repeat(n)),
+            format!("{}::x", "ffff:".repeat(n)),
+            format!("2024-02-10T09:30:00.{}X", "9".repeat(n)),
+            format!("a{}\n", " ".repeat(n)),
+            format!("{}!", "a.".repeat(n)),
+        ];
+        let start = std::time::Instant::now();
+        for kind in DatatypeKind::ALL {
+            let Some(declared) = declared_pattern(*kind) else {
+                continue;
+            };
+            let declared = regex(declared);
+            let safe = safe_pattern(*kind).unwrap().pattern().map(regex);
+            for input in &inputs {
+                let matched = declared.is_match(input);
+                if let Some(safe) = &safe {
+                    if *kind != DatatypeKind::YearMonthDuration {
+                        assert_eq!(safe.is_match(input), matched, "{kind}");
+                    }
+                }
+                let _ = kind.validate(input);
+            }
+        }
+        assert!(start.elapsed() < std::time::Duration::from_secs(10));
+    }
+}
@@ -0,0 +1,157 @@
+//! Names of OSCAL props and annotations.
+//!
+//! A prop `name` is a token qualified by the namespace URI in its `ns`.
+//! When `ns` is absent the name is in the default namespace,
+//! [crate::config::OSCAL_NAMESPACE] unless
+//! [crate::config::Config::default_namespace] says otherwise, so
+//! `{"name": "marking"}` and
+//! `{"name": "marking", "ns": "http://csrc.nist.gov/ns/oscal"}` name the
+//! same property.  [QualifiedToken] models this, for crates that model
+//! props and annotations.
+//!
+//! ```
+//! use oscal_types::prop::QualifiedToken;
+//! use serde_json::json;
+//!
+//! let bare: QualifiedToken = serde_json::from_str(r#"{"name": "marking"}"#).unwrap();
+//! let explicit: QualifiedToken = serde_json::from_str(
+//!     r#"{"name": "marking", "ns": "http://csrc.nist.gov/ns/oscal"}"#,
+//! )
+//! .unwrap();
+//! assert_eq!(bare, explicit);
+//! assert_eq!(serde_json::to_value(&bare).unwrap(), json!({"name": "marking"}));
+//! ```
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::hash::{Hash, Hasher};
+
+use crate::*;
+
+/// A token with an optional namespace.  Equality and hashing compare the
+/// name and the namespace in effect, see [QualifiedToken::namespace].
+/// Changing [config::Config::default_namespace] can therefore change
+/// whether two names are equal, so do not change it while names are keys
+/// in a map.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QualifiedToken {
+    name: TokenDatatype,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ns: Option<URIDatatype>,
+}
+
+impl QualifiedToken {
+    /// A name in the default namespace.
+    pub fn new(name: TokenDatatype) -> Self {
+        Self { name, ns: None }
+    }
+
+    pub fn with_namespace(name: TokenDatatype, ns: URIDatatype) -> Self {
+        Self { name, ns: Some(ns) }
+    }
+
+    pub fn name(&self) -> &TokenDatatype {
+        &self.name
+    }
+
+    /// The namespace as declared, if it was.
+    pub fn ns(&self) -> Option<&URIDatatype> {
+        self.ns.as_ref()
+    }
+
+    /// The namespace the name is in: its `ns`, or the default namespace.
+    pub fn namespace(&self) -> String {
+        match &self.ns {
+            Some(ns) => ns.to_string(),
+            None => config::get().namespace().to_string(),
+        }
+    }
+
+    /// Whether the name is in [config::OSCAL_NAMESPACE], i.e. defined by
+    /// OSCAL rather than by an extension.
+    pub fn is_oscal(&self) -> bool {
+        self.namespace() == config::OSCAL_NAMESPACE
+    }
+}
+
+impl From<TokenDatatype> for QualifiedToken {
+    fn from(name: TokenDatatype) -> Self {
+        Self::new(name)
+    }
+}
+
+impl PartialEq for QualifiedToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.namespace() == other.namespace()
+    }
+}
+
+impl Eq for QualifiedToken {}
+
+impl Hash for QualifiedToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+        self.namespace().hash(state);
+    }
+}
+
+/// The name in Clark notation, `{namespace}name`, with the namespace in
+/// effect.
+impl fmt::Display for QualifiedToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{{}}}{}", self.namespace(), &*self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    const FEDRAMP: &str = "https://fedramp.gov/ns/oscal";
+
+    fn token(value: &str) -> TokenDatatype {
+        TokenDatatype::try_from(value).unwrap()
+    }
+
+    fn uri(value: &str) -> URIDatatype {
+        URIDatatype::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_equality() {
+        let bare = QualifiedToken::new(token("marking"));
+        let oscal = QualifiedToken::with_namespace(token("marking"), uri(config::OSCAL_NAMESPACE));
+        let fedramp = QualifiedToken::with_namespace(token("marking"), uri(FEDRAMP));
+        assert_eq!(bare, oscal);
+        assert_ne!(bare, fedramp);
+        assert_ne!(bare, QualifiedToken::from(token("label")));
+        assert!(bare.is_oscal() && !fedramp.is_oscal());
+        assert_eq!(
+            HashSet::from([bare.clone(), oscal.clone(), fedramp.clone()]).len(),
+            2
+        );
+        assert_eq!(fedramp.to_string(), "{https://fedramp.gov/ns/oscal}marking");
+
+        let config = config::Config {
+            default_namespace: Some(FEDRAMP.to_string()),
+            ..config::Config::new()
+        };
+        config::scoped(config, || {
+            assert_eq!(bare, fedramp);
+            assert_ne!(bare, oscal);
+        });
+    }
+
+    #[test]
+    fn test_serde() {
+        let value = json!({"name": "cloud-service-model", "ns": FEDRAMP});
+        let name: QualifiedToken = serde_json::from_str(&value.to_string()).unwrap();
+        assert_eq!(&**name.name(), "cloud-service-model");
+        assert_eq!(name.ns().map(|ns| &**ns), Some(FEDRAMP));
+        assert_eq!(serde_json::to_value(&name).unwrap(), value);
+
+        assert!(serde_json::from_str::<QualifiedToken>(r#"{"name": "1st"}"#).is_err());
+        assert!(serde_json::from_str::<QualifiedToken>(r#"{"ns": "urn:x"}"#).is_err());
+    }
+}
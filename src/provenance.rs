@@ -0,0 +1,86 @@
+//! Audit trail of machine generated values.
+//!
+//! With the `provenance` feature, the `new()` constructors of
+//! [crate::UUIDDatatype] and the date and time datatypes record every value
+//! they generate.  [origin] then tells whether a value was produced by this
+//! process or came from input, e.g. when reporting which timestamps a tool
+//! filled in.
+//!
+//! The record grows with every generated value; call [clear] once a
+//! document has been audited.
+//!
+//! ```
+//! use oscal_types::provenance::{self, Origin};
+//! use oscal_types::UUIDDatatype;
+//!
+//! let generated = UUIDDatatype::new();
+//! let supplied = UUIDDatatype::try_from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").unwrap();
+//! assert_eq!(provenance::origin_of(&generated), Origin::Generated);
+//! assert_eq!(provenance::origin_of(&supplied), Origin::Supplied);
+//! ```
+use std::collections::HashSet;
+use std::ops::Deref;
+use std::sync::{LazyLock, Mutex};
+
+use crate::{DatatypeKind, OscalDatatype};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Origin {
+    /// Produced by a constructor in this process.
+    Generated,
+    /// Not generated here, so supplied by input.
+    Supplied,
+}
+
+static GENERATED: LazyLock<Mutex<HashSet<(DatatypeKind, String)>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn with_generated<R>(f: impl FnOnce(&mut HashSet<(DatatypeKind, String)>) -> R) -> R {
+    match GENERATED.lock() {
+        Ok(mut generated) => f(&mut generated),
+        Err(poisoned) => f(&mut poisoned.into_inner()),
+    }
+}
+
+pub(crate) fn record(kind: DatatypeKind, value: &str) {
+    with_generated(|generated| generated.insert((kind, value.to_string())));
+}
+
+/// The origin of the lexical `value` of `kind`.
+pub fn origin(kind: DatatypeKind, value: &str) -> Origin {
+    match with_generated(|generated| generated.contains(&(kind, value.to_string()))) {
+        true => Origin::Generated,
+        false => Origin::Supplied,
+    }
+}
+
+pub fn origin_of<T: OscalDatatype + Deref<Target = str>>(value: &T) -> Origin {
+    origin(T::KIND, value)
+}
+
+/// Every value generated since the last [clear].
+pub fn generated() -> Vec<(DatatypeKind, String)> {
+    let mut values: Vec<_> = with_generated(|generated| generated.iter().cloned().collect());
+    values.sort();
+    values
+}
+
+pub fn clear() {
+    with_generated(|generated| generated.clear());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DateTimeWithTimezoneDatatype;
+
+    #[test]
+    fn test_generated_timestamps() {
+        let now = DateTimeWithTimezoneDatatype::new();
+        assert_eq!(origin_of(&now), Origin::Generated);
+        assert!(generated().contains(&(DatatypeKind::DateTimeWithTimezone, now.to_string())));
+
+        let supplied = DateTimeWithTimezoneDatatype::try_from("2024-02-10T12:00:00Z").unwrap();
+        assert_eq!(origin_of(&supplied), Origin::Supplied);
+    }
+}
@@ -0,0 +1,173 @@
+//! Qualified names (`prefix:local`) and their namespace resolution.
+//!
+//! XML sourced tokens may carry a namespace prefix.  [QName] parses the
+//! syntax, and a [NamespaceContext] binds prefixes to namespace URIs so a
+//! name can be resolved to a `(namespace, local name)` pair.
+//!
+//! ```
+//! use oscal_types::qname::NamespaceContext;
+//!
+//! let mut context = NamespaceContext::oscal();
+//! context.bind("fedramp", "https://fedramp.gov/ns/oscal".try_into().unwrap()).unwrap();
+//!
+//! let (ns, name) = context.resolve("fedramp:cloud-service-model").unwrap();
+//! assert_eq!(&*ns, "https://fedramp.gov/ns/oscal");
+//! assert_eq!(&*name, "cloud-service-model");
+//!
+//! let (ns, _) = context.resolve("marking").unwrap();
+//! assert_eq!(&*ns, "http://csrc.nist.gov/ns/oscal");
+//! ```
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::config::OSCAL_NAMESPACE;
+use crate::nc_name::NCName;
+use crate::{Error, URIDatatype};
+
+/// A name with an optional namespace prefix.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QName {
+    prefix: Option<NCName>,
+    local: NCName,
+}
+
+impl QName {
+    pub fn prefix(&self) -> Option<&NCName> {
+        self.prefix.as_ref()
+    }
+
+    pub fn local(&self) -> &NCName {
+        &self.local
+    }
+}
+
+fn non_empty_ncname(value: &str) -> Result<NCName, Error> {
+    match value.is_empty() {
+        true => Err(Error::QNameParse(
+            "prefix and local name must not be empty".to_string(),
+        )),
+        false => NCName::try_from(value),
+    }
+}
+
+impl FromStr for QName {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((prefix, local)) => Ok(Self {
+                prefix: Some(non_empty_ncname(prefix)?),
+                local: non_empty_ncname(local)?,
+            }),
+            None => Ok(Self {
+                prefix: None,
+                local: non_empty_ncname(s)?,
+            }),
+        }
+    }
+}
+
+impl fmt::Display for QName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.prefix {
+            Some(prefix) => write!(f, "{}:{}", &**prefix, &*self.local),
+            None => f.write_str(&self.local),
+        }
+    }
+}
+
+/// Prefix to namespace URI bindings, plus an optional default namespace for
+/// unprefixed names.
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceContext {
+    default: Option<URIDatatype>,
+    prefixes: HashMap<String, URIDatatype>,
+}
+
+impl NamespaceContext {
+    /// A context with no bindings and no default namespace.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A context whose default namespace is the OSCAL namespace.
+    pub fn oscal() -> Self {
+        Self::new().with_default(URIDatatype::try_from(OSCAL_NAMESPACE).expect("OSCAL namespace"))
+    }
+
+    pub fn with_default(mut self, namespace: URIDatatype) -> Self {
+        self.default = Some(namespace);
+        self
+    }
+
+    /// Bind `prefix` to `namespace`, replacing any earlier binding.  The
+    /// reserved `xml` and `xmlns` prefixes cannot be rebound.
+    pub fn bind(&mut self, prefix: &str, namespace: URIDatatype) -> Result<(), Error> {
+        let prefix = non_empty_ncname(prefix)?;
+        if matches!(&*prefix, "xml" | "xmlns") {
+            return Err(Error::QNameParse(format!(
+                "prefix {} is reserved",
+                &*prefix
+            )));
+        }
+        self.prefixes.insert(prefix.to_string(), namespace);
+        Ok(())
+    }
+
+    /// The namespace bound to `prefix`, or the default namespace for `None`.
+    pub fn namespace(&self, prefix: Option<&str>) -> Option<&URIDatatype> {
+        match prefix {
+            Some(prefix) => self.prefixes.get(prefix),
+            None => self.default.as_ref(),
+        }
+    }
+
+    /// Resolve a parsed name to its namespace and local name.
+    pub fn resolve_qname(&self, qname: &QName) -> Result<(URIDatatype, NCName), Error> {
+        let prefix = qname.prefix().map(|p| &**p);
+        match self.namespace(prefix) {
+            Some(namespace) => Ok((namespace.clone(), qname.local().clone())),
+            None => Err(Error::UnboundPrefix(prefix.unwrap_or("").to_string())),
+        }
+    }
+
+    /// Parse and resolve `prefix:local` or `local`.
+    pub fn resolve(&self, qname: &str) -> Result<(URIDatatype, NCName), Error> {
+        self.resolve_qname(&qname.parse()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_qname() {
+        let name = "ns:abc".parse::<QName>().unwrap();
+        assert_eq!(name.prefix().map(|p| &**p), Some("ns"));
+        assert_eq!(&**name.local(), "abc");
+        assert_eq!(name.to_string(), "ns:abc");
+
+        assert!("abc".parse::<QName>().unwrap().prefix().is_none());
+        assert!(":abc".parse::<QName>().is_err());
+        assert!("ns:".parse::<QName>().is_err());
+        assert!("a:b:c".parse::<QName>().is_err());
+        assert!("1ns:abc".parse::<QName>().is_err());
+    }
+
+    #[test]
+    fn test_resolve() {
+        let mut context = NamespaceContext::new();
+        let ns = URIDatatype::try_from("https://example.com/ns").unwrap();
+        context.bind("ex", ns.clone()).unwrap();
+
+        assert_eq!(context.resolve("ex:name").unwrap().0, ns);
+        assert!(matches!(
+            context.resolve("other:name"),
+            Err(Error::UnboundPrefix(p)) if p == "other"
+        ));
+        // No default namespace
+        assert!(context.resolve("name").is_err());
+        assert!(context.bind("xml", ns).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Datatypes that never validate.
+//!
+//! Every datatype is available here under its usual name as a [Raw]
+//! wrapper, which keeps the lexical form exactly as read.  This suits tools
+//! that must load and rewrite documents with invalid values.  A raw value
+//! can be checked later with [Raw::validate] or [Raw::parse].  See
+//! [crate::checked] for the opposite.
+//!
+//! ```
+//! use oscal_types::raw::{DateDatatype, PositiveIntegerDatatype};
+//!
+//! let date: DateDatatype = serde_json::from_str(r#""2024-02-30""#).unwrap();
+//! assert_eq!(&*date, "2024-02-30");
+//! assert!(date.validate().is_err());
+//!
+//! let count: PositiveIntegerDatatype = serde_json::from_str("0").unwrap();
+//! assert_eq!(serde_json::to_string(&count).unwrap(), "0");
+//! ```
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::macros::for_each_datatype;
+use crate::registry::from_lexical;
+use crate::walk::lexical_form;
+use crate::*;
+
+/// The unvalidated lexical form of a `T`.
+pub struct Raw<T> {
+    lexical: String,
+    datatype: PhantomData<fn() -> T>,
+}
+
+impl<T: OscalDatatype> Raw<T> {
+    /// Wrap `value` without validating it.
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            lexical: value.into(),
+            datatype: PhantomData,
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.lexical
+    }
+
+    /// Validate the value as a `T`, with [Validate::validate_strict].
+    pub fn validate(&self) -> Result<(), Error> {
+        T::validate_strict(&self.lexical)
+    }
+
+    /// Validate and parse the value as a `T`.
+    pub fn parse(&self) -> Result<T, Error> {
+        self.validate()?;
+        from_lexical(&self.lexical)
+    }
+}
+
+impl<T: OscalDatatype> From<&str> for Raw<T> {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for Raw<T> {
+    type Target = str;
+    fn deref(&self) -> &Self::Target {
+        &self.lexical
+    }
+}
+
+impl<T> Clone for Raw<T> {
+    fn clone(&self) -> Self {
+        Self {
+            lexical: self.lexical.clone(),
+            datatype: PhantomData,
+        }
+    }
+}
+
+impl<T> PartialEq for Raw<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.lexical == other.lexical
+    }
+}
+
+impl<T> Eq for Raw<T> {}
+
+impl<T> Hash for Raw<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.lexical.hash(state);
+    }
+}
+
+impl<T> PartialOrd for Raw<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Ordered by lexical form.
+impl<T> Ord for Raw<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lexical.cmp(&other.lexical)
+    }
+}
+
+impl<T> fmt::Debug for Raw<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Raw").field(&self.lexical).finish()
+    }
+}
+
+/// Numbers and booleans are written as JSON literals when the lexical form
+/// is one, and as strings otherwise.
+impl<T: OscalDatatype> Serialize for Raw<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match T::_type() {
+            Some("string") | None => serializer.serialize_str(&self.lexical),
+            Some(_) => match serde_json::from_str::<Value>(&self.lexical) {
+                Ok(literal @ (Value::Number(_) | Value::Bool(_))) => literal.serialize(serializer),
+                _ => serializer.serialize_str(&self.lexical),
+            },
+        }
+    }
+}
+
+impl<'de, T: OscalDatatype> Deserialize<'de> for Raw<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        lexical_form(&value)
+            .map(Self::new)
+            .ok_or_else(|| serde::de::Error::custom("expected a string, number or boolean"))
+    }
+}
+
+macro_rules! raw_aliases {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        $(pub type $t = Raw<crate::$t>;)*
+    };
+}
+
+for_each_datatype!(raw_aliases);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_raw() {
+        let uuid: UUIDDatatype = serde_json::from_str(r#""not-a-uuid""#).unwrap();
+        assert!(uuid.parse().is_err());
+        assert_eq!(serde_json::to_string(&uuid).unwrap(), r#""not-a-uuid""#);
+
+        let flag: BooleanDatatype = serde_json::from_str("true").unwrap();
+        assert_eq!(serde_json::to_string(&flag).unwrap(), "true");
+        assert!(*flag.parse().unwrap());
+
+        let count = PositiveIntegerDatatype::from("many");
+        assert_eq!(serde_json::to_string(&count).unwrap(), r#""many""#);
+        assert!(count.validate().is_err());
+    }
+}
@@ -0,0 +1,366 @@
+//! Runtime lookup of datatypes by name.
+//!
+//! A [TypeRegistry] maps datatype names to a [TypeInfo] describing the type
+//! and holding its validator and constructor.  [TypeRegistry::builtin]
+//! knows every datatype in this crate; code generators and schema processors
+//! can register their own on top.
+//!
+//! ```
+//! use oscal_types::{registry::TypeRegistry, DateDatatype};
+//!
+//! let registry = TypeRegistry::builtin();
+//! assert!(registry.validate("DateDatatype", "2024-02-10").is_ok());
+//! assert!(registry.validate("UUIDDatatype", "not-a-uuid").is_err());
+//!
+//! let date = registry.construct("DateDatatype", "2024-02-10").unwrap();
+//! assert_eq!(&**date.downcast_ref::<DateDatatype>().unwrap(), "2024-02-10");
+//! ```
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::macros::for_each_datatype;
+use crate::*;
+
+type Validator = Arc<dyn Fn(&str) -> Result<(), Error> + Send + Sync>;
+type Constructor = Arc<dyn Fn(&str) -> Result<Box<dyn Any + Send + Sync>, Error> + Send + Sync>;
+
+/// Everything known about a datatype at runtime.
+#[derive(Clone)]
+pub struct TypeInfo {
+    name: String,
+    base_type: String,
+    ref_type: String,
+    json_type: Option<String>,
+    description: Option<String>,
+    pattern: Option<String>,
+    format: Option<String>,
+    content_encoding: Option<String>,
+    minimum: Option<i64>,
+    validator: Validator,
+    constructor: Option<Constructor>,
+}
+
+impl TypeInfo {
+    /// A type validated by `validator`.  The base and reference types
+    /// default to `String` and `str`.
+    pub fn new(
+        name: &str,
+        validator: impl Fn(&str) -> Result<(), Error> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            base_type: String::from("String"),
+            ref_type: String::from("str"),
+            json_type: Some(String::from("string")),
+            description: None,
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            minimum: None,
+            validator: Arc::new(validator),
+            constructor: None,
+        }
+    }
+
+    /// The description of `T`, with its [Validate] impl as validator and its
+    /// `Deserialize` impl as constructor.
+    pub fn of<T>(name: &str) -> Self
+    where
+        T: Base + Metaschema + Validate + DeserializeOwned + Send + Sync + 'static,
+    {
+        Self {
+            name: name.to_string(),
+            base_type: T::base_type(),
+            ref_type: T::ref_type(),
+            json_type: T::_type().map(String::from),
+            description: T::description().map(String::from),
+            pattern: None,
+            format: None,
+            content_encoding: None,
+            minimum: None,
+            validator: Arc::new(T::validate),
+            constructor: Some(Arc::new(construct::<T>)),
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(pattern.to_string());
+        self
+    }
+
+    /// The JSON schema format, e.g. `date-time`.
+    pub fn with_format(mut self, format: &str) -> Self {
+        self.format = Some(format.to_string());
+        self
+    }
+
+    pub fn with_content_encoding(mut self, content_encoding: &str) -> Self {
+        self.content_encoding = Some(content_encoding.to_string());
+        self
+    }
+
+    pub fn with_minimum(mut self, minimum: i64) -> Self {
+        self.minimum = Some(minimum);
+        self
+    }
+
+    pub fn with_types(mut self, base_type: &str, ref_type: &str) -> Self {
+        self.base_type = base_type.to_string();
+        self.ref_type = ref_type.to_string();
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn base_type(&self) -> &str {
+        &self.base_type
+    }
+
+    pub fn ref_type(&self) -> &str {
+        &self.ref_type
+    }
+
+    /// The JSON schema type, e.g. `string` or `integer`.
+    pub fn json_type(&self) -> Option<&str> {
+        self.json_type.as_deref()
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn pattern(&self) -> Option<&str> {
+        self.pattern.as_deref()
+    }
+
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.content_encoding.as_deref()
+    }
+
+    pub fn minimum(&self) -> Option<i64> {
+        self.minimum
+    }
+
+    pub fn validate(&self, value: &str) -> Result<(), Error> {
+        (self.validator)(value)
+    }
+}
+
+impl fmt::Debug for TypeInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TypeInfo")
+            .field("name", &self.name)
+            .field("base_type", &self.base_type)
+            .field("ref_type", &self.ref_type)
+            .field("json_type", &self.json_type)
+            .field("description", &self.description)
+            .field("pattern", &self.pattern)
+            .field("format", &self.format)
+            .field("content_encoding", &self.content_encoding)
+            .field("minimum", &self.minimum)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Deserialize `T` from its lexical form, checked in the
+/// [ValidationMode] in effect.  Non string types are read as the JSON
+/// literal, e.g. `42` or `true`.
+pub(crate) fn from_lexical<T>(value: &str) -> Result<T, Error>
+where
+    T: Metaschema + Validate + DeserializeOwned,
+{
+    crate::validate::validate_in_mode::<T>(value)?;
+    let json = match T::_type() {
+        Some("string") | None => Value::String(value.to_string()),
+        Some(_) => serde_json::from_str(value).map_err(|e| Error::StringParse(e.to_string()))?,
+    };
+    T::deserialize(&json).map_err(|e| Error::StringParse(e.to_string()))
+}
+
+fn construct<T>(value: &str) -> Result<Box<dyn Any + Send + Sync>, Error>
+where
+    T: Metaschema + Validate + DeserializeOwned + Send + Sync + 'static,
+{
+    Ok(Box::new(from_lexical::<T>(value)?))
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    types: BTreeMap<String, TypeInfo>,
+}
+
+/// The [TypeInfo] of `$t`, with the facets of its `$facets` trait.
+macro_rules! builtin_info {
+    (plain, $t:ident) => {
+        TypeInfo::of::<$t>(stringify!($t))
+    };
+    (number, $t:ident) => {{
+        let mut info = TypeInfo::of::<$t>(stringify!($t));
+        info.minimum = <$t as NumberType>::minimum();
+        info
+    }};
+    (string, $t:ident) => {{
+        let mut info = TypeInfo::of::<$t>(stringify!($t));
+        info.pattern = <$t as StringType>::pattern().map(String::from);
+        info.format = <$t as StringType>::format().map(String::from);
+        info.content_encoding = <$t as StringType>::content_encoding().map(String::from);
+        info
+    }};
+}
+
+macro_rules! register_builtin {
+    ($($kind:ident => $t:ident: $facets:ident),* $(,)?) => {
+        fn register_builtin(registry: &mut TypeRegistry) {
+            $(registry.register(builtin_info!($facets, $t));)*
+        }
+    };
+}
+
+for_each_datatype!(register_builtin, facets);
+
+impl TypeRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry of every datatype in this crate.
+    pub fn builtin() -> Self {
+        let mut registry = Self::new();
+        register_builtin(&mut registry);
+        registry
+    }
+
+    /// Add `info`, returning the registration it replaces, if any.
+    pub fn register(&mut self, info: TypeInfo) -> Option<TypeInfo> {
+        self.types.insert(info.name.clone(), info)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&TypeInfo> {
+        self.types.get(name)
+    }
+
+    /// Registered names, in sorted order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.types.keys().map(String::as_str)
+    }
+
+    fn lookup(&self, name: &str) -> Result<&TypeInfo, Error> {
+        self.get(name)
+            .ok_or_else(|| Error::UnrecognizedTypeName(name.to_string()))
+    }
+
+    /// Validate `value` against the datatype registered as `name`.
+    pub fn validate(&self, name: &str, value: &str) -> Result<(), Error> {
+        self.lookup(name)?.validate(value)
+    }
+
+    /// Construct a value of the datatype registered as `name`.  Use
+    /// `downcast` to recover the concrete type.
+    pub fn construct(&self, name: &str, value: &str) -> Result<Box<dyn Any + Send + Sync>, Error> {
+        let info = self.lookup(name)?;
+        match &info.constructor {
+            Some(constructor) => constructor(value),
+            None => Err(Error::StringParse(format!("{name} has no constructor"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_covers_every_kind() {
+        let registry = TypeRegistry::builtin();
+        assert_eq!(registry.names().count(), DatatypeKind::ALL.len());
+        for kind in DatatypeKind::ALL {
+            let info = registry.get(kind.name()).expect("registered");
+            assert_eq!(info.base_type(), kind.base_type());
+            assert!(info.description().is_some());
+        }
+        assert!(registry.get("DateDatatype").unwrap().pattern().is_some());
+        assert_eq!(
+            registry.get("PositiveIntegerDatatype").unwrap().minimum(),
+            Some(1)
+        );
+        assert_eq!(
+            registry.get("IntegerDatatype").unwrap().json_type(),
+            Some("integer")
+        );
+    }
+
+    #[test]
+    fn test_builtin_patterns_compile() {
+        let registry = TypeRegistry::builtin();
+        for kind in DatatypeKind::ALL {
+            let info = registry.get(kind.name()).unwrap();
+            if let Some(pattern) = info.pattern() {
+                assert!(regex::Regex::new(pattern).is_ok(), "{kind}");
+            }
+        }
+        let ipv4 = registry
+            .get("IPV4AddressDatatype")
+            .unwrap()
+            .pattern()
+            .unwrap();
+        assert!(regex::Regex::new(ipv4).unwrap().is_match("10.0.0.1"));
+    }
+
+    #[test]
+    fn test_email_is_a_format() {
+        let registry = TypeRegistry::builtin();
+        let email = registry.get("EmailAddressDatatype").unwrap();
+        assert_eq!(email.format(), Some("email"));
+        assert_eq!(email.content_encoding(), None);
+    }
+
+    #[test]
+    fn test_only_base64_has_a_content_encoding() {
+        let registry = TypeRegistry::builtin();
+        for &kind in DatatypeKind::ALL {
+            let encoding = registry.get(kind.name()).unwrap().content_encoding();
+            let expected = (kind == DatatypeKind::Base64).then_some("base64");
+            assert_eq!(encoding, expected, "{kind}");
+        }
+    }
+
+    #[test]
+    fn test_construct() {
+        let registry = TypeRegistry::builtin();
+        let n = registry.construct("PositiveIntegerDatatype", "7").unwrap();
+        assert_eq!(**n.downcast_ref::<PositiveIntegerDatatype>().unwrap(), 7);
+        assert!(registry.construct("PositiveIntegerDatatype", "0").is_err());
+        assert!(registry.construct("NoSuchDatatype", "0").is_err());
+    }
+
+    #[test]
+    fn test_user_registered_type() {
+        let mut registry = TypeRegistry::builtin();
+        registry.register(
+            TypeInfo::new("ControlId", |value| match value.starts_with("ac-") {
+                true => Ok(()),
+                false => Err(Error::StringParse("not an AC control".to_string())),
+            })
+            .with_description("An access control identifier"),
+        );
+        assert!(registry.validate("ControlId", "ac-2").is_ok());
+        assert!(registry.validate("ControlId", "au-2").is_err());
+        assert!(registry.construct("ControlId", "ac-2").is_err());
+    }
+}
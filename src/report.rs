@@ -0,0 +1,535 @@
+//! Detailed validation results, e.g. for form feedback.
+//!
+//! [Validate::validate] stops at the first
+//! problem.  [validate_detailed] instead reports every constraint a value
+//! violates, each with a machine-readable code and, where there is one, the
+//! index of the offending character, so a UI can highlight it.
+//!
+//! ```
+//! use oscal_types::{report::Constraint, OscalDatatype, TokenDatatype};
+//!
+//! let report = TokenDatatype::validate_detailed("1 ac");
+//! assert!(!report.is_valid());
+//! let found: Vec<_> = report.violations().iter().map(|v| (v.code, v.index)).collect();
+//! assert_eq!(found, [("illegal-start-char", Some(0)), ("illegal-char", Some(1))]);
+//! assert_eq!(report.violations()[0].constraint, Constraint::Pattern);
+//! ```
+//!
+//! A report is valid exactly when the datatype's validator accepts the
+//! value.  Problems that are not broken down further are reported as a
+//! single violation with the [Error::code] of the validation error.
+use serde::Serialize;
+use std::fmt;
+
+use crate::nc_name::NCName;
+use crate::*;
+
+/// The kind of constraint a value violates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Constraint {
+    /// A character that the datatype's pattern does not allow.
+    Pattern,
+    /// The overall form of the value, e.g. a missing part.
+    Format,
+    /// A number or date component outside its allowed range.
+    Bounds,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Violation {
+    pub constraint: Constraint,
+    /// A stable, machine-readable code, e.g. `"illegal-char"`.
+    pub code: &'static str,
+    /// The char index of the offending character, if there is one.
+    pub index: Option<usize>,
+    pub message: String,
+}
+
+impl Violation {
+    fn new(
+        constraint: Constraint,
+        code: &'static str,
+        index: Option<usize>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            constraint,
+            code,
+            index,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.index {
+            Some(index) => write!(f, "at {index}: {} ({})", self.message, self.code),
+            None => write!(f, "{} ({})", self.message, self.code),
+        }
+    }
+}
+
+/// Every constraint a value violates, in the order found.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationReport {
+    kind: DatatypeKind,
+    violations: Vec<Violation>,
+}
+
+impl ValidationReport {
+    pub fn kind(&self) -> DatatypeKind {
+        self.kind
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+}
+
+/// One line per violation.
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_valid() {
+            return write!(f, "{}: valid", self.kind);
+        }
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}: {violation}", self.kind)?;
+        }
+        Ok(())
+    }
+}
+
+/// Validate `value` as the datatype `kind`, collecting every violation.
+pub fn validate_detailed(kind: DatatypeKind, value: &str) -> ValidationReport {
+    let mut violations = vec![];
+    if let Err(error) = kind.validate(value) {
+        let chars: Vec<char> = value.chars().collect();
+        match kind {
+            DatatypeKind::Token => token(&chars, &mut violations),
+            DatatypeKind::String => string(&chars, &mut violations),
+            DatatypeKind::Uuid => uuid(&chars, &error, &mut violations),
+            DatatypeKind::Base64 => base64(&chars, &mut violations),
+            DatatypeKind::Decimal => number(&chars, true, None, &mut violations),
+            DatatypeKind::Integer => number(
+                &chars,
+                false,
+                Some((i64::MIN.into(), i64::MAX.into())),
+                &mut violations,
+            ),
+            DatatypeKind::NonNegativeInteger => number(
+                &chars,
+                false,
+                Some((minimum::<NonNegativeIntegerDatatype>(), u64::MAX.into())),
+                &mut violations,
+            ),
+            DatatypeKind::PositiveInteger => number(
+                &chars,
+                false,
+                Some((minimum::<PositiveIntegerDatatype>(), u64::MAX.into())),
+                &mut violations,
+            ),
+            DatatypeKind::Date if date(&chars, &mut violations) && chars.len() > 10 => {
+                violations.push(Violation::new(
+                    Constraint::Pattern,
+                    "unexpected-char",
+                    Some(10),
+                    "unexpected text after the date",
+                ));
+            }
+            DatatypeKind::DateTime => date_time(&chars, false, &mut violations),
+            DatatypeKind::DateTimeWithTimezone => date_time(&chars, true, &mut violations),
+            _ => {}
+        }
+        if violations.is_empty() {
+            violations.push(Violation::new(
+                Constraint::Format,
+                error.code(),
+                None,
+                error.to_string(),
+            ));
+        }
+    }
+    ValidationReport { kind, violations }
+}
+
+fn minimum<T: NumberType>() -> i128 {
+    T::minimum().unwrap_or(i64::MIN).into()
+}
+
+fn illegal_char(index: usize, c: char, expected: &str) -> Violation {
+    Violation::new(
+        Constraint::Pattern,
+        "illegal-char",
+        Some(index),
+        format!("{c:?} is not allowed, expected {expected}"),
+    )
+}
+
+fn token(chars: &[char], violations: &mut Vec<Violation>) {
+    for (i, &c) in chars.iter().enumerate() {
+        if i == 0 && !NCName::is_valid_start_char(c) {
+            violations.push(Violation::new(
+                Constraint::Pattern,
+                "illegal-start-char",
+                Some(0),
+                format!("{c:?} cannot start a token, expected a letter or '_'"),
+            ));
+        } else if i > 0 && !NCName::is_valid_char(c) {
+            violations.push(illegal_char(i, c, "a letter, digit, '-', '_' or '.'"));
+        }
+    }
+}
+
+fn string(chars: &[char], violations: &mut Vec<Violation>) {
+    if chars.first().is_some_and(|c| c.is_whitespace()) {
+        violations.push(Violation::new(
+            Constraint::Pattern,
+            "leading-whitespace",
+            Some(0),
+            "leading whitespace is not allowed",
+        ));
+    }
+    if chars.last().is_some_and(|c| c.is_whitespace()) {
+        violations.push(Violation::new(
+            Constraint::Pattern,
+            "trailing-whitespace",
+            Some(chars.len() - 1),
+            "trailing whitespace is not allowed",
+        ));
+    }
+}
+
+fn uuid(chars: &[char], error: &Error, violations: &mut Vec<Violation>) {
+    if chars.len() != 36 {
+        violations.push(Violation::new(
+            Constraint::Format,
+            "length",
+            None,
+            format!("expected 36 characters, found {}", chars.len()),
+        ));
+    }
+    for (i, &c) in chars.iter().enumerate().take(36) {
+        match i {
+            8 | 13 | 18 | 23 if c != '-' => violations.push(illegal_char(i, c, "'-'")),
+            8 | 13 | 18 | 23 => {}
+            _ if !c.is_ascii_hexdigit() => violations.push(illegal_char(i, c, "a hex digit")),
+            _ => {}
+        }
+    }
+    if let Error::UuidVersion(version) = error {
+        violations.push(Violation::new(
+            Constraint::Format,
+            "uuid-version",
+            Some(14),
+            format!("version {version} UUIDs are not allowed"),
+        ));
+    }
+}
+
+fn base64(chars: &[char], violations: &mut Vec<Violation>) {
+    let data = chars.iter().rposition(|&c| c != '=').map_or(0, |i| i + 1);
+    for (i, &c) in chars[..data].iter().enumerate() {
+        match c {
+            '=' => violations.push(Violation::new(
+                Constraint::Pattern,
+                "misplaced-padding",
+                Some(i),
+                "'=' is only allowed at the end",
+            )),
+            'A'..='Z' | 'a'..='z' | '0'..='9' | '+' | '/' => {}
+            _ => violations.push(illegal_char(i, c, "a base64 character")),
+        }
+    }
+    if !chars.len().is_multiple_of(4) {
+        violations.push(Violation::new(
+            Constraint::Format,
+            "length",
+            None,
+            format!("length {} is not a multiple of 4", chars.len()),
+        ));
+    }
+}
+
+/// Decimal and integer lexical forms, and for integers the range
+/// `bounds`.
+fn number(
+    chars: &[char],
+    decimal: bool,
+    bounds: Option<(i128, i128)>,
+    violations: &mut Vec<Violation>,
+) {
+    let start = usize::from(matches!(chars.first(), Some('+' | '-')));
+    let mut point = false;
+    let found = violations.len();
+    for (i, &c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '0'..='9' => {}
+            '.' if decimal && !point => point = true,
+            _ if decimal => violations.push(illegal_char(i, c, "a digit or '.'")),
+            _ => violations.push(illegal_char(i, c, "a digit")),
+        }
+    }
+    let Some((min, max)) = bounds else {
+        return;
+    };
+    if violations.len() > found || chars.len() == start {
+        return;
+    }
+    let text: String = chars.iter().collect();
+    let negative = chars[0] == '-';
+    let value = text
+        .parse::<i128>()
+        .unwrap_or(if negative { i128::MIN } else { i128::MAX });
+    if value < min {
+        violations.push(Violation::new(
+            Constraint::Bounds,
+            "below-minimum",
+            None,
+            format!("must be at least {min}"),
+        ));
+    } else if value > max {
+        violations.push(Violation::new(
+            Constraint::Bounds,
+            "above-maximum",
+            None,
+            format!("must be at most {max}"),
+        ));
+    }
+}
+
+/// Check `chars` against a template of digits (`d`) and literal
+/// characters, returning the digits if they all match.
+fn template(chars: &[char], start: usize, template: &str, violations: &mut Vec<Violation>) -> bool {
+    let found = violations.len();
+    for (offset, expected) in template.chars().enumerate() {
+        let i = start + offset;
+        let Some(&c) = chars.get(i) else {
+            violations.push(Violation::new(
+                Constraint::Format,
+                "incomplete",
+                Some(i),
+                format!("incomplete, expected {template:?}"),
+            ));
+            break;
+        };
+        match expected {
+            'd' if !c.is_ascii_digit() => violations.push(illegal_char(i, c, "a digit")),
+            'd' => {}
+            _ if c != expected => violations.push(illegal_char(i, c, &format!("{expected:?}"))),
+            _ => {}
+        }
+    }
+    violations.len() == found
+}
+
+fn field(chars: &[char], start: usize, len: usize) -> u32 {
+    chars[start..start + len]
+        .iter()
+        .fold(0, |n, c| n * 10 + c.to_digit(10).unwrap_or(0))
+}
+
+fn out_of_range(code: &'static str, index: usize, message: &str) -> Violation {
+    Violation::new(Constraint::Bounds, code, Some(index), message)
+}
+
+/// Check the `YYYY-MM-DD` date at the start of `chars`, returning whether
+/// it is valid.
+fn date(chars: &[char], violations: &mut Vec<Violation>) -> bool {
+    if !template(chars, 0, "dddd-dd-dd", violations) {
+        return false;
+    }
+    let (year, month, day) = (field(chars, 0, 4), field(chars, 5, 2), field(chars, 8, 2));
+    let leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => {
+            violations.push(out_of_range(
+                "month-out-of-range",
+                5,
+                "the month must be 01 to 12",
+            ));
+            return false;
+        }
+    };
+    if day == 0 || day > days {
+        violations.push(out_of_range(
+            "day-out-of-range",
+            8,
+            &format!("the day must be 01 to {days} in this month"),
+        ));
+        return false;
+    }
+    true
+}
+
+fn date_time(chars: &[char], timezone: bool, violations: &mut Vec<Violation>) {
+    date(chars, violations);
+    match chars.get(10) {
+        None if chars.len() == 10 => {
+            violations.push(Violation::new(
+                Constraint::Format,
+                "missing-time",
+                Some(10),
+                "a time is required, e.g. T12:00:00",
+            ));
+            return;
+        }
+        Some('T' | 't' | ' ') => {}
+        Some(&c) => violations.push(illegal_char(10, c, "'T'")),
+        None => return,
+    }
+    if template(chars, 11, "dd:dd:dd", violations) {
+        if field(chars, 11, 2) > 23 {
+            violations.push(out_of_range(
+                "hour-out-of-range",
+                11,
+                "the hour must be 00 to 23",
+            ));
+        }
+        if field(chars, 14, 2) > 59 {
+            violations.push(out_of_range(
+                "minute-out-of-range",
+                14,
+                "the minute must be 00 to 59",
+            ));
+        }
+        if field(chars, 17, 2) > 60 {
+            violations.push(out_of_range(
+                "second-out-of-range",
+                17,
+                "the second must be 00 to 60",
+            ));
+        }
+        let mut end = 19;
+        if chars.get(end) == Some(&'.') {
+            end += 1;
+            while chars.get(end).is_some_and(char::is_ascii_digit) {
+                end += 1;
+            }
+        }
+        if timezone && end == chars.len() {
+            violations.push(Violation::new(
+                Constraint::Format,
+                "missing-timezone",
+                Some(end),
+                "a timezone is required, e.g. Z or +05:00",
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn codes(kind: DatatypeKind, value: &str) -> Vec<(&'static str, Option<usize>)> {
+        validate_detailed(kind, value)
+            .violations()
+            .iter()
+            .map(|v| (v.code, v.index))
+            .collect()
+    }
+
+    #[test]
+    fn test_agrees_with_validate() {
+        for &kind in DatatypeKind::ALL {
+            let help = crate::help::describe(kind);
+            assert!(validate_detailed(kind, help.example).is_valid(), "{kind}");
+            for value in ["", " x", "1 ac", "2024-13-45T25:00:00", "-1", "%%"] {
+                assert_eq!(
+                    validate_detailed(kind, value).is_valid(),
+                    kind.validate(value).is_ok(),
+                    "{kind}: {value:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_violations() {
+        assert_eq!(
+            codes(DatatypeKind::String, " padded "),
+            [
+                ("leading-whitespace", Some(0)),
+                ("trailing-whitespace", Some(7))
+            ]
+        );
+        let strict = config::Config {
+            strict_uuid_versions: true,
+            ..config::Config::new()
+        };
+        assert_eq!(
+            config::scoped(strict, || codes(
+                DatatypeKind::Uuid,
+                "a78f7e4c-a27a-1b1e-901b-ebfecf2b0301"
+            )),
+            [("uuid-version", Some(14))]
+        );
+        assert_eq!(
+            codes(DatatypeKind::Uuid, "a78f7e4cxa27a-4b1e-901b-ebfecf2b03g"),
+            [
+                ("length", None),
+                ("illegal-char", Some(8)),
+                ("illegal-char", Some(34))
+            ]
+        );
+        assert_eq!(
+            codes(DatatypeKind::PositiveInteger, "0"),
+            [("below-minimum", None)]
+        );
+        assert_eq!(
+            codes(DatatypeKind::Integer, "1x2y"),
+            [("illegal-char", Some(1)), ("illegal-char", Some(3))]
+        );
+        assert_eq!(
+            codes(DatatypeKind::Base64, "ab=c!"),
+            [
+                ("misplaced-padding", Some(2)),
+                ("illegal-char", Some(4)),
+                ("length", None)
+            ]
+        );
+        assert_eq!(
+            codes(DatatypeKind::DateTimeWithTimezone, "2024-02-30T24:00:00"),
+            [
+                ("day-out-of-range", Some(8)),
+                ("hour-out-of-range", Some(11)),
+                ("missing-timezone", Some(19))
+            ]
+        );
+        assert_eq!(
+            codes(DatatypeKind::EmailAddress, "nobody"),
+            [("email-missing-at", None)]
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        #[cfg(feature = "date_validation")]
+        {
+            let report = validate_detailed(DatatypeKind::Date, "2024-13-01");
+            assert_eq!(
+                report.to_string(),
+                "DateDatatype: at 5: the month must be 01 to 12 (month-out-of-range)"
+            );
+            let json = serde_json::to_value(&report).unwrap();
+            assert_eq!(json["violations"][0]["constraint"], "bounds");
+        }
+        assert_eq!(
+            validate_detailed(DatatypeKind::Date, "2024-01-01").to_string(),
+            "DateDatatype: valid"
+        );
+    }
+}
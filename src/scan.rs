@@ -0,0 +1,213 @@
+//! Extract datatype values from streams of log lines.
+//!
+//! A [Scanner] reads newline-delimited input, such as NDJSON logs, one line
+//! at a time and yields every valid value of the requested datatypes with
+//! its byte offset in the stream.  Datatypes that are
+//! [Searchable](crate::search::Searchable) are found anywhere in a line, as
+//! by [crate::search::find_all]; other datatypes are matched against whole
+//! JSON string values.
+//!
+//! ```
+//! use oscal_types::{scan::Scanner, DatatypeKind};
+//!
+//! let log = br#"{"ts":"2024-02-10T09:30:00Z","msg":"updated 5c8b5b4e-4a2f-4e0c-9a54-3c9a8f3a9e1d"}
+//! {"ts":"not a time","msg":"nothing to see"}
+//! "#;
+//! let scanner = Scanner::new([DatatypeKind::Uuid, DatatypeKind::DateTimeWithTimezone]);
+//! let found: Vec<_> = scanner.scan(&log[..]).map(Result::unwrap).collect();
+//! assert_eq!(found.len(), 2);
+//! assert_eq!(found[0].kind, DatatypeKind::DateTimeWithTimezone);
+//! assert_eq!(found[0].offset, 7..27);
+//! assert_eq!(found[1].line, 1);
+//! ```
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::BufRead;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use crate::search::{is_token, search_pattern};
+use crate::value::OscalValue;
+use crate::*;
+
+/// A JSON string literal, with its contents in the first group.
+static STRING_LITERAL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#""((?:[^"\\]|\\.)*)"(\s*:)?"#).expect("invalid pattern"));
+
+/// A value found by a [Scanner].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Found {
+    pub kind: DatatypeKind,
+    pub value: OscalValue,
+    /// The byte range of the lexical form in the stream.  For a JSON string
+    /// with escapes, this is the range of the escaped contents.
+    pub offset: Range<usize>,
+    /// The line number, counting from 1.
+    pub line: usize,
+}
+
+/// Extracts values of a set of datatypes from newline-delimited input.
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    kinds: Vec<DatatypeKind>,
+}
+
+impl Scanner {
+    pub fn new(kinds: impl IntoIterator<Item = DatatypeKind>) -> Self {
+        Self {
+            kinds: kinds.into_iter().collect(),
+        }
+    }
+
+    /// Every value in `reader`, in stream order.  Input is read a line at a
+    /// time, so only the current line is held in memory.  Bytes that are
+    /// not UTF-8 are skipped.
+    pub fn scan<R: BufRead>(self, reader: R) -> Scan<R> {
+        Scan {
+            scanner: self,
+            reader,
+            buffer: Vec::new(),
+            line: 0,
+            offset: 0,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The values in `text`, which starts at byte `offset` of line `line`.
+    fn scan_text(&self, text: &str, offset: usize, line: usize, found: &mut Vec<Found>) {
+        let mut push = |kind, value, range: Range<usize>| {
+            found.push(Found {
+                kind,
+                value,
+                offset: offset + range.start..offset + range.end,
+                line,
+            })
+        };
+        for &kind in &self.kinds {
+            match search_pattern(kind) {
+                Some(pattern) => {
+                    for m in pattern.find_iter(text) {
+                        if !is_token(text, &m.range()) {
+                            continue;
+                        }
+                        if let Ok(value) = OscalValue::parse(kind, m.as_str()) {
+                            push(kind, value, m.range());
+                        }
+                    }
+                }
+                None => {
+                    for captures in STRING_LITERAL.captures_iter(text) {
+                        // Skip object keys.
+                        if captures.get(2).is_some() {
+                            continue;
+                        }
+                        let contents = captures.get(1).expect("a group");
+                        let lexical = match contents.as_str().contains('\\') {
+                            true => match serde_json::from_str::<String>(&captures[0]) {
+                                Ok(unescaped) => unescaped,
+                                Err(_) => continue,
+                            },
+                            false => contents.as_str().to_string(),
+                        };
+                        if let Ok(value) = OscalValue::parse(kind, &lexical) {
+                            push(kind, value, contents.range());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The iterator returned by [Scanner::scan].
+pub struct Scan<R> {
+    scanner: Scanner,
+    reader: R,
+    buffer: Vec<u8>,
+    line: usize,
+    offset: usize,
+    pending: VecDeque<Found>,
+}
+
+impl<R: BufRead> Iterator for Scan<R> {
+    type Item = Result<Found, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pending.is_empty() {
+            self.buffer.clear();
+            match self.reader.read_until(b'\n', &mut self.buffer) {
+                Ok(0) => return None,
+                Ok(read) => {
+                    self.line += 1;
+                    let mut found = vec![];
+                    let mut start = self.offset;
+                    for chunk in self.buffer.utf8_chunks() {
+                        let text = chunk.valid();
+                        self.scanner.scan_text(text, start, self.line, &mut found);
+                        start += text.len() + chunk.invalid().len();
+                    }
+                    found.sort_by_key(|f| f.offset.start);
+                    self.pending.extend(found);
+                    self.offset += read;
+                }
+                Err(e) => return Some(Err(Error::Io(e.to_string()))),
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan() {
+        let mut bytes = br#"{"event":"login","user":"ops@example.gov","from":"10.0.0.1"}"#.to_vec();
+        bytes.extend_from_slice(b"\n\xff");
+        bytes.extend_from_slice(br#"{"control":"ac-1","token":"1ac","at":"2024-02-10"}"#);
+        let scanner = Scanner::new([
+            DatatypeKind::Token,
+            DatatypeKind::Date,
+            DatatypeKind::Ipv4Address,
+        ]);
+        let found: Vec<Found> = scanner.scan(&bytes[..]).map(Result::unwrap).collect();
+        let lexical: Vec<(&str, usize)> = found
+            .iter()
+            .map(|f| {
+                (
+                    std::str::from_utf8(&bytes[f.offset.clone()]).unwrap(),
+                    f.line,
+                )
+            })
+            .collect();
+        assert_eq!(
+            lexical,
+            [
+                ("login", 1),
+                ("10.0.0.1", 1),
+                ("ac-1", 2),
+                ("2024-02-10", 2)
+            ]
+        );
+        assert_eq!(found[3].kind, DatatypeKind::Date);
+        assert_eq!(
+            found[3].value,
+            OscalValue::parse_as("DateDatatype", "2024-02-10").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_escaped_strings() {
+        let log = br#"{"href":"\/catalog#ac-1"}"#;
+        let found: Vec<Found> = Scanner::new([DatatypeKind::UriReference])
+            .scan(&log[..])
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(found[0].offset, 9..23);
+        assert_eq!(
+            found[0].value,
+            OscalValue::parse_as("URIReferenceDatatype", "/catalog#ac-1").unwrap()
+        );
+    }
+}
@@ -0,0 +1,92 @@
+//! JSON Schema for datatypes.
+//!
+//! [definitions] writes a JSON Schema (draft 2020-12) with one definition
+//! per datatype in a [TypeRegistry], from the type, description, pattern,
+//! format and minimum the registry records.  Models can refer to them with
+//! `{"$ref": "#/$defs/UUIDDatatype"}`.  The patterns are the declared
+//! ones; see [crate::patterns] before handing them to a backtracking regex
+//! engine.
+//!
+//! ```
+//! use oscal_types::{registry::TypeRegistry, schema};
+//!
+//! let schema = schema::definitions(&TypeRegistry::builtin());
+//! let count = &schema["$defs"]["PositiveIntegerDatatype"];
+//! assert_eq!(count["type"], "integer");
+//! assert_eq!(count["minimum"], 1);
+//! ```
+use serde_json::{json, Map, Value};
+
+use crate::registry::{TypeInfo, TypeRegistry};
+
+/// The JSON Schema dialect of [definitions].
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// The JSON Schema definition of one datatype.  Properties the registry
+/// does not record are left out.
+pub fn definition(info: &TypeInfo) -> Value {
+    let mut schema = Map::new();
+    let properties = [
+        ("type", info.json_type()),
+        ("description", info.description()),
+        ("pattern", info.pattern()),
+        ("format", info.format()),
+        ("contentEncoding", info.content_encoding()),
+    ];
+    for (name, value) in properties {
+        if let Some(value) = value {
+            schema.insert(name.to_string(), json!(value));
+        }
+    }
+    if let Some(minimum) = info.minimum() {
+        schema.insert("minimum".to_string(), json!(minimum));
+    }
+    Value::Object(schema)
+}
+
+/// A schema holding the [definition] of every datatype in `registry` under
+/// `$defs`, keyed by name.
+pub fn definitions(registry: &TypeRegistry) -> Value {
+    let defs: Map<String, Value> = registry
+        .names()
+        .filter_map(|name| registry.get(name))
+        .map(|info| (info.name().to_string(), definition(info)))
+        .collect();
+    json!({"$schema": SCHEMA_DIALECT, "$defs": defs})
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DatatypeKind;
+    use regex::Regex;
+
+    #[test]
+    fn test_definitions() {
+        let schema = definitions(&TypeRegistry::builtin());
+        let defs = schema["$defs"].as_object().unwrap();
+        assert_eq!(defs.len(), DatatypeKind::ALL.len());
+        assert_eq!(defs["DateTimeWithTimezoneDatatype"]["type"], "string");
+        assert_eq!(defs["IPV4AddressDatatype"]["format"], "ipv4");
+        assert_eq!(defs["EmailAddressDatatype"]["format"], "email");
+        assert_eq!(defs["Base64Datatype"]["contentEncoding"], "base64");
+        assert!(defs["StringDatatype"].get("contentEncoding").is_none());
+        assert!(defs["BooleanDatatype"].get("pattern").is_none());
+        for (name, definition) in defs {
+            if let Some(pattern) = definition["pattern"].as_str() {
+                assert!(Regex::new(pattern).is_ok(), "{name}: {pattern}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_user_registered_type() {
+        let info = TypeInfo::new("ControlId", |_| Ok(()))
+            .with_pattern("^[a-z]{2}-[0-9]+$")
+            .with_minimum(0);
+        assert_eq!(
+            definition(&info),
+            json!({"type": "string", "pattern": "^[a-z]{2}-[0-9]+$", "minimum": 0})
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! Find datatype values embedded in free text.
+//!
+//! Each [Searchable] datatype provides an unanchored pattern that locates
+//! candidate values.  Candidates are then validated, so only values that
+//! would be accepted by the datatype are returned.
+//!
+//! ```
+//! use oscal_types::{search::find_all, UUIDDatatype};
+//!
+//! let text = "component 5c8b5b4e-4a2f-4e0c-9a54-3c9a8f3a9e1d was updated";
+//! let found = find_all::<UUIDDatatype>(text);
+//! assert_eq!(found[0].0, 10..46);
+//! ```
+use regex::Regex;
+use std::ops::Range;
+use std::sync::LazyLock;
+
+use crate::*;
+
+pub trait Searchable: OscalDatatype + for<'a> TryFrom<&'a str, Error = Error> {
+    /// Unanchored pattern matching candidate values in running text.
+    fn search_pattern() -> &'static Regex;
+}
+
+/// Whether `range` is a whole token of `haystack` rather than the tail or
+/// head of a longer word, e.g. `beef::1` in `deadbeef::1`.
+pub(crate) fn is_token(haystack: &str, range: &Range<usize>) -> bool {
+    let joins = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    !haystack[..range.start]
+        .chars()
+        .next_back()
+        .is_some_and(joins)
+        && !haystack[range.end..].chars().next().is_some_and(joins)
+}
+
+/// The candidates for `T` in `haystack` that are whole tokens.
+fn candidates<'h, T: Searchable>(haystack: &'h str) -> impl Iterator<Item = regex::Match<'h>> {
+    T::search_pattern()
+        .find_iter(haystack)
+        .filter(move |m| is_token(haystack, &m.range()))
+}
+
+/// Find every valid `T` in `haystack`, with its byte range.  Values must
+/// stand alone: a candidate joined to letters, digits, `_` or `-` on
+/// either side is skipped.
+pub fn find_all<T: Searchable>(haystack: &str) -> Vec<(Range<usize>, T)> {
+    candidates::<T>(haystack)
+        .filter_map(|m| T::try_from(m.as_str()).ok().map(|value| (m.range(), value)))
+        .collect()
+}
+
+/// Returns `true` if `haystack` contains at least one valid `T`.
+pub fn contains<T: Searchable>(haystack: &str) -> bool {
+    candidates::<T>(haystack).any(|m| T::validate(m.as_str()).is_ok())
+}
+
+/// The search pattern of the datatype `kind`, if it is [Searchable].
+pub(crate) fn search_pattern(kind: DatatypeKind) -> Option<&'static Regex> {
+    match kind {
+        DatatypeKind::Uuid => Some(UUIDDatatype::search_pattern()),
+        DatatypeKind::Date => Some(DateDatatype::search_pattern()),
+        DatatypeKind::DateTime => Some(DateTimeDatatype::search_pattern()),
+        DatatypeKind::DateTimeWithTimezone => Some(DateTimeWithTimezoneDatatype::search_pattern()),
+        DatatypeKind::EmailAddress => Some(EmailAddressDatatype::search_pattern()),
+        DatatypeKind::Ipv4Address => Some(IPV4AddressDatatype::search_pattern()),
+        DatatypeKind::Ipv6Address => Some(IPV6AddressDatatype::search_pattern()),
+        _ => None,
+    }
+}
+
+macro_rules! searchable {
+    ($t:ty, $pattern:expr) => {
+        impl Searchable for $t {
+            fn search_pattern() -> &'static Regex {
+                static PATTERN: LazyLock<Regex> =
+                    LazyLock::new(|| Regex::new($pattern).expect("invalid search pattern"));
+                &PATTERN
+            }
+        }
+    };
+}
+
+searchable!(
+    UUIDDatatype,
+    r"\b[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\b"
+);
+searchable!(DateDatatype, r"\b[0-9]{4}-[0-9]{2}-[0-9]{2}\b");
+searchable!(
+    DateTimeDatatype,
+    r"\b[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})?"
+);
+searchable!(
+    DateTimeWithTimezoneDatatype,
+    r"\b[0-9]{4}-[0-9]{2}-[0-9]{2}T[0-9]{2}:[0-9]{2}:[0-9]{2}(\.[0-9]+)?(Z|[+-][0-9]{2}:[0-9]{2})"
+);
+searchable!(
+    EmailAddressDatatype,
+    r"[A-Za-z0-9._%+-]+@[A-Za-z0-9-]+(\.[A-Za-z0-9-]+)+"
+);
+searchable!(IPV4AddressDatatype, r"\b([0-9]{1,3}\.){3}[0-9]{1,3}\b");
+searchable!(
+    IPV6AddressDatatype,
+    r"[0-9a-fA-F]{0,4}(:[0-9a-fA-F]{0,4}){2,7}"
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_uuids() {
+        let text =
+            "see #A78F7E4C-A27A-4B1E-901B-EBFECF2B0301 and a78f7e4c-a27a-4b1e-901b-ebfecf2b030";
+        let found = find_all::<UUIDDatatype>(text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].0, 5..41);
+        assert_eq!(&*found[0].1, "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+    }
+
+    #[test]
+    fn test_find_dates() {
+        let text = "due 2024-02-10, not 2024-02-30, assessed 2024-04-13T09:57:13Z";
+        let dates: Vec<String> = find_all::<DateDatatype>(text)
+            .into_iter()
+            .map(|(_, d)| d.to_string())
+            .collect();
+        // Either the date parser or the OSCAL date pattern rejects Feb 30
+        if cfg!(any(
+            feature = "date_validation",
+            feature = "regex_validation"
+        )) {
+            assert_eq!(dates, vec!["2024-02-10"]);
+        } else {
+            assert_eq!(dates, vec!["2024-02-10", "2024-02-30"]);
+        }
+
+        let found = find_all::<DateTimeWithTimezoneDatatype>(text);
+        assert_eq!(found.len(), 1);
+        assert_eq!(&text[found[0].0.clone()], "2024-04-13T09:57:13Z");
+    }
+
+    #[test]
+    fn test_find_addresses() {
+        let text = "contact ops@example.gov from 10.0.0.1, 999.1.1.1 or fe80::1";
+        assert_eq!(
+            &*find_all::<EmailAddressDatatype>(text)[0].1,
+            "ops@example.gov"
+        );
+
+        let v4 = find_all::<IPV4AddressDatatype>(text);
+        assert_eq!(v4.len(), 1);
+        assert_eq!(&*v4[0].1, "10.0.0.1");
+
+        let v6 = find_all::<IPV6AddressDatatype>(text);
+        assert_eq!(v6.len(), 1);
+        assert_eq!(&*v6[0].1, "fe80::1");
+
+        assert!(contains::<IPV6AddressDatatype>(text));
+        assert!(!contains::<UUIDDatatype>(text));
+    }
+
+    #[test]
+    fn test_token_boundaries() {
+        let text = "hash deadbeef::1 seen";
+        assert!(find_all::<IPV6AddressDatatype>(text).is_empty());
+        assert!(!contains::<IPV6AddressDatatype>(text));
+        assert!(find_all::<IPV4AddressDatatype>("build-10.0.0.1").is_empty());
+        let text = "(id a78f7e4c-a27a-4b1e-901b-ebfecf2b0301).";
+        assert_eq!(find_all::<UUIDDatatype>(text)[0].0, 4..40);
+    }
+}
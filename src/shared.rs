@@ -0,0 +1,138 @@
+//! Values shared between cloned documents.
+//!
+//! Cloning a deserialized document to build a modified variant, as diff and
+//! merge workflows do, copies every string in it.  Declaring the leaves as
+//! [Shared] makes a clone share each value with the original through an
+//! [Arc] instead; only the values that are then changed get their own copy.
+//! Changes go through [Shared::make_mut] or [Shared::set], which re-validate
+//! the new value and leave the old one in place if it is invalid.
+//!
+//! ```
+//! use oscal_types::shared::Shared;
+//! use oscal_types::TokenDatatype;
+//!
+//! let original: Vec<Shared<TokenDatatype>> =
+//!     serde_json::from_str(r#"["ac-1", "ac-2"]"#).unwrap();
+//! let mut variant = original.clone();
+//! variant[1].make_mut(|id| id.push_str(".a")).unwrap();
+//! assert!(variant[1].make_mut(|id| id.insert(0, ' ')).is_err());
+//!
+//! assert!(Shared::ptr_eq(&original[0], &variant[0]));
+//! assert_eq!(serde_json::to_string(&variant).unwrap(), r#"["ac-1","ac-2.a"]"#);
+//! assert_eq!(&**original[1], "ac-2");
+//! ```
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::registry::from_lexical;
+use crate::walk::lexical_form;
+use crate::*;
+
+/// A `T` that clones share until one of them is changed.
+#[derive(Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Shared<T>(Arc<T>);
+
+impl<T: OscalDatatype> Shared<T> {
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Whether `a` and `b` share the same value, rather than only being
+    /// equal.
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+
+    /// The lexical form of the value, as written when serialized.
+    pub fn lexical(&self) -> String {
+        serde_json::to_value(&*self.0)
+            .ok()
+            .as_ref()
+            .and_then(lexical_form)
+            .unwrap_or_default()
+    }
+
+    /// Replace the value with one parsed from `lexical`.  Other clones keep
+    /// the old value.  On failure the value is unchanged.
+    pub fn set(&mut self, lexical: &str) -> Result<(), Error> {
+        self.0 = Arc::new(from_lexical(lexical)?);
+        Ok(())
+    }
+
+    /// Edit the lexical form of the value with `f` and re-validate it, as
+    /// [Shared::set].  A value that `f` leaves unchanged stays shared.
+    pub fn make_mut(&mut self, f: impl FnOnce(&mut String)) -> Result<(), Error> {
+        let original = self.lexical();
+        let mut lexical = original.clone();
+        f(&mut lexical);
+        match lexical == original {
+            true => Ok(()),
+            false => self.set(&lexical),
+        }
+    }
+
+    /// The value, copied only if it is still shared.
+    pub fn into_inner(self) -> T {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
+
+/// Shares the value rather than copying it.
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T: OscalDatatype> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Serialize> Serialize for Shared<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: OscalDatatype> Deserialize<'de> for Shared<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared() {
+        let original = Shared::new(PositiveIntegerDatatype::try_from(3u64).unwrap());
+        let mut copy = original.clone();
+        assert!(Shared::ptr_eq(&original, &copy));
+
+        copy.make_mut(|n| n.push('0')).unwrap();
+        assert_eq!((**original, **copy), (3, 30));
+        assert!(!Shared::ptr_eq(&original, &copy));
+
+        let mut same = original.clone();
+        same.make_mut(|_| {}).unwrap();
+        assert!(Shared::ptr_eq(&original, &same));
+        assert!(same.set("0").is_err());
+        assert!(Shared::ptr_eq(&original, &same));
+        assert_eq!(*same.into_inner(), 3);
+
+        let mut date = Shared::new(DateDatatype::try_from("2024-02-10").unwrap());
+        date.set("2024-02-11").unwrap();
+        assert_eq!(date.lexical(), "2024-02-11");
+    }
+}
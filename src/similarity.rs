@@ -0,0 +1,72 @@
+//! Fuzzy matching of string-backed datatypes, backed by [strsim].
+//!
+//! Useful for spotting near-duplicates, e.g. `acces-control` and
+//! `access-control`, when merging catalogs.
+//!
+//! ```
+//! use oscal_types::{similarity::Similarity, TokenDatatype};
+//!
+//! let typo = TokenDatatype::try_from("acces-control").unwrap();
+//! let tokens = ["audit", "access-control", "awareness"]
+//!     .map(|t| TokenDatatype::try_from(t).unwrap());
+//! assert_eq!(typo.levenshtein(&tokens[1]), 1);
+//! assert_eq!(&**typo.closest_match(&tokens).unwrap(), "access-control");
+//! ```
+use std::ops::Deref;
+
+use crate::OscalDatatype;
+
+pub trait Similarity {
+    /// The number of single character edits between the values.
+    fn levenshtein(&self, other: &str) -> usize;
+
+    /// Jaro-Winkler similarity between 0.0 and 1.0, favouring common
+    /// prefixes.
+    fn jaro_winkler(&self, other: &str) -> f64;
+
+    /// The candidate most similar to the value by [Similarity::jaro_winkler].
+    /// Ties go to the earliest candidate.
+    fn closest_match<'a, C>(&self, candidates: impl IntoIterator<Item = &'a C>) -> Option<&'a C>
+    where
+        C: Deref<Target = str> + 'a,
+    {
+        let mut best: Option<(&'a C, f64)> = None;
+        for candidate in candidates {
+            let score = self.jaro_winkler(candidate);
+            if best.is_none_or(|(_, best_score)| score > best_score) {
+                best = Some((candidate, score));
+            }
+        }
+        best.map(|(candidate, _)| candidate)
+    }
+}
+
+impl<T> Similarity for T
+where
+    T: OscalDatatype + Deref<Target = str>,
+{
+    fn levenshtein(&self, other: &str) -> usize {
+        strsim::levenshtein(self, other)
+    }
+
+    fn jaro_winkler(&self, other: &str) -> f64 {
+        strsim::jaro_winkler(self, other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StringDatatype;
+
+    #[test]
+    fn test_similarity() {
+        let title = StringDatatype::try_from("Access Control").unwrap();
+        assert_eq!(title.levenshtein("Access Control"), 0);
+        assert_eq!(title.jaro_winkler("Access Control"), 1.0);
+        assert!(title.jaro_winkler("Access Contorl") > title.jaro_winkler("Audit"));
+
+        let none: [StringDatatype; 0] = [];
+        assert!(title.closest_match(&none).is_none());
+    }
+}
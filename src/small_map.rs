@@ -0,0 +1,218 @@
+//! A map for the small keyed collections that fill OSCAL documents.
+//!
+//! Props, links and responsible roles are keyed by a token or UUID and
+//! rarely hold more than ten entries, but a large document has millions of
+//! them.  For so few entries a linear scan of a vector beats hashing, and
+//! takes less memory than a `HashMap`.  [SmallIndexMap] is such a map.  It
+//! keeps entries in insertion order and serializes as a JSON object in that
+//! order.  Run `cargo bench --bench small_map` to compare it with `HashMap`.
+//!
+//! ```
+//! use oscal_types::small_map::SmallIndexMap;
+//! use oscal_types::TokenDatatype;
+//!
+//! let mut props: SmallIndexMap<TokenDatatype, String> =
+//!     serde_json::from_str(r#"{"marking": "cui", "status": "draft"}"#).unwrap();
+//! let status = TokenDatatype::try_from("status").unwrap();
+//! props.insert(status.clone(), "final".to_string());
+//! assert_eq!(props.get(&status).map(String::as_str), Some("final"));
+//! assert_eq!(
+//!     serde_json::to_string(&props).unwrap(),
+//!     r#"{"marking":"cui","status":"final"}"#
+//! );
+//! ```
+use serde::de::{MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::*;
+
+/// A vector of entries with unique keys, in insertion order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmallIndexMap<K: OscalDatatype, V> {
+    entries: Vec<(K, V)>,
+}
+
+impl<K: OscalDatatype, V> SmallIndexMap<K, V> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn position(&self, key: &K) -> Option<usize> {
+        self.entries.iter().position(|(k, _)| k == key)
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.entries
+            .iter_mut()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.position(key).is_some()
+    }
+
+    /// Insert `value` under `key`, returning the value it replaces.  A
+    /// replaced value keeps its position; a new key goes last.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        match self.position(&key) {
+            Some(i) => Some(std::mem::replace(&mut self.entries[i].1, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Remove `key`, keeping the order of the other entries.
+    pub fn remove(&mut self, key: &K) -> Option<V> {
+        let i = self.position(key)?;
+        Some(self.entries.remove(i).1)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(k, v)| (k, v))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.entries.iter().map(|(k, _)| k)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, v)| v)
+    }
+}
+
+impl<K: OscalDatatype, V> Default for SmallIndexMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Later entries replace earlier ones with the same key.
+impl<K: OscalDatatype, V> Extend<(K, V)> for SmallIndexMap<K, V> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, entries: I) {
+        for (key, value) in entries {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: OscalDatatype, V> FromIterator<(K, V)> for SmallIndexMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(entries: I) -> Self {
+        let mut map = Self::new();
+        map.extend(entries);
+        map
+    }
+}
+
+impl<K: OscalDatatype, V> IntoIterator for SmallIndexMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
+
+impl<K: OscalDatatype, V: Serialize> Serialize for SmallIndexMap<K, V> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in &self.entries {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+/// A key that appears twice fails, rather than one entry silently
+/// replacing the other.
+impl<'de, K: OscalDatatype, V: Deserialize<'de>> Deserialize<'de> for SmallIndexMap<K, V> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct EntriesVisitor<K, V>(PhantomData<(K, V)>);
+
+        impl<'de, K: OscalDatatype, V: Deserialize<'de>> Visitor<'de> for EntriesVisitor<K, V> {
+            type Value = SmallIndexMap<K, V>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Self::Value, A::Error> {
+                let mut map = SmallIndexMap::with_capacity(access.size_hint().unwrap_or(0));
+                while let Some((key, value)) = access.next_entry::<K, V>()? {
+                    if map.contains_key(&key) {
+                        let key = serde_json::to_string(&key).unwrap_or_default();
+                        return Err(serde::de::Error::custom(format!("duplicate key {key}")));
+                    }
+                    map.entries.push((key, value));
+                }
+                Ok(map)
+            }
+        }
+
+        deserializer.deserialize_map(EntriesVisitor(PhantomData))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(value: &str) -> TokenDatatype {
+        TokenDatatype::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_small_index_map() {
+        let mut map: SmallIndexMap<TokenDatatype, u32> =
+            [(token("b"), 1), (token("a"), 2)].into_iter().collect();
+        assert_eq!(map.insert(token("c"), 3), None);
+        assert_eq!(map.insert(token("b"), 4), Some(1));
+        let keys: Vec<&str> = map.keys().map(|k| &**k).collect();
+        assert_eq!(keys, ["b", "a", "c"]);
+
+        *map.get_mut(&token("a")).unwrap() += 10;
+        assert_eq!(map.remove(&token("b")), Some(4));
+        assert_eq!(map.remove(&token("b")), None);
+        let entries: Vec<(&str, u32)> = map.iter().map(|(k, v)| (&**k, *v)).collect();
+        assert_eq!(entries, [("a", 12), ("c", 3)]);
+        assert_eq!(map.values().sum::<u32>(), 15);
+        assert!(!map.contains_key(&token("b")) && map.len() == 2);
+    }
+
+    #[test]
+    fn test_serde() {
+        let json = r#"{"a78f7e4c-a27a-4b1e-901b-ebfecf2b0301":1,"cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53":2}"#;
+        let map: SmallIndexMap<UUIDDatatype, u8> = serde_json::from_str(json).unwrap();
+        assert_eq!(serde_json::to_string(&map).unwrap(), json);
+
+        let error = serde_json::from_str::<SmallIndexMap<TokenDatatype, u8>>(r#"{"a": 1, "a": 2}"#)
+            .unwrap_err();
+        assert!(error.to_string().contains(r#"duplicate key "a""#));
+        assert!(serde_json::from_str::<SmallIndexMap<TokenDatatype, u8>>(r#"{"1a": 1}"#).is_err());
+    }
+}
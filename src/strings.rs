@@ -1,16 +1,214 @@
-use serde::{Deserialize, Serialize};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
+use std::io::{self, Read, Write};
+use std::net::{Ipv4Addr, Ipv6Addr};
 use std::ops::Deref;
 use std::str::FromStr;
 
-use crate::{Base, Error, Metaschema, StringType, Validate, string_impl};
 use super::nc_name::NCName;
+use crate::lexical::collapse_whitespace;
+use crate::warnings::{self, Warning};
+use crate::{config, string_impl, Base, Error, Metaschema, StringType, Validate};
 
 /// A string representing arbitrary binary data encoded using the Base 64 algorithm as defined by RFC4648
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(try_from = "&str")]
+///
+/// The value is always stored and serialized unwrapped.  Line wrapped input
+/// (RFC 4648 section 3.3) is accepted by [Base64Datatype::from_wrapped], and
+/// by deserialization from human readable formats when
+/// [config::Config::base64_line_breaks] is set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub struct Base64Datatype(String);
+
+impl Base64Datatype {
+    /// Parse a value that may be wrapped across lines, removing the line
+    /// breaks and any indentation around them.
+    pub fn from_wrapped(value: &str) -> Result<Self, Error> {
+        let unwrapped: String = value.split_ascii_whitespace().collect();
+        Self::try_from(unwrapped.as_str())
+    }
+
+    /// Encode bytes.  Empty input has no valid encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Self::try_from(STANDARD.encode(bytes).as_str())
+    }
+
+    /// The decoded bytes.
+    pub fn decode(&self) -> Result<Vec<u8>, Error> {
+        STANDARD
+            .decode(&self.0)
+            .map_err(|e| Error::Base64(e.to_string()))
+    }
+
+    /// Encode everything read from `reader`, e.g. a large attachment file,
+    /// without holding the raw bytes in memory.
+    pub fn encode_from<R: Read>(mut reader: R) -> io::Result<Self> {
+        let mut encoder = base64::write::EncoderStringWriter::new(&STANDARD);
+        io::copy(&mut reader, &mut encoder)?;
+        Self::try_from(encoder.into_inner().as_str())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Decode into `writer` without holding the decoded bytes in memory,
+    /// returning the number of bytes written.
+    pub fn decode_to<W: Write>(&self, mut writer: W) -> io::Result<u64> {
+        let mut decoder = base64::read::DecoderReader::new(self.0.as_bytes(), &STANDARD);
+        io::copy(&mut decoder, &mut writer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base64Datatype {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let wrapped = deserializer.is_human_readable() && config::get().base64_line_breaks;
+        let value = String::deserialize(deserializer)?;
+        match wrapped {
+            true => Self::from_wrapped(&value),
+            false => Self::try_from(value.as_str()),
+        }
+        .map_err(serde::de::Error::custom)
+    }
+}
+
+/// Builds a [Base64Datatype] piece by piece, e.g. from a streaming upload,
+/// rejecting invalid input as it arrives rather than after the whole
+/// payload has been buffered.
+///
+/// Pieces are raw bytes, which are encoded, or text that is already
+/// encoded.  The two can be mixed, but encoded text must start where a
+/// whole group of three bytes ended and vice versa.
+///
+/// ```
+/// use oscal_types::Base64Builder;
+///
+/// let mut builder = Base64Builder::new();
+/// builder.push_chunk(b"hel").unwrap();
+/// builder.push_chunk(b"lo").unwrap();
+/// assert_eq!(&*builder.finish().unwrap(), "aGVsbG8=");
+///
+/// let mut builder = Base64Builder::new();
+/// builder.push_encoded("aGVs").unwrap();
+/// assert!(builder.push_encoded("bG8!").is_err());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Base64Builder {
+    encoded: String,
+    /// Raw bytes that do not yet make up a whole group of three.
+    pending: Vec<u8>,
+    padding: usize,
+}
+
+impl Base64Builder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Encode and append `bytes`.
+    pub fn push_chunk(&mut self, mut bytes: &[u8]) -> Result<(), Error> {
+        if self.padding > 0 || !self.encoded.len().is_multiple_of(4) {
+            return Err(Error::Base64(
+                "raw bytes after a partial group of encoded data".to_string(),
+            ));
+        }
+        if !self.pending.is_empty() {
+            let take = bytes.len().min(3 - self.pending.len());
+            self.pending.extend_from_slice(&bytes[..take]);
+            bytes = &bytes[take..];
+            if self.pending.len() < 3 {
+                return Ok(());
+            }
+            STANDARD.encode_string(&self.pending, &mut self.encoded);
+            self.pending.clear();
+        }
+        let whole = bytes.len() / 3 * 3;
+        STANDARD.encode_string(&bytes[..whole], &mut self.encoded);
+        self.pending.extend_from_slice(&bytes[whole..]);
+        Ok(())
+    }
+
+    /// Append encoded text, checking each character as
+    /// [Base64Datatype::validate] would.  Offsets in errors are from the
+    /// start of the whole value.
+    pub fn push_encoded(&mut self, chunk: &str) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::Base64(reason));
+        if !self.pending.is_empty() {
+            return invalid("encoded data after a partial group of raw bytes".to_string());
+        }
+        let mut padding = self.padding;
+        for (i, c) in chunk.bytes().enumerate() {
+            let offset = self.encoded.len() + i;
+            match c {
+                b'=' if offset % 4 < 2 => return invalid(format!("padding at offset {offset}")),
+                b'=' if padding == 2 => {
+                    return invalid("more than two padding characters".to_string())
+                }
+                b'=' => padding += 1,
+                _ if padding > 0 => {
+                    return invalid(format!("data after padding at offset {offset}"))
+                }
+                _ if base64_value(c).is_none() => {
+                    return invalid(format!("invalid character at offset {offset}"))
+                }
+                _ => {}
+            }
+        }
+        self.padding = padding;
+        self.encoded.push_str(chunk);
+        Ok(())
+    }
+
+    /// The value built, checked as a whole.
+    pub fn finish(mut self) -> Result<Base64Datatype, Error> {
+        STANDARD.encode_string(&self.pending, &mut self.encoded);
+        Base64Datatype::try_from(self.encoded)
+    }
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+/// Strict RFC 4648 section 4: the standard alphabet, padded to a multiple
+/// of four characters, and no set bits in the padding (section 3.5), so
+/// every value has exactly one encoding.
 impl Validate for Base64Datatype {
-    fn validate(_value: &str) -> Result<(), Error> {
+    fn validate(value: &str) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::Base64(reason));
+        if value.is_empty() {
+            return invalid("empty".to_string());
+        }
+        if !value.len().is_multiple_of(4) {
+            return invalid(format!("length {} is not a multiple of 4", value.len()));
+        }
+        let data = value.trim_end_matches('=');
+        let padding = value.len() - data.len();
+        if padding > 2 {
+            return invalid("more than two padding characters".to_string());
+        }
+        let mut last = 0;
+        for (i, c) in data.bytes().enumerate() {
+            match base64_value(c) {
+                Some(v) => last = v,
+                None if c == b'=' => return invalid(format!("padding at offset {i}")),
+                None => return invalid(format!("invalid character at offset {i}")),
+            }
+        }
+        let unused_bits = [0, 0b11, 0b1111][padding];
+        if last & unused_bits != 0 {
+            return invalid("non-zero padding bits".to_string());
+        }
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
         Ok(())
     }
 }
@@ -22,31 +220,215 @@ string_impl!(
     content_encoding = "base64"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(try_from = "&str")]
+/// A non-empty string without leading or trailing whitespace.
+///
+/// Deserialization rejects surrounding whitespace unless
+/// [config::Config::trim_strings] is set, in which case the value is trimmed
+/// and a [Warning::TrimmedWhitespace] is recorded.  With
+/// [config::Config::collapse_whitespace] set the value is
+/// [normalized](StringDatatype::normalized) instead, and a
+/// [Warning::CollapsedWhitespace] holding the raw form is recorded.  Use
+/// [StringDatatype::normalized_with_raw] to keep the raw form directly.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
 pub struct StringDatatype(String);
+
+/// Collapse the whitespace in `value` if [config::Config::collapse_whitespace]
+/// is set, recording a [Warning::CollapsedWhitespace] if it changed.
+fn collapse_if_configured(datatype: &'static str, value: String) -> String {
+    if !config::read(|config| config.collapse_whitespace) {
+        return value;
+    }
+    let collapsed = collapse_whitespace(&value);
+    if collapsed != value {
+        warnings::emit(Warning::CollapsedWhitespace {
+            datatype,
+            original: value,
+        });
+    }
+    collapsed
+}
+
+impl StringDatatype {
+    /// Parse `value` after collapsing its whitespace as the XSD `collapse`
+    /// facet does: tabs, line feeds and carriage returns become spaces,
+    /// runs of spaces become one space, and leading and trailing spaces are
+    /// removed.
+    ///
+    /// ```
+    /// use oscal_types::StringDatatype;
+    ///
+    /// let title = StringDatatype::normalized(" Access\n\tControl  Policy ").unwrap();
+    /// assert_eq!(&*title, "Access Control Policy");
+    /// ```
+    pub fn normalized(value: &str) -> Result<Self, Error> {
+        Self::try_from(collapse_whitespace(value).as_str())
+    }
+
+    /// [Self::normalized], also returning the raw form of `value` if
+    /// collapsing its whitespace changed it.
+    ///
+    /// ```
+    /// use oscal_types::StringDatatype;
+    ///
+    /// let (title, raw) = StringDatatype::normalized_with_raw("Access\tControl").unwrap();
+    /// assert_eq!((&*title, raw.as_deref()), ("Access Control", Some("Access\tControl")));
+    /// ```
+    pub fn normalized_with_raw(value: &str) -> Result<(Self, Option<String>), Error> {
+        let collapsed = collapse_whitespace(value);
+        let raw = (collapsed != value).then(|| value.to_string());
+        Ok((Self::try_from(collapsed.as_str())?, raw))
+    }
+}
+
+impl<'de> Deserialize<'de> for StringDatatype {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = collapse_if_configured("StringDatatype", String::deserialize(deserializer)?);
+        let trimmed = value.trim();
+        if trimmed.len() != value.len() && config::get().trim_strings {
+            if trimmed.is_empty() {
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(<Self as crate::OscalDatatype>::KIND, false);
+                return Err(serde::de::Error::custom(Error::StringParse(
+                    "Only whitespace, which trims to an empty string".to_string(),
+                )));
+            }
+            let trimmed = Self::try_from(trimmed).map_err(serde::de::Error::custom)?;
+            warnings::emit(Warning::TrimmedWhitespace {
+                datatype: "StringDatatype",
+                original: value,
+            });
+            return Ok(trimmed);
+        }
+        Self::try_from(value).map_err(serde::de::Error::custom)
+    }
+}
+
 impl Validate for StringDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        match value.trim() == value {
-            true => Ok(()),
-            false => Err(Error::StringParse(
+        if value.trim() != value {
+            return Err(Error::StringParse(
                 "Trailing and leading whitespace is not allowed".to_string(),
-            )),
+            ));
         }
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
     }
 }
 
 string_impl!(
     StringDatatype,
     description = "A non-empty string with leading and trailing whitespace disallowed. Whitespace is: U+9, U+10, U+32 or [ \n\t]+",
-    pattern =  "^\\S(.*\\S)?$",
-    content_encoding = "string"
+    pattern =  "^\\S(.*\\S)?$"
 );
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct EmailAddressDatatype(String);
+
+/// Characters allowed in an RFC 5322 dot-atom besides ASCII alphanumerics.
+/// RFC 6531 adds all non-ASCII characters.
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || !c.is_ascii()
+}
+
+fn validate_local_part(local: &str) -> Result<(), Error> {
+    let invalid = |reason: &str| Err(Error::EmailLocalPart(reason.to_string()));
+    if local.is_empty() {
+        return invalid("empty");
+    }
+    if local.len() > 64 {
+        return invalid("longer than 64 octets");
+    }
+    if let Some(quoted) = local.strip_prefix('"') {
+        let Some(quoted) = quoted.strip_suffix('"') else {
+            return invalid("unterminated quoted string");
+        };
+        let mut chars = quoted.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some(' ' | '\t') => {}
+                    Some(escaped) if escaped.is_ascii_graphic() || !escaped.is_ascii() => {}
+                    _ => return invalid("invalid quoted pair"),
+                },
+                '"' => return invalid("unescaped quote"),
+                c if !c.is_control() => {}
+                _ => return invalid("control character in quoted string"),
+            }
+        }
+        return Ok(());
+    }
+    if local.split('.').any(str::is_empty) {
+        return invalid("leading, trailing or consecutive dots");
+    }
+    match local.chars().find(|&c| c != '.' && !is_atext(c)) {
+        Some(c) => invalid(&format!("unexpected character {c:?}")),
+        None => Ok(()),
+    }
+}
+
+/// Check the dot separated labels of a domain name: letters, digits and
+/// hyphens, not empty, at most 63 octets, and not starting or ending with a
+/// hyphen.  Returns the reason a label is invalid.
+fn validate_labels(domain: &str) -> Result<(), String> {
+    for label in domain.split('.') {
+        if label.is_empty() {
+            return Err("empty label".to_string());
+        }
+        if label.len() > 63 {
+            return Err(format!("label {label:?} is longer than 63 octets"));
+        }
+        if label.starts_with('-') || label.ends_with('-') {
+            return Err(format!("label {label:?} starts or ends with a hyphen"));
+        }
+        let allowed = |c: char| c.is_ascii_alphanumeric() || c == '-' || c.is_alphanumeric();
+        if let Some(c) = label.chars().find(|&c| !allowed(c)) {
+            return Err(format!("unexpected character {c:?}"));
+        }
+    }
+    Ok(())
+}
+
+fn validate_email_domain(domain: &str) -> Result<(), Error> {
+    let invalid = |reason: String| Err(Error::EmailDomain(reason));
+    if let Some(literal) = domain.strip_prefix('[') {
+        let Some(literal) = literal.strip_suffix(']') else {
+            return invalid("unterminated address literal".to_string());
+        };
+        let parsed = match literal.strip_prefix("IPv6:") {
+            Some(v6) => v6.parse::<std::net::Ipv6Addr>().is_ok(),
+            None => literal.parse::<std::net::Ipv4Addr>().is_ok(),
+        };
+        return match parsed {
+            true => Ok(()),
+            false => invalid(format!("invalid address literal {literal:?}")),
+        };
+    }
+    if domain.is_empty() {
+        return invalid("empty".to_string());
+    }
+    if domain.len() > 255 {
+        return invalid("longer than 255 octets".to_string());
+    }
+    validate_labels(domain).or_else(invalid)
+}
+
 impl Validate for EmailAddressDatatype {
-    fn validate(_value: &str) -> Result<(), Error> {
+    /// Validate an RFC 5322 address, with the internationalized local parts
+    /// and domains of RFC 6531.  Comments and folding whitespace are not
+    /// accepted.
+    fn validate(value: &str) -> Result<(), Error> {
+        let (local, domain) = value.rsplit_once('@').ok_or(Error::EmailMissingAt)?;
+        if value.len() > 254 {
+            return Err(Error::EmailLength);
+        }
+        validate_local_part(local)?;
+        validate_email_domain(domain)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
         Ok(())
     }
 }
@@ -54,16 +436,50 @@ impl Validate for EmailAddressDatatype {
 string_impl!(
     EmailAddressDatatype,
     description = "An email address string formatted according to RFC 6531.",
-    pattern = "^.+@.+$",
-    content_encoding = "email"
+    format = "email",
+    pattern = "^.+@.+$"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct HostnameDatatype(String);
+
+/// The ASCII compatible form of a hostname.  With the `idn` feature this is
+/// the result of IDNA processing (UTS #46 with STD3 rules), which also checks
+/// the labels.  Without it, non-ASCII labels are kept as is and nothing is
+/// allocated.
+fn hostname_to_ascii(value: &str) -> Result<Cow<'_, str>, Error> {
+    #[cfg(feature = "idn")]
+    {
+        idna::Config::default()
+            .use_std3_ascii_rules(true)
+            .check_hyphens(true)
+            .verify_dns_length(true)
+            .to_ascii(value)
+            .map(Cow::Owned)
+            .map_err(|e| Error::Hostname(format!("{value:?}: {e}")))
+    }
+    #[cfg(not(feature = "idn"))]
+    Ok(Cow::Borrowed(value))
+}
+
 impl Validate for HostnameDatatype {
-    fn validate(_value: &str) -> Result<(), Error> {
-        Ok(())
+    /// Validate an internationalized hostname (RFC 5890 section 2.3.2.3):
+    /// dot separated labels of letters, digits and hyphens, at most 63
+    /// octets each and 253 octets in total in their ASCII form.  A trailing
+    /// dot, as in `example.com.`, is allowed.
+    fn validate(value: &str) -> Result<(), Error> {
+        let invalid = |reason: String| Err(Error::Hostname(format!("{value:?}: {reason}")));
+        let ascii = hostname_to_ascii(value)?;
+        // A fully qualified name may end with the dot of the root label
+        let ascii = ascii.strip_suffix('.').unwrap_or(&ascii);
+        if ascii.is_empty() {
+            return invalid("empty".to_string());
+        }
+        if ascii.len() > 253 {
+            return invalid("longer than 253 octets".to_string());
+        }
+        validate_labels(ascii).or_else(invalid)
     }
 }
 
@@ -73,15 +489,40 @@ string_impl!(
     format = "idn-hostname"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[cfg(feature = "idn")]
+impl HostnameDatatype {
+    /// The ASCII Compatible Encoding of the hostname, with non-ASCII labels
+    /// in punycode, e.g. `xn--bcher-kva.example` for `bücher.example`.  A
+    /// value kept unchecked with [crate::ValidationMode::Off] that cannot
+    /// be encoded is returned as written.
+    pub fn to_ascii(&self) -> String {
+        hostname_to_ascii(self).map_or_else(|_| self.0.clone(), Cow::into_owned)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct IPV4AddressDatatype(String);
 impl Validate for IPV4AddressDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        match value.parse::<std::net::Ipv4Addr>() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::AddressParse(e)),
-        }
+        value.parse::<Ipv4Addr>()?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+}
+
+impl IPV4AddressDatatype {
+    /// The address, or `0.0.0.0` for a value kept unchecked with
+    /// [crate::ValidationMode::Off].
+    pub fn to_ipv4(&self) -> Ipv4Addr {
+        self.0.parse().unwrap_or(Ipv4Addr::UNSPECIFIED)
+    }
+}
+
+impl From<Ipv4Addr> for IPV4AddressDatatype {
+    fn from(addr: Ipv4Addr) -> Self {
+        Self(addr.to_string())
     }
 }
 
@@ -89,18 +530,33 @@ string_impl!(
     IPV4AddressDatatype,
     description = "An Internet Protocol version 4 address represented using dotted-quad syntax as defined in section 3.2 of RFC2673.",
     format = "ipv4",
-    pattern = "^((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])\\.){{3}}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])$"
+    pattern = "^((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])\\.){3}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])$"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct IPV6AddressDatatype(String);
 impl Validate for IPV6AddressDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        match value.parse::<std::net::Ipv6Addr>() {
-            Ok(_) => Ok(()),
-            Err(e) => Err(Error::AddressParse(e)),
-        }
+        value.parse::<Ipv6Addr>()?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+}
+
+impl IPV6AddressDatatype {
+    /// The address, or `::` for a value kept unchecked with
+    /// [crate::ValidationMode::Off].
+    pub fn to_ipv6(&self) -> Ipv6Addr {
+        self.0.parse().unwrap_or(Ipv6Addr::UNSPECIFIED)
+    }
+}
+
+/// Written in the canonical form of RFC 5952, e.g. `2001:db8::1`.
+impl From<Ipv6Addr> for IPV6AddressDatatype {
+    fn from(addr: Ipv6Addr) -> Self {
+        Self(addr.to_string())
     }
 }
 
@@ -108,14 +564,20 @@ string_impl!(
     IPV6AddressDatatype,
     description = "An Internet Protocol version 6 address represented using the syntax defined in section 2.2 of RFC3513.",
     format = "ipv6",
-    pattern = "^(([0-9a-fA-F]{{1,4}}:){{7,7}}[0-9a-fA-F]{{1,4}}|([0-9a-fA-F]{{1,4}}:){{1,7}}:|([0-9a-fA-F]{{1,4}}:){{1,6}}:[0-9a-fA-F]{{1,4}}|([0-9a-fA-F]{{1,4}}:){{1,5}}(:[0-9a-fA-F]{{1,4}}){{1,2}}|([0-9a-fA-F]{{1,4}}:){{1,4}}(:[0-9a-fA-F]{{1,4}}){{1,3}}|([0-9a-fA-F]{{1,4}}:){{1,3}}(:[0-9a-fA-F]{{1,4}}){{1,4}}|([0-9a-fA-F]{{1,4}}:){{1,2}}(:[0-9a-fA-F]{{1,4}}){{1,5}}|[0-9a-fA-F]{{1,4}}:((:[0-9a-fA-F]{{1,4}}){{1,6}})|:((:[0-9a-fA-F]{{1,4}}){{1,7}}|:)|[fF][eE]80:(:[0-9a-fA-F]{{0,4}}){{0,4}}%[0-9a-zA-Z]{{1,}}|::([fF]{{4}}(:0{{1,4}}){{0,1}}:){{0,1}}((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]).){{3,3}}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])|([0-9a-fA-F]{{1,4}}:){{1,4}}:((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]).){{3,3}}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]))$"
+    pattern = "^(([0-9a-fA-F]{1,4}:){7,7}[0-9a-fA-F]{1,4}|([0-9a-fA-F]{1,4}:){1,7}:|([0-9a-fA-F]{1,4}:){1,6}:[0-9a-fA-F]{1,4}|([0-9a-fA-F]{1,4}:){1,5}(:[0-9a-fA-F]{1,4}){1,2}|([0-9a-fA-F]{1,4}:){1,4}(:[0-9a-fA-F]{1,4}){1,3}|([0-9a-fA-F]{1,4}:){1,3}(:[0-9a-fA-F]{1,4}){1,4}|([0-9a-fA-F]{1,4}:){1,2}(:[0-9a-fA-F]{1,4}){1,5}|[0-9a-fA-F]{1,4}:((:[0-9a-fA-F]{1,4}){1,6})|:((:[0-9a-fA-F]{1,4}){1,7}|:)|[fF][eE]80:(:[0-9a-fA-F]{0,4}){0,4}%[0-9a-zA-Z]{1,}|::([fF]{4}(:0{1,4}){0,1}:){0,1}((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]).){3,3}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9])|([0-9a-fA-F]{1,4}:){1,4}:((25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]).){3,3}(25[0-5]|2[0-4][0-9]|1[0-9][0-9]|[1-9][0-9]|[0-9]))$"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct MarkupLineDatatype(String);
+
+/// With the `markup` feature, the value must parse as OSCAL markup-line.
 impl Validate for MarkupLineDatatype {
     fn validate(_value: &str) -> Result<(), Error> {
+        #[cfg(feature = "markup")]
+        crate::markup::parse_line(_value)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(_value)?;
         Ok(())
     }
 }
@@ -126,52 +588,345 @@ string_impl!(
     pattern = "^[^\n]+$"
 );
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+impl MarkupLineDatatype {
+    /// Build a markup line from untrusted text (e.g. scanner output) so that
+    /// it renders literally.
+    ///
+    /// Every character with Markdown or HTML meaning is backslash escaped, and
+    /// line breaks are replaced with spaces, so the result never contains
+    /// active content.  The result is validated like any other markup line;
+    /// text that is empty or only whitespace fails with [Error::Markup].
+    pub fn escape_untrusted(value: &str) -> Result<Self, Error> {
+        let mut escaped = String::with_capacity(value.len());
+        for c in value.chars() {
+            match c {
+                '\r' | '\n' => escaped.push(' '),
+                c if MARKDOWN_ESCAPES.contains(c) => {
+                    escaped.push('\\');
+                    escaped.push(c);
+                }
+                c => escaped.push(c),
+            }
+        }
+        if escaped.trim().is_empty() {
+            return Err(Error::Markup("markup-line has no content".to_string()));
+        }
+        Self::try_from(escaped)
+    }
+
+    /// Returns `true` if the markup contains links, images or raw HTML.
+    pub fn contains_active_content(&self) -> bool {
+        contains_active_content(&self.0)
+    }
+}
+
+/// ASCII punctuation escaped by [MarkupLineDatatype::escape_untrusted]
+const MARKDOWN_ESCAPES: &str = "\\`*_{}[]()<>!#|~&";
+
+/// Scan Markdown for unescaped links (`[text](url)`, `[text][ref]`), images
+/// (`![alt](src)`), autolinks (`<https://...>`) and inline HTML.
+fn contains_active_content(value: &str) -> bool {
+    let chars: Vec<char> = value.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        match chars[i] {
+            // Skip the escaped character
+            '\\' => i += 1,
+            '<' => {
+                if let Some(&next) = chars.get(i + 1) {
+                    if next.is_ascii_alphabetic() || next == '/' || next == '!' || next == '?' {
+                        return true;
+                    }
+                }
+            }
+            '[' => {
+                if let Some(close) = find_unescaped(&chars, i + 1, ']') {
+                    if matches!(chars.get(close + 1), Some('(') | Some('[')) {
+                        return true;
+                    }
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    false
+}
+
+fn find_unescaped(chars: &[char], start: usize, target: char) -> Option<usize> {
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 1,
+            c if c == target => return Some(i),
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
 #[serde(try_from = "&str")]
 pub struct MarkupMultilineDatatype(String);
+
+/// With the `markup` feature, the value must parse as OSCAL
+/// markup-multiline.
 impl Validate for MarkupMultilineDatatype {
     fn validate(_value: &str) -> Result<(), Error> {
+        #[cfg(feature = "markup")]
+        crate::markup::parse_multiline(_value)?;
         Ok(())
     }
 }
 
 string_impl!(
     MarkupMultilineDatatype,
-    description = "A multiple lines of Markdown content conformant to the Commonmark specification.",
-    pattern = "^[^\n]+$"
+    description =
+        "A multiple lines of Markdown content conformant to the Commonmark specification."
 );
 
+impl MarkupMultilineDatatype {
+    /// Returns `true` if the markup contains links, images or raw HTML.
+    pub fn contains_active_content(&self) -> bool {
+        contains_active_content(&self.0)
+    }
+
+    /// The markup joined from `lines` with newlines.
+    pub fn from_lines<I, S>(lines: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut markup = String::new();
+        for (i, line) in lines.into_iter().enumerate() {
+            if i > 0 {
+                markup.push('\n');
+            }
+            markup.push_str(line.as_ref());
+        }
+        Self::try_from(markup)
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = &str> {
+        self.0.lines()
+    }
+
+    /// The blocks of lines separated by blank lines, e.g. paragraphs and
+    /// lists, without their trailing newline.
+    pub fn paragraphs(&self) -> impl Iterator<Item = &str> {
+        let mut paragraphs = Vec::new();
+        let mut start = None;
+        let mut offset = 0;
+        for line in self.0.split_inclusive('\n') {
+            match (line.trim().is_empty(), start) {
+                (true, Some(from)) => {
+                    paragraphs.push(self.0[from..offset].trim_end_matches(['\r', '\n']));
+                    start = None;
+                }
+                (false, None) => start = Some(offset),
+                _ => {}
+            }
+            offset += line.len();
+        }
+        if let Some(from) = start {
+            paragraphs.push(self.0[from..].trim_end_matches(['\r', '\n']));
+        }
+        paragraphs.into_iter()
+    }
+
+    /// Append `paragraph` after a blank line.  On failure the markup is
+    /// unchanged.
+    pub fn append_paragraph(&mut self, paragraph: &str) -> Result<(), Error> {
+        let existing = self.0.trim_end();
+        let markup = match existing.is_empty() {
+            true => paragraph.to_string(),
+            false => format!("{existing}\n\n{paragraph}"),
+        };
+        *self = Self::try_from(markup)?;
+        Ok(())
+    }
+}
+
 /// Wrapper for NCName
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(try_from = "&str")]
-pub struct TokenDatatype(String);
+///
+/// Deserialization collapses whitespace as [StringDatatype] does when
+/// [config::Config::collapse_whitespace] is set.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct TokenDatatype(pub(crate) String);
+
+impl<'de> Deserialize<'de> for TokenDatatype {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = collapse_if_configured("TokenDatatype", String::deserialize(deserializer)?);
+        Self::try_from(value.as_str()).map_err(serde::de::Error::custom)
+    }
+}
 
 string_impl!(
     TokenDatatype,
     description = "A non-colonized name as defined by XML Schema Part 2: Datatypes Second Edition. https://www.w3.org/TR/xmlschema11-2/#NCName.",
-    pattern = "^(\\p{{L}}|_)(\\p{{L}}|\\p{{N}}|[.\\-_])*$"
+    pattern = "^(\\p{L}|_)(\\p{L}|\\p{N}|[.\\-_])*$"
 );
 
 impl Validate for TokenDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-       NCName::try_from(value).map(|_| ())
+        NCName::validate(value)?;
+        #[cfg(feature = "regex_validation")]
+        crate::validate::validate_pattern::<Self>(value)?;
+        Ok(())
+    }
+}
+
+/// A change made by [StringDatatype::as_token_lossy].  Positions are char
+/// offsets into the original string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TokenChange {
+    /// A run of whitespace was replaced by a single `-`
+    CollapsedWhitespace { at: usize, len: usize },
+    /// A character not allowed in a token was replaced by `-`
+    ReplacedChar { at: usize, original: char },
+    /// A leading character that may not start a token was kept, and `_`
+    /// was prepended
+    PrefixedUnderscore,
+}
+
+impl StringDatatype {
+    /// Coerce a string into a token, changing what is necessary and reporting
+    /// each change.
+    ///
+    /// This is the coercion policy for comparing prop values against token
+    /// allow-lists:
+    ///
+    /// - each run of whitespace becomes a single `-`
+    /// - every other character not allowed in an NCName becomes `-`
+    /// - if the first character cannot start an NCName, `_` is prepended
+    ///
+    /// Fails only for the empty string. Use [TokenDatatype::from_string_strict]
+    /// when no changes are acceptable.
+    pub fn as_token_lossy(&self) -> Result<(TokenDatatype, Vec<TokenChange>), Error> {
+        if self.0.is_empty() {
+            return Err(Error::StringParse(
+                "An empty string is not a token".to_string(),
+            ));
+        }
+
+        let mut token = String::with_capacity(self.0.len());
+        let mut changes = Vec::new();
+        let mut chars = self.0.chars().enumerate().peekable();
+        while let Some((at, c)) = chars.next() {
+            if c.is_whitespace() {
+                let mut len = 1;
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {
+                    len += 1;
+                }
+                token.push('-');
+                changes.push(TokenChange::CollapsedWhitespace { at, len });
+            } else if NCName::is_valid_char(c) || (at == 0 && NCName::is_valid_start_char(c)) {
+                token.push(c);
+            } else {
+                token.push('-');
+                changes.push(TokenChange::ReplacedChar { at, original: c });
+            }
+        }
+
+        if !token.starts_with(NCName::is_valid_start_char) {
+            token.insert(0, '_');
+            changes.push(TokenChange::PrefixedUnderscore);
+        }
+        Ok((TokenDatatype::try_from(token.as_str())?, changes))
+    }
+}
+
+impl TokenDatatype {
+    /// Parse `value` after collapsing its whitespace, as
+    /// [StringDatatype::normalized].  A token cannot contain spaces, so
+    /// only surrounding whitespace can be removed.
+    pub fn normalized(value: &str) -> Result<Self, Error> {
+        Self::try_from(collapse_whitespace(value).as_str())
+    }
+
+    /// [Self::normalized], also returning the raw form of `value` if
+    /// collapsing its whitespace changed it.
+    pub fn normalized_with_raw(value: &str) -> Result<(Self, Option<String>), Error> {
+        let collapsed = collapse_whitespace(value);
+        let raw = (collapsed != value).then(|| value.to_string());
+        Ok((Self::try_from(collapsed.as_str())?, raw))
+    }
+
+    /// Convert a string to a token only if it already is one, without any
+    /// normalization.  See [StringDatatype::as_token_lossy] for the lenient
+    /// alternative.
+    pub fn from_string_strict(value: &StringDatatype) -> Result<Self, Error> {
+        if value.is_empty() {
+            return Err(Error::StringParse(
+                "An empty string is not a token".to_string(),
+            ));
+        }
+        Self::try_from(&**value)
+    }
+
+    /// The snake case Rust identifier for this token, e.g.
+    /// `system_security_plan`.  See [crate::ident] for the rules.
+    pub fn to_rust_ident(&self) -> String {
+        crate::ident::to_rust_ident(self)
+    }
+
+    /// The upper camel case Rust identifier for this token, e.g.
+    /// `SystemSecurityPlan`.
+    pub fn to_pascal_case(&self) -> String {
+        crate::ident::to_pascal_case(self)
+    }
+
+    /// The token for a snake or upper camel case Rust identifier.
+    pub fn from_rust_ident(ident: &str) -> Result<Self, Error> {
+        crate::ident::from_rust_ident(ident)
+    }
+}
+
+/// Every token is an NCName.
+impl From<TokenDatatype> for NCName {
+    fn from(token: TokenDatatype) -> Self {
+        NCName(token.0)
     }
 }
 
+/// Fails only with `regex_validation`, which also holds tokens to the
+/// Metaschema pattern.
+impl TryFrom<NCName> for TokenDatatype {
+    type Error = Error;
+    fn try_from(name: NCName) -> Result<Self, Self::Error> {
+        Self::try_from(name.0)
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+
     #[derive(Debug, Deserialize, Serialize, PartialEq)]
     struct Dummy {
-        pub sdt: StringDatatype
+        pub sdt: StringDatatype,
+    }
+
+    #[test]
+    fn test_try_from_string() {
+        let owned = String::from("ac-1");
+        let buffer = owned.as_ptr();
+        let token = TokenDatatype::try_from(owned).unwrap();
+        assert_eq!(token.as_ptr(), buffer);
+        assert!(TokenDatatype::try_from(String::from("1 ac")).is_err());
+        assert!(StringDatatype::try_from(String::from(" x")).is_err());
     }
 
     #[test]
     fn test_de_se_string_data_type_in_struct() {
         let value = "abc";
-        let dummy = Dummy{sdt: StringDatatype::try_from(value).unwrap()};
+        let dummy = Dummy {
+            sdt: StringDatatype::try_from(value).unwrap(),
+        };
         let result = serde_json::to_string(&dummy);
         assert!(result.is_ok());
         let json = result.unwrap();
@@ -215,7 +970,342 @@ mod tests {
     fn test_de_se_token_datatype() {
         assert!(serde_json::from_str::<TokenDatatype>(r#""_abc""#).is_ok());
     }
-    
-}
 
+    #[test]
+    fn test_de_trim_string_datatype() {
+        let json = r#"" abc  ""#;
+        assert!(serde_json::from_str::<StringDatatype>(json).is_err());
+
+        let trim = config::Config {
+            trim_strings: true,
+            ..config::Config::new()
+        };
+        let (sdt, blank) = config::scoped(trim, || {
+            (
+                serde_json::from_str::<StringDatatype>(json),
+                serde_json::from_str::<StringDatatype>(r#""   ""#),
+            )
+        });
+        assert!(blank.is_err());
+        let sdt = sdt.expect("trimmed");
+        assert_eq!(&*sdt, "abc");
+        assert_eq!(
+            warnings::take(),
+            vec![Warning::TrimmedWhitespace {
+                datatype: "StringDatatype",
+                original: " abc  ".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(&*StringDatatype::normalized(" a \t b\n").unwrap(), "a b");
+        assert_eq!(&*TokenDatatype::normalized("\tac-1 ").unwrap(), "ac-1");
+        assert!(TokenDatatype::normalized("ac 1").is_err());
+
+        let (s, raw) = StringDatatype::normalized_with_raw(" a \t b\n").unwrap();
+        assert_eq!((&*s, raw.as_deref()), ("a b", Some(" a \t b\n")));
+        let (s, raw) = StringDatatype::normalized_with_raw("a b").unwrap();
+        assert_eq!((&*s, raw), ("a b", None));
+        let (token, raw) = TokenDatatype::normalized_with_raw("ac-1\n").unwrap();
+        assert_eq!((&*token, raw.as_deref()), ("ac-1", Some("ac-1\n")));
+        assert!(TokenDatatype::normalized_with_raw("ac 1").is_err());
+
+        type Values = (StringDatatype, StringDatatype, TokenDatatype);
+        let json = r#"["a  b", "a b", " ac-1\n"]"#;
+        assert!(serde_json::from_str::<Values>(r#"["a  b", "a b", "ac-1"]"#).is_ok());
+        assert!(serde_json::from_str::<Values>(json).is_err());
+
+        let collapse = config::Config {
+            collapse_whitespace: true,
+            ..config::Config::new()
+        };
+        let (s, same, token) =
+            config::scoped(collapse, || serde_json::from_str::<Values>(json)).expect("collapsed");
+        assert_eq!((&*s, &*same, &*token), ("a b", "a b", "ac-1"));
+        assert_eq!(
+            warnings::take(),
+            vec![
+                Warning::CollapsedWhitespace {
+                    datatype: "StringDatatype",
+                    original: "a  b".to_string()
+                },
+                Warning::CollapsedWhitespace {
+                    datatype: "TokenDatatype",
+                    original: " ac-1\n".to_string()
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn test_de_wrapped_base64_datatype() {
+        let json = r#""aGVsbG8g\n  d29ybGQ=\n""#;
+        let wrapped = config::Config {
+            base64_line_breaks: true,
+            ..config::Config::new()
+        };
+        let b64 = config::scoped(wrapped, || serde_json::from_str::<Base64Datatype>(json))
+            .expect("unwrapped");
+        assert_eq!(&*b64, "aGVsbG8gd29ybGQ=");
+        assert_eq!(
+            serde_json::to_string(&b64).unwrap(),
+            r#""aGVsbG8gd29ybGQ=""#
+        );
+        assert_eq!(
+            &*Base64Datatype::from_wrapped("aGVs\r\nbG8=").unwrap(),
+            "aGVsbG8="
+        );
+    }
+
+    #[test]
+    fn test_base64_datatype() {
+        for valid in ["aGVsbG8=", "aGVsbA==", "aGVs", "+/8="] {
+            assert!(Base64Datatype::try_from(valid).is_ok(), "{valid}");
+        }
+        for invalid in [
+            "", "aGVsbG8", "aGVs=bG8", "aGV=", "a===", "aGVsbG9=", "aGVs-_8=",
+        ] {
+            assert!(Base64Datatype::try_from(invalid).is_err(), "{invalid}");
+        }
+
+        let b64 = Base64Datatype::from_bytes(b"hello").unwrap();
+        assert_eq!(&*b64, "aGVsbG8=");
+        assert_eq!(b64.decode().unwrap(), b"hello");
+
+        let bytes: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let streamed = Base64Datatype::encode_from(bytes.as_slice()).unwrap();
+        assert_eq!(streamed, Base64Datatype::from_bytes(&bytes).unwrap());
+        let mut decoded = vec![];
+        assert_eq!(streamed.decode_to(&mut decoded).unwrap(), 10_000);
+        assert_eq!(decoded, bytes);
+    }
+
+    #[test]
+    fn test_base64_builder() {
+        let bytes: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut builder = Base64Builder::new();
+        for chunk in bytes.chunks(7) {
+            builder.push_chunk(chunk).unwrap();
+        }
+        assert_eq!(
+            builder.finish().unwrap(),
+            Base64Datatype::from_bytes(&bytes).unwrap()
+        );
+
+        let mut builder = Base64Builder::new();
+        builder.push_chunk(b"hel").unwrap();
+        builder.push_encoded("bG").unwrap();
+        assert!(builder.push_chunk(b"lo").is_err());
+        builder.push_encoded("8=").unwrap();
+        assert_eq!(&*builder.finish().unwrap(), "aGVsbG8=");
+
+        let mut builder = Base64Builder::new();
+        builder.push_chunk(b"he").unwrap();
+        assert!(builder.push_encoded("bG8=").is_err());
+
+        let pushed = |chunks: &[&str]| {
+            let mut builder = Base64Builder::new();
+            for chunk in chunks {
+                builder.push_encoded(chunk)?;
+            }
+            builder.finish()
+        };
+        assert!(pushed(&["aGVs", "bA", "=="]).is_ok());
+        for invalid in [
+            &["aGVs", "=bG8"][..],
+            &["aG", "V=="],
+            &["aGV=", "bG8="],
+            &["aG-s"],
+        ] {
+            assert!(pushed(invalid).is_err(), "{invalid:?}");
+        }
+        // Checked as a whole when finished
+        for invalid in [&[][..], &["aGV"], &["aGVsbG9="]] {
+            assert!(pushed(invalid).is_err(), "{invalid:?}");
+        }
+        let error = pushed(&["aGVs", "b!"]).unwrap_err();
+        assert!(
+            matches!(error, Error::Base64(ref reason) if reason == "invalid character at offset 5")
+        );
+    }
+
+    #[test]
+    fn test_string_as_token_lossy() {
+        let lossy = |s: &str| {
+            StringDatatype::try_from(s)
+                .unwrap()
+                .as_token_lossy()
+                .unwrap()
+        };
+
+        let (token, changes) = lossy("access-control");
+        assert_eq!(&*token, "access-control");
+        assert!(changes.is_empty());
+
+        let (token, changes) = lossy("access  control/policy");
+        assert_eq!(&*token, "access-control-policy");
+        assert_eq!(
+            changes,
+            vec![
+                TokenChange::CollapsedWhitespace { at: 6, len: 2 },
+                TokenChange::ReplacedChar {
+                    at: 15,
+                    original: '/'
+                },
+            ]
+        );
+
+        let (token, changes) = lossy("800-53 rev5");
+        assert_eq!(&*token, "_800-53-rev5");
+        assert_eq!(changes.last(), Some(&TokenChange::PrefixedUnderscore));
+
+        // The Metaschema pattern rejects empty strings.
+        #[cfg(not(feature = "regex_validation"))]
+        assert!(StringDatatype::try_from("")
+            .unwrap()
+            .as_token_lossy()
+            .is_err());
+    }
+
+    #[test]
+    fn test_token_from_string_strict() {
+        let strict =
+            |s: &str| TokenDatatype::from_string_strict(&StringDatatype::try_from(s).unwrap());
+        assert!(strict("access-control").is_ok());
+        assert!(strict("access control").is_err());
+        #[cfg(not(feature = "regex_validation"))]
+        assert!(strict("").is_err());
+    }
+
+    #[test]
+    fn test_markup_multiline_paragraphs() {
+        let lines = ["The organization:", "", "- reviews", "- approves"];
+        let mut narrative = MarkupMultilineDatatype::from_lines(lines).unwrap();
+        assert_eq!(narrative.lines().count(), 4);
+        narrative.append_paragraph("Reviews are *annual*.").unwrap();
+        assert_eq!(
+            &*narrative,
+            "The organization:\n\n- reviews\n- approves\n\nReviews are *annual*."
+        );
+        let paragraphs: Vec<_> = narrative.paragraphs().collect();
+        assert_eq!(paragraphs[1], "- reviews\n- approves");
+        assert_eq!(paragraphs.len(), 3);
+
+        let spaced = MarkupMultilineDatatype::try_from("\none\r\n  \r\ntwo\n\n").unwrap();
+        assert_eq!(spaced.paragraphs().collect::<Vec<_>>(), ["one", "two"]);
+        let mut empty = MarkupMultilineDatatype::from_lines([""; 0]).unwrap();
+        empty.append_paragraph("first").unwrap();
+        assert_eq!(&*empty, "first");
+    }
+
+    #[test]
+    fn test_token_nc_name_conversion() {
+        let token = TokenDatatype::try_from("ac-1").unwrap();
+        let name = NCName::from(token.clone());
+        assert_eq!(&*name, "ac-1");
+        assert_eq!(TokenDatatype::try_from(name).unwrap(), token);
+    }
+
+    #[test]
+    fn test_email_address() {
+        let valid = |s: &str| EmailAddressDatatype::try_from(s).is_ok();
+        assert!(valid("first.last@example.com"));
+        assert!(valid("o'brien+tag@sub.example.org"));
+        assert!(valid("\"john doe\"@example.com"));
+        assert!(valid("用户@例子.广告"));
+        assert!(valid("admin@[192.168.0.1]"));
+        assert!(valid("admin@[IPv6:2001:db8::1]"));
+
+        let error = |s: &str| EmailAddressDatatype::try_from(s).unwrap_err();
+        assert!(matches!(error("example.com"), Error::EmailMissingAt));
+        assert!(matches!(
+            error("a..b@example.com"),
+            Error::EmailLocalPart(_)
+        ));
+        assert!(matches!(error("a b@example.com"), Error::EmailLocalPart(_)));
+        assert!(matches!(
+            error(&format!("{}@a.b", "x".repeat(65))),
+            Error::EmailLocalPart(_)
+        ));
+        assert!(matches!(error("a@-example.com"), Error::EmailDomain(_)));
+        assert!(matches!(error("a@example..com"), Error::EmailDomain(_)));
+        assert!(matches!(error("a@exa_mple.com"), Error::EmailDomain(_)));
+        assert!(matches!(error("a@[300.1.1.1]"), Error::EmailDomain(_)));
+        assert!(matches!(
+            error(&format!("a@{}.com", "b.".repeat(130))),
+            Error::EmailLength
+        ));
+    }
+
+    #[test]
+    fn test_hostname() {
+        let valid = |s: &str| HostnameDatatype::try_from(s).is_ok();
+        assert!(valid("csrc.nist.gov"));
+        assert!(valid("localhost"));
+        assert!(valid("bücher.example"));
+        assert!(valid("example.com."));
+        assert!(valid(&format!("{}.", "a.".repeat(126) + "a")));
+        assert!(!valid(""));
+        assert!(!valid("."));
+        assert!(!valid("example.com.."));
+        assert!(!valid("a..b"));
+        assert!(!valid("-a.gov"));
+        assert!(!valid("under_score.gov"));
+        assert!(!valid(&format!("{}.gov", "a".repeat(64))));
+        assert!(!valid(&("a.".repeat(127) + "a")));
+    }
+
+    #[test]
+    fn test_ip_addresses() {
+        let v4 = IPV4AddressDatatype::from(Ipv4Addr::new(192, 0, 2, 7));
+        assert_eq!(&*v4, "192.0.2.7");
+        assert_eq!(v4.to_ipv4(), Ipv4Addr::new(192, 0, 2, 7));
+
+        let v6 = IPV6AddressDatatype::try_from("2001:DB8:0:0:0:0:0:1").unwrap();
+        assert_eq!(v6.to_ipv6(), Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1));
+        assert_eq!(&*IPV6AddressDatatype::from(v6.to_ipv6()), "2001:db8::1");
+    }
+
+    #[cfg(feature = "idn")]
+    #[test]
+    fn test_hostname_to_ascii() {
+        let host = HostnameDatatype::try_from("Bücher.example").unwrap();
+        assert_eq!(host.to_ascii(), "xn--bcher-kva.example");
+        assert!(HostnameDatatype::try_from("xn--a.example").is_err());
+    }
+
+    #[test]
+    fn test_markup_active_content() {
+        // Raw HTML is rejected by validation with the `markup` feature, so
+        // check the scanner directly
+        let active = contains_active_content;
+
+        let line = MarkupLineDatatype::try_from("see [the docs](https://example.com)").unwrap();
+        assert!(line.contains_active_content());
+        assert!(active("see [the docs][ref]"));
+        assert!(active("![logo](logo.png)"));
+        assert!(active("<https://example.com>"));
+        assert!(active("click <a href=\"x\">here</a>"));
+        assert!(active("<!-- comment -->"));
+
+        assert!(!active("plain *emphasis* and `code`"));
+        assert!(!active("a < b and [brackets] alone"));
+        assert!(!active(r"escaped \[text\](url)"));
+    }
 
+    #[test]
+    fn test_markup_escape_untrusted() {
+        let raw = "found ![x](http://evil) and <script>\nnext line";
+        let escaped = MarkupLineDatatype::escape_untrusted(raw).unwrap();
+        assert!(!escaped.contains_active_content());
+        assert!(!escaped.contains('\n'));
+        assert_eq!(
+            &*escaped,
+            r"found \!\[x\]\(http://evil\) and \<script\> next line"
+        );
+        assert!(MarkupLineDatatype::escape_untrusted("").is_err());
+        assert!(MarkupLineDatatype::escape_untrusted(" \n").is_err());
+    }
+}
@@ -0,0 +1,154 @@
+//! Checks across related date-time fields.
+//!
+//! An assessment task runs from a `start` to an `end` date-time, often
+//! `within` a maximum duration.  [check_window] reports every way such a
+//! window is inconsistent.  Date-times are compared as by their `Ord`
+//! implementation (see [crate::dates]), so a value without an offset is
+//! taken to be in UTC.
+//!
+//! ```
+//! use oscal_types::temporal::{check_window, WindowViolation};
+//! use oscal_types::{DateTimeWithTimezoneDatatype as DateTime, DayTimeDurationDatatype};
+//!
+//! let start = DateTime::try_from("2024-02-10T09:00:00-05:00").unwrap();
+//! let end = DateTime::try_from("2024-02-12T14:00:00Z").unwrap();
+//! let within = DayTimeDurationDatatype::try_from("P1D").unwrap();
+//!
+//! let violations = check_window(&start, &end, Some(&within));
+//! assert_eq!(violations.len(), 1);
+//! assert_eq!(violations[0].code(), "exceeds-max");
+//! assert_eq!(violations[0].to_string(), "window of P2D exceeds P1D");
+//! ```
+use serde::Serialize;
+use std::fmt;
+use std::ops::Deref;
+
+use crate::dates::utc_instant;
+use crate::*;
+
+/// The date-time datatypes a window can be made of.
+pub trait Temporal: Ord + Deref<Target = str> {}
+
+impl Temporal for DateTimeDatatype {}
+
+impl Temporal for DateTimeWithTimezoneDatatype {}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "code", rename_all = "kebab-case")]
+pub enum WindowViolation {
+    /// The window ends before it starts.
+    EndBeforeStart,
+    /// The window is longer than its maximum.
+    ExceedsMax {
+        length: DayTimeDurationDatatype,
+        max: DayTimeDurationDatatype,
+    },
+}
+
+impl WindowViolation {
+    /// A stable, machine-readable code, e.g. `"end-before-start"`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            WindowViolation::EndBeforeStart => "end-before-start",
+            WindowViolation::ExceedsMax { .. } => "exceeds-max",
+        }
+    }
+}
+
+impl fmt::Display for WindowViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowViolation::EndBeforeStart => f.write_str("window ends before it starts"),
+            WindowViolation::ExceedsMax { length, max } => {
+                write!(f, "window of {} exceeds {}", &**length, &**max)
+            }
+        }
+    }
+}
+
+/// The time from `start` to `end`, negative if `end` is earlier.  `None`
+/// if either cannot be parsed, which only happens without
+/// `date_validation`.
+pub fn window_length<T: Temporal>(start: &T, end: &T) -> Option<DayTimeDurationDatatype> {
+    let delta = utc_instant(end)?.signed_duration_since(utc_instant(start)?);
+    Some(DayTimeDurationDatatype::from_chrono_duration(delta))
+}
+
+/// Every way the window from `start` to `end` is inconsistent, if it lasts
+/// at most `max`.  A window may end when it starts.  A window that ends
+/// before it starts is not also checked against `max`.
+pub fn check_window<T: Temporal>(
+    start: &T,
+    end: &T,
+    max: Option<&DayTimeDurationDatatype>,
+) -> Vec<WindowViolation> {
+    let mut violations = Vec::new();
+    if end < start {
+        violations.push(WindowViolation::EndBeforeStart);
+        return violations;
+    }
+    if let (Some(max), Some(length)) = (max, window_length(start, end)) {
+        if &length > max {
+            violations.push(WindowViolation::ExceedsMax {
+                length,
+                max: max.clone(),
+            });
+        }
+    }
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn date_time(value: &str) -> DateTimeWithTimezoneDatatype {
+        DateTimeWithTimezoneDatatype::try_from(value).unwrap()
+    }
+
+    fn duration(value: &str) -> DayTimeDurationDatatype {
+        DayTimeDurationDatatype::try_from(value).unwrap()
+    }
+
+    #[test]
+    fn test_check_window() {
+        let start = date_time("2024-02-10T09:00:00Z");
+        let end = date_time("2024-02-10T11:00:00+01:00");
+        assert_eq!(&*window_length(&start, &end).unwrap(), "PT1H");
+        assert!(check_window(&start, &end, Some(&duration("PT1H"))).is_empty());
+        assert!(check_window(&start, &start, Some(&duration("PT0S"))).is_empty());
+        assert!(check_window(&start, &end, None).is_empty());
+
+        let violations = check_window(&start, &end, Some(&duration("PT59M")));
+        assert_eq!(
+            violations,
+            [WindowViolation::ExceedsMax {
+                length: duration("PT1H"),
+                max: duration("PT59M"),
+            }]
+        );
+        assert_eq!(
+            serde_json::to_value(&violations).unwrap(),
+            json!([{"code": "exceeds-max", "length": "PT1H", "max": "PT59M"}])
+        );
+
+        let violations = check_window(&end, &start, Some(&duration("PT1M")));
+        assert_eq!(violations, [WindowViolation::EndBeforeStart]);
+        assert_eq!(violations[0].code(), "end-before-start");
+        assert_eq!(&*window_length(&end, &start).unwrap(), "-PT1H");
+    }
+
+    #[test]
+    fn test_naive_window() {
+        let date_time = |s: &str| DateTimeDatatype::try_from(s).unwrap();
+        let start = date_time("2024-02-10T09:00:00");
+        let end = date_time("2024-02-10T09:30:00+01:00");
+        assert_eq!(
+            check_window(&start, &end, None),
+            [WindowViolation::EndBeforeStart]
+        );
+        let end = date_time("2024-02-10T09:30:00Z");
+        assert_eq!(&*window_length(&start, &end).unwrap(), "PT30M");
+    }
+}
@@ -0,0 +1,186 @@
+//! Values that keep their raw form when they are invalid.
+//!
+//! Deserializing a large document fails at the first invalid value.  A
+//! [Tolerant] field instead holds either the parsed value or the raw
+//! lexical form with its validation error, so the rest of the document
+//! still loads.  Invalid values can then be listed, repaired and parsed
+//! again, and are written back as they were read.
+//!
+//! ```
+//! use oscal_types::tolerant::Tolerant;
+//! use oscal_types::{TokenDatatype, UUIDDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Control {
+//!     id: Tolerant<TokenDatatype>,
+//!     owner: Tolerant<UUIDDatatype>,
+//! }
+//!
+//! let json = r#"{"id": "ac-1", "owner": "a78f7e4c_a27a_4b1e_901b_ebfecf2b0301"}"#;
+//! let mut control: Control = serde_json::from_str(json).unwrap();
+//! assert!(control.id.is_valid());
+//! assert!(control.owner.error().is_some());
+//!
+//! let fixed = control.owner.repair(|uuid| uuid.replace('_', "-"));
+//! assert_eq!(&**fixed.unwrap(), "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+//! ```
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+use crate::raw::Raw;
+use crate::registry::from_lexical;
+use crate::walk::lexical_form;
+use crate::*;
+
+#[derive(Debug, Clone)]
+pub enum Tolerant<T> {
+    Valid(T),
+    /// The value as read, and why it is not a valid `T`.
+    Invalid(Raw<T>, Error),
+}
+
+impl<T: OscalDatatype> Tolerant<T> {
+    /// Parse `value`, keeping it raw if it is invalid.
+    pub fn parse(value: &str) -> Self {
+        match from_lexical(value) {
+            Ok(parsed) => Tolerant::Valid(parsed),
+            Err(e) => Tolerant::Invalid(Raw::new(value), e),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        matches!(self, Tolerant::Valid(_))
+    }
+
+    pub fn valid(&self) -> Option<&T> {
+        match self {
+            Tolerant::Valid(value) => Some(value),
+            Tolerant::Invalid(..) => None,
+        }
+    }
+
+    pub fn error(&self) -> Option<&Error> {
+        match self {
+            Tolerant::Valid(_) => None,
+            Tolerant::Invalid(_, e) => Some(e),
+        }
+    }
+
+    pub fn into_result(self) -> Result<T, (Raw<T>, Error)> {
+        match self {
+            Tolerant::Valid(value) => Ok(value),
+            Tolerant::Invalid(raw, e) => Err((raw, e)),
+        }
+    }
+
+    /// Parse an invalid value again, e.g. after the configuration that
+    /// rejected it was changed.
+    pub fn retry(&mut self) -> Result<&T, &Error> {
+        if let Tolerant::Invalid(raw, _) = self {
+            *self = Self::parse(raw);
+        }
+        self.as_result()
+    }
+
+    /// Rewrite an invalid value with `fix` and parse it again.  If it is
+    /// still invalid, the rewritten value is kept with the new error.
+    /// Valid values are left alone.
+    pub fn repair(&mut self, fix: impl FnOnce(&str) -> String) -> Result<&T, &Error> {
+        if let Tolerant::Invalid(raw, _) = self {
+            *self = Self::parse(&fix(raw));
+        }
+        self.as_result()
+    }
+
+    fn as_result(&self) -> Result<&T, &Error> {
+        match self {
+            Tolerant::Valid(value) => Ok(value),
+            Tolerant::Invalid(_, e) => Err(e),
+        }
+    }
+}
+
+impl<T> From<T> for Tolerant<T> {
+    fn from(value: T) -> Self {
+        Tolerant::Valid(value)
+    }
+}
+
+/// Invalid values are written as they were read.
+impl<T: OscalDatatype> Serialize for Tolerant<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Tolerant::Valid(value) => value.serialize(serializer),
+            Tolerant::Invalid(raw, _) => raw.serialize(serializer),
+        }
+    }
+}
+
+/// Only values that are not a string, number or boolean fail, since they
+/// have no lexical form to keep.  Under [crate::de::from_value_collecting]
+/// the error of an invalid value is also collected.
+impl<'de, T: OscalDatatype> Deserialize<'de> for Tolerant<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let tolerant = lexical_form(&value)
+            .map(|lexical| Self::parse(&lexical))
+            .ok_or_else(|| serde::de::Error::custom("expected a string, number or boolean"))?;
+        if let Some(e) = tolerant.error() {
+            crate::de::collect(e);
+        }
+        Ok(tolerant)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_tolerant() {
+        let values: Vec<Tolerant<PositiveIntegerDatatype>> =
+            serde_json::from_value(json!([3, 0, "many"])).unwrap();
+        assert_eq!(values[0].valid().map(|n| **n), Some(3));
+        assert!(matches!(values[1].error(), Some(Error::NumberRange)));
+        assert!(values[2].error().is_some());
+        assert_eq!(
+            serde_json::to_value(&values).unwrap(),
+            json!([3, 0, "many"])
+        );
+
+        assert!(serde_json::from_value::<Tolerant<DateDatatype>>(json!(null)).is_err());
+        assert!(serde_json::from_value::<Tolerant<DateDatatype>>(json!({})).is_err());
+    }
+
+    #[test]
+    fn test_repair() {
+        let mut uuid = Tolerant::<UUIDDatatype>::parse("not-a-uuid");
+        assert!(uuid.repair(|s| s.replace('-', "")).is_err());
+        let (raw, _) = uuid.clone().into_result().unwrap_err();
+        assert_eq!(&*raw, "notauuid");
+
+        let id = "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301";
+        assert!(uuid.repair(|_| id.to_string()).is_ok());
+        assert!(uuid.is_valid());
+        // Valid values are not rewritten
+        assert!(uuid.repair(|_| String::new()).is_ok());
+    }
+
+    #[test]
+    fn test_retry() {
+        let strict = config::Config {
+            strict_uuid_versions: true,
+            ..config::Config::new()
+        };
+        let v7 = "01890a5d-ac96-774b-bcce-b302099a8057";
+        let mut uuid = config::scoped(strict.clone(), || Tolerant::<UUIDDatatype>::parse(v7));
+        assert!(matches!(uuid.error(), Some(Error::UuidVersion(7))));
+        let config = config::Config {
+            uuid_v7: true,
+            ..strict
+        };
+        config::scoped(config, || assert!(uuid.retry().is_ok()));
+    }
+}
@@ -0,0 +1,104 @@
+//! Length limiting for string-backed datatypes.
+//!
+//! Lengths are counted in `char`s, so truncation never splits a multi-byte
+//! character.
+use std::ops::Deref;
+
+use crate::Error;
+
+/// How many chars [Truncate::truncate_valid] cuts back from the limit to
+/// find a valid prefix.  Validating is linear in the length, so trying every
+/// prefix of a long value would be quadratic.
+pub const MAX_BACKOFF: usize = 16;
+
+pub trait Truncate: Sized {
+    /// Shorten the value to at most `max` chars for display, marking the cut
+    /// with an ellipsis.  The result is not necessarily a valid value.
+    fn display_truncated(&self, max: usize) -> String;
+
+    /// The longest prefix of at most `max` chars that is still a valid
+    /// value, giving up at most [MAX_BACKOFF] chars to find one.  Fails
+    /// with the validation error of the `max` char prefix if none of those
+    /// prefixes is valid.
+    fn truncate_valid(&self, max: usize) -> Result<Self, Error>;
+}
+
+impl<T> Truncate for T
+where
+    T: Deref<Target = str> + for<'a> TryFrom<&'a str, Error = Error>,
+{
+    fn display_truncated(&self, max: usize) -> String {
+        let value: &str = self;
+        if value.chars().count() <= max {
+            return value.to_string();
+        }
+        match max {
+            0 => String::new(),
+            _ => {
+                let mut shortened: String = value.chars().take(max - 1).collect();
+                shortened.push('…');
+                shortened
+            }
+        }
+    }
+
+    fn truncate_valid(&self, max: usize) -> Result<Self, Error> {
+        let value: &str = self;
+        let end = value
+            .char_indices()
+            .nth(max)
+            .map_or(value.len(), |(i, _)| i);
+        // Cut points from the longest prefix back, each validated once
+        let ends = value[..end]
+            .char_indices()
+            .rev()
+            .map(|(i, c)| i + c.len_utf8())
+            .take(MAX_BACKOFF);
+
+        let mut first_error = None;
+        for end in ends {
+            match T::try_from(&value[..end]) {
+                Ok(truncated) => return Ok(truncated),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(Error::StringParse(
+            "Cannot truncate to zero length".to_string(),
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{StringDatatype, URIDatatype, UUIDDatatype};
+
+    #[test]
+    fn test_display_truncated() {
+        let s = StringDatatype::try_from("héllo wörld").unwrap();
+        assert_eq!(s.display_truncated(20), "héllo wörld");
+        assert_eq!(s.display_truncated(5), "héll…");
+        assert_eq!(s.display_truncated(0), "");
+    }
+
+    #[test]
+    fn test_truncate_valid() {
+        // Trailing whitespace is not allowed, so the cut backs up to "hello"
+        let s = StringDatatype::try_from("hello world").unwrap();
+        assert_eq!(&*s.truncate_valid(6).unwrap(), "hello");
+
+        let uri = URIDatatype::try_from("https://example.com/a/long/path").unwrap();
+        assert_eq!(&*uri.truncate_valid(19).unwrap(), "https://example.com");
+
+        let uuid = UUIDDatatype::new();
+        assert!(uuid.truncate_valid(10).is_err());
+        assert_eq!(uuid.truncate_valid(36).unwrap(), uuid);
+
+        let spaced = format!("a{}b", " ".repeat(MAX_BACKOFF));
+        let spaced = StringDatatype::try_from(spaced.as_str()).unwrap();
+        assert_eq!(&*spaced.truncate_valid(MAX_BACKOFF).unwrap(), "a");
+        assert!(spaced.truncate_valid(MAX_BACKOFF + 1).is_err());
+    }
+}
@@ -1,17 +1,28 @@
 use fluent_uri::Uri;
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, CONTROLS};
 use serde::{Deserialize, Serialize};
-use std::{ops::Deref, str::FromStr};
+use std::path::{Component, Path, PathBuf, Prefix};
+use std::{fmt, ops::Deref, str::FromStr};
 
-use crate::{Base, Error, Validate};
+use crate::{Base, Error, Metaschema, TokenDatatype, UUIDDatatype, Validate};
 
-/// Repesents an absolute URI, with schema.  For relative paths,
-/// use [URIReferenceDatatype].
+/// Repesents a URI, with schema, as the RFC 3986 `URI` production: it may
+/// have a fragment.  For relative paths, use [URIReferenceDatatype].
 ///
 /// URIDatatype uses [fluent_uri] for validation.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(transparent)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 pub struct URIDatatype(String);
 
+impl Metaschema for URIDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("string")
+    }
+    fn description() -> Option<&'static str> {
+        Some("A universal resource identifier (URI) formatted according to RFC3986.")
+    }
+}
+
 impl Base for URIDatatype {
     fn base_type() -> String {
         String::from("String")
@@ -29,14 +40,36 @@ impl Deref for URIDatatype {
     }
 }
 
+impl fmt::Display for URIDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Copies `value` once, and only if it is valid.
 impl TryFrom<&str> for URIDatatype {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Self::validate(value)?;
+        let result = crate::validate::validate_in_mode::<Self>(value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+        result?;
         Ok(Self(value.to_owned()))
     }
 }
 
+/// Takes ownership of `value` rather than copying it.
+impl TryFrom<String> for URIDatatype {
+    type Error = Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let result = crate::validate::validate_in_mode::<Self>(&value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+        result?;
+        Ok(Self(value))
+    }
+}
+
 impl FromStr for URIDatatype {
     type Err = Error;
 
@@ -47,18 +80,27 @@ impl FromStr for URIDatatype {
 
 impl Validate for URIDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        let uri = value.parse::<Uri<String>>()?;
-        match uri.is_absolute_uri() {
-            true => Ok(()),
-            false => Err(Error::UriAbsolute),
+        let uri = Uri::parse(value)?;
+        match uri.scheme() {
+            Some(_) => Ok(()),
+            None => Err(Error::UriAbsolute),
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(transparent)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize)]
+#[serde(try_from = "String")]
 pub struct URIReferenceDatatype(String);
 
+impl Metaschema for URIReferenceDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("string")
+    }
+    fn description() -> Option<&'static str> {
+        Some("A URI Reference, either a URI or a relative-reference, formatted according to section 4.1 of RFC3986.")
+    }
+}
+
 impl Base for URIReferenceDatatype {
     fn base_type() -> String {
         String::from("String")
@@ -76,14 +118,36 @@ impl Deref for URIReferenceDatatype {
     }
 }
 
+impl fmt::Display for URIReferenceDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Copies `value` once, and only if it is valid.
 impl TryFrom<&str> for URIReferenceDatatype {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        Self::validate(value)?;
+        let result = crate::validate::validate_in_mode::<Self>(value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+        result?;
         Ok(Self(value.to_owned()))
     }
 }
 
+/// Takes ownership of `value` rather than copying it.
+impl TryFrom<String> for URIReferenceDatatype {
+    type Error = Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        let result = crate::validate::validate_in_mode::<Self>(&value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+        result?;
+        Ok(Self(value))
+    }
+}
+
 impl FromStr for URIReferenceDatatype {
     type Err = Error;
 
@@ -94,11 +158,540 @@ impl FromStr for URIReferenceDatatype {
 
 impl Validate for URIReferenceDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        let _ = value.parse::<Uri<String>>()?;
+        let _ = Uri::parse(value)?;
         Ok(())
     }
 }
 
+/// Characters percent-encoded in a path segment.
+const SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'/')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'[')
+    .add(b'\\')
+    .add(b']')
+    .add(b'^')
+    .add(b'`')
+    .add(b'{')
+    .add(b'|')
+    .add(b'}');
+
+fn path_error(path: &Path, reason: &str) -> Error {
+    Error::UriPath(format!("{}: {reason}", path.display()))
+}
+
+fn encode_segment(path: &Path, segment: &std::ffi::OsStr) -> Result<String, Error> {
+    let segment = segment
+        .to_str()
+        .ok_or_else(|| path_error(path, "not valid UTF-8"))?;
+    Ok(utf8_percent_encode(segment, SEGMENT).to_string())
+}
+
+impl URIDatatype {
+    /// The `file:` URI of an absolute path.  Windows drive letters become
+    /// `file:///C:/...` and UNC shares `file://server/share/...`.
+    pub fn from_file_path(path: &Path) -> Result<Self, Error> {
+        if !path.is_absolute() {
+            return Err(path_error(path, "path must be absolute"));
+        }
+        let mut uri = String::from("file://");
+        let mut segments: Vec<String> = vec![];
+        for component in path.components() {
+            match component {
+                Component::Prefix(prefix) => match prefix.kind() {
+                    Prefix::Disk(d) | Prefix::VerbatimDisk(d) => {
+                        uri.push('/');
+                        uri.push(d as char);
+                        uri.push(':');
+                    }
+                    Prefix::UNC(server, share) | Prefix::VerbatimUNC(server, share) => {
+                        uri.push_str(&encode_segment(path, server)?);
+                        uri.push('/');
+                        uri.push_str(&encode_segment(path, share)?);
+                    }
+                    _ => return Err(path_error(path, "unsupported path prefix")),
+                },
+                Component::RootDir | Component::CurDir => {}
+                Component::ParentDir => {
+                    segments.pop();
+                }
+                Component::Normal(segment) => segments.push(encode_segment(path, segment)?),
+            }
+        }
+        uri.push('/');
+        uri.push_str(&segments.join("/"));
+        Self::try_from(uri.as_str())
+    }
+}
+
+/// How far URIs are normalized before comparing them, following the
+/// comparison ladder of RFC 3986 section 6.2.  Each level includes the
+/// previous ones.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UriComparison {
+    /// Character for character.
+    Simple,
+    /// Lowercase scheme and host, uppercase percent-encoding hex digits,
+    /// decode percent-encoded unreserved characters and remove `.` and `..`
+    /// path segments.
+    Syntax,
+    /// Also drop default ports and empty ports, and read an empty path as
+    /// `/`, for the `http`, `https`, `ws`, `wss` and `ftp` schemes.
+    Scheme,
+}
+
+fn default_port(scheme: &str) -> Option<&'static str> {
+    match scheme {
+        "http" | "ws" => Some("80"),
+        "https" | "wss" => Some("443"),
+        "ftp" => Some("21"),
+        _ => None,
+    }
+}
+
+/// Uppercase percent-encoding hex digits and decode unreserved characters.
+fn normalize_percent_encoding(component: &str) -> String {
+    let bytes = component.as_bytes();
+    let mut out = String::with_capacity(component.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+                    out.push(byte as char);
+                } else {
+                    out.push_str(&format!("%{byte:02X}"));
+                }
+                i += 3;
+            }
+            _ => {
+                let len = component[i..].chars().next().map_or(1, char::len_utf8);
+                out.push_str(&component[i..i + len]);
+                i += len;
+            }
+        }
+    }
+    out
+}
+
+/// Remove `.` and `..` segments from a path (RFC 3986 section 5.2.4).
+pub(crate) fn remove_dot_segments(path: &str) -> String {
+    let mut input = path;
+    let mut output: Vec<&str> = vec![];
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../").or(input.strip_prefix("./")) {
+            input = rest;
+        } else if input.starts_with("/./") || input == "/." {
+            input = &input[2..];
+            if input.is_empty() {
+                input = "/";
+            }
+        } else if input.starts_with("/../") || input == "/.." {
+            input = &input[3..];
+            if input.is_empty() {
+                input = "/";
+            }
+            output.pop();
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let start = usize::from(input.starts_with('/'));
+            let end = input[start..].find('/').map_or(input.len(), |i| i + start);
+            output.push(&input[..end]);
+            input = &input[end..];
+        }
+    }
+    output.concat()
+}
+
+impl URIDatatype {
+    /// The URI normalized to `level`.  A value kept unchecked with
+    /// [crate::ValidationMode::Off] that does not parse is returned as is.
+    pub fn normalized(&self, level: UriComparison) -> String {
+        let uri = match self.parsed() {
+            Ok(uri) if level != UriComparison::Simple => uri,
+            _ => return self.0.clone(),
+        };
+        let scheme = uri
+            .scheme()
+            .map(|scheme| scheme.as_str().to_ascii_lowercase())
+            .unwrap_or_default();
+        let mut normalized = format!("{scheme}:");
+        let mut path = normalize_percent_encoding(uri.path().as_str());
+        if let Some(authority) = uri.authority() {
+            normalized.push_str("//");
+            if let Some(userinfo) = authority.userinfo() {
+                normalized.push_str(&normalize_percent_encoding(userinfo.as_str()));
+                normalized.push('@');
+            }
+            normalized.push_str(&normalize_percent_encoding(authority.host()).to_ascii_lowercase());
+            let port = authority.port().filter(|port| {
+                level < UriComparison::Scheme
+                    || !(port.is_empty() || default_port(&scheme) == Some(*port))
+            });
+            if let Some(port) = port {
+                normalized.push(':');
+                normalized.push_str(port);
+            }
+            if level == UriComparison::Scheme && path.is_empty() && default_port(&scheme).is_some()
+            {
+                path.push('/');
+            }
+        }
+        if path.starts_with('/') {
+            path = remove_dot_segments(&path);
+        }
+        normalized.push_str(&path);
+        if let Some(query) = uri.query() {
+            normalized.push('?');
+            normalized.push_str(&normalize_percent_encoding(query.as_str()));
+        }
+        if let Some(fragment) = uri.fragment() {
+            normalized.push('#');
+            normalized.push_str(&normalize_percent_encoding(fragment.as_str()));
+        }
+        normalized
+    }
+
+    /// Whether the URIs identify the same resource when normalized to
+    /// `level`.
+    ///
+    /// ```
+    /// use oscal_types::{UriComparison, URIDatatype};
+    ///
+    /// let a = URIDatatype::try_from("HTTP://Example.com:80").unwrap();
+    /// let b = URIDatatype::try_from("http://example.com/").unwrap();
+    /// assert!(!a.equivalent(&b, UriComparison::Syntax));
+    /// assert!(a.equivalent(&b, UriComparison::Scheme));
+    /// ```
+    pub fn equivalent(&self, other: &Self, level: UriComparison) -> bool {
+        self.normalized(level) == other.normalized(level)
+    }
+}
+
+/// Accessors for the components of a URI or URI reference.  Components are
+/// parsed on demand and returned as written, still percent-encoded.  A
+/// value kept unchecked with [crate::ValidationMode::Off] that does not
+/// parse has no components and an empty path.
+macro_rules! uri_components {
+    ($t:ty) => {
+        impl $t {
+            fn parsed(&self) -> Result<Uri<&str>, Error> {
+                Ok(Uri::parse(self.0.as_str())?)
+            }
+
+            /// The authority, e.g. `user@example.com:8443`.
+            pub fn authority(&self) -> Option<&str> {
+                let uri = self.parsed().ok()?;
+                uri.authority().map(|authority| authority.as_str())
+            }
+
+            /// The host of the authority.  IPv6 addresses keep their
+            /// brackets, e.g. `[::1]`.
+            pub fn host(&self) -> Option<&str> {
+                let uri = self.parsed().ok()?;
+                uri.authority().map(|authority| authority.host())
+            }
+
+            /// The port of the authority, if one is given.
+            pub fn port(&self) -> Option<u16> {
+                let uri = self.parsed().ok()?;
+                uri.authority()?.port_to_u16().ok().flatten()
+            }
+
+            /// The path, which may be empty.
+            pub fn path(&self) -> &str {
+                self.parsed().map_or("", |uri| uri.path().as_str())
+            }
+
+            pub fn query(&self) -> Option<&str> {
+                let uri = self.parsed().ok()?;
+                uri.query().map(|query| query.as_str())
+            }
+
+            /// The fragment, e.g. `ac-1` for `#ac-1`.
+            pub fn fragment(&self) -> Option<&str> {
+                let uri = self.parsed().ok()?;
+                uri.fragment().map(|fragment| fragment.as_str())
+            }
+
+            /// Whether the scheme is `https`, in any case.
+            pub fn is_https(&self) -> bool {
+                self.parsed().is_ok_and(|uri| {
+                    uri.scheme()
+                        .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("https"))
+                })
+            }
+
+            /// Whether the scheme is `urn`, in any case, e.g.
+            /// `urn:ietf:rfc:3986`.
+            pub fn is_urn(&self) -> bool {
+                self.parsed().is_ok_and(|uri| {
+                    uri.scheme()
+                        .is_some_and(|scheme| scheme.as_str().eq_ignore_ascii_case("urn"))
+                })
+            }
+        }
+    };
+}
+
+uri_components!(URIDatatype);
+uri_components!(URIReferenceDatatype);
+
+impl URIDatatype {
+    /// The scheme, as written.
+    ///
+    /// ```
+    /// use oscal_types::URIDatatype;
+    ///
+    /// let uri = URIDatatype::try_from("https://example.gov:8443/catalog.json?v=2").unwrap();
+    /// assert_eq!(uri.scheme(), "https");
+    /// assert_eq!(uri.host(), Some("example.gov"));
+    /// assert_eq!(uri.port(), Some(8443));
+    /// assert_eq!(uri.path(), "/catalog.json");
+    /// assert_eq!(uri.query(), Some("v=2"));
+    /// assert!(uri.is_https());
+    /// ```
+    ///
+    /// Empty for a value kept unchecked with [crate::ValidationMode::Off]
+    /// that has no scheme.
+    pub fn scheme(&self) -> &str {
+        let uri = self.parsed().ok();
+        uri.and_then(|uri| uri.scheme())
+            .map_or("", |scheme| scheme.as_str())
+    }
+}
+
+impl URIReferenceDatatype {
+    /// The scheme, which relative references do not have.
+    pub fn scheme(&self) -> Option<&str> {
+        let uri = self.parsed().ok()?;
+        uri.scheme().map(|scheme| scheme.as_str())
+    }
+
+    /// Resolve the reference against `base` (RFC 3986 section 5.2), e.g.
+    /// a back-matter `rlink` against the URI of the document.  Dot
+    /// segments are removed, and the fragment is the reference's own.
+    ///
+    /// Fails if either value does not parse, as a value kept unchecked
+    /// with [crate::ValidationMode::Off] may not, or if the result is not a
+    /// valid URI.
+    ///
+    /// ```
+    /// use oscal_types::{URIDatatype, URIReferenceDatatype};
+    ///
+    /// let base = URIDatatype::try_from("https://example.gov/oscal/catalog.json").unwrap();
+    /// let href = URIReferenceDatatype::try_from("../profiles/moderate.json#ac-1").unwrap();
+    /// assert_eq!(
+    ///     &*href.resolve_against(&base).unwrap(),
+    ///     "https://example.gov/profiles/moderate.json#ac-1"
+    /// );
+    /// ```
+    pub fn resolve_against(&self, base: &URIDatatype) -> Result<URIDatatype, Error> {
+        let reference = self.parsed()?;
+        let base_uri = base.parsed()?;
+        let (scheme, authority, path, query);
+        if let Some(own) = reference.scheme() {
+            scheme = own.as_str();
+            authority = reference.authority().map(|authority| authority.as_str());
+            path = remove_dot_segments(reference.path().as_str());
+            query = reference.query();
+        } else {
+            scheme = base_uri.scheme().ok_or(Error::UriAbsolute)?.as_str();
+            if let Some(own) = reference.authority() {
+                authority = Some(own.as_str());
+                path = remove_dot_segments(reference.path().as_str());
+                query = reference.query();
+            } else {
+                authority = base_uri.authority().map(|authority| authority.as_str());
+                let relative = reference.path().as_str();
+                if relative.is_empty() {
+                    path = base_uri.path().as_str().to_string();
+                    query = reference.query().or(base_uri.query());
+                } else {
+                    path = match relative.starts_with('/') {
+                        true => remove_dot_segments(relative),
+                        false => remove_dot_segments(&merge(&base_uri, relative)),
+                    };
+                    query = reference.query();
+                }
+            }
+        }
+
+        // Recompose the components (RFC 3986 section 5.3)
+        let mut resolved = format!("{scheme}:");
+        if let Some(authority) = authority {
+            resolved.push_str("//");
+            resolved.push_str(authority);
+        }
+        resolved.push_str(&path);
+        if let Some(query) = query {
+            resolved.push('?');
+            resolved.push_str(query.as_str());
+        }
+        if let Some(fragment) = reference.fragment() {
+            resolved.push('#');
+            resolved.push_str(fragment.as_str());
+        }
+        URIDatatype::try_from(resolved)
+    }
+}
+
+/// Merge a relative path with the path of `base` (RFC 3986 section 5.2.3).
+fn merge(base: &Uri<&str>, relative: &str) -> String {
+    let base_path = base.path().as_str();
+    if base.authority().is_some() && base_path.is_empty() {
+        return format!("/{relative}");
+    }
+    match base_path.rfind('/') {
+        Some(slash) => format!("{}{relative}", &base_path[..=slash]),
+        None => relative.to_string(),
+    }
+}
+
+/// The target of a fragment-only reference, such as the `#uuid` links OSCAL
+/// uses to point within a document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UriFragmentRef {
+    /// A UUID, e.g. a back-matter resource
+    Uuid(UUIDDatatype),
+    /// An NCName, e.g. a control ID
+    Token(TokenDatatype),
+    /// Any other fragment, percent-decoded
+    Other(String),
+}
+
+impl URIReferenceDatatype {
+    /// Whether the reference is only a fragment, e.g. `#ac-1`.
+    pub fn is_fragment_only(&self) -> bool {
+        self.0.starts_with('#')
+    }
+
+    /// The target of a fragment-only reference, or `None` for any other
+    /// reference.  The fragment is percent-decoded, then read as a UUID if
+    /// it is one, or else as a token.
+    ///
+    /// ```
+    /// use oscal_types::{URIReferenceDatatype, UriFragmentRef};
+    ///
+    /// let href = URIReferenceDatatype::try_from("#ac-1").unwrap();
+    /// assert!(matches!(href.fragment_ref(), Some(UriFragmentRef::Token(id)) if &*id == "ac-1"));
+    /// let href = URIReferenceDatatype::try_from("#a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").unwrap();
+    /// assert!(matches!(href.fragment_ref(), Some(UriFragmentRef::Uuid(_))));
+    /// ```
+    pub fn fragment_ref(&self) -> Option<UriFragmentRef> {
+        let fragment = self.0.strip_prefix('#')?;
+        let decoded = percent_decode_str(fragment).decode_utf8_lossy();
+        if let Ok(uuid) = UUIDDatatype::try_from(decoded.as_ref()) {
+            return Some(UriFragmentRef::Uuid(uuid));
+        }
+        Some(match TokenDatatype::try_from(decoded.as_ref()) {
+            Ok(token) if !token.is_empty() => UriFragmentRef::Token(token),
+            _ => UriFragmentRef::Other(decoded.into_owned()),
+        })
+    }
+
+    /// The UUID a fragment-only reference points to, if it is one.  The
+    /// UUID is in the lowercase hyphenated form, ready to be looked up.
+    pub fn fragment_uuid(&self) -> Option<UUIDDatatype> {
+        match self.fragment_ref()? {
+            UriFragmentRef::Uuid(uuid) => Some(uuid),
+            _ => None,
+        }
+    }
+}
+
+impl URIReferenceDatatype {
+    /// A relative reference to `path`, using `/` separators and
+    /// percent-encoding each segment.
+    pub fn from_relative_path(path: &Path) -> Result<Self, Error> {
+        let mut segments: Vec<String> = vec![];
+        for component in path.components() {
+            match component {
+                Component::Normal(segment) => segments.push(encode_segment(path, segment)?),
+                Component::ParentDir => segments.push("..".to_string()),
+                Component::CurDir => {}
+                Component::Prefix(_) | Component::RootDir => {
+                    return Err(path_error(path, "path must be relative"))
+                }
+            }
+        }
+        let mut reference = segments.join("/");
+        // A colon in the first segment would be read as a scheme (RFC 3986
+        // section 4.2)
+        if segments.first().is_some_and(|s| s.contains(':')) {
+            reference.insert_str(0, "./");
+        }
+        Self::try_from(reference.as_str())
+    }
+
+    /// Whether the reference starts with a scheme.
+    fn has_scheme(&self) -> bool {
+        self.find([':', '/', '?', '#'])
+            .is_some_and(|i| self.as_bytes()[i] == b':')
+    }
+
+    /// The decoded relative path of a relative-path reference without
+    /// query or fragment.  `..` segments are kept.  Segments that decode
+    /// to a separator or to an absolute path, e.g. `%2Fetc`, are rejected.
+    pub fn to_relative_path(&self) -> Result<PathBuf, Error> {
+        let as_path = || Path::new(&self.0);
+        if self.has_scheme() || self.starts_with('/') {
+            return Err(path_error(as_path(), "not a relative path reference"));
+        }
+        if self.contains(['?', '#']) {
+            return Err(path_error(as_path(), "query and fragment cannot be a path"));
+        }
+        let mut path = PathBuf::new();
+        for segment in self.split('/') {
+            let segment = percent_decode_str(segment)
+                .decode_utf8()
+                .map_err(|_| path_error(as_path(), "not valid UTF-8"))?;
+            let root = matches!(
+                Path::new(segment.as_ref()).components().next(),
+                Some(Component::Prefix(_) | Component::RootDir)
+            );
+            if root || segment.contains(['/', '\\']) {
+                return Err(path_error(as_path(), "a segment decodes to a path"));
+            }
+            match segment.as_ref() {
+                "" | "." => {}
+                segment => path.push(segment),
+            }
+        }
+        Ok(path)
+    }
+
+    /// The `file:` URI of this reference resolved against the absolute
+    /// directory `base_dir`.  `file:` URIs are returned as is.  Fails if
+    /// the reference climbs out of `base_dir` with `..` segments.
+    pub fn to_file_uri(&self, base_dir: &Path) -> Result<URIDatatype, Error> {
+        if self.starts_with("file:") {
+            return URIDatatype::try_from(self.0.as_str());
+        }
+        let relative = self.to_relative_path()?;
+        let mut depth = 0usize;
+        for component in relative.components() {
+            depth = match component {
+                Component::ParentDir => depth
+                    .checked_sub(1)
+                    .ok_or_else(|| path_error(&relative, "escapes the base directory"))?,
+                _ => depth + 1,
+            };
+        }
+        URIDatatype::from_file_path(&base_dir.join(relative))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,6 +703,45 @@ mod tests {
 
         let bad_uri = "#a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
         assert!(bad_uri.parse::<URIDatatype>().is_err());
+
+        let fragment = "https://fedramp.gov/ns/oscal#ac-1";
+        assert!(fragment.parse::<URIDatatype>().is_ok());
+    }
+
+    #[test]
+    fn test_uri_equivalence() {
+        let uri = |s: &str| URIDatatype::try_from(s).unwrap();
+        let syntax = |a: &str| uri(a).normalized(UriComparison::Syntax);
+        let scheme = |a: &str| uri(a).normalized(UriComparison::Scheme);
+
+        assert_eq!(
+            uri("HTTP://A.gov").normalized(UriComparison::Simple),
+            "HTTP://A.gov"
+        );
+        assert_eq!(
+            syntax("HTTP://User@A.GOV/%7euser/%2f%c3%a9"),
+            "http://User@a.gov/~user/%2F%C3%A9"
+        );
+        assert_eq!(
+            syntax("http://a.gov/a/./b/../c?q=%7E"),
+            "http://a.gov/a/c?q=~"
+        );
+        assert_eq!(syntax("urn:ISO:std:iso:8601"), "urn:ISO:std:iso:8601");
+        assert_eq!(syntax("http://a.gov/x#%7e"), "http://a.gov/x#~");
+        assert_eq!(scheme("https://a.gov:443"), "https://a.gov/");
+        assert_eq!(scheme("http://a.gov:/x"), "http://a.gov/x");
+        assert_eq!(scheme("http://a.gov:8080"), "http://a.gov:8080/");
+        assert_eq!(scheme("mailto:a@b.gov"), "mailto:a@b.gov");
+
+        assert!(uri("http://a.gov/x").equivalent(&uri("http://A.gov/./x"), UriComparison::Syntax));
+        assert!(!uri("http://a.gov/x").equivalent(&uri("http://A.gov/./x"), UriComparison::Simple));
+    }
+
+    #[test]
+    fn test_remove_dot_segments() {
+        assert_eq!(remove_dot_segments("/a/b/c/./../../g"), "/a/g");
+        assert_eq!(remove_dot_segments("mid/content=5/../6"), "mid/6");
+        assert_eq!(remove_dot_segments("/.."), "/");
     }
 
     #[test]
@@ -121,11 +753,234 @@ mod tests {
         assert!(bad_uri.parse::<URIReferenceDatatype>().is_ok());
     }
 
+    #[test]
+    fn test_components() {
+        let uri = URIDatatype::try_from("HTTPS://ops@[::1]/a%20b?q=1").unwrap();
+        assert_eq!(uri.scheme(), "HTTPS");
+        assert_eq!(uri.authority(), Some("ops@[::1]"));
+        assert_eq!(uri.host(), Some("[::1]"));
+        assert_eq!(uri.port(), None);
+        assert_eq!(uri.path(), "/a%20b");
+        assert_eq!(uri.query(), Some("q=1"));
+        assert!(uri.is_https());
+        assert!(!uri.is_urn());
+        assert_eq!(uri.fragment(), None);
+
+        let urn = URIDatatype::try_from("urn:ietf:rfc:3986").unwrap();
+        assert!(urn.is_urn());
+        assert_eq!(urn.host(), None);
+        assert_eq!(urn.path(), "ietf:rfc:3986");
+
+        let reference = URIReferenceDatatype::try_from("../catalog.json#ac-1").unwrap();
+        assert_eq!(reference.scheme(), None);
+        assert_eq!(reference.path(), "../catalog.json");
+        assert_eq!(reference.fragment(), Some("ac-1"));
+        assert!(!reference.is_https());
+
+        let empty = URIReferenceDatatype::try_from("http://example.com:").unwrap();
+        assert_eq!(empty.port(), None);
+        assert_eq!(empty.fragment(), None);
+    }
+
+    #[test]
+    fn test_invalid_components() {
+        assert!(serde_json::from_str::<URIDatatype>(r#""not a uri""#).is_err());
+        assert!(serde_json::from_str::<URIReferenceDatatype>(r#""a b""#).is_err());
+        let uri: URIDatatype = serde_json::from_str(r#""https:\/\/a.gov\/x""#).unwrap();
+        assert_eq!(uri.host(), Some("a.gov"));
+
+        let off = crate::config::Config {
+            validation_mode: crate::ValidationMode::Off,
+            ..crate::config::Config::new()
+        };
+        let (uri, reference) = crate::config::scoped(off, || {
+            (
+                URIDatatype::try_from("not a uri").unwrap(),
+                URIReferenceDatatype::try_from("a b").unwrap(),
+            )
+        });
+        assert_eq!((uri.scheme(), uri.host(), uri.path()), ("", None, ""));
+        assert!(!uri.is_https() && !uri.is_urn());
+        assert_eq!(uri.normalized(UriComparison::Scheme), "not a uri");
+        assert_eq!((reference.scheme(), reference.fragment()), (None, None));
+    }
+
+    #[test]
+    fn test_resolve_unchecked() {
+        let off = crate::config::Config {
+            validation_mode: crate::ValidationMode::Off,
+            ..crate::config::Config::new()
+        };
+        let (bad_base, bad_reference) = crate::config::scoped(off, || {
+            (
+                URIDatatype::try_from("a b").unwrap(),
+                URIReferenceDatatype::try_from("x y").unwrap(),
+            )
+        });
+        let base = URIDatatype::try_from("https://a.gov/b/c").unwrap();
+        let reference = URIReferenceDatatype::try_from("d").unwrap();
+        assert!(reference.resolve_against(&bad_base).is_err());
+        assert!(bad_reference.resolve_against(&base).is_err());
+        assert_eq!(
+            &*reference.resolve_against(&base).unwrap(),
+            "https://a.gov/b/d"
+        );
+    }
+
+    #[test]
+    fn test_resolve_against() {
+        // The examples of RFC 3986 section 5.4
+        let base = URIDatatype::try_from("http://a/b/c/d;p?q").unwrap();
+        let cases = [
+            ("g:h", "g:h"),
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q#s"),
+            ("g#s", "http://a/b/c/g#s"),
+            ("g?y#s", "http://a/b/c/g?y#s"),
+            (";x", "http://a/b/c/;x"),
+            ("g;x?y#s", "http://a/b/c/g;x?y#s"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("g#s/../x", "http://a/b/c/g#s/../x"),
+            ("http:g", "http:g"),
+        ];
+        for (reference, resolved) in cases {
+            let reference = URIReferenceDatatype::try_from(reference).unwrap();
+            assert_eq!(
+                &*reference.resolve_against(&base).unwrap(),
+                resolved,
+                "{}",
+                &*reference
+            );
+        }
+
+        let base = URIDatatype::try_from("https://example.gov").unwrap();
+        let reference = URIReferenceDatatype::try_from("catalog.json").unwrap();
+        assert_eq!(
+            &*reference.resolve_against(&base).unwrap(),
+            "https://example.gov/catalog.json"
+        );
+        let base = URIDatatype::try_from("urn:example:catalog").unwrap();
+        let reference = URIReferenceDatatype::try_from("#ac-1").unwrap();
+        assert_eq!(
+            &*reference.resolve_against(&base).unwrap(),
+            "urn:example:catalog#ac-1"
+        );
+    }
+
+    #[test]
+    fn test_fragment_ref() {
+        let fragment = |s: &str| URIReferenceDatatype::try_from(s).unwrap().fragment_ref();
+        let uuid = "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301";
+
+        assert_eq!(
+            fragment("#A78F7E4C-A27A-4B1E-901B-EBFECF2B0301"),
+            Some(UriFragmentRef::Uuid(UUIDDatatype::try_from(uuid).unwrap()))
+        );
+        assert_eq!(
+            fragment("#ac-1_smt.a"),
+            Some(UriFragmentRef::Token(
+                TokenDatatype::try_from("ac-1_smt.a").unwrap()
+            ))
+        );
+        assert_eq!(
+            fragment("#%C3%A9tape"),
+            Some(UriFragmentRef::Token(
+                TokenDatatype::try_from("étape").unwrap()
+            ))
+        );
+        assert_eq!(
+            fragment("#1ac"),
+            Some(UriFragmentRef::Other("1ac".to_string()))
+        );
+        assert_eq!(fragment("#"), Some(UriFragmentRef::Other(String::new())));
+        assert_eq!(fragment("catalog.json#ac-1"), None);
+        assert_eq!(fragment("ac-1"), None);
+
+        let href = URIReferenceDatatype::try_from(format!("#{uuid}")).unwrap();
+        assert!(href.is_fragment_only());
+        assert_eq!(&*href.fragment_uuid().unwrap(), uuid);
+        let href = URIReferenceDatatype::try_from("#ac-1").unwrap();
+        assert_eq!(href.fragment_uuid(), None);
+    }
+
     #[test]
     fn test_serde() {
-        let json = format!("\"{}\"", uuid::Uuid::new_v4());
+        let json = format!("\"urn:uuid:{}\"", uuid::Uuid::new_v4());
         let uri = serde_json::from_str::<URIDatatype>(&json).expect("fail");
         let result = serde_json::to_string(&uri).expect("fail");
         assert_eq!(&json, &result);
     }
+
+    #[test]
+    fn test_relative_path_reference() {
+        let path = Path::new("attachments").join("scan results #1.pdf");
+        let reference = URIReferenceDatatype::from_relative_path(&path).unwrap();
+        assert_eq!(&*reference, "attachments/scan%20results%20%231.pdf");
+        assert_eq!(reference.to_relative_path().unwrap(), path);
+
+        let colon = URIReferenceDatatype::from_relative_path(Path::new("a:b.txt")).unwrap();
+        assert_eq!(&*colon, "./a:b.txt");
+        assert_eq!(colon.to_relative_path().unwrap(), Path::new("a:b.txt"));
+
+        let absolute = URIReferenceDatatype::try_from("https://a.gov/x").unwrap();
+        assert!(absolute.to_relative_path().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_file_uri() {
+        assert!(URIReferenceDatatype::from_relative_path(Path::new("/etc")).is_err());
+
+        let reference = URIReferenceDatatype::try_from("old/../docs/ssp%20v2.xml").unwrap();
+        let uri = reference.to_file_uri(Path::new("/srv/oscal/pkg")).unwrap();
+        assert_eq!(&*uri, "file:///srv/oscal/pkg/docs/ssp%20v2.xml");
+        assert!(reference.to_file_uri(Path::new("relative")).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_path_traversal() {
+        let reference = |s: &str| URIReferenceDatatype::try_from(s).unwrap();
+        let base = Path::new("/srv/docs");
+
+        for encoded in ["%2Fetc%2Fpasswd", "..%2F..%2Fsecret", "a/..%5C..%5Csecret"] {
+            assert!(reference(encoded).to_relative_path().is_err(), "{encoded}");
+            assert!(reference(encoded).to_file_uri(base).is_err(), "{encoded}");
+        }
+
+        let parent = reference("../../secret");
+        assert_eq!(
+            parent.to_relative_path().unwrap(),
+            Path::new("../../secret")
+        );
+        assert!(parent.to_file_uri(base).is_err());
+        assert!(reference("a/../../secret").to_file_uri(base).is_err());
+        assert_eq!(
+            &*reference("a/../secret").to_file_uri(base).unwrap(),
+            "file:///srv/docs/secret"
+        );
+    }
 }
@@ -13,17 +13,40 @@
 /// The OSCAL program recommends using a version 4 (random) UUID as the document
 /// identifier, which is highly resistant to [collisions](https://en.wikipedia.org/wiki/Universally_unique_identifier#Collisions).
 ///
+/// Any RFC 4122 UUID is accepted unless
+/// [crate::config::Config::strict_uuid_versions] is set, which limits values
+/// to versions 4 and 5.
+/// [Version 7](https://www.rfc-editor.org/rfc/rfc9562.html#section-5.7) UUIDs,
+/// which start with a millisecond timestamp and so sort by creation time, are
+/// then also accepted when [crate::config::Config::uuid_v7] is set.
+///
 /// This implementation of OSCAL UUID uses the [uuid] crate
 ///
+/// UUIDs compare and hash ignoring ASCII case, so `A78F7E4C-...` and
+/// `a78f7e4c-...` are equal and can be used interchangeably as map keys.
+/// They are ordered by their lowercase form, which for the hyphenated form
+/// is the order of [UUIDDatatype::sort_key].
+///
 use serde::{Deserialize, Serialize};
-use std::ops::Deref;
-use uuid::Uuid;
+use std::cmp::Ordering;
+use std::hash::{Hash, Hasher};
+use std::{fmt, ops::Deref};
+use uuid::{Builder, Uuid};
 
-use crate::{Base, Error, Validate};
+use crate::{config, Base, Error, Metaschema, Validate, ValidationMode};
 
-#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
-#[serde(transparent)]
-pub struct UUIDDatatype(String);
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(try_from = "String")]
+pub struct UUIDDatatype(pub(crate) String);
+
+impl Metaschema for UUIDDatatype {
+    fn _type() -> Option<&'static str> {
+        Some("string")
+    }
+    fn description() -> Option<&'static str> {
+        Some("A version 4 or 5 Universally Unique Identifier (UUID) as defined by RFC 4122.")
+    }
+}
 
 impl Base for UUIDDatatype {
     fn base_type() -> String {
@@ -37,7 +60,106 @@ impl Base for UUIDDatatype {
 
 impl UUIDDatatype {
     pub fn new() -> Self {
-        Self(Uuid::new_v4().to_string())
+        let uuid = Self(Uuid::new_v4().to_string());
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::Uuid, &uuid);
+        uuid
+    }
+
+    /// A new version 7 UUID for the current time.
+    pub fn new_v7() -> Self {
+        let millis = crate::clock::now_utc().timestamp_millis().max(0) as u64;
+        let mut random = [0u8; 10];
+        random.copy_from_slice(&Uuid::new_v4().as_bytes()[..10]);
+        let uuid = Builder::from_unix_timestamp_millis(millis, &random).into_uuid();
+        let uuid = Self(uuid.to_string());
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::Uuid, &uuid);
+        uuid
+    }
+
+    /// The version 5 UUID for `name` in `namespace`.  The same namespace and
+    /// name always give the same UUID, so documents generated from the same
+    /// source keep stable identifiers.
+    pub fn new_v5(namespace: &UUIDDatatype, name: &str) -> Self {
+        let uuid = Self(Uuid::new_v5(&namespace.uuid(), name.as_bytes()).to_string());
+        #[cfg(feature = "provenance")]
+        crate::provenance::record(crate::DatatypeKind::Uuid, &uuid);
+        uuid
+    }
+
+    /// The nil UUID for a value kept unchecked with [ValidationMode::Off].
+    fn uuid(&self) -> Uuid {
+        Uuid::parse_str(&self.0).unwrap_or(Uuid::nil())
+    }
+
+    /// The UUID version, e.g. 4.
+    pub fn version(&self) -> usize {
+        self.uuid().get_version_num()
+    }
+
+    /// Whether this is a random, version 4 UUID.
+    pub fn is_v4(&self) -> bool {
+        self.version() == 4
+    }
+
+    /// Whether this is a name-based, version 5 UUID.
+    pub fn is_v5(&self) -> bool {
+        self.version() == 5
+    }
+
+    /// The Unix timestamp in milliseconds of a version 7 UUID.
+    pub fn timestamp_ms(&self) -> Option<u64> {
+        match self.version() {
+            7 => {
+                let bytes = self.uuid().into_bytes();
+                let mut millis = [0u8; 8];
+                millis[2..].copy_from_slice(&bytes[..6]);
+                Some(u64::from_be_bytes(millis))
+            }
+            _ => None,
+        }
+    }
+
+    /// A key that orders UUIDs by their 128 bit value, which for version 7
+    /// UUIDs is creation order.
+    pub fn sort_key(&self) -> u128 {
+        self.uuid().as_u128()
+    }
+}
+
+impl UUIDDatatype {
+    fn lowercase_bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.bytes().map(|b| b.to_ascii_lowercase())
+    }
+}
+
+impl PartialEq for UUIDDatatype {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.eq_ignore_ascii_case(&other.0)
+    }
+}
+
+impl Eq for UUIDDatatype {}
+
+impl Hash for UUIDDatatype {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.lowercase_bytes() {
+            state.write_u8(b);
+        }
+        state.write_u8(0xff);
+    }
+}
+
+impl PartialOrd for UUIDDatatype {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for UUIDDatatype {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.lowercase_bytes().cmp(other.lowercase_bytes())
     }
 }
 
@@ -54,24 +176,73 @@ impl Deref for UUIDDatatype {
     }
 }
 
-impl ToString for UUIDDatatype {
-    fn to_string(&self) -> String {
-        self.0.clone()
+impl fmt::Display for UUIDDatatype {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
     }
 }
 
+/// Whether a UUID is written in the lowercase hyphenated form.  The other
+/// forms accepted by [Uuid::parse_str] differ in length.
+fn is_canonical(value: &str) -> bool {
+    value.len() == 36 && !value.bytes().any(|b| b.is_ascii_uppercase())
+}
+
+/// Copies `value` once, and only if it is valid.  Other forms are written
+/// in the lowercase hyphenated form instead.
 impl TryFrom<&str> for UUIDDatatype {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let result = Uuid::parse_str(value)?;
-        Ok(Self(result.to_string()))
+        let result = crate::validate::validate_in_mode::<Self>(value);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+        result?;
+        if is_canonical(value) {
+            return Ok(Self(value.to_string()));
+        }
+        let uuid = match Uuid::parse_str(value) {
+            Ok(uuid) => uuid,
+            Err(_) if config::read(|c| c.validation_mode) == ValidationMode::Off => {
+                return Ok(Self(value.to_string()))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut buffer = Uuid::encode_buffer();
+        let lower = uuid.hyphenated().encode_lower(&mut buffer);
+        Ok(Self(lower.to_string()))
     }
 }
 
+/// Takes ownership of `value` rather than copying it when it is already in
+/// the lowercase hyphenated form.
+impl TryFrom<String> for UUIDDatatype {
+    type Error = Error;
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match is_canonical(&value) {
+            true => {
+                let result = crate::validate::validate_in_mode::<Self>(&value);
+                #[cfg(feature = "metrics")]
+                crate::metrics::record(<Self as crate::OscalDatatype>::KIND, result.is_ok());
+                result.map(|_| Self(value))
+            }
+            false => Self::try_from(value.as_str()),
+        }
+    }
+}
+
+/// Accepts any UUID, or with [config::Config::strict_uuid_versions] set
+/// only version 4 and 5 UUIDs, and version 7 when [config::Config::uuid_v7]
+/// is set.
 impl Validate for UUIDDatatype {
     fn validate(value: &str) -> Result<(), Error> {
-        let _ = uuid::Uuid::parse_str(value)?;
-        Ok(())
+        let version = uuid::Uuid::parse_str(value)?.get_version_num();
+        let (strict, v7) = config::read(|c| (c.strict_uuid_versions, c.uuid_v7));
+        match version {
+            _ if !strict => Ok(()),
+            4 | 5 => Ok(()),
+            7 if v7 => Ok(()),
+            version => Err(Error::UuidVersion(version)),
+        }
     }
 }
 
@@ -79,6 +250,21 @@ impl Validate for UUIDDatatype {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_try_from_string() {
+        let lower = String::from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+        let buffer = lower.as_ptr();
+        assert_eq!(UUIDDatatype::try_from(lower).unwrap().as_ptr(), buffer);
+        let upper = String::from("A78F7E4C-A27A-4B1E-901B-EBFECF2B0301");
+        assert_eq!(
+            &*UUIDDatatype::try_from(upper).unwrap(),
+            "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"
+        );
+        assert!(
+            UUIDDatatype::try_from(String::from("a78f7e4c-a27a-4b1e-901b-ebfecf2b030")).is_err()
+        );
+    }
+
     #[test]
     fn test_validate_uuid() {
         let input = "blah";
@@ -87,4 +273,80 @@ mod tests {
         let input = UUIDDatatype::new();
         assert!(UUIDDatatype::validate(&input.to_string()).is_ok());
     }
+
+    #[test]
+    fn test_uuid_versions() {
+        let v1 = "c232ab00-9414-11ec-b3c8-9f6bdeced846";
+        let nil = "00000000-0000-0000-0000-000000000000";
+        let v7 = UUIDDatatype::new_v7();
+        assert_eq!(v7.version(), 7);
+        for uuid in [v1, nil, &v7] {
+            assert!(UUIDDatatype::try_from(uuid).is_ok(), "{uuid}");
+        }
+
+        let strict = config::Config {
+            strict_uuid_versions: true,
+            ..config::Config::new()
+        };
+        config::scoped(strict.clone(), || {
+            assert!(matches!(
+                UUIDDatatype::try_from(v1),
+                Err(Error::UuidVersion(1))
+            ));
+            assert!(UUIDDatatype::try_from(nil).is_err());
+            assert!(UUIDDatatype::validate(&v7).is_err());
+            assert!(UUIDDatatype::validate(&UUIDDatatype::new()).is_ok());
+        });
+        let accept_v7 = config::Config {
+            uuid_v7: true,
+            ..strict
+        };
+        assert!(config::scoped(accept_v7, || UUIDDatatype::validate(&v7)).is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_validates() {
+        assert!(serde_json::from_str::<UUIDDatatype>(r#""TBD""#).is_err());
+        let uuid: UUIDDatatype =
+            serde_json::from_str(r#""A78F7E4C-A27A-4B1E-901B-EBFECF2B0301""#).unwrap();
+        assert_eq!(&*uuid, "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301");
+    }
+
+    #[test]
+    fn test_new_v5() {
+        let namespace = UUIDDatatype::try_from("a78f7e4c-a27a-4b1e-901b-ebfecf2b0301").unwrap();
+        let first = UUIDDatatype::new_v5(&namespace, "ac-1");
+        assert_eq!(first, UUIDDatatype::new_v5(&namespace, "ac-1"));
+        assert_ne!(first, UUIDDatatype::new_v5(&namespace, "ac-2"));
+        assert!(first.is_v5() && !first.is_v4());
+        assert!(UUIDDatatype::validate(&first).is_ok());
+        assert!(UUIDDatatype::new().is_v4());
+    }
+
+    #[test]
+    fn test_v7_timestamp_and_sort_key() {
+        let before = chrono::Utc::now().timestamp_millis() as u64;
+        let first = UUIDDatatype::new_v7();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        let second = UUIDDatatype::new_v7();
+
+        assert!(first.timestamp_ms().unwrap() >= before);
+        assert!(first.sort_key() < second.sort_key());
+        assert_eq!(UUIDDatatype::new().timestamp_ms(), None);
+    }
+
+    #[test]
+    fn test_map_keys_ignore_case() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let lower: UUIDDatatype =
+            serde_json::from_str(r#""a78f7e4c-a27a-4b1e-901b-ebfecf2b0301""#).unwrap();
+        let upper: UUIDDatatype =
+            serde_json::from_str(r#""A78F7E4C-A27A-4B1E-901B-EBFECF2B0301""#).unwrap();
+        let other = UUIDDatatype::try_from("5c8b5b4e-4a2f-4e0c-9a54-3c9a8f3a9e1d").unwrap();
+        assert_eq!(lower, upper);
+        assert_eq!(HashSet::from([lower.clone(), upper.clone()]).len(), 1);
+        let sorted = BTreeSet::from([lower.clone(), other.clone(), upper]);
+        assert_eq!(sorted.into_iter().collect::<Vec<_>>(), [other, lower]);
+    }
 }
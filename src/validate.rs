@@ -1,6 +1,69 @@
 use crate::error::Error;
+#[cfg(feature = "regex_validation")]
+use crate::{OscalDatatype, StringType};
 
 pub trait Validate {
     /// Test the value to determine whether it is valid
     fn validate(value: &str) -> Result<(), Error>;
+
+    /// Test the value regardless of the crate features that relax
+    /// [Validate::validate], e.g. `date_validation`.
+    fn validate_strict(value: &str) -> Result<(), Error> {
+        Self::validate(value)
+    }
+}
+
+/// How much checking values get when they are constructed, chosen at
+/// runtime with [crate::config::Config::validation_mode] or per call with
+/// [crate::OscalDatatype::parse_with_mode].  Numbers and booleans are
+/// always parsed, whatever the mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ValidationMode {
+    /// [Validate::validate_strict]: every check, whatever the crate
+    /// features.
+    Strict,
+    /// [Validate::validate]: the checks the crate features enable.
+    #[default]
+    Lenient,
+    /// No checks; values are kept as written.
+    Off,
+}
+
+/// Validate `value` as a `T` in the [ValidationMode] in effect.
+pub(crate) fn validate_in_mode<T: Validate>(value: &str) -> Result<(), Error> {
+    match crate::config::read(|config| config.validation_mode) {
+        ValidationMode::Strict => T::validate_strict(value),
+        ValidationMode::Lenient => T::validate(value),
+        ValidationMode::Off => Ok(()),
+    }
+}
+
+/// Match `value` against the Metaschema pattern of `T`, if it has one.
+/// Validators call this first when the `regex_validation` feature is
+/// enabled, so values are also held to the published OSCAL patterns.
+#[cfg(feature = "regex_validation")]
+pub(crate) fn validate_pattern<T: StringType + OscalDatatype>(value: &str) -> Result<(), Error> {
+    match T::pattern_regex() {
+        Some(pattern) if !pattern.is_match(value) => Err(Error::PatternMismatch(T::KIND.name())),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(all(test, feature = "regex_validation"))]
+mod tests {
+    use crate::*;
+
+    #[test]
+    fn test_validate_pattern() {
+        let mismatch = |e| matches!(e, Error::PatternMismatch(_));
+        assert!(mismatch(StringDatatype::try_from("").unwrap_err()));
+        assert!(mismatch(DateDatatype::try_from("1850-01-01").unwrap_err()));
+        assert!(DateTimeWithTimezoneDatatype::try_from("2024-02-10T09:30:00.5+05:30").is_ok());
+        assert!(MarkupMultilineDatatype::try_from("one\n\ntwo").is_ok());
+        // The datatype's own errors are reported first.
+        assert!(matches!(
+            TokenDatatype::try_from("1ac").unwrap_err(),
+            Error::NCNameIllegalFirstChar
+        ));
+    }
 }
@@ -0,0 +1,160 @@
+//! Dynamically typed datatype values.
+//!
+//! [OscalValue] holds a value of any datatype, for tools that only learn the
+//! datatype at runtime, e.g. from a Metaschema definition.
+//!
+//! ```
+//! use oscal_types::value::OscalValue;
+//! use oscal_types::DatatypeKind;
+//!
+//! let value = OscalValue::parse_as("DateDatatype", "2024-02-10").unwrap();
+//! assert_eq!(value.kind(), DatatypeKind::Date);
+//! assert_eq!(value.type_name(), "DateDatatype");
+//!
+//! let json = serde_json::to_string(&value).unwrap();
+//! assert_eq!(json, r#"{"type":"DateDatatype","value":"2024-02-10"}"#);
+//! assert_eq!(serde_json::from_str::<OscalValue>(&json).unwrap(), value);
+//! ```
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::macros::for_each_datatype;
+use crate::metapath::{MetapathValue, ToMetapathValue};
+use crate::registry::from_lexical;
+use crate::*;
+
+macro_rules! oscal_value {
+    ($($kind:ident => $t:ident),* $(,)?) => {
+        /// A value of any datatype, with one variant per [DatatypeKind].
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum OscalValue {
+            $($kind($t),)*
+        }
+
+        impl OscalValue {
+            /// Parse `value` as the lexical form of the datatype `kind`.
+            pub fn parse(kind: DatatypeKind, value: &str) -> Result<Self, Error> {
+                match kind {
+                    $(DatatypeKind::$kind => from_lexical::<$t>(value).map(OscalValue::$kind),)*
+                }
+            }
+
+            pub fn kind(&self) -> DatatypeKind {
+                match self {
+                    $(OscalValue::$kind(_) => DatatypeKind::$kind,)*
+                }
+            }
+
+            pub fn to_metapath_value(&self) -> MetapathValue {
+                match self {
+                    $(OscalValue::$kind(value) => value.to_metapath_value(),)*
+                }
+            }
+
+            fn serialize_value<S: Serializer>(
+                &self,
+                state: &mut S::SerializeStruct,
+            ) -> Result<(), S::Error> {
+                match self {
+                    $(OscalValue::$kind(value) => state.serialize_field("value", value),)*
+                }
+            }
+
+            fn deserialize_value<E: de::Error>(
+                kind: DatatypeKind,
+                value: serde_json::Value,
+            ) -> Result<Self, E> {
+                match kind {
+                    $(DatatypeKind::$kind => $t::deserialize(&value)
+                        .map(OscalValue::$kind)
+                        .map_err(E::custom),)*
+                }
+            }
+        }
+
+        $(
+            impl From<$t> for OscalValue {
+                fn from(value: $t) -> Self {
+                    OscalValue::$kind(value)
+                }
+            }
+        )*
+    };
+}
+
+for_each_datatype!(oscal_value);
+
+impl OscalValue {
+    /// Parse `value` as the datatype named `type_name`, e.g. `"UUIDDatatype"`.
+    pub fn parse_as(type_name: &str, value: &str) -> Result<Self, Error> {
+        Self::parse(type_name.parse()?, value)
+    }
+
+    /// The datatype name, e.g. `"UUIDDatatype"`.
+    pub fn type_name(&self) -> &'static str {
+        self.kind().name()
+    }
+}
+
+/// Serialized with its datatype, e.g.
+/// `{"type": "DateDatatype", "value": "2024-02-10"}`.
+impl Serialize for OscalValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("OscalValue", 2)?;
+        state.serialize_field("type", &self.kind())?;
+        self.serialize_value::<S>(&mut state)?;
+        state.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for OscalValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(serde::Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Tagged {
+            #[serde(rename = "type")]
+            kind: DatatypeKind,
+            value: serde_json::Value,
+        }
+
+        let tagged = Tagged::deserialize(deserializer)?;
+        Self::deserialize_value(tagged.kind, tagged.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_as() {
+        let uuid = "cc4a0ba2-e9d3-4d5e-9c0b-7e1d2a4a0f53";
+        let value = OscalValue::parse_as("UUIDDatatype", uuid).unwrap();
+        assert_eq!(
+            value,
+            OscalValue::Uuid(UUIDDatatype::try_from(uuid).unwrap())
+        );
+        assert_eq!(value.type_name(), "UUIDDatatype");
+
+        let count = OscalValue::parse_as("PositiveIntegerDatatype", "3").unwrap();
+        assert_eq!(count.to_metapath_value(), MetapathValue::Integer(3));
+        assert!(OscalValue::parse_as("PositiveIntegerDatatype", "0").is_err());
+        assert!(matches!(
+            OscalValue::parse_as("NoSuchDatatype", "x"),
+            Err(Error::UnrecognizedTypeName(_))
+        ));
+    }
+
+    #[test]
+    fn test_serde() {
+        let flag = OscalValue::from(BooleanDatatype::from(true));
+        let json = serde_json::to_string(&flag).unwrap();
+        assert_eq!(json, r#"{"type":"BooleanDatatype","value":true}"#);
+        assert_eq!(serde_json::from_str::<OscalValue>(&json).unwrap(), flag);
+
+        let invalid = r#"{"type":"UUIDDatatype","value":"not-a-uuid"}"#;
+        assert!(serde_json::from_str::<OscalValue>(invalid).is_err());
+        let unknown = r#"{"type":"DateDatatype","value":"2024-02-10","x":1}"#;
+        assert!(serde_json::from_str::<OscalValue>(unknown).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+//! Visiting the bound values of a JSON document.
+//!
+//! [walk] calls back with every scalar whose path is bound in a
+//! [FieldBindings], together with its JSON Pointer and datatype, and
+//! [walk_mut] does the same with mutable access.  Validation, reporting
+//! and normalization of whole documents are built on these.
+//!
+//! ```
+//! use oscal_types::{bindings::FieldBindings, walk, DatatypeKind};
+//! use serde_json::json;
+//!
+//! let bindings = FieldBindings::new().bind("/**/uuid", DatatypeKind::Uuid);
+//! let doc = json!({"catalog": {"uuid": "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301", "groups": [
+//!     {"uuid": "not a uuid"}
+//! ]}});
+//! let mut invalid = vec![];
+//! walk::walk(&doc, &bindings, |pointer, kind, value| {
+//!     let lexical = walk::lexical_form(value).unwrap();
+//!     if kind.validate(&lexical).is_err() {
+//!         invalid.push(pointer.to_string());
+//!     }
+//! });
+//! assert_eq!(invalid, ["/catalog/groups/0/uuid"]);
+//! ```
+use serde_json::Value;
+
+use crate::bindings::FieldBindings;
+use crate::de::push_pointer;
+use crate::DatatypeKind;
+
+/// The lexical form of a JSON scalar, e.g. `42` for the number 42.  `None`
+/// for null, arrays and objects.
+pub fn lexical_form(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(_) | Value::Bool(_) => Some(value.to_string()),
+        _ => None,
+    }
+}
+
+/// Call `visit` with the pointer, datatype and value of every bound scalar
+/// in `value`, in document order.  Nulls are skipped.
+pub fn walk(
+    value: &Value,
+    bindings: &FieldBindings,
+    mut visit: impl FnMut(&str, DatatypeKind, &Value),
+) {
+    walk_at(value, "", bindings, &mut visit);
+}
+
+/// Like [walk], for a `value` found at the JSON Pointer `pointer` of a
+/// larger document.
+pub fn walk_at(
+    value: &Value,
+    pointer: &str,
+    bindings: &FieldBindings,
+    visit: &mut impl FnMut(&str, DatatypeKind, &Value),
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map {
+                walk_at(child, &push_pointer(pointer, key), bindings, visit);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter().enumerate() {
+                walk_at(
+                    child,
+                    &push_pointer(pointer, &index.to_string()),
+                    bindings,
+                    visit,
+                );
+            }
+        }
+        Value::Null => {}
+        scalar => {
+            if let Some(kind) = bindings.kind_for(pointer) {
+                visit(pointer, kind, scalar);
+            }
+        }
+    }
+}
+
+/// Like [walk], with mutable access to each bound scalar.
+pub fn walk_mut(
+    value: &mut Value,
+    bindings: &FieldBindings,
+    mut visit: impl FnMut(&str, DatatypeKind, &mut Value),
+) {
+    walk_mut_at(value, "", bindings, &mut visit);
+}
+
+fn walk_mut_at(
+    value: &mut Value,
+    pointer: &str,
+    bindings: &FieldBindings,
+    visit: &mut impl FnMut(&str, DatatypeKind, &mut Value),
+) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                walk_mut_at(child, &push_pointer(pointer, key), bindings, visit);
+            }
+        }
+        Value::Array(items) => {
+            for (index, child) in items.iter_mut().enumerate() {
+                walk_mut_at(
+                    child,
+                    &push_pointer(pointer, &index.to_string()),
+                    bindings,
+                    visit,
+                );
+            }
+        }
+        Value::Null => {}
+        scalar => {
+            if let Some(kind) = bindings.kind_for(pointer) {
+                visit(pointer, kind, scalar);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_walk() {
+        let bindings = FieldBindings::new()
+            .bind("/**/count", DatatypeKind::PositiveInteger)
+            .bind("/**/flag", DatatypeKind::Boolean)
+            .bind("/items", DatatypeKind::String);
+        let doc = json!({"items": [{"count": 2, "flag": true}, {"count": null}]});
+        let mut seen = vec![];
+        walk(&doc, &bindings, |pointer, kind, value| {
+            seen.push((pointer.to_string(), kind, lexical_form(value).unwrap()));
+        });
+        assert_eq!(
+            seen,
+            [
+                (
+                    "/items/0/count".to_string(),
+                    DatatypeKind::PositiveInteger,
+                    "2".to_string()
+                ),
+                (
+                    "/items/0/flag".to_string(),
+                    DatatypeKind::Boolean,
+                    "true".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_walk_mut() {
+        let bindings = FieldBindings::new().bind("/*/name", DatatypeKind::Token);
+        let mut doc = json!({"a": {"name": "x"}, "b": {"name": "y"}});
+        walk_mut(&mut doc, &bindings, |_, _, value| {
+            *value = json!(value.as_str().unwrap().to_uppercase());
+        });
+        assert_eq!(doc, json!({"a": {"name": "X"}, "b": {"name": "Y"}}));
+    }
+}
@@ -0,0 +1,103 @@
+//! Non-fatal diagnostics.
+//!
+//! Some operations recover from questionable input instead of failing (see
+//! [crate::config]).  Each recovery is recorded as a [Warning] on the current
+//! thread, and can be collected with [take] once the operation is done.
+//! At most [MAX_WARNINGS] are kept between calls to [take].
+//!
+use std::cell::RefCell;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// Leading or trailing whitespace was removed from a value.
+    TrimmedWhitespace {
+        datatype: &'static str,
+        original: String,
+    },
+    /// Runs of whitespace in a value were collapsed to single spaces.
+    CollapsedWhitespace {
+        datatype: &'static str,
+        original: String,
+    },
+    /// The local timezone could not be determined and UTC was used.
+    LocalTimezone(String),
+    /// A datatype was named by its historical name.
+    DeprecatedName { name: String, current: &'static str },
+    /// A number outside the range of its datatype was clamped into it.
+    Clamped {
+        datatype: &'static str,
+        original: String,
+        clamped: String,
+    },
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::TrimmedWhitespace { datatype, original } => {
+                write!(f, "{datatype}: trimmed whitespace from {original:?}")
+            }
+            Warning::CollapsedWhitespace { datatype, original } => {
+                write!(f, "{datatype}: collapsed whitespace in {original:?}")
+            }
+            Warning::LocalTimezone(message) => {
+                write!(f, "local timezone unavailable, using UTC: {message}")
+            }
+            Warning::DeprecatedName { name, current } => {
+                write!(f, "{name} is deprecated, use {current}")
+            }
+            Warning::Clamped {
+                datatype,
+                original,
+                clamped,
+            } => write!(f, "{datatype}: clamped {original} to {clamped}"),
+        }
+    }
+}
+
+thread_local! {
+    static WARNINGS: RefCell<Vec<Warning>> = const { RefCell::new(Vec::new()) };
+}
+
+/// How many warnings a thread keeps until they are taken.  Later warnings
+/// are dropped, so a thread that never calls [take] does not grow without
+/// bound.
+pub const MAX_WARNINGS: usize = 1000;
+
+/// Record a warning on the current thread, unless [MAX_WARNINGS] are
+/// already waiting to be taken.
+pub(crate) fn emit(warning: Warning) {
+    WARNINGS.with(|warnings| {
+        let mut warnings = warnings.borrow_mut();
+        if warnings.len() < MAX_WARNINGS {
+            warnings.push(warning);
+        }
+    });
+}
+
+/// Remove and return all warnings recorded on the current thread.
+pub fn take() -> Vec<Warning> {
+    WARNINGS.with(|warnings| std::mem::take(&mut *warnings.borrow_mut()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded() {
+        let deprecated = |n: usize| Warning::DeprecatedName {
+            name: n.to_string(),
+            current: "StringDatatype",
+        };
+        for n in 0..MAX_WARNINGS + 10 {
+            emit(deprecated(n));
+        }
+        let taken = take();
+        assert_eq!(taken.len(), MAX_WARNINGS);
+        assert_eq!(taken[MAX_WARNINGS - 1], deprecated(MAX_WARNINGS - 1));
+        emit(deprecated(0));
+        assert_eq!(take(), [deprecated(0)]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Serde support for XML readers and writers.
+//!
+//! XML serde backends such as quick-xml present every attribute and text
+//! node as a string.  The adapters here read and write the datatypes through
+//! their [XmlLexical] forms, so whitespace is collapsed where XSD requires it
+//! and `xs:boolean`, `xs:base64Binary` and `xs:dateTime` lexical quirks are
+//! handled.  Use [lexical] as a field attribute, or wrap values in [Xml]
+//! inside collections:
+//!
+//! ```
+//! use oscal_types::xml::{self, Xml};
+//! use oscal_types::{BooleanDatatype, TokenDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Prop {
+//!     #[serde(with = "xml::lexical")]
+//!     name: TokenDatatype,
+//!     #[serde(with = "xml::lexical")]
+//!     value: BooleanDatatype,
+//!     classes: Vec<Xml<TokenDatatype>>,
+//! }
+//!
+//! // What an XML reader produces for
+//! // <prop name=" marking " value="1"><class>a</class></prop>
+//! let json = r#"{"name": " marking ", "value": "1", "classes": ["a"]}"#;
+//! let prop: Prop = serde_json::from_str(json).unwrap();
+//! assert_eq!(&*prop.name, "marking");
+//! assert!(*prop.value);
+//! ```
+//!
+//! Markup datatypes are element content rather than lexical strings and are
+//! not covered.
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ops::Deref;
+
+use crate::lexical::XmlLexical;
+
+/// `#[serde(with = "oscal_types::xml::lexical")]` for any [XmlLexical]
+/// field.
+pub mod lexical {
+    use super::*;
+
+    pub fn serialize<T: XmlLexical, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_xml_lexical())
+    }
+
+    pub fn deserialize<'de, T: XmlLexical, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        T::from_xml_lexical(&value).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A datatype (de)serialized in its XML lexical form.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Xml<T>(pub T);
+
+impl<T> Xml<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Xml<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: XmlLexical> Serialize for Xml<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        lexical::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: XmlLexical> Deserialize<'de> for Xml<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        lexical::deserialize(deserializer).map(Xml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DateTimeWithTimezoneDatatype, IntegerDatatype};
+
+    #[test]
+    fn test_round_trip() {
+        let n: Xml<IntegerDatatype> = serde_json::from_str(r#"" 42 ""#).unwrap();
+        assert_eq!(**n, 42);
+        assert_eq!(serde_json::to_string(&n).unwrap(), r#""42""#);
+
+        let dt: Xml<DateTimeWithTimezoneDatatype> =
+            serde_json::from_str(r#""2024-02-10T24:00:00Z""#).unwrap();
+        assert_eq!(&**dt, "2024-02-11T00:00:00Z");
+        assert!(serde_json::from_str::<Xml<IntegerDatatype>>(r#""4 2""#).is_err());
+    }
+}
@@ -0,0 +1,377 @@
+//! Serde support for YAML readers and writers.
+//!
+//! OSCAL documents may be written in YAML, but YAML 1.1 parsers resolve
+//! some plain scalars to other types: `on`, `no` and `y` are booleans, and
+//! `2024-02-10 09:30:00` is a timestamp.  Depending on the parser a value
+//! therefore arrives as a string, boolean or number, and timestamps arrive
+//! in YAML's own forms.
+//!
+//! The adapters here accept all of these.  [YamlLexical] reads every
+//! datatype from its YAML scalar: [BooleanDatatype] accepts the YAML 1.1
+//! boolean words, and the date-time types accept YAML timestamps, e.g.
+//! `2001-12-14 21:59:43.10 -5`.  Use [lexical] as a field attribute, or wrap
+//! values in [Yaml] inside collections:
+//!
+//! ```
+//! use oscal_types::yaml::{self, Yaml};
+//! use oscal_types::{BooleanDatatype, DateTimeWithTimezoneDatatype};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Task {
+//!     #[serde(with = "yaml::lexical")]
+//!     enabled: BooleanDatatype,
+//!     runs: Vec<Yaml<DateTimeWithTimezoneDatatype>>,
+//! }
+//!
+//! // What a YAML 1.2 parser produces for
+//! // enabled: on
+//! // runs: [2024-2-10 9:30:00 -5, 2024-02-11T09:30:00Z]
+//! let json = r#"{"enabled": "on", "runs": ["2024-2-10 9:30:00 -5", "2024-02-11T09:30:00Z"]}"#;
+//! let task: Task = serde_json::from_str(json).unwrap();
+//! assert!(*task.enabled);
+//! assert_eq!(&**task.runs[0], "2024-02-10T09:30:00-05:00");
+//! ```
+//!
+//! Writing is the other half.  The adapters serialize values as the
+//! datatypes do, and a YAML emitter decides how to write each string.
+//! Emitters that write YAML text themselves should write values with
+//! [scalar], which quotes any string a YAML 1.1 or 1.2 parser would read
+//! as something else.
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::borrow::Cow;
+use std::ops::Deref;
+use std::sync::LazyLock;
+
+use crate::registry::from_lexical;
+use crate::walk::lexical_form;
+use crate::*;
+
+pub trait YamlLexical: Sized {
+    /// Parse a YAML scalar, as written or as resolved by a YAML parser and
+    /// converted to its lexical form, e.g. `true` for the boolean true.
+    fn from_yaml_scalar(value: &str) -> Result<Self, Error>;
+}
+
+/// The YAML 1.1 boolean words.
+const TRUE: &[&str] = &[
+    "y", "Y", "yes", "Yes", "YES", "true", "True", "TRUE", "on", "On", "ON",
+];
+const FALSE: &[&str] = &[
+    "n", "N", "no", "No", "NO", "false", "False", "FALSE", "off", "Off", "OFF",
+];
+
+/// The YAML 1.1 null words.
+const NULL: &[&str] = &["~", "null", "Null", "NULL"];
+
+impl YamlLexical for BooleanDatatype {
+    fn from_yaml_scalar(value: &str) -> Result<Self, Error> {
+        match value {
+            _ if TRUE.contains(&value) => Ok(Self::from(true)),
+            _ if FALSE.contains(&value) => Ok(Self::from(false)),
+            _ => Err(Error::BooleanParse),
+        }
+    }
+}
+
+/// The YAML timestamp type, https://yaml.org/type/timestamp.html.
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"^([0-9]{4})-([0-9]{1,2})-([0-9]{1,2})",
+        r"(T|t|[ \t]+)([0-9]{1,2}):([0-9]{2}):([0-9]{2})(?:\.([0-9]*))?",
+        r"(?:[ \t]*(?:(Z)|([-+])([0-9]{1,2})(?::([0-9]{2}))?))?$",
+    ))
+    .expect("invalid pattern")
+});
+
+/// Rewrite a YAML timestamp as an RFC 3339 date-time.  A timestamp without
+/// a timezone is in UTC, unless it is written as a JSON date-time without
+/// an offset.
+fn rfc3339(value: &str) -> Option<String> {
+    let parts = TIMESTAMP.captures(value)?;
+    let part = |i| parts.get(i).map_or("", |m| m.as_str());
+    let fraction = match part(8) {
+        "" => String::new(),
+        digits => format!(".{digits}"),
+    };
+    let zone = match (part(9), part(10)) {
+        ("Z", _) => "Z".to_string(),
+        (_, "") if part(4) == "T" => String::new(),
+        (_, "") => "Z".to_string(),
+        (_, sign) => format!("{sign}{:0>2}:{:0>2}", part(11), part(12)),
+    };
+    Some(format!(
+        "{}-{:0>2}-{:0>2}T{:0>2}:{}:{}{fraction}{zone}",
+        part(1),
+        part(2),
+        part(3),
+        part(5),
+        part(6),
+        part(7),
+    ))
+}
+
+macro_rules! yaml_lexical_date_time {
+    ($($t:ty),* $(,)?) => {
+        $(
+            /// A date-time without an offset only becomes UTC if it is
+            /// written in a YAML form, e.g. with a space before the time.
+            impl YamlLexical for $t {
+                fn from_yaml_scalar(value: &str) -> Result<Self, Error> {
+                    match rfc3339(value) {
+                        Some(date_time) => Self::try_from(date_time.as_str()),
+                        None => Self::try_from(value),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+yaml_lexical_date_time!(DateTimeDatatype, DateTimeWithTimezoneDatatype);
+
+/// Types whose YAML scalar is their JSON lexical form.  YAML dates already
+/// are.
+macro_rules! yaml_lexical_plain {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl YamlLexical for $t {
+                fn from_yaml_scalar(value: &str) -> Result<Self, Error> {
+                    from_lexical(value)
+                }
+            }
+        )*
+    };
+}
+
+yaml_lexical_plain!(
+    DateDatatype,
+    DayTimeDurationDatatype,
+    YearMonthDurationDatatype,
+    TimezoneOffsetDatatype,
+    DecimalDatatype,
+    IntegerDatatype,
+    NonNegativeIntegerDatatype,
+    PositiveIntegerDatatype,
+    StringDatatype,
+    Base64Datatype,
+    EmailAddressDatatype,
+    HostnameDatatype,
+    IPV4AddressDatatype,
+    IPV6AddressDatatype,
+    IPV4CidrDatatype,
+    IPV6CidrDatatype,
+    MarkupLineDatatype,
+    MarkupMultilineDatatype,
+    TokenDatatype,
+    URIDatatype,
+    URIReferenceDatatype,
+    UUIDDatatype,
+);
+
+/// Plain scalars that YAML 1.1 or 1.2 resolves to a number.
+static NUMBER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(concat!(
+        r"^[-+]?(?:[0-9][0-9_]*(?:\.[0-9_]*)?|\.[0-9_]+)(?:[eE][-+]?[0-9]+)?$",
+        r"|^[-+]?0(?:x[0-9a-fA-F_]+|o?[0-7_]+|b[01_]+)$",
+        r"|^[-+]?[0-9][0-9_]*(?::[0-5]?[0-9])+(?:\.[0-9_]*)?$",
+        r"|^[-+]?\.(?:inf|Inf|INF)$|^\.(?:nan|NaN|NAN)$",
+    ))
+    .expect("invalid pattern")
+});
+
+/// Whether `value` must be quoted to be read back as a string by YAML 1.1
+/// and 1.2 parsers: it would resolve to a boolean, null, number or
+/// timestamp, or it is not a valid plain scalar.
+pub fn needs_quotes(value: &str) -> bool {
+    let first = value.chars().next();
+    value.is_empty()
+        || TRUE.contains(&value)
+        || FALSE.contains(&value)
+        || NULL.contains(&value)
+        || matches!(value, "=" | "<<")
+        || NUMBER.is_match(value)
+        || value.parse::<chrono::NaiveDate>().is_ok()
+        || TIMESTAMP.is_match(value)
+        || matches!(first, Some(c) if "-?:,[]{}#&*!|>'\"%@`".contains(c) || c.is_whitespace())
+        || value.ends_with(|c: char| c == ':' || c.is_whitespace())
+        || value.contains(": ")
+        || value.contains(" #")
+        || value.contains(|c: char| c.is_control())
+}
+
+/// `value` as a YAML scalar: as it is if it can be written plain, otherwise
+/// double quoted.
+pub fn scalar(value: &str) -> Cow<'_, str> {
+    if !needs_quotes(value) {
+        return Cow::Borrowed(value);
+    }
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\r' => quoted.push_str("\\r"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    Cow::Owned(quoted)
+}
+
+/// `#[serde(with = "oscal_types::yaml::lexical")]` for any [YamlLexical]
+/// field.
+pub mod lexical {
+    use super::*;
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.serialize(serializer)
+    }
+
+    /// Accepts a string, boolean or number, whichever the YAML parser
+    /// resolved the scalar to.
+    pub fn deserialize<'de, T: YamlLexical, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        let lexical = lexical_form(&value)
+            .ok_or_else(|| serde::de::Error::custom("expected a string, number or boolean"))?;
+        T::from_yaml_scalar(&lexical).map_err(serde::de::Error::custom)
+    }
+}
+
+/// A datatype deserialized from its YAML scalar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Yaml<T>(pub T);
+
+impl<T> Yaml<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> Deref for Yaml<T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Serialize> Serialize for Yaml<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        lexical::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: YamlLexical> Deserialize<'de> for Yaml<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        lexical::deserialize(deserializer).map(Yaml)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_booleans() {
+        for (value, expected) in [("on", true), ("No", false), ("y", true), ("FALSE", false)] {
+            assert_eq!(*BooleanDatatype::from_yaml_scalar(value).unwrap(), expected);
+        }
+        assert!(BooleanDatatype::from_yaml_scalar("oN").is_err());
+
+        let values: Vec<Yaml<BooleanDatatype>> =
+            serde_json::from_value(json!([true, "off", "yes"])).unwrap();
+        let values: Vec<bool> = values.iter().map(|b| ***b).collect();
+        assert_eq!(values, [true, false, true]);
+        assert!(serde_json::from_value::<Yaml<BooleanDatatype>>(json!(null)).is_err());
+    }
+
+    #[test]
+    fn test_timestamps() {
+        let date_time = |value| {
+            DateTimeWithTimezoneDatatype::from_yaml_scalar(value)
+                .map(|dt| dt.to_string())
+                .map_err(|_| value)
+        };
+        assert_eq!(
+            date_time("2001-12-14t21:59:43.10-05:00").unwrap(),
+            "2001-12-14T21:59:43.10-05:00"
+        );
+        assert_eq!(
+            date_time("2001-12-14 21:59:43.10 -5").unwrap(),
+            "2001-12-14T21:59:43.10-05:00"
+        );
+        assert_eq!(
+            date_time("2001-12-15 2:59:43.10").unwrap(),
+            "2001-12-15T02:59:43.10Z"
+        );
+        assert_eq!(
+            date_time("2002-1-2 10:00:00 +5:30").unwrap(),
+            "2002-01-02T10:00:00+05:30"
+        );
+        assert_eq!(
+            date_time("2024-02-10T09:30:00Z").unwrap(),
+            "2024-02-10T09:30:00Z"
+        );
+        assert!(date_time("2024-02-30 09:30:00").is_err());
+        assert!(date_time("2024-02-10").is_err());
+
+        // The JSON lexical form without an offset is kept as it is
+        let naive = DateTimeDatatype::from_yaml_scalar("2024-02-10T09:30:00").unwrap();
+        assert_eq!(&*naive, "2024-02-10T09:30:00");
+        let yaml = DateTimeDatatype::from_yaml_scalar("2024-02-10 09:30:00").unwrap();
+        assert_eq!(&*yaml, "2024-02-10T09:30:00Z");
+
+        let count: Yaml<PositiveIntegerDatatype> = serde_json::from_value(json!(3)).unwrap();
+        assert_eq!(**count, 3);
+        assert_eq!(serde_json::to_value(&count).unwrap(), json!(3));
+    }
+
+    #[test]
+    fn test_scalar() {
+        for plain in ["marking", "ac-1", "a b", "http://example.com/a#b", "v1.0.0"] {
+            assert_eq!(scalar(plain), plain);
+        }
+        for ambiguous in [
+            "",
+            "on",
+            "NO",
+            "~",
+            "null",
+            "=",
+            "42",
+            "-1.5",
+            "1_000",
+            "0x1F",
+            "0o17",
+            "1:20",
+            ".inf",
+            ".NaN",
+            "2024-02-10",
+            "2024-2-10 9:30:00",
+            "- item",
+            "key: value",
+            "a #comment",
+            " padded",
+            "ends:",
+            "*alias",
+            "'quoted'",
+        ] {
+            assert!(needs_quotes(ambiguous), "{ambiguous:?}");
+        }
+        assert_eq!(scalar("on"), r#""on""#);
+        assert_eq!(scalar("say \"no\"\n"), r#""say \"no\"\n""#);
+        assert_eq!(scalar("\u{7}bell: x"), r#""\u0007bell: x""#);
+    }
+}
@@ -0,0 +1,153 @@
+//! Constructors allocate at most once on success: validation works on the
+//! borrowed input and the only allocation is the stored copy.  Taking a
+//! `String` allocates nothing when it is kept as is.
+//!
+//! The counting allocator is global, so these tests live in their own
+//! binary.  Counts are per thread, so tests running in parallel do not
+//! disturb each other.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use oscal_types::nc_name::NCName;
+use oscal_types::*;
+
+struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn record() {
+    // The thread local is unavailable while the thread is torn down
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+        record();
+        System.realloc(ptr, layout, size)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// The allocations made by `f`.  It is run once first so that lazily
+/// compiled patterns and cached configuration are not counted.
+fn allocations<T>(f: impl Fn() -> T) -> usize {
+    drop(f());
+    let before = ALLOCATIONS.with(Cell::get);
+    drop(f());
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Asserts that `T::try_from` allocates once for each `&str` and not at
+/// all for an owned `String`, which is created outside the count.
+macro_rules! assert_one_allocation {
+    ($($t:ty => $value:expr),* $(,)?) => {
+        $(
+            let from_str = allocations(|| <$t>::try_from($value).unwrap());
+            assert_eq!(from_str, 1, "{} from {:?}", stringify!($t), $value);
+            let owned = allocations(|| {
+                let value = $value.to_string();
+                let before = ALLOCATIONS.with(Cell::get);
+                let datatype = <$t>::try_from(value).unwrap();
+                assert_eq!(
+                    ALLOCATIONS.with(Cell::get),
+                    before,
+                    "{} from String {:?}",
+                    stringify!($t),
+                    $value
+                );
+                datatype
+            });
+            assert_eq!(owned, 1, "{} from String {:?}", stringify!($t), $value);
+        )*
+    };
+}
+
+#[test]
+fn test_string_types() {
+    assert_one_allocation!(
+        DateDatatype => "2024-02-10",
+        DateTimeDatatype => "2024-02-10T09:30:00",
+        DateTimeWithTimezoneDatatype => "2024-02-10T09:30:00.5-05:00",
+        DayTimeDurationDatatype => "P1DT2H",
+        YearMonthDurationDatatype => "P1Y2M",
+        TimezoneOffsetDatatype => "-05:00",
+        StringDatatype => "Access Control",
+        Base64Datatype => "aGVsbG8=",
+        EmailAddressDatatype => "ops@example.gov",
+        IPV4AddressDatatype => "10.0.0.1",
+        IPV6AddressDatatype => "2001:db8::1",
+        IPV4CidrDatatype => "192.0.2.0/24",
+        IPV6CidrDatatype => "2001:db8::/32",
+        TokenDatatype => "ac-1",
+        URIDatatype => "https://example.gov/catalog.json",
+        URIReferenceDatatype => "#ac-1",
+        UUIDDatatype => "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+    );
+}
+
+/// Hostnames are converted to their ASCII form with `idn`.
+#[cfg(not(feature = "idn"))]
+#[test]
+fn test_hostname() {
+    assert_one_allocation!(HostnameDatatype => "bücher.example");
+}
+
+/// Markup is parsed to validate it with `markup`.
+#[cfg(not(feature = "markup"))]
+#[test]
+fn test_markup() {
+    assert_one_allocation!(
+        MarkupLineDatatype => "Some **bold** text",
+        MarkupMultilineDatatype => "# Title\n\nSome text",
+    );
+}
+
+#[test]
+fn test_uuid_forms() {
+    for value in [
+        "A78F7E4C-A27A-4B1E-901B-EBFECF2B0301",
+        "a78f7e4ca27a4b1e901bebfecf2b0301",
+        "{a78f7e4c-a27a-4b1e-901b-ebfecf2b0301}",
+        "urn:uuid:a78f7e4c-a27a-4b1e-901b-ebfecf2b0301",
+    ] {
+        assert_eq!(
+            allocations(|| UUIDDatatype::try_from(value).unwrap()),
+            1,
+            "{value}"
+        );
+        assert_eq!(
+            &*UUIDDatatype::try_from(value).unwrap(),
+            "a78f7e4c-a27a-4b1e-901b-ebfecf2b0301"
+        );
+    }
+}
+
+#[test]
+fn test_other_types() {
+    assert_eq!(allocations(|| NCName::try_from("ac-1").unwrap()), 1);
+    #[cfg(not(feature = "exact_decimal"))]
+    assert_eq!(allocations(|| DecimalDatatype::try_from("4.2").unwrap()), 0);
+    #[cfg(feature = "exact_decimal")]
+    assert_eq!(allocations(|| DecimalDatatype::try_from("4.2").unwrap()), 1);
+}
+
+#[test]
+fn test_borrowed() {
+    assert_eq!(
+        allocations(|| borrowed::TokenDatatype::try_from("ac-1").unwrap()),
+        0
+    );
+}